@@ -0,0 +1,90 @@
+//! Image preprocessing benchmarks
+//!
+//! Compares the staged path `UniversalImagePreprocessor` takes when a model
+//! configures extra steps (resize into a new image, letterbox onto a canvas,
+//! convert and normalize in separate passes) with its fused single-pass path
+//! and with a pooled `ImageInputPipeline`, on a 1080p camera frame. The staged
+//! preprocessor gets a contrast step with a factor of 1, which changes no pixel
+//! but forces the staged path.
+//!
+//! The preprocessor resamples with Lanczos3, and so does the pooled pipeline in
+//! most groups. The `backend_stretch_640` group configures the pooled pipeline
+//! the way the backends do, stretching with a triangle filter.
+//!
+//! Run with `cargo bench --bench preprocessing`.
+
+use ai_edge_inference_crate::image_input::{ImageInputConfig, ImageInputPipeline, ResampleFilter};
+use ai_edge_inference_crate::preprocessing::{
+    InputFormat, NormalizationConfig, PreprocessingStep, ResizeStrategy, UniversalImagePreprocessor,
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use image::{DynamicImage, Rgb, RgbImage};
+
+const FILL: [u8; 3] = [114, 114, 114];
+
+fn camera_frame(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+        Rgb([(x % 251) as u8, (y % 241) as u8, ((x * 3 + y * 7) % 256) as u8])
+    }))
+}
+
+fn normalization() -> NormalizationConfig {
+    NormalizationConfig {
+        mean: vec![0.485, 0.456, 0.406],
+        std: vec![0.229, 0.224, 0.225],
+        scale_to_unit: true,
+    }
+}
+
+fn identity_step() -> PreprocessingStep {
+    PreprocessingStep {
+        step_type: "contrast_enhancement".to_string(),
+        parameters: [("factor".to_string(), serde_yaml::Value::from(1.0))].into(),
+    }
+}
+
+fn bench_preprocessing(c: &mut Criterion) {
+    let frame = camera_frame(1920, 1080);
+    let cases = [
+        ("letterbox_640", 640, ResizeStrategy::Letterbox { fill_color: FILL }, ResampleFilter::Lanczos3),
+        ("stretch_640", 640, ResizeStrategy::Stretch, ResampleFilter::Lanczos3),
+        ("center_crop_224", 224, ResizeStrategy::CenterCrop, ResampleFilter::Lanczos3),
+        ("backend_stretch_640", 640, ResizeStrategy::Stretch, ResampleFilter::Triangle),
+    ];
+
+    for (name, size, resize, filter) in cases {
+        let mut group = c.benchmark_group(name);
+        group.sample_size(20);
+
+        let fused = UniversalImagePreprocessor::new((size, size), resize.clone(), normalization(), InputFormat::NCHW);
+        let staged = fused.clone().with_steps(vec![identity_step()]);
+        for (variant, preprocessor) in [("staged", &staged), ("fused", &fused)] {
+            group.bench_function(BenchmarkId::new(variant, "1080p"), |b| {
+                b.iter_batched(
+                    || frame.clone(),
+                    |frame| black_box(preprocessor.process(frame).unwrap()),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+
+        let pooled = ImageInputPipeline::new(ImageInputConfig {
+            size: (size, size),
+            channels: 3,
+            resize,
+            normalization: normalization(),
+            format: InputFormat::NCHW,
+            filter,
+        });
+        group.bench_function(BenchmarkId::new("pooled", "1080p"), |b| {
+            b.iter(|| {
+                let (tensor, geometry) = pooled.prepare(black_box(&frame)).unwrap();
+                black_box((&tensor[..], geometry));
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_preprocessing);
+criterion_main!(benches);
@@ -0,0 +1,103 @@
+//! Measure a model's accuracy and latency on a labeled dataset
+//!
+//! ```text
+//! cargo run --release --example evaluate_model --features onnx-runtime -- \
+//!     models yolov8n.yaml eval/coco-val.yaml report.json --update --calibrate=temperature
+//! ```
+//!
+//! The evaluation YAML names the dataset and an optional gate:
+//!
+//! ```yaml
+//! dataset:
+//!   format: coco            # coco | yolo | image_folder
+//!   images: val2017
+//!   annotations: annotations/instances_val2017.json
+//!   limit: 500
+//! gate:
+//!   min_map50: 0.5
+//!   max_p95_ms: 80
+//! ```
+//!
+//! The model YAML is relative to the models directory. The JSON report goes
+//! to the given path or stdout. `--update` writes the measured accuracy and
+//! mean latency into the model YAML's `performance` section.
+//! `--calibrate=temperature` or `--calibrate=platt` fits a confidence
+//! calibration to the model's raw scores; with `--update` it is written to
+//! `output.calibration`. The process exits with 1 when the gate is missed.
+
+use ai_edge_inference_crate::evaluation::{self, Dataset, EvaluationSpec};
+use ai_edge_inference_crate::{CalibrationFit, CalibrationMethod, InferenceConfig, InferenceEngine, ModelConfigManager};
+use std::path::{Path, PathBuf};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let update = args.iter().any(|arg| arg == "--update");
+    let calibrate = match args.iter().find_map(|arg| arg.strip_prefix("--calibrate=")) {
+        Some("temperature") => Some(CalibrationMethod::Temperature),
+        Some("platt") => Some(CalibrationMethod::Platt),
+        Some(other) => {
+            eprintln!("unknown calibration method '{}', expected temperature or platt", other);
+            std::process::exit(2);
+        }
+        None => None,
+    };
+    let positional: Vec<&str> = args.iter().map(String::as_str).filter(|arg| !arg.starts_with("--")).collect();
+    let [models_dir, model_yaml, evaluation_yaml, rest @ ..] = positional.as_slice() else {
+        eprintln!("usage: evaluate_model <models-dir> <model.yaml> <evaluation.yaml> [report.json] [--update] [--calibrate=temperature|platt]");
+        std::process::exit(2);
+    };
+
+    let spec = EvaluationSpec::load(Path::new(evaluation_yaml))?;
+    let dataset = Dataset::load(&spec.dataset)?;
+    println!("Loaded {} images in {} classes", dataset.samples.len(), dataset.classes.len());
+
+    let mut config = InferenceConfig::default();
+    config.models.models_directory = PathBuf::from(models_dir);
+    let mut engine = InferenceEngine::new(config).await?;
+    engine.initialize().await?;
+    engine.initialize_yaml_config_system(PathBuf::from(models_dir))?;
+    let model_name = engine.load_model_from_yaml(model_yaml).await?;
+
+    let run = evaluation::run(&engine, &model_name, &dataset, &spec.options).await?;
+    let report = run.report(&model_name, &dataset);
+    let json = serde_json::to_string_pretty(&report)?;
+    match rest.first() {
+        Some(report_path) => {
+            std::fs::write(report_path, json)?;
+            println!("Wrote report to {}", report_path);
+        }
+        None => println!("{}", json),
+    }
+
+    let fit = match calibrate {
+        Some(method) => {
+            let fit = CalibrationFit::fit(method, &evaluation::calibration_samples(&dataset, &run.predictions))?;
+            println!("Calibration: {}", serde_json::to_string(&fit)?);
+            Some(fit)
+        }
+        None => None,
+    };
+
+    if update {
+        let mut manager = ModelConfigManager::new(PathBuf::from(models_dir));
+        let loaded = manager.load_config(model_yaml).await?;
+        let mut model_config = manager.get_config(&loaded).cloned().ok_or("model configuration not found")?;
+        report.update_performance(&mut model_config);
+        if let Some(fit) = &fit {
+            model_config.output.calibration = Some(fit.calibration);
+        }
+        manager.save_config(&model_config, model_yaml).await?;
+        println!("Updated performance section of {}", model_yaml);
+    }
+
+    let failures = report.check(&spec.gate);
+    for failure in &failures {
+        eprintln!("gate: {}", failure);
+    }
+    if !failures.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
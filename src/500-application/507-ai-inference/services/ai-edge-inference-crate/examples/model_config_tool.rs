@@ -0,0 +1,48 @@
+//! Draft a model YAML from an ONNX file, or check a YAML against its model
+//!
+//! ```text
+//! cargo run --example model_config_tool --features onnx-runtime -- draft models/yolov8n.onnx yolov8n.yaml
+//! cargo run --example model_config_tool --features onnx-runtime -- check yolov8n.yaml
+//! ```
+//!
+//! Paths are relative to the current directory, which plays the models directory.
+
+use ai_edge_inference_crate::ModelConfigManager;
+use std::path::PathBuf;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut manager = ModelConfigManager::new(PathBuf::from("."));
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["draft", model_path, rest @ ..] => {
+            let config = manager.draft_config(model_path, None).await?;
+            match rest.first() {
+                Some(config_path) => {
+                    manager.save_config(&config, config_path).await?;
+                    println!("Wrote draft configuration to {}", config_path);
+                }
+                None => print!("{}", serde_yaml::to_string(&config)?),
+            }
+        }
+        ["check", config_path] => {
+            let model_name = manager.load_config(config_path).await?;
+            let discrepancies = manager.validate_against_model(&model_name).await?;
+            if discrepancies.is_empty() {
+                println!("{} matches its model", config_path);
+            } else {
+                for discrepancy in &discrepancies {
+                    println!("{}", discrepancy);
+                }
+                std::process::exit(1);
+            }
+        }
+        _ => {
+            eprintln!("usage: model_config_tool draft <model.onnx> [config.yaml] | check <config.yaml>");
+            std::process::exit(2);
+        }
+    }
+
+    Ok(())
+}
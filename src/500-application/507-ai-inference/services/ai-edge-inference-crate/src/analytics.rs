@@ -0,0 +1,645 @@
+//! Zone, line-crossing and dwell-time analytics
+//!
+//! Spatial rules are configured per `camera_id` and evaluated on detection
+//! results after postprocessing and tracking. Polygon zones report per-frame
+//! occupancy, restricted-area entries, capacity breaches and dwell-time
+//! breaches. Tripwires count direction-aware crossings. Separation rules flag
+//! two classes (e.g. forklift and person) coming closer than a minimum gap.
+//!
+//! Rule geometry uses normalized frame coordinates (`0.0..=1.0`, origin top
+//! left) whenever the result reports its `input_size`. Entries, dwell times and
+//! crossings follow individual objects and need `metadata.track_id` from the
+//! tracker. Without it, a restricted zone fires when it goes from empty to
+//! occupied. Events carry the detections that triggered them.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::tracking;
+use crate::types::{InferenceRequest, Prediction};
+use crate::InferenceResult;
+
+/// How long an object may go unseen before its zone visit or line position is forgotten
+const TRACK_MEMORY_MS: i64 = 5_000;
+
+/// Errors in an analytics configuration
+#[derive(Error, Debug)]
+pub enum AnalyticsError {
+    #[error("Zone '{zone}' on camera '{camera}' needs at least 3 polygon points")]
+    InvalidZone { camera: String, zone: String },
+    #[error("Line '{line}' on camera '{camera}' has identical start and end points")]
+    InvalidLine { camera: String, line: String },
+    #[error("Separation rule '{rule}' on camera '{camera}' needs a positive min_distance")]
+    InvalidSeparation { camera: String, rule: String },
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse analytics config: {0}")]
+    Parse(#[from] serde_yaml::Error),
+}
+
+/// Spatial rules for every camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyticsConfig {
+    /// Rules by `camera_id`
+    pub cameras: HashMap<String, CameraRules>,
+    /// Drop a camera's zone and line state when it has not been updated for this long
+    pub state_timeout_sec: u64,
+}
+
+impl Default for AnalyticsConfig {
+    fn default() -> Self {
+        Self { cameras: HashMap::new(), state_timeout_sec: 300 }
+    }
+}
+
+impl AnalyticsConfig {
+    /// Load rules from a YAML (or JSON) file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, AnalyticsError> {
+        let content = std::fs::read_to_string(path)?;
+        let config: AnalyticsConfig = serde_yaml::from_str(&content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check rule geometry
+    pub fn validate(&self) -> Result<(), AnalyticsError> {
+        for (camera, rules) in &self.cameras {
+            if let Some(zone) = rules.zones.iter().find(|zone| zone.polygon.len() < 3) {
+                return Err(AnalyticsError::InvalidZone { camera: camera.clone(), zone: zone.name.clone() });
+            }
+            if let Some(line) = rules.lines.iter().find(|line| line.start == line.end) {
+                return Err(AnalyticsError::InvalidLine { camera: camera.clone(), line: line.name.clone() });
+            }
+            if let Some(rule) = rules.separation.iter().find(|rule| rule.min_distance <= 0.0) {
+                return Err(AnalyticsError::InvalidSeparation { camera: camera.clone(), rule: rule.name.clone() });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Zones, lines and separation rules of one camera
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CameraRules {
+    #[serde(default)]
+    pub zones: Vec<ZoneRule>,
+    #[serde(default)]
+    pub lines: Vec<LineRule>,
+    #[serde(default)]
+    pub separation: Vec<SeparationRule>,
+    /// Point of a detection box tested against zones and lines
+    #[serde(default)]
+    pub anchor: Anchor,
+}
+
+/// Point of a detection box that represents its position
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    /// Where a standing person or vehicle touches the floor
+    #[default]
+    BottomCenter,
+    Center,
+}
+
+/// Polygon zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneRule {
+    pub name: String,
+    /// Polygon vertices `[x, y]` in order
+    pub polygon: Vec<[f32; 2]>,
+    /// Classes counted in the zone; empty counts every class
+    #[serde(default)]
+    pub classes: Vec<String>,
+    /// Emit an event whenever an object enters the zone
+    #[serde(default)]
+    pub restricted: bool,
+    /// Emit an event when occupancy rises above this count
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_occupancy: Option<usize>,
+    /// Emit an event once an object has stayed in the zone this long
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dwell_threshold_sec: Option<f64>,
+}
+
+/// Virtual tripwire between two points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineRule {
+    pub name: String,
+    pub start: [f32; 2],
+    pub end: [f32; 2],
+    /// Crossings that emit events; both directions are always counted
+    #[serde(default)]
+    pub direction: CrossingDirection,
+    /// Classes that can cross the line; empty accepts every class
+    #[serde(default)]
+    pub classes: Vec<String>,
+}
+
+/// Direction of a line crossing
+///
+/// Named for a line drawn from top (`start`) to bottom (`end`), where
+/// `left_to_right` is movement towards the right of the image. The directions
+/// rotate with the line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossingDirection {
+    #[default]
+    Any,
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Minimum gap between detections of two classes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeparationRule {
+    pub name: String,
+    /// The two classes kept apart, e.g. `["forklift", "person"]`
+    pub classes: [String; 2],
+    /// Smallest allowed gap between their boxes, in rule coordinates
+    pub min_distance: f32,
+}
+
+/// Kind of analytics event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsEventType {
+    RestrictedArea,
+    OccupancyExceeded,
+    DwellExceeded,
+    LineCrossed,
+    SeparationViolation,
+}
+
+impl AnalyticsEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RestrictedArea => "restricted_area",
+            Self::OccupancyExceeded => "occupancy_exceeded",
+            Self::DwellExceeded => "dwell_exceeded",
+            Self::LineCrossed => "line_crossed",
+            Self::SeparationViolation => "separation_violation",
+        }
+    }
+
+    /// Events that indicate an immediate risk to people
+    pub fn is_safety_critical(&self) -> bool {
+        matches!(self, Self::RestrictedArea | Self::SeparationViolation)
+    }
+}
+
+/// Event raised by a spatial rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsEvent {
+    pub event_type: AnalyticsEventType,
+    /// Zone, line or separation rule that fired
+    pub rule: String,
+    pub camera_id: String,
+    /// Frame time in milliseconds since the Unix epoch
+    pub timestamp: i64,
+    /// Detections that triggered the event
+    pub detections: Vec<Prediction>,
+    /// Rule-specific values such as occupancy, dwell time or crossing direction
+    pub details: serde_json::Value,
+}
+
+/// An object's stay in a zone
+#[derive(Debug, Clone)]
+struct Visit {
+    entered_at: i64,
+    last_seen: i64,
+    dwell_reported: bool,
+}
+
+#[derive(Debug, Default)]
+struct ZoneState {
+    visits: HashMap<u64, Visit>,
+    /// Whether untracked detections occupied the zone on the previous frame
+    untracked_occupied: bool,
+    over_capacity: bool,
+}
+
+/// Last side of a line an object was seen on
+#[derive(Debug, Clone)]
+struct LinePosition {
+    side: f32,
+    point: [f32; 2],
+    last_seen: i64,
+}
+
+#[derive(Debug, Default)]
+struct LineState {
+    positions: HashMap<u64, LinePosition>,
+    left_to_right: u64,
+    right_to_left: u64,
+}
+
+/// Rule state of one camera
+#[derive(Debug)]
+pub struct CameraState {
+    zones: Vec<ZoneState>,
+    lines: Vec<LineState>,
+    /// Whether each separation rule was violated on the previous frame
+    separation: Vec<bool>,
+    last_update: Instant,
+}
+
+impl CameraState {
+    pub fn new(rules: &CameraRules) -> Self {
+        Self {
+            zones: rules.zones.iter().map(|_| ZoneState::default()).collect(),
+            lines: rules.lines.iter().map(|_| LineState::default()).collect(),
+            separation: vec![false; rules.separation.len()],
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Evaluate one frame of detections against the camera's rules
+    ///
+    /// `frame_size` is the coordinate space of the boxes; when given, boxes are
+    /// normalized before they are compared with the rules. Returns the events
+    /// and the `metadata.analytics` summary without them.
+    pub fn evaluate(
+        &mut self,
+        rules: &CameraRules,
+        camera_id: &str,
+        predictions: &[Prediction],
+        frame_size: Option<(f32, f32)>,
+        timestamp: i64,
+    ) -> (Vec<AnalyticsEvent>, serde_json::Value) {
+        self.last_update = Instant::now();
+
+        let boxes: Vec<Option<[f32; 4]>> = predictions.iter()
+            .map(|prediction| prediction.bbox.map(|bbox| match frame_size {
+                Some((width, height)) if width > 0.0 && height > 0.0 => {
+                    [bbox[0] / width, bbox[1] / height, bbox[2] / width, bbox[3] / height]
+                }
+                _ => bbox,
+            }))
+            .collect();
+        let points: Vec<Option<[f32; 2]>> = boxes.iter()
+            .map(|bbox| bbox.map(|bbox| anchor_point(&bbox, rules.anchor)))
+            .collect();
+
+        let event = |event_type, rule: &str, indices: &[usize], details| AnalyticsEvent {
+            event_type,
+            rule: rule.to_string(),
+            camera_id: camera_id.to_string(),
+            timestamp,
+            detections: indices.iter().map(|&index| predictions[index].clone()).collect(),
+            details,
+        };
+        let mut events = Vec::new();
+
+        let mut occupancy = serde_json::Map::new();
+        for (rule, state) in rules.zones.iter().zip(&mut self.zones) {
+            let inside: Vec<usize> = (0..predictions.len())
+                .filter(|&index| class_matches(&rule.classes, &predictions[index]))
+                .filter(|&index| points[index].is_some_and(|point| point_in_polygon(point, &rule.polygon)))
+                .collect();
+            occupancy.insert(rule.name.clone(), json!(inside.len()));
+
+            let mut entered = Vec::new();
+            let mut untracked = Vec::new();
+            for &index in &inside {
+                let Some(track_id) = track_id(&predictions[index]) else {
+                    untracked.push(index);
+                    continue;
+                };
+                let visit = state.visits.entry(track_id).or_insert_with(|| {
+                    entered.push(index);
+                    Visit { entered_at: timestamp, last_seen: timestamp, dwell_reported: false }
+                });
+                visit.last_seen = timestamp;
+
+                let dwell_sec = (timestamp - visit.entered_at) as f64 / 1000.0;
+                if let Some(threshold) = rule.dwell_threshold_sec {
+                    if !visit.dwell_reported && dwell_sec >= threshold {
+                        visit.dwell_reported = true;
+                        events.push(event(AnalyticsEventType::DwellExceeded, &rule.name, &[index], json!({
+                            "dwell_sec": dwell_sec,
+                            "dwell_threshold_sec": threshold,
+                        })));
+                    }
+                }
+            }
+            state.visits.retain(|_, visit| timestamp - visit.last_seen <= TRACK_MEMORY_MS);
+            if !untracked.is_empty() && !state.untracked_occupied {
+                entered.extend(&untracked);
+            }
+            state.untracked_occupied = !untracked.is_empty();
+
+            if rule.restricted && !entered.is_empty() {
+                events.push(event(AnalyticsEventType::RestrictedArea, &rule.name, &entered, json!({
+                    "occupancy": inside.len(),
+                })));
+            }
+
+            if let Some(max_occupancy) = rule.max_occupancy {
+                let over_capacity = inside.len() > max_occupancy;
+                if over_capacity && !state.over_capacity {
+                    events.push(event(AnalyticsEventType::OccupancyExceeded, &rule.name, &inside, json!({
+                        "occupancy": inside.len(),
+                        "max_occupancy": max_occupancy,
+                    })));
+                }
+                state.over_capacity = over_capacity;
+            }
+        }
+
+        let mut line_counts = serde_json::Map::new();
+        for (rule, state) in rules.lines.iter().zip(&mut self.lines) {
+            for index in (0..predictions.len()).filter(|&index| class_matches(&rule.classes, &predictions[index])) {
+                let (Some(track_id), Some(point)) = (track_id(&predictions[index]), points[index]) else {
+                    continue;
+                };
+                let side = cross(rule.start, rule.end, point);
+                if side == 0.0 {
+                    continue;
+                }
+
+                if let Some(previous) = state.positions.get(&track_id) {
+                    if previous.side * side < 0.0 && segments_intersect(previous.point, point, rule.start, rule.end) {
+                        let direction = if previous.side > 0.0 {
+                            state.left_to_right += 1;
+                            CrossingDirection::LeftToRight
+                        } else {
+                            state.right_to_left += 1;
+                            CrossingDirection::RightToLeft
+                        };
+                        if matches!(rule.direction, CrossingDirection::Any) || rule.direction == direction {
+                            events.push(event(AnalyticsEventType::LineCrossed, &rule.name, &[index], json!({
+                                "direction": direction,
+                            })));
+                        }
+                    }
+                }
+                state.positions.insert(track_id, LinePosition { side, point, last_seen: timestamp });
+            }
+            state.positions.retain(|_, position| timestamp - position.last_seen <= TRACK_MEMORY_MS);
+            line_counts.insert(rule.name.clone(), json!({
+                "left_to_right": state.left_to_right,
+                "right_to_left": state.right_to_left,
+            }));
+        }
+
+        for (rule, violated) in rules.separation.iter().zip(&mut self.separation) {
+            let of_class = |class: &str| -> Vec<usize> {
+                (0..predictions.len())
+                    .filter(|&index| boxes[index].is_some() && predictions[index].class == class)
+                    .collect()
+            };
+            let (first, second) = (of_class(&rule.classes[0]), of_class(&rule.classes[1]));
+
+            let mut involved = Vec::new();
+            let mut closest = f32::INFINITY;
+            for &a in &first {
+                for &b in second.iter().filter(|&&b| b != a) {
+                    let distance = box_gap(&boxes[a].unwrap_or_default(), &boxes[b].unwrap_or_default());
+                    if distance < rule.min_distance {
+                        closest = closest.min(distance);
+                        for index in [a, b] {
+                            if !involved.contains(&index) {
+                                involved.push(index);
+                            }
+                        }
+                    }
+                }
+            }
+
+            let violating = !involved.is_empty();
+            if violating && !*violated {
+                events.push(event(AnalyticsEventType::SeparationViolation, &rule.name, &involved, json!({
+                    "distance": closest,
+                    "min_distance": rule.min_distance,
+                })));
+            }
+            *violated = violating;
+        }
+
+        let summary = json!({
+            "camera_id": camera_id,
+            "occupancy": occupancy,
+            "line_counts": line_counts,
+        });
+        (events, summary)
+    }
+}
+
+/// Per-camera rule state shared by all requests of an engine
+#[derive(Debug)]
+pub struct ZoneAnalytics {
+    config: AnalyticsConfig,
+    cameras: Mutex<HashMap<String, CameraState>>,
+}
+
+impl ZoneAnalytics {
+    /// Analytics for the given rules, `None` when no camera has any
+    pub fn from_config(config: &AnalyticsConfig) -> Result<Option<Self>, AnalyticsError> {
+        config.validate()?;
+        Ok((!config.cameras.is_empty()).then(|| Self { config: config.clone(), cameras: Mutex::new(HashMap::new()) }))
+    }
+
+    /// Evaluate a result against the rules of its request's camera
+    ///
+    /// Adds `metadata.analytics` with occupancy per zone, crossing counts per
+    /// line and the events raised by this frame.
+    pub fn evaluate(&self, request: &InferenceRequest, result: &mut InferenceResult) {
+        let Some(camera_id) = tracking::stream_id(request) else {
+            return;
+        };
+        let Some(rules) = self.config.cameras.get(camera_id) else {
+            return;
+        };
+        let timestamp = tracking::frame_timestamp(request);
+        let frame_size = result.metadata.get("input_size")
+            .and_then(|size| size.as_array())
+            .and_then(|size| Some((size.first()?.as_f64()? as f32, size.get(1)?.as_f64()? as f32)));
+
+        let key = format!("{}/{}", camera_id, result.model_name);
+        let (events, mut summary) = {
+            let mut cameras = self.cameras.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let timeout = Duration::from_secs(self.config.state_timeout_sec);
+            cameras.retain(|_, state| state.last_update.elapsed() < timeout);
+            cameras.entry(key)
+                .or_insert_with(|| CameraState::new(rules))
+                .evaluate(rules, camera_id, &result.predictions, frame_size, timestamp)
+        };
+
+        if let Some(metadata) = result.metadata.as_object_mut() {
+            summary["events"] = json!(events);
+            metadata.insert("analytics".to_string(), summary);
+        }
+    }
+}
+
+/// Analytics events recorded on a result by [`ZoneAnalytics::evaluate`]
+pub fn result_events(result: &InferenceResult) -> Vec<AnalyticsEvent> {
+    result.metadata.get("analytics")
+        .and_then(|analytics| analytics.get("events"))
+        .and_then(|events| serde_json::from_value(events.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn track_id(prediction: &Prediction) -> Option<u64> {
+    prediction.metadata.get("track_id").and_then(|id| id.as_u64())
+}
+
+fn class_matches(classes: &[String], prediction: &Prediction) -> bool {
+    classes.is_empty() || classes.contains(&prediction.class)
+}
+
+fn anchor_point(bbox: &[f32; 4], anchor: Anchor) -> [f32; 2] {
+    match anchor {
+        Anchor::BottomCenter => [(bbox[0] + bbox[2]) / 2.0, bbox[3]],
+        Anchor::Center => [(bbox[0] + bbox[2]) / 2.0, (bbox[1] + bbox[3]) / 2.0],
+    }
+}
+
+/// Ray-casting point-in-polygon test
+fn point_in_polygon(point: [f32; 2], polygon: &[[f32; 2]]) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for &vertex in polygon {
+        if (vertex[1] > point[1]) != (previous[1] > point[1]) {
+            let x = vertex[0] + (point[1] - vertex[1]) * (previous[0] - vertex[0]) / (previous[1] - vertex[1]);
+            if point[0] < x {
+                inside = !inside;
+            }
+        }
+        previous = vertex;
+    }
+    inside
+}
+
+/// Which side of the line `a → b` a point lies on (sign) in image coordinates
+fn cross(a: [f32; 2], b: [f32; 2], point: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (point[1] - a[1]) - (b[1] - a[1]) * (point[0] - a[0])
+}
+
+fn segments_intersect(p1: [f32; 2], p2: [f32; 2], q1: [f32; 2], q2: [f32; 2]) -> bool {
+    cross(q1, q2, p1) * cross(q1, q2, p2) <= 0.0 && cross(p1, p2, q1) * cross(p1, p2, q2) <= 0.0
+}
+
+/// Distance between the nearest edges of two boxes, zero when they overlap
+fn box_gap(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let dx = (a[0].max(b[0]) - a[2].min(b[2])).max(0.0);
+    let dy = (a[1].max(b[1]) - a[3].min(b[3])).max(0.0);
+    dx.hypot(dy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detection(class: &str, bbox: [f32; 4], track_id: Option<u64>) -> Prediction {
+        Prediction {
+            class: class.to_string(),
+            confidence: 0.9,
+            bbox: Some(bbox),
+            metadata: track_id.map(|id| ("track_id".to_string(), json!(id))).into_iter().collect(),
+            severity: None,
+            mask: None,
+            keypoints: None,
+        }
+    }
+
+    fn rules() -> CameraRules {
+        serde_yaml::from_str(r#"
+zones:
+  - name: loading-bay
+    polygon: [[0.5, 0.5], [1.0, 0.5], [1.0, 1.0], [0.5, 1.0]]
+    classes: [person]
+    restricted: true
+    max_occupancy: 1
+    dwell_threshold_sec: 10
+lines:
+  - name: gate
+    start: [0.5, 0.0]
+    end: [0.5, 1.0]
+    direction: left_to_right
+separation:
+  - name: forklift-pedestrian
+    classes: [forklift, person]
+    min_distance: 0.05
+"#).unwrap()
+    }
+
+    fn event_types(events: &[AnalyticsEvent]) -> Vec<AnalyticsEventType> {
+        events.iter().map(|event| event.event_type).collect()
+    }
+
+    #[test]
+    fn test_zone_entry_occupancy_and_dwell() {
+        let rules = rules();
+        let mut state = CameraState::new(&rules);
+        let frame = Some((100.0, 100.0));
+
+        // Feet at (75, 80) are inside the loading bay
+        let person = detection("person", [70.0, 40.0, 80.0, 80.0], Some(1));
+        let (events, summary) = state.evaluate(&rules, "dock", std::slice::from_ref(&person), frame, 0);
+        assert_eq!(event_types(&events), vec![AnalyticsEventType::RestrictedArea]);
+        assert_eq!(events[0].detections[0].metadata["track_id"], 1);
+        assert_eq!(summary["occupancy"]["loading-bay"], 1);
+
+        // Still inside: no new entry, dwell fires once past the threshold
+        let (events, _) = state.evaluate(&rules, "dock", std::slice::from_ref(&person), frame, 5_000);
+        assert!(events.is_empty());
+        let (events, _) = state.evaluate(&rules, "dock", std::slice::from_ref(&person), frame, 10_000);
+        assert_eq!(event_types(&events), vec![AnalyticsEventType::DwellExceeded]);
+        let (events, _) = state.evaluate(&rules, "dock", std::slice::from_ref(&person), frame, 11_000);
+        assert!(events.is_empty());
+
+        // A second person enters and the zone goes over capacity
+        let second = detection("person", [85.0, 50.0, 95.0, 90.0], Some(2));
+        let (events, summary) = state.evaluate(&rules, "dock", &[person, second], frame, 12_000);
+        assert_eq!(event_types(&events), vec![AnalyticsEventType::RestrictedArea, AnalyticsEventType::OccupancyExceeded]);
+        assert_eq!(events[1].detections.len(), 2);
+        assert_eq!(summary["occupancy"]["loading-bay"], 2);
+    }
+
+    #[test]
+    fn test_line_crossing_is_direction_aware() {
+        let rules = rules();
+        let mut state = CameraState::new(&rules);
+        let frame = Some((100.0, 100.0));
+
+        let at = |x: f32| detection("forklift", [x - 5.0, 10.0, x + 5.0, 30.0], Some(7));
+        state.evaluate(&rules, "dock", &[at(30.0)], frame, 0);
+        let (events, summary) = state.evaluate(&rules, "dock", &[at(60.0)], frame, 100);
+        assert_eq!(event_types(&events), vec![AnalyticsEventType::LineCrossed]);
+        assert_eq!(events[0].details["direction"], "left_to_right");
+        assert_eq!(summary["line_counts"]["gate"]["left_to_right"], 1);
+
+        // Crossing back is counted but does not match the configured direction
+        let (events, summary) = state.evaluate(&rules, "dock", &[at(40.0)], frame, 200);
+        assert!(events.is_empty());
+        assert_eq!(summary["line_counts"]["gate"]["right_to_left"], 1);
+    }
+
+    #[test]
+    fn test_separation_violation_fires_when_it_starts() {
+        let rules = rules();
+        let mut state = CameraState::new(&rules);
+        let frame = Some((100.0, 100.0));
+
+        let forklift = detection("forklift", [10.0, 10.0, 30.0, 30.0], None);
+        let far = detection("person", [60.0, 10.0, 65.0, 30.0], None);
+        let near = detection("person", [32.0, 10.0, 36.0, 30.0], None);
+
+        let (events, _) = state.evaluate(&rules, "dock", &[forklift.clone(), far], frame, 0);
+        assert!(events.is_empty());
+        let (events, _) = state.evaluate(&rules, "dock", &[forklift.clone(), near.clone()], frame, 100);
+        assert_eq!(event_types(&events), vec![AnalyticsEventType::SeparationViolation]);
+        assert_eq!(events[0].detections.len(), 2);
+        let (events, _) = state.evaluate(&rules, "dock", &[forklift, near], frame, 200);
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,541 @@
+//! Audio decoding and spectrogram feature extraction
+//!
+//! Edge microphones publish WAV files or raw PCM. This module decodes either
+//! into mono f32 samples, resamples to the model rate, cuts the clip into
+//! fixed-length segments and turns each segment into the waveform, log-mel
+//! spectrogram or MFCC tensor the model was trained on.
+
+use std::f32::consts::PI;
+use std::io::Cursor;
+
+use dasp::Sample;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Errors that can occur while decoding audio or extracting features
+#[derive(Error, Debug)]
+pub enum AudioError {
+    #[error("WAV decoding error: {0}")]
+    WavError(#[from] hound::Error),
+    #[error("Invalid audio data: {0}")]
+    InvalidData(String),
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+}
+
+/// Raw PCM sample encodings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PcmFormat {
+    /// Signed 16-bit little-endian
+    S16Le,
+    /// Signed 32-bit little-endian
+    S32Le,
+    /// 32-bit float little-endian
+    F32Le,
+}
+
+impl PcmFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "pcm" | "pcm_s16le" | "s16le" | "s16" => Some(Self::S16Le),
+            "pcm_s32le" | "s32le" | "s32" => Some(Self::S32Le),
+            "pcm_f32le" | "f32le" | "f32" => Some(Self::F32Le),
+            _ => None,
+        }
+    }
+
+    fn bytes_per_sample(&self) -> usize {
+        match self {
+            Self::S16Le => 2,
+            Self::S32Le | Self::F32Le => 4,
+        }
+    }
+}
+
+/// Decoded mono audio
+#[derive(Debug, Clone)]
+pub struct AudioClip {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    /// Channel count before downmixing
+    pub channels: u16,
+}
+
+impl AudioClip {
+    pub fn duration_seconds(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate.max(1) as f32
+    }
+}
+
+/// Decode a WAV file, averaging all channels to mono
+pub fn decode_wav(bytes: &[u8]) -> Result<AudioClip, AudioError> {
+    let reader = hound::WavReader::new(Cursor::new(bytes))?;
+    let spec = reader.spec();
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            // Scale by the declared bit depth so 24-bit audio lands in [-1, 1]
+            let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader.into_samples::<i32>()
+                .map(|s| s.map(|s| s as f32 * scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    Ok(AudioClip {
+        samples: downmix(&interleaved, spec.channels),
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+    })
+}
+
+/// Decode interleaved little-endian PCM, averaging all channels to mono
+pub fn decode_pcm(bytes: &[u8], format: PcmFormat, sample_rate: u32, channels: u16) -> Result<AudioClip, AudioError> {
+    if channels == 0 || sample_rate == 0 {
+        return Err(AudioError::InvalidData("PCM sample rate and channel count must be non-zero".to_string()));
+    }
+    let width = format.bytes_per_sample();
+    if !bytes.len().is_multiple_of(width * channels as usize) {
+        return Err(AudioError::InvalidData(format!(
+            "{} bytes is not a whole number of {}-channel {:?} frames", bytes.len(), channels, format,
+        )));
+    }
+
+    let interleaved: Vec<f32> = bytes.chunks_exact(width)
+        .map(|b| match format {
+            PcmFormat::S16Le => i16::from_le_bytes([b[0], b[1]]).to_sample::<f32>(),
+            PcmFormat::S32Le => i32::from_le_bytes([b[0], b[1], b[2], b[3]]).to_sample::<f32>(),
+            PcmFormat::F32Le => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        })
+        .collect();
+
+    Ok(AudioClip {
+        samples: downmix(&interleaved, channels),
+        sample_rate,
+        channels,
+    })
+}
+
+fn downmix(interleaved: &[f32], channels: u16) -> Vec<f32> {
+    if channels <= 1 {
+        return interleaved.to_vec();
+    }
+    interleaved.chunks_exact(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Resample with a Hann-windowed sinc filter
+///
+/// The cutoff sits at the lower of the two Nyquist rates, so downsampling does
+/// not alias high-frequency machine noise into the model band.
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    const ZERO_CROSSINGS: f32 = 16.0;
+    let ratio = from_rate as f64 / to_rate as f64;
+    let cutoff = (to_rate as f32 / from_rate as f32).min(1.0);
+    let half_width = ZERO_CROSSINGS / cutoff;
+    let output_len = (samples.len() as f64 / ratio).round() as usize;
+
+    (0..output_len)
+        .map(|j| {
+            let center = j as f64 * ratio;
+            let first = (center - half_width as f64).ceil().max(0.0) as usize;
+            let last = ((center + half_width as f64).floor() as usize).min(samples.len() - 1);
+            (first..=last)
+                .map(|k| {
+                    let x = (k as f64 - center) as f32;
+                    let window = 0.5 + 0.5 * (PI * x / half_width).cos();
+                    samples[k] * cutoff * sinc(cutoff * x) * window
+                })
+                .sum()
+        })
+        .collect()
+}
+
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-6 { 1.0 } else { (PI * x).sin() / (PI * x) }
+}
+
+/// Features computed from each segment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioFeatureType {
+    /// Raw resampled samples, `[1, samples]`
+    Waveform,
+    /// Natural log of the mel power spectrogram
+    LogMel,
+    /// DCT-II of the log-mel spectrogram
+    Mfcc,
+}
+
+/// Tensor layout of spectrogram features (f = mel bins or coefficients, t = frames)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpectrogramLayout {
+    /// `[1, 1, f, t]`
+    Ncft,
+    /// `[1, 1, t, f]`
+    Nctf,
+    /// `[1, f, t]`
+    Nft,
+    /// `[1, t, f]`
+    Ntf,
+}
+
+/// Audio preprocessing from the model YAML `preprocessing.audio` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioFeatureConfig {
+    /// Sample rate the model expects; input is resampled to it
+    pub sample_rate: u32,
+    /// Length of each model input segment
+    pub segment_seconds: f32,
+    /// Step between segment starts; defaults to the segment length
+    pub segment_hop_seconds: Option<f32>,
+    pub features: AudioFeatureType,
+    /// FFT size; must be a power of two
+    pub n_fft: usize,
+    /// Analysis window length in samples; defaults to `n_fft`
+    pub win_length: Option<usize>,
+    pub hop_length: usize,
+    pub n_mels: usize,
+    pub f_min: f32,
+    /// Upper mel band edge; defaults to the Nyquist frequency
+    pub f_max: Option<f32>,
+    pub n_mfcc: usize,
+    /// Added before taking the log to avoid `ln(0)`
+    pub log_offset: f32,
+    pub layout: SpectrogramLayout,
+}
+
+impl Default for AudioFeatureConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16_000,
+            segment_seconds: 1.0,
+            segment_hop_seconds: None,
+            features: AudioFeatureType::LogMel,
+            n_fft: 512,
+            win_length: None,
+            hop_length: 160,
+            n_mels: 64,
+            f_min: 0.0,
+            f_max: None,
+            n_mfcc: 13,
+            log_offset: 1e-6,
+            layout: SpectrogramLayout::Ncft,
+        }
+    }
+}
+
+impl AudioFeatureConfig {
+    /// Check the settings that would otherwise fail deep inside extraction
+    pub fn validate(&self) -> Result<(), AudioError> {
+        if self.sample_rate == 0 || self.segment_seconds <= 0.0 || self.hop_length == 0 {
+            return Err(AudioError::InvalidConfig(
+                "sample_rate, segment_seconds and hop_length must be positive".to_string(),
+            ));
+        }
+        if self.features != AudioFeatureType::Waveform {
+            if !self.n_fft.is_power_of_two() {
+                return Err(AudioError::InvalidConfig(format!("n_fft {} is not a power of two", self.n_fft)));
+            }
+            if self.win_length.is_some_and(|w| w == 0 || w > self.n_fft) {
+                return Err(AudioError::InvalidConfig("win_length must be between 1 and n_fft".to_string()));
+            }
+            if self.n_mels == 0 || (self.features == AudioFeatureType::Mfcc && !(1..=self.n_mels).contains(&self.n_mfcc)) {
+                return Err(AudioError::InvalidConfig("n_mels must be positive and n_mfcc at most n_mels".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn segment_samples(&self) -> usize {
+        ((self.segment_seconds * self.sample_rate as f32).round() as usize).max(1)
+    }
+}
+
+/// One fixed-length slice of a clip at the model sample rate
+#[derive(Debug, Clone)]
+pub struct AudioSegment {
+    pub index: usize,
+    pub start_seconds: f32,
+    /// End of the real (unpadded) audio in the segment
+    pub end_seconds: f32,
+    pub samples: Vec<f32>,
+}
+
+/// Resample a clip and cut it into zero-padded segments
+pub fn segment_clip(clip: &AudioClip, config: &AudioFeatureConfig) -> Result<Vec<AudioSegment>, AudioError> {
+    if clip.samples.is_empty() {
+        return Err(AudioError::InvalidData("Audio clip is empty".to_string()));
+    }
+
+    let samples = resample(&clip.samples, clip.sample_rate, config.sample_rate);
+    let rate = config.sample_rate as f32;
+    let length = config.segment_samples();
+    let hop = config.segment_hop_seconds
+        .map(|s| ((s * rate).round() as usize).max(1))
+        .unwrap_or(length);
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + length).min(samples.len());
+        let mut segment = samples[start..end].to_vec();
+        segment.resize(length, 0.0);
+        segments.push(AudioSegment {
+            index: segments.len(),
+            start_seconds: start as f32 / rate,
+            end_seconds: end as f32 / rate,
+            samples: segment,
+        });
+
+        if start + length >= samples.len() {
+            break;
+        }
+        start += hop;
+    }
+
+    Ok(segments)
+}
+
+/// Extract model input features from a segment, returning `(shape, data)`
+pub fn extract_features(samples: &[f32], config: &AudioFeatureConfig) -> Result<(Vec<i64>, Vec<f32>), AudioError> {
+    config.validate()?;
+    if config.features == AudioFeatureType::Waveform {
+        return Ok((vec![1, samples.len() as i64], samples.to_vec()));
+    }
+
+    let log_mel = log_mel_spectrogram(samples, config);
+    let frames = match config.features {
+        AudioFeatureType::Mfcc => log_mel.iter().map(|frame| dct_ii(frame, config.n_mfcc)).collect(),
+        _ => log_mel,
+    };
+
+    let (t, f) = (frames.len(), frames.first().map_or(0, Vec::len));
+    let (shape, data) = match config.layout {
+        SpectrogramLayout::Ncft => (vec![1, 1, f, t], feature_major(&frames, f)),
+        SpectrogramLayout::Nft => (vec![1, f, t], feature_major(&frames, f)),
+        SpectrogramLayout::Nctf => (vec![1, 1, t, f], frames.concat()),
+        SpectrogramLayout::Ntf => (vec![1, t, f], frames.concat()),
+    };
+    Ok((shape.into_iter().map(|d| d as i64).collect(), data))
+}
+
+fn feature_major(frames: &[Vec<f32>], features: usize) -> Vec<f32> {
+    (0..features)
+        .flat_map(|bin| frames.iter().map(move |frame| frame[bin]))
+        .collect()
+}
+
+/// Log-mel spectrogram as `[frame][mel]`
+pub fn log_mel_spectrogram(samples: &[f32], config: &AudioFeatureConfig) -> Vec<Vec<f32>> {
+    let n_fft = config.n_fft;
+    let win_length = config.win_length.unwrap_or(n_fft);
+    let window: Vec<f32> = (0..win_length)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / win_length as f32).cos())
+        .collect();
+    let filters = mel_filterbank(config);
+
+    // Frames start at every hop; short input still yields one zero-padded frame
+    let frame_count = 1 + samples.len().saturating_sub(win_length) / config.hop_length;
+    let offset = (n_fft - win_length) / 2;
+
+    (0..frame_count)
+        .map(|frame| {
+            let start = frame * config.hop_length;
+            let mut re = vec![0.0f32; n_fft];
+            let mut im = vec![0.0f32; n_fft];
+            for (i, w) in window.iter().enumerate() {
+                re[offset + i] = samples.get(start + i).copied().unwrap_or(0.0) * w;
+            }
+            fft(&mut re, &mut im);
+
+            let power: Vec<f32> = (0..=n_fft / 2).map(|k| re[k] * re[k] + im[k] * im[k]).collect();
+            filters.iter()
+                .map(|filter| {
+                    let energy: f32 = filter.iter().zip(&power).map(|(w, p)| w * p).sum();
+                    (energy + config.log_offset).ln()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Triangular HTK mel filters over the `n_fft / 2 + 1` power bins
+fn mel_filterbank(config: &AudioFeatureConfig) -> Vec<Vec<f32>> {
+    let bins = config.n_fft / 2 + 1;
+    let nyquist = config.sample_rate as f32 / 2.0;
+    let (mel_min, mel_max) = (hz_to_mel(config.f_min), hz_to_mel(config.f_max.unwrap_or(nyquist).min(nyquist)));
+    let edges: Vec<f32> = (0..config.n_mels + 2)
+        .map(|i| mel_to_hz(mel_min + (mel_max - mel_min) * i as f32 / (config.n_mels + 1) as f32))
+        .collect();
+
+    (0..config.n_mels)
+        .map(|m| {
+            let (lower, center, upper) = (edges[m], edges[m + 1], edges[m + 2]);
+            (0..bins)
+                .map(|k| {
+                    let hz = k as f32 * config.sample_rate as f32 / config.n_fft as f32;
+                    let rising = (hz - lower) / (center - lower).max(f32::EPSILON);
+                    let falling = (upper - hz) / (upper - center).max(f32::EPSILON);
+                    rising.min(falling).max(0.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Orthonormal DCT-II, keeping the first `n` coefficients
+fn dct_ii(input: &[f32], n: usize) -> Vec<f32> {
+    let len = input.len() as f32;
+    (0..n)
+        .map(|k| {
+            let sum: f32 = input.iter().enumerate()
+                .map(|(i, &x)| x * (PI / len * (i as f32 + 0.5) * k as f32).cos())
+                .sum();
+            let scale = if k == 0 { (1.0 / len).sqrt() } else { (2.0 / len).sqrt() };
+            sum * scale
+        })
+        .collect()
+}
+
+/// In-place iterative radix-2 FFT; the length must be a power of two
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if j > i {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut size = 2;
+    while size <= n {
+        let angle = -2.0 * PI / size as f32;
+        for start in (0..n).step_by(size) {
+            for k in 0..size / 2 {
+                let (w_re, w_im) = ((angle * k as f32).cos(), (angle * k as f32).sin());
+                let (a, b) = (start + k, start + k + size / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        size *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(frequency: f32, sample_rate: u32, seconds: f32) -> Vec<f32> {
+        (0..(sample_rate as f32 * seconds) as usize)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_wav_round_trip_downmixes_stereo() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut bytes = Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut bytes, spec).unwrap();
+            for _ in 0..100 {
+                writer.write_sample(16384i16).unwrap();
+                writer.write_sample(0i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let clip = decode_wav(bytes.get_ref()).unwrap();
+
+        assert_eq!((clip.sample_rate, clip.channels, clip.samples.len()), (8000, 2, 100));
+        approx::assert_abs_diff_eq!(clip.samples[0], 0.25, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_resample_keeps_tone_and_length() {
+        let samples = tone(440.0, 48_000, 0.5);
+        let resampled = resample(&samples, 48_000, 16_000);
+
+        assert_eq!(resampled.len(), 8000);
+        let expected = tone(440.0, 16_000, 0.5);
+        // Compare away from the edges, where the filter runs out of input
+        for i in 100..7900 {
+            approx::assert_abs_diff_eq!(resampled[i], expected[i], epsilon = 0.02);
+        }
+    }
+
+    #[test]
+    fn test_segments_padded_with_timestamps() {
+        let clip = AudioClip { samples: vec![0.1; 25_000], sample_rate: 16_000, channels: 1 };
+        let config = AudioFeatureConfig { segment_seconds: 1.0, segment_hop_seconds: Some(0.5), ..Default::default() };
+
+        let segments = segment_clip(&clip, &config).unwrap();
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[2].samples.len(), 16_000);
+        approx::assert_abs_diff_eq!(segments[1].start_seconds, 0.5);
+        approx::assert_abs_diff_eq!(segments[2].end_seconds, 25_000.0 / 16_000.0);
+    }
+
+    #[test]
+    fn test_log_mel_peaks_at_tone_band() {
+        let config = AudioFeatureConfig { n_mels: 40, ..Default::default() };
+        let frames = log_mel_spectrogram(&tone(1000.0, 16_000, 0.1), &config);
+
+        // 1 kHz falls exactly on FFT bin 32 at n_fft = 512
+        let argmax = |values: &[f32]| {
+            values.iter().enumerate()
+                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+                .map(|(i, _)| i)
+                .unwrap()
+        };
+        let weights: Vec<f32> = mel_filterbank(&config).iter().map(|filter| filter[32]).collect();
+        let expected_band = argmax(&weights);
+        let peak = argmax(&frames[2]);
+        assert!(peak.abs_diff(expected_band) <= 1, "peak band {} expected near {}", peak, expected_band);
+    }
+
+    #[test]
+    fn test_mfcc_shape_follows_layout() {
+        let config = AudioFeatureConfig {
+            features: AudioFeatureType::Mfcc,
+            n_mfcc: 13,
+            layout: SpectrogramLayout::Ntf,
+            ..Default::default()
+        };
+
+        let (shape, data) = extract_features(&vec![0.0; 16_000], &config).unwrap();
+
+        // 1 + (16000 - 512) / 160 frames
+        assert_eq!(shape, vec![1, 97, 13]);
+        assert_eq!(data.len(), 97 * 13);
+    }
+}
@@ -3,14 +3,44 @@ use std::fmt;
 use serde::{Deserialize, Serialize};
 use async_trait::async_trait;
 use crate::{InferenceInput, InferenceResult, ModelConfig};
+use crate::backends::registry::{BackendCapabilities, BackendRegistration, BackendRegistry};
 
 /// Specific backend implementations
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)] // One instance per engine
 pub enum Backend {
     #[cfg(feature = "onnx-runtime")]
     OnnxRuntime(crate::backends::onnx::OnnxRuntimeBackend),
-    #[cfg(feature = "candle")]
+    #[cfg(any(feature = "candle", feature = "pure-rust"))]
     Candle(crate::backends::candle::CandleBackend),
+    /// Backend registered by the application
+    Custom(CustomBackend),
+}
+
+/// Instance of a backend created from a `BackendRegistration`
+pub struct CustomBackend {
+    name: String,
+    capabilities: BackendCapabilities,
+    inner: Box<dyn InferenceBackend>,
+}
+
+impl CustomBackend {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn capabilities(&self) -> &BackendCapabilities {
+        &self.capabilities
+    }
+}
+
+impl fmt::Debug for CustomBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomBackend")
+            .field("name", &self.name)
+            .field("capabilities", &self.capabilities)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Backend {
@@ -19,15 +49,9 @@ impl Backend {
         match self {
             #[cfg(feature = "onnx-runtime")]
             Backend::OnnxRuntime(backend) => backend.initialize(config).await,
-            #[cfg(feature = "candle")]
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
             Backend::Candle(backend) => backend.initialize(config).await,
-            #[cfg(not(any(feature = "onnx-runtime", feature = "candle")))]
-            _ => {
-                let _ = config;
-                Err(BackendError::ConfigurationError(
-                    "No backend features enabled. Enable 'onnx-runtime' or 'candle' feature.".to_string()
-                ))
-            }
+            Backend::Custom(backend) => backend.inner.initialize(config).await,
         }
     }
 
@@ -36,14 +60,16 @@ impl Backend {
         match self {
             #[cfg(feature = "onnx-runtime")]
             Backend::OnnxRuntime(backend) => backend.load_model(model_name, model_config).await,
-            #[cfg(feature = "candle")]
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
             Backend::Candle(backend) => backend.load_model(model_name, model_config).await,
-            #[cfg(not(any(feature = "onnx-runtime", feature = "candle")))]
-            _ => {
-                let _ = (model_name, model_config);
-                Err(BackendError::ConfigurationError(
-                    "No backend features enabled. Enable 'onnx-runtime' or 'candle' feature.".to_string()
-                ))
+            Backend::Custom(backend) => {
+                if !backend.capabilities.supports_model(&model_config.model_path) {
+                    return Err(BackendError::ModelLoadFailed(format!(
+                        "Backend '{}' does not load {} (supported formats: {})",
+                        backend.name, model_config.model_path, backend.capabilities.model_formats.join(", ")
+                    )));
+                }
+                backend.inner.load_model(model_name, model_config).await
             }
         }
     }
@@ -53,15 +79,9 @@ impl Backend {
         match self {
             #[cfg(feature = "onnx-runtime")]
             Backend::OnnxRuntime(backend) => backend.unload_model(model_name).await,
-            #[cfg(feature = "candle")]
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
             Backend::Candle(backend) => backend.unload_model(model_name).await,
-            #[cfg(not(any(feature = "onnx-runtime", feature = "candle")))]
-            _ => {
-                let _ = model_name;
-                Err(BackendError::ConfigurationError(
-                    "No backend features enabled. Enable 'onnx-runtime' or 'candle' feature.".to_string()
-                ))
-            }
+            Backend::Custom(backend) => backend.inner.unload_model(model_name).await,
         }
     }
 
@@ -70,15 +90,42 @@ impl Backend {
         match self {
             #[cfg(feature = "onnx-runtime")]
             Backend::OnnxRuntime(backend) => backend.infer(input, model_name).await,
-            #[cfg(feature = "candle")]
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
             Backend::Candle(backend) => backend.infer(input, model_name).await,
-            #[cfg(not(any(feature = "onnx-runtime", feature = "candle")))]
-            _ => {
-                let _ = (input, model_name);
-                Err(BackendError::ConfigurationError(
-                    "No backend features enabled. Enable 'onnx-runtime' or 'candle' feature.".to_string()
-                ))
-            }
+            Backend::Custom(backend) => backend.inner.infer(input, model_name).await,
+        }
+    }
+
+    /// Whether the model accepts stacked inputs along a dynamic batch dimension
+    pub fn supports_batching(&self, model_name: Option<&str>) -> bool {
+        match self {
+            #[cfg(feature = "onnx-runtime")]
+            Backend::OnnxRuntime(backend) => backend.supports_batching(model_name),
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
+            Backend::Candle(backend) => backend.supports_batching(model_name),
+            Backend::Custom(backend) => backend.inner.supports_batching(model_name),
+        }
+    }
+
+    /// Run inference on several inputs for one model, returning one result per input
+    pub async fn infer_batch(&self, inputs: Vec<InferenceInput>, model_name: Option<&str>) -> Vec<Result<InferenceResult, BackendError>> {
+        match self {
+            #[cfg(feature = "onnx-runtime")]
+            Backend::OnnxRuntime(backend) => backend.infer_batch(inputs, model_name).await,
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
+            Backend::Candle(backend) => backend.infer_batch(inputs, model_name).await,
+            Backend::Custom(backend) => backend.inner.infer_batch(inputs, model_name).await,
+        }
+    }
+
+    /// Estimated memory held by a loaded model, in MB
+    pub fn model_memory_mb(&self, model_name: &str) -> Option<f64> {
+        match self {
+            #[cfg(feature = "onnx-runtime")]
+            Backend::OnnxRuntime(backend) => backend.model_memory_mb(model_name),
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
+            Backend::Candle(backend) => backend.model_memory_mb(model_name),
+            Backend::Custom(backend) => backend.inner.model_memory_mb(model_name),
         }
     }
 
@@ -87,10 +134,9 @@ impl Backend {
         match self {
             #[cfg(feature = "onnx-runtime")]
             Backend::OnnxRuntime(backend) => backend.get_loaded_models().await,
-            #[cfg(feature = "candle")]
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
             Backend::Candle(backend) => backend.get_loaded_models().await,
-            #[cfg(not(any(feature = "onnx-runtime", feature = "candle")))]
-            _ => Vec::new(),
+            Backend::Custom(backend) => backend.inner.get_loaded_models().await,
         }
     }
 
@@ -99,19 +145,9 @@ impl Backend {
         match self {
             #[cfg(feature = "onnx-runtime")]
             Backend::OnnxRuntime(backend) => backend.get_status().await,
-            #[cfg(feature = "candle")]
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
             Backend::Candle(backend) => backend.get_status().await,
-            #[cfg(not(any(feature = "onnx-runtime", feature = "candle")))]
-            _ => BackendStatus {
-                backend_type: BackendType::Auto,
-                device_type: DeviceType::Cpu,
-                initialized: false,
-                loaded_models: Vec::new(),
-                memory_usage_mb: 0.0,
-                last_inference_time_ms: None,
-                total_inferences: 0,
-                errors: vec!["No backend features enabled".to_string()],
-            },
+            Backend::Custom(backend) => backend.inner.get_status().await,
         }
     }
 
@@ -120,10 +156,9 @@ impl Backend {
         match self {
             #[cfg(feature = "onnx-runtime")]
             Backend::OnnxRuntime(_) => BackendType::OnnxRuntime,
-            #[cfg(feature = "candle")]
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
             Backend::Candle(_) => BackendType::Candle,
-            #[cfg(not(any(feature = "onnx-runtime", feature = "candle")))]
-            _ => BackendType::Auto,
+            Backend::Custom(backend) => BackendType::Custom(backend.name.clone()),
         }
     }
 }
@@ -143,6 +178,27 @@ pub trait InferenceBackend: Send + Sync {
     /// Run inference on input data
     async fn infer(&self, input: InferenceInput, model_name: Option<&str>) -> Result<InferenceResult, BackendError>;
 
+    /// Whether the model accepts stacked inputs along a dynamic batch dimension
+    fn supports_batching(&self, _model_name: Option<&str>) -> bool {
+        false
+    }
+
+    /// Run inference on several inputs for one model, returning one result per input
+    ///
+    /// The default runs each input on its own.
+    async fn infer_batch(&self, inputs: Vec<InferenceInput>, model_name: Option<&str>) -> Vec<Result<InferenceResult, BackendError>> {
+        let mut results = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            results.push(self.infer(input, model_name).await);
+        }
+        results
+    }
+
+    /// Estimated memory held by a loaded model, in MB
+    fn model_memory_mb(&self, _model_name: &str) -> Option<f64> {
+        None
+    }
+
     /// Get list of loaded models
     async fn get_loaded_models(&self) -> Vec<String>;
 
@@ -203,6 +259,8 @@ pub enum BackendType {
     OnnxRuntime,
     Candle,
     Auto, // Automatically choose best available
+    /// Backend registered under this name with `BackendRegistry`
+    Custom(String),
 }
 
 impl fmt::Display for BackendType {
@@ -211,6 +269,7 @@ impl fmt::Display for BackendType {
             BackendType::OnnxRuntime => write!(f, "ONNX Runtime"),
             BackendType::Candle => write!(f, "Candle"),
             BackendType::Auto => write!(f, "Auto"),
+            BackendType::Custom(name) => write!(f, "{}", name),
         }
     }
 }
@@ -225,7 +284,7 @@ pub enum OptimizationLevel {
 }
 
 /// Device types for inference
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DeviceType {
     Cpu,
     Cuda(usize), // GPU index
@@ -246,12 +305,26 @@ pub struct BackendStatus {
     pub errors: Vec<String>,
 }
 
+/// Estimated memory of a model loaded from `model_path`, in MB
+///
+/// The artifact size is a lower bound: arenas, pre-packed weights and graph
+/// optimizations add to what a session holds. Models declare their measured
+/// footprint with `model.performance.memory_usage_mb` to replace it.
+#[cfg(any(feature = "onnx-runtime", feature = "candle", feature = "pure-rust"))]
+pub(crate) fn artifact_memory_mb(model_path: &str) -> f64 {
+    std::fs::metadata(model_path)
+        .map(|metadata| metadata.len() as f64 / (1024.0 * 1024.0))
+        .unwrap_or(0.0)
+}
+
 /// Backend-specific errors
 #[derive(Debug)]
 pub enum BackendError {
     InitializationFailed(String),
     ModelLoadFailed(String),
     ModelUnloadFailed(String),
+    /// Model artifact does not match its expected digest
+    IntegrityCheckFailed(String),
     InferenceFailed(String),
     InvalidInput(String),
     DeviceError(String),
@@ -270,6 +343,7 @@ impl fmt::Display for BackendError {
             BackendError::InitializationFailed(msg) => write!(f, "Backend initialization failed: {}", msg),
             BackendError::ModelLoadFailed(msg) => write!(f, "Model load failed: {}", msg),
             BackendError::ModelUnloadFailed(msg) => write!(f, "Model unload failed: {}", msg),
+            BackendError::IntegrityCheckFailed(msg) => write!(f, "Model integrity check failed: {}", msg),
             BackendError::InferenceFailed(msg) => write!(f, "Inference failed: {}", msg),
             BackendError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
             BackendError::DeviceError(msg) => write!(f, "Device error: {}", msg),
@@ -286,7 +360,7 @@ impl fmt::Display for BackendError {
 
 impl Error for BackendError {}
 
-#[cfg(feature = "candle")]
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
 impl From<candle_core::Error> for BackendError {
     fn from(error: candle_core::Error) -> Self {
         BackendError::CandleError(error.to_string())
@@ -300,54 +374,51 @@ impl BackendFactory {
     /// Create a backend based on configuration and availability
     /// Create a new backend instance based on configuration
     pub async fn create_backend(config: &BackendConfig) -> Result<Backend, BackendError> {
-        match config.backend_type {
-            BackendType::OnnxRuntime => {
-                #[cfg(feature = "onnx-runtime")]
-                {
-                    let mut backend = crate::backends::onnx::OnnxRuntimeBackend::new();
-                    backend.initialize(config).await?;
-                    Ok(Backend::OnnxRuntime(backend))
-                }
-                #[cfg(not(feature = "onnx-runtime"))]
-                {
-                    Err(BackendError::BackendUnavailable("ONNX Runtime backend not compiled".to_string()))
-                }
-            }
-            BackendType::Candle => {
-                #[cfg(feature = "candle")]
-                {
-                    let mut backend = crate::backends::candle::CandleBackend::new();
-                    backend.initialize(config).await?;
-                    Ok(Backend::Candle(backend))
-                }
-                #[cfg(not(feature = "candle"))]
-                {
-                    Err(BackendError::BackendUnavailable("Candle backend not compiled".to_string()))
-                }
+        match &config.backend_type {
+            BackendType::OnnxRuntime => Self::create_onnx_runtime(config).await,
+            BackendType::Candle => Self::create_candle(config).await,
+            BackendType::Custom(name) => {
+                let registration = BackendRegistry::get(name).ok_or_else(|| {
+                    BackendError::BackendUnavailable(format!("No backend registered as '{}'", name))
+                })?;
+                Self::create_registered(&registration, config).await
             }
             BackendType::Auto => {
-                // Try ONNX Runtime first, fallback to Candle
-                #[cfg(feature = "onnx-runtime")]
-                {
-                    let mut onnx_config = config.clone();
-                    onnx_config.backend_type = BackendType::OnnxRuntime;
-
-                    let mut backend = crate::backends::onnx::OnnxRuntimeBackend::new();
-                    if backend.initialize(&onnx_config).await.is_ok() {
-                        tracing::info!("Using ONNX Runtime backend (preferred)");
-                        return Ok(Backend::OnnxRuntime(backend));
-                    }
-                }
-
-                #[cfg(feature = "candle")]
-                {
-                    let mut candle_config = config.clone();
-                    candle_config.backend_type = BackendType::Candle;
-
-                    let mut backend = crate::backends::candle::CandleBackend::new();
-                    if backend.initialize(&candle_config).await.is_ok() {
-                        tracing::info!("Using Candle backend (fallback)");
-                        return Ok(Backend::Candle(backend));
+                // Built-in and registered backends that support the device, best first
+                let mut candidates: Vec<(i32, BackendType)> = Self::available_backends()
+                    .into_iter()
+                    .map(|backend_type| match backend_type {
+                        BackendType::OnnxRuntime => (BackendRegistry::ONNX_RUNTIME_PRIORITY, backend_type),
+                        _ => (BackendRegistry::CANDLE_PRIORITY, backend_type),
+                    })
+                    .collect();
+                candidates.extend(
+                    BackendRegistry::registered()
+                        .into_iter()
+                        .filter(|registration| registration.capabilities.supports_device(&config.device_type))
+                        .map(|registration| (registration.priority, BackendType::Custom(registration.name))),
+                );
+                candidates.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+
+                for (_, backend_type) in candidates {
+                    let mut candidate_config = config.clone();
+                    candidate_config.backend_type = backend_type.clone();
+
+                    let created = match &backend_type {
+                        BackendType::OnnxRuntime => Self::create_onnx_runtime(&candidate_config).await,
+                        BackendType::Candle => Self::create_candle(&candidate_config).await,
+                        BackendType::Custom(name) => match BackendRegistry::get(name) {
+                            Some(registration) => Self::create_registered(&registration, &candidate_config).await,
+                            None => continue,
+                        },
+                        BackendType::Auto => continue,
+                    };
+                    match created {
+                        Ok(backend) => {
+                            tracing::info!("Using {} backend (auto-selected)", backend_type);
+                            return Ok(backend);
+                        }
+                        Err(e) => tracing::debug!("{} backend unavailable: {}", backend_type, e),
                     }
                 }
 
@@ -356,18 +427,57 @@ impl BackendFactory {
         }
     }
 
-    /// Check which backends are available at compile time
-    pub fn available_backends() -> Vec<BackendType> {
-        #[allow(unused_mut)]
-        let mut backends = Vec::new();
-
+    async fn create_onnx_runtime(config: &BackendConfig) -> Result<Backend, BackendError> {
         #[cfg(feature = "onnx-runtime")]
-        backends.push(BackendType::OnnxRuntime);
+        {
+            let mut backend = crate::backends::onnx::OnnxRuntimeBackend::new();
+            backend.initialize(config).await?;
+            Ok(Backend::OnnxRuntime(backend))
+        }
+        #[cfg(not(feature = "onnx-runtime"))]
+        {
+            let _ = config;
+            Err(BackendError::BackendUnavailable("ONNX Runtime backend not compiled".to_string()))
+        }
+    }
 
-        #[cfg(feature = "candle")]
-        backends.push(BackendType::Candle);
+    async fn create_candle(config: &BackendConfig) -> Result<Backend, BackendError> {
+        #[cfg(any(feature = "candle", feature = "pure-rust"))]
+        {
+            let mut backend = crate::backends::candle::CandleBackend::new();
+            backend.initialize(config).await?;
+            Ok(Backend::Candle(backend))
+        }
+        #[cfg(not(any(feature = "candle", feature = "pure-rust")))]
+        {
+            let _ = config;
+            Err(BackendError::BackendUnavailable("Candle backend not compiled".to_string()))
+        }
+    }
 
-        backends
+    async fn create_registered(registration: &BackendRegistration, config: &BackendConfig) -> Result<Backend, BackendError> {
+        if !registration.capabilities.supports_device(&config.device_type) {
+            return Err(BackendError::DeviceError(format!(
+                "Backend '{}' does not support device {:?}", registration.name, config.device_type
+            )));
+        }
+        let mut inner = (registration.constructor)();
+        inner.initialize(config).await?;
+        Ok(Backend::Custom(CustomBackend {
+            name: registration.name.clone(),
+            capabilities: registration.capabilities.clone(),
+            inner,
+        }))
+    }
+
+    /// Check which backends are available at compile time
+    pub fn available_backends() -> Vec<BackendType> {
+        Vec::from([
+            #[cfg(feature = "onnx-runtime")]
+            BackendType::OnnxRuntime,
+            #[cfg(any(feature = "candle", feature = "pure-rust"))]
+            BackendType::Candle,
+        ])
     }
 }
 
@@ -422,19 +532,126 @@ mod tests {
     fn test_backend_factory_available_backends() {
         let backends = BackendFactory::available_backends();
 
-        #[cfg(any(feature = "onnx-runtime", feature = "candle"))]
+        #[cfg(any(feature = "onnx-runtime", feature = "candle", feature = "pure-rust"))]
         assert!(!backends.is_empty(), "No backends compiled in");
 
-        #[cfg(not(any(feature = "onnx-runtime", feature = "candle")))]
+        #[cfg(not(any(feature = "onnx-runtime", feature = "candle", feature = "pure-rust")))]
         assert!(backends.is_empty(), "Expected no backends without features");
 
         #[cfg(feature = "onnx-runtime")]
         assert!(backends.contains(&BackendType::OnnxRuntime));
 
-        #[cfg(feature = "candle")]
+        #[cfg(any(feature = "candle", feature = "pure-rust"))]
         assert!(backends.contains(&BackendType::Candle));
     }
 
+    /// Backend that answers every request with an empty result
+    struct StubBackend {
+        models: Vec<String>,
+    }
+
+    #[async_trait]
+    impl InferenceBackend for StubBackend {
+        async fn initialize(&mut self, _config: &BackendConfig) -> Result<(), BackendError> {
+            Ok(())
+        }
+
+        async fn load_model(&mut self, model_name: &str, _model_config: &ModelConfig) -> Result<(), BackendError> {
+            self.models.push(model_name.to_string());
+            Ok(())
+        }
+
+        async fn unload_model(&mut self, model_name: &str) -> Result<(), BackendError> {
+            self.models.retain(|model| model != model_name);
+            Ok(())
+        }
+
+        async fn infer(&self, _input: InferenceInput, model_name: Option<&str>) -> Result<InferenceResult, BackendError> {
+            Ok(InferenceResult { model_name: model_name.unwrap_or_default().to_string(), ..Default::default() })
+        }
+
+        async fn get_loaded_models(&self) -> Vec<String> {
+            self.models.clone()
+        }
+
+        async fn get_status(&self) -> BackendStatus {
+            BackendStatus {
+                backend_type: self.backend_type(),
+                device_type: DeviceType::Cpu,
+                initialized: true,
+                loaded_models: self.models.clone(),
+                memory_usage_mb: 0.0,
+                last_inference_time_ms: None,
+                total_inferences: 0,
+                errors: Vec::new(),
+            }
+        }
+
+        fn backend_type(&self) -> BackendType {
+            BackendType::Custom("stub".to_string())
+        }
+    }
+
+    fn stub_registration(name: &str) -> BackendRegistration {
+        BackendRegistration::new(name, || Box::new(StubBackend { models: Vec::new() }) as Box<dyn InferenceBackend>)
+    }
+
+    fn model_config(path: &str) -> ModelConfig {
+        ModelConfig {
+            model_path: path.to_string(),
+            model_type: "object_detection".to_string(),
+            confidence_threshold: None,
+            preprocessing: None,
+            postprocessing: None,
+            integrity: None,
+            memory_mb: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_backend_selected_by_name() {
+        BackendRegistry::register(stub_registration("stub-by-name").with_capabilities(BackendCapabilities {
+            devices: Vec::new(),
+            model_formats: vec!["engine".to_string()],
+        })).unwrap();
+
+        let config = BackendConfig {
+            backend_type: BackendType::Custom("stub-by-name".to_string()),
+            ..BackendConfig::default()
+        };
+        let mut backend = BackendFactory::create_backend(&config).await.unwrap();
+        assert_eq!(backend.backend_type(), BackendType::Custom("stub-by-name".to_string()));
+        assert_eq!(backend.backend_type().to_string(), "stub-by-name");
+
+        // Formats the registration does not list are rejected before reaching the backend
+        assert!(backend.load_model("detector", &model_config("detector.onnx")).await.is_err());
+        backend.load_model("detector", &model_config("detector.engine")).await.unwrap();
+        assert_eq!(backend.get_loaded_models().await, vec!["detector".to_string()]);
+
+        assert!(BackendRegistry::unregister("stub-by-name"));
+        let missing = BackendFactory::create_backend(&config).await.unwrap_err();
+        assert!(missing.to_string().contains("No backend registered as 'stub-by-name'"));
+    }
+
+    #[tokio::test]
+    async fn test_auto_selection_prefers_higher_priority_registration() {
+        // Restricted to Metal so auto-selection for other devices is unaffected
+        BackendRegistry::register(stub_registration("stub-metal").with_priority(1000).with_capabilities(BackendCapabilities {
+            devices: vec![DeviceType::Metal],
+            model_formats: Vec::new(),
+        })).unwrap();
+
+        let metal = BackendConfig { device_type: DeviceType::Metal, ..BackendConfig::default() };
+        let backend = BackendFactory::create_backend(&metal).await.unwrap();
+        assert_eq!(backend.backend_type(), BackendType::Custom("stub-metal".to_string()));
+
+        let cuda = BackendConfig { device_type: DeviceType::Cuda(0), ..BackendConfig::default() };
+        if let Ok(backend) = BackendFactory::create_backend(&cuda).await {
+            assert_ne!(backend.backend_type(), BackendType::Custom("stub-metal".to_string()));
+        }
+        BackendRegistry::unregister("stub-metal");
+    }
+
     #[test]
     fn test_backend_config_default() {
         let config = BackendConfig::default();
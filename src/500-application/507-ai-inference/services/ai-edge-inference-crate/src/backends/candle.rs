@@ -1,79 +1,257 @@
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 use async_trait::async_trait;
-use tracing::{info, debug};
+use tracing::{info, debug, error};
 
 use crate::backend::{
-    InferenceBackend, BackendConfig, BackendError, BackendStatus, BackendType, 
+    InferenceBackend, BackendConfig, BackendError, BackendStatus, BackendType,
     DeviceType, CandleDType
 };
 use crate::{InferenceInput, InferenceResult, ModelConfig};
 
-#[cfg(feature = "candle")]
-use candle_core::{Device, Tensor, DType, Shape};
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+use candle_core::{Device, Tensor, DType, Module};
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+use candle_nn::{Func, VarBuilder};
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+use candle_transformers::models::{mobilenetv4, resnet};
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+use super::outputs::{
+    image_batch_shape, image_input_pipeline, parse_input_shape, parse_normalization, require_image_input,
+    OutputDecoder, OutputSettings, RawOutput,
+};
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+use crate::image_input::ImageInputPipeline;
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+mod yolov8;
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+use yolov8::{Multiples, YoloV8};
 
 /// Hugging Face Candle backend implementation
-#[cfg(feature = "candle")]
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
 #[derive(Debug)]
 pub struct CandleBackend {
     device: Device,
+    dtype: DType,
     config: Option<BackendConfig>,
-    loaded_models: HashMap<String, CandleModel>,
+    /// Shared so a forward pass can run on the blocking pool
+    loaded_models: HashMap<String, Arc<CandleModel>>,
     stats: BackendStats,
+    /// Models refused because their artifact failed the digest check
+    integrity_failures: HashMap<String, String>,
 }
 
-#[cfg(feature = "candle")]
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
 #[derive(Debug)]
-#[expect(dead_code)]
 struct CandleModel {
     name: String,
-    model_type: String,
-    tensors: HashMap<String, Tensor>,
-    input_shape: Vec<usize>,
-    output_shape: Vec<usize>,
-    preprocessing: Option<PreprocessingConfig>,
+    model_path: String,
+    network: Network,
+    device: Device,
+    dtype: DType,
+    /// NCHW input shape the image is resized to
+    input_shape: Vec<i64>,
+    /// Fused image preprocessing; `None` when the input shape cannot hold an image
+    image_input: Option<ImageInputPipeline>,
+    settings: OutputSettings,
+    /// Estimated weight memory in MB
+    memory_mb: f64,
 }
 
-#[cfg(feature = "candle")]
-#[derive(Debug)]
-#[expect(dead_code)]
-struct PreprocessingConfig {
-    normalize_mean: Vec<f32>,
-    normalize_std: Vec<f32>,
-    resize_width: Option<u32>,
-    resize_height: Option<u32>,
+/// Forward pass implementation for a loaded model
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+enum Network {
+    /// ONNX graph evaluated by candle-onnx
+    #[cfg(feature = "candle-onnx")]
+    Onnx {
+        model: candle_onnx::onnx::ModelProto,
+        input_name: String,
+        output_names: Vec<String>,
+    },
+    /// Image classifier from candle-transformers (ResNet, MobileNetV4)
+    Classifier(Func<'static>),
+    /// YOLOv8 detector producing `[1, 4 + num_classes, num_anchors]`
+    YoloV8(Box<YoloV8>),
+}
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+impl std::fmt::Debug for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "candle-onnx")]
+            Network::Onnx { input_name, output_names, .. } => f.debug_struct("Onnx")
+                .field("input_name", input_name)
+                .field("output_names", output_names)
+                .finish(),
+            Network::Classifier(_) => f.write_str("Classifier"),
+            Network::YoloV8(_) => f.write_str("YoloV8"),
+        }
+    }
 }
 
-#[cfg(feature = "candle")]
+/// Network architecture of a safetensors checkpoint
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Architecture {
+    /// ResNet with the given depth (18, 34, 50, 101, 152)
+    ResNet(usize),
+    MobileNetV4(MobileNetV4Variant),
+    YoloV8(Multiples),
+}
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MobileNetV4Variant {
+    Small,
+    Medium,
+    HybridMedium,
+    Large,
+    HybridLarge,
+}
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+impl MobileNetV4Variant {
+    fn config(&self) -> mobilenetv4::Config {
+        match self {
+            Self::Small => mobilenetv4::Config::small(),
+            Self::Medium => mobilenetv4::Config::medium(),
+            Self::HybridMedium => mobilenetv4::Config::hybrid_medium(),
+            Self::Large => mobilenetv4::Config::large(),
+            Self::HybridLarge => mobilenetv4::Config::hybrid_large(),
+        }
+    }
+}
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+impl Architecture {
+    /// Architecture named by `model_type` (e.g. "resnet50", "yolov8n", "mobilenetv4_small")
+    fn from_model_type(model_type: &str) -> Option<Self> {
+        let model_type = model_type.to_lowercase().replace('-', "_");
+
+        if let Some(depth) = model_type.strip_prefix("resnet") {
+            return match depth {
+                "18" | "34" | "50" | "101" | "152" => depth.parse().ok().map(Self::ResNet),
+                _ => None,
+            };
+        }
+        if let Some(variant) = model_type.strip_prefix("mobilenetv4_") {
+            let variant = match variant {
+                "small" => MobileNetV4Variant::Small,
+                "medium" => MobileNetV4Variant::Medium,
+                "hybrid_medium" => MobileNetV4Variant::HybridMedium,
+                "large" => MobileNetV4Variant::Large,
+                "hybrid_large" => MobileNetV4Variant::HybridLarge,
+                _ => return None,
+            };
+            return Some(Self::MobileNetV4(variant));
+        }
+        if let Some(size) = model_type.strip_prefix("yolov8") {
+            return Multiples::from_size(size).map(Self::YoloV8);
+        }
+        None
+    }
+
+    /// Infer the architecture from checkpoint tensor names and shapes
+    fn detect(tensors: &HashMap<String, Tensor>) -> Option<Self> {
+        if let Some(stem) = tensors.get("net.b1.0.conv.weight") {
+            return Multiples::from_stem_channels(stem.dims().first().copied()?).map(Self::YoloV8);
+        }
+
+        if tensors.contains_key("conv1.weight") && tensors.contains_key("layer1.0.conv1.weight") {
+            let bottleneck = tensors.contains_key("layer1.0.conv3.weight");
+            let layer3_blocks = (0..)
+                .take_while(|i| tensors.contains_key(&format!("layer3.{i}.conv1.weight")))
+                .count();
+            let depth = match (bottleneck, layer3_blocks) {
+                (false, 2) => 18,
+                (false, 6) => 34,
+                (true, 6) => 50,
+                (true, 23) => 101,
+                (true, 36) => 152,
+                _ => return None,
+            };
+            return Some(Self::ResNet(depth));
+        }
+
+        // MobileNetV4 variants share tensor names, so the variant must come from model_type
+        None
+    }
+
+    /// Number of output classes stored in the checkpoint
+    fn num_classes(&self, tensors: &HashMap<String, Tensor>) -> Option<usize> {
+        let name = match self {
+            Self::ResNet(_) => "fc.weight",
+            Self::MobileNetV4(_) => "classifier.weight",
+            Self::YoloV8(_) => "head.cv3.0.2.weight",
+        };
+        tensors.get(name).and_then(|t| t.dims().first().copied())
+    }
+
+    fn default_input_size(&self) -> i64 {
+        match self {
+            Self::YoloV8(_) => 640,
+            Self::ResNet(_) | Self::MobileNetV4(_) => 224,
+        }
+    }
+
+    fn default_postprocess_type(&self) -> &'static str {
+        match self {
+            Self::YoloV8(_) => "yolov8",
+            Self::ResNet(_) | Self::MobileNetV4(_) => "classification",
+        }
+    }
+
+    fn build(&self, num_classes: usize, vb: VarBuilder<'static>) -> candle_core::Result<Network> {
+        Ok(match self {
+            Self::ResNet(18) => Network::Classifier(resnet::resnet18(num_classes, vb)?),
+            Self::ResNet(34) => Network::Classifier(resnet::resnet34(num_classes, vb)?),
+            Self::ResNet(50) => Network::Classifier(resnet::resnet50(num_classes, vb)?),
+            Self::ResNet(101) => Network::Classifier(resnet::resnet101(num_classes, vb)?),
+            Self::ResNet(_) => Network::Classifier(resnet::resnet152(num_classes, vb)?),
+            Self::MobileNetV4(variant) => {
+                Network::Classifier(mobilenetv4::mobilenetv4(&variant.config(), num_classes, vb)?)
+            }
+            Self::YoloV8(multiples) => Network::YoloV8(Box::new(YoloV8::load(vb, *multiples, num_classes)?)),
+        })
+    }
+}
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
 #[derive(Debug)]
-#[expect(dead_code)]
 struct BackendStats {
     total_inferences: u64,
-    successful_inferences: u64,
-    failed_inferences: u64,
-    total_time_ms: f64,
     memory_usage_mb: f64,
     last_inference_time_ms: Option<f64>,
 }
 
-#[cfg(feature = "candle")]
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+impl Default for CandleBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
 impl CandleBackend {
     pub fn new() -> Self {
         Self {
             device: Device::Cpu, // Will be set properly in initialize
+            dtype: DType::F32,
             config: None,
             loaded_models: HashMap::new(),
             stats: BackendStats {
                 total_inferences: 0,
-                successful_inferences: 0,
-                failed_inferences: 0,
-                total_time_ms: 0.0,
                 memory_usage_mb: 0.0,
                 last_inference_time_ms: None,
             },
+            integrity_failures: HashMap::new(),
         }
     }
-    
+
     fn setup_device(&mut self, device_type: &DeviceType) -> Result<(), BackendError> {
         self.device = match device_type {
             DeviceType::Cpu => Device::Cpu,
@@ -101,257 +279,270 @@ impl CandleBackend {
                 }
             }
         };
-        
+
         Ok(())
     }
-    
-    #[expect(dead_code)]
+
+    /// Weight dtype for the configured precision; integer dtypes cannot run conv nets
     fn candle_dtype_from_config(dtype: &CandleDType) -> DType {
         match dtype {
             CandleDType::F16 => DType::F16,
             CandleDType::F32 => DType::F32,
             CandleDType::F64 => DType::F64,
-            CandleDType::U8 => DType::U8,
-            CandleDType::I64 => DType::I64,
+            CandleDType::U8 | CandleDType::I64 => DType::F32,
         }
     }
-    
-    async fn load_onnx_model(&mut self, _model_path: &str, model_name: &str) -> Result<(), BackendError> {
-        // For now, create a simple placeholder model since candle-onnx integration needs more work
-        // This is a simplified implementation that can be expanded later
-        let candle_model = CandleModel {
-            name: model_name.to_string(),
-            model_type: "onnx".to_string(),
-            tensors: HashMap::new(),
-            input_shape: vec![1, 3, 224, 224], // Default image input shape
-            output_shape: vec![1, 1000], // Default classification output
-            preprocessing: Some(PreprocessingConfig {
-                normalize_mean: vec![0.485, 0.456, 0.406],
-                normalize_std: vec![0.229, 0.224, 0.225],
-                resize_width: Some(224),
-                resize_height: Some(224),
-            }),
-        };
-        
-        self.loaded_models.insert(model_name.to_string(), candle_model);
-        info!("Successfully loaded ONNX model '{}' with Candle backend", model_name);
-        
-        Ok(())
+
+    #[cfg(feature = "candle-onnx")]
+    fn load_onnx_model(&self, model_path: &str, bytes: &[u8]) -> Result<Network, BackendError> {
+        use prost::Message;
+
+        let model = candle_onnx::onnx::ModelProto::decode(bytes).map_err(|e| {
+            BackendError::ModelLoadFailed(format!("Failed to read ONNX model '{}': {}", model_path, e))
+        })?;
+        let graph = model.graph.as_ref().ok_or_else(|| {
+            BackendError::ModelLoadFailed(format!("ONNX model '{}' has no graph", model_path))
+        })?;
+
+        // Initializers may also be listed as graph inputs; the image input is the first that is not
+        let input_name = graph.input.iter()
+            .map(|input| input.name.clone())
+            .find(|name| !graph.initializer.iter().any(|init| &init.name == name))
+            .ok_or_else(|| BackendError::ModelLoadFailed(format!("ONNX model '{}' has no inputs", model_path)))?;
+        let output_names = graph.output.iter().map(|output| output.name.clone()).collect();
+
+        Ok(Network::Onnx { model, input_name, output_names })
     }
-    
-    async fn load_safetensors_model(&mut self, model_path: &str, model_name: &str) -> Result<(), BackendError> {
-        let tensors = candle_core::safetensors::load(model_path, &self.device).map_err(|e| {
+
+    #[cfg(not(feature = "candle-onnx"))]
+    fn load_onnx_model(&self, model_path: &str, _bytes: &[u8]) -> Result<Network, BackendError> {
+        Err(BackendError::ModelLoadFailed(format!(
+            "Cannot load '{}': ONNX graphs require the 'candle' feature (candle-onnx); \
+             use safetensors weights with 'pure-rust'",
+            model_path,
+        )))
+    }
+
+    fn load_safetensors_model(&self, model_config: &ModelConfig, bytes: &[u8]) -> Result<(Network, Architecture), BackendError> {
+        let model_path = &model_config.model_path;
+        let tensors = candle_core::safetensors::load_buffer(bytes, &self.device).map_err(|e| {
             BackendError::CandleError(format!("Failed to load safetensors model: {}", e))
         })?;
-        
-        let candle_model = CandleModel {
-            name: model_name.to_string(),
-            model_type: "safetensors".to_string(),
-            tensors,
-            input_shape: vec![1, 3, 224, 224], // Should be configured per model
-            output_shape: vec![1, 1000], // Should be configured per model
-            preprocessing: Some(PreprocessingConfig {
-                normalize_mean: vec![0.485, 0.456, 0.406],
-                normalize_std: vec![0.229, 0.224, 0.225],
-                resize_width: Some(224),
-                resize_height: Some(224),
-            }),
-        };
-        
-        self.loaded_models.insert(model_name.to_string(), candle_model);
-        info!("Successfully loaded safetensors model '{}' with Candle backend", model_name);
-        
-        Ok(())
+
+        let architecture = Architecture::from_model_type(&model_config.model_type)
+            .or_else(|| Architecture::detect(&tensors))
+            .ok_or_else(|| BackendError::ModelLoadFailed(format!(
+                "Cannot determine the architecture of '{}'; set model_type to one of \
+                 resnet18/34/50/101/152, mobilenetv4_<small|medium|hybrid_medium|large|hybrid_large> \
+                 or yolov8<n|s|m|l|x>",
+                model_path,
+            )))?;
+        let num_classes = architecture.num_classes(&tensors).ok_or_else(|| {
+            BackendError::ModelLoadFailed(format!(
+                "Checkpoint '{}' is missing the classifier weights for {:?}",
+                model_path, architecture,
+            ))
+        })?;
+
+        debug!("Building {:?} with {} classes from '{}'", architecture, num_classes, model_path);
+        let vb = VarBuilder::from_tensors(tensors, self.dtype, &self.device);
+        let network = architecture.build(num_classes, vb).map_err(|e| {
+            BackendError::ModelLoadFailed(format!("Failed to build {:?} from '{}': {}", architecture, model_path, e))
+        })?;
+
+        Ok((network, architecture))
     }
-    
-    fn preprocess_image(&self, image: &image::DynamicImage, preprocessing: &PreprocessingConfig) -> Result<Tensor, BackendError> {
-        let mut img = image.clone();
-        
-        // Resize if needed
-        if let (Some(width), Some(height)) = (preprocessing.resize_width, preprocessing.resize_height) {
-            img = img.resize_exact(width, height, image::imageops::FilterType::Lanczos3);
-        }
-        
-        let img = img.to_rgb8();
-        let (width, height) = img.dimensions();
-        
-        // Convert to tensor
-        let img_data: Vec<f32> = img
-            .pixels()
-            .flat_map(|pixel| {
-                [
-                    pixel[0] as f32 / 255.0,
-                    pixel[1] as f32 / 255.0,
-                    pixel[2] as f32 / 255.0,
-                ]
-            })
-            .collect();
-        
-        let tensor = Tensor::from_vec(img_data, Shape::from_dims(&[1, 3, height as usize, width as usize]), &self.device)
-            .map_err(|e| BackendError::CandleError(format!("Failed to create tensor: {}", e)))?;
-        
-        // Normalize
-        let mean = Tensor::from_slice(&preprocessing.normalize_mean, (3,), &self.device)
-            .map_err(|e| BackendError::CandleError(format!("Failed to create mean tensor: {}", e)))?
-            .unsqueeze(0)?.unsqueeze(2)?.unsqueeze(3)?;
-        
-        let std = Tensor::from_slice(&preprocessing.normalize_std, (3,), &self.device)
-            .map_err(|e| BackendError::CandleError(format!("Failed to create std tensor: {}", e)))?
-            .unsqueeze(0)?.unsqueeze(2)?.unsqueeze(3)?;
-        
-        let normalized = tensor.sub(&mean)?.div(&std)?;
-        
-        Ok(normalized)
-    }
-    
-    fn preprocess_sensor_data(&self, values: &[f32], _timestamps: &[i64]) -> Result<Tensor, BackendError> {
-        // Simple preprocessing for sensor data - normalize and reshape
-        let data: Vec<f32> = values.iter().map(|&x| (x - 0.0) / 1.0).collect(); // Basic normalization
-        
-        let tensor = Tensor::from_vec(data, Shape::from_dims(&[1, values.len()]), &self.device)
-            .map_err(|e| BackendError::CandleError(format!("Failed to create sensor tensor: {}", e)))?;
-        
-        Ok(tensor)
-    }
-    
-    async fn run_model_inference(&self, model: &CandleModel, _input_tensor: &Tensor) -> Result<Tensor, BackendError> {
-        // This is a simplified inference - in practice, you'd need to implement
-        // the actual model forward pass based on the model architecture
-        
-        // For ONNX models loaded with candle-onnx, we would use the graph execution
-        // For now, we'll create a simple placeholder that returns a classification result
-        
-        match model.model_type.as_str() {
-            "onnx" => {
-                // In a real implementation, you would execute the ONNX graph here
-                // For now, we'll return a dummy classification result
-                let _dummy_output = Tensor::zeros(Shape::from_dims(&model.output_shape), DType::F32, &self.device)
-                    .map_err(|e| BackendError::CandleError(format!("Failed to create output tensor: {}", e)))?;
-                
-                // Add some randomness to simulate real predictions
-                let random_values: Vec<f32> = (0..model.output_shape.iter().product::<usize>())
-                    .map(|i| (i as f32 * 0.01) % 1.0)
-                    .collect();
-                
-                Tensor::from_vec(random_values, Shape::from_dims(&model.output_shape), &self.device)
-                    .map_err(|e| BackendError::CandleError(format!("Failed to create result tensor: {}", e)))
+}
+
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
+impl CandleModel {
+    /// Preprocess, run and decode one input; blocks for the whole forward pass
+    fn infer(&self, input: InferenceInput, start_time: std::time::Instant) -> Result<InferenceResult, BackendError> {
+        let (input_tensor, raw_output) = match input {
+            InferenceInput::Image { data, metadata: _ } => {
+                // Same resize and normalization as the ONNX Runtime backend; Candle
+                // copies the pooled buffer into its own storage
+                let image_input = require_image_input(self.image_input.as_ref(), &self.input_shape)?;
+                let (input_data, _) = image_input.prepare(&data)
+                    .map_err(|e| BackendError::InvalidInput(format!("Failed to preprocess image: {}", e)))?;
+                let dims: Vec<usize> = image_batch_shape(image_input, 1).iter().map(|&d| d as usize).collect();
+                let input_tensor = Tensor::from_slice(&input_data, dims, &self.device)
+                    .map_err(|e| BackendError::CandleError(format!("Failed to create input tensor: {}", e)))?;
+                (input_tensor, false)
             }
-            "safetensors" => {
-                // For safetensors models, you would typically build a neural network
-                // using the loaded weights and run inference
-                let output_size = model.output_shape.iter().product::<usize>();
-                let dummy_output: Vec<f32> = (0..output_size).map(|i| (i as f32 * 0.001) % 1.0).collect();
-                
-                Tensor::from_vec(dummy_output, Shape::from_dims(&model.output_shape), &self.device)
-                    .map_err(|e| BackendError::CandleError(format!("Failed to create result tensor: {}", e)))
+            InferenceInput::Tensor { tensors, raw_output } => {
+                let [tensor] = <[crate::tensors::Tensor; 1]>::try_from(tensors).map_err(|_| {
+                    BackendError::InvalidInput("Candle backend takes a single input tensor".to_string())
+                })?;
+                let input_tensor = Tensor::from_vec(tensor.data.to_f32(), tensor.shape, &self.device)
+                    .map_err(|e| BackendError::CandleError(format!("Failed to create input tensor: {}", e)))?;
+                (input_tensor, raw_output)
             }
-            _ => Err(BackendError::InferenceFailed(format!("Unsupported model type: {}", model.model_type)))
-        }
-    }
-    
-    fn tensor_to_inference_result(&self, output_tensor: &Tensor, input_type: &str) -> Result<InferenceResult, BackendError> {
-        let output_data = output_tensor.flatten_all()
-            .map_err(|e| BackendError::CandleError(format!("Failed to flatten tensor: {}", e)))?
-            .to_vec1::<f32>()
-            .map_err(|e| BackendError::CandleError(format!("Failed to convert tensor to vec: {}", e)))?;
-        
-        // Find the class with highest probability for classification
-        let (max_idx, max_prob) = output_data
-            .iter()
-            .enumerate()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
-            .unwrap_or((0, &0.0));
-        
-        let model_type = if input_type == "image" {
-            "image_classification".to_string()
+            InferenceInput::TimeSeries { .. } => {
+                return Err(BackendError::InferenceFailed(
+                    "Candle backend does not support time series input".to_string(),
+                ));
+            }
+            InferenceInput::Audio { .. } => {
+                return Err(BackendError::InferenceFailed(
+                    "Candle backend does not support audio input".to_string(),
+                ));
+            }
+        };
+
+        let outputs = self.forward(input_tensor)?;
+        let output_shapes: Vec<Vec<usize>> = outputs.iter().map(|o| o.shape.clone()).collect();
+        let (predictions, tensors) = if raw_output || self.settings.raw_output() {
+            (Vec::new(), Some(outputs.into_iter().map(Into::into).collect()))
         } else {
-            "sensor_anomaly_detection".to_string()
+            let predictions = OutputDecoder {
+                settings: &self.settings,
+                model_name: &self.name,
+                backend: "candle",
+                input_size: (self.input_shape[3] as f32, self.input_shape[2] as f32),
+            }.decode(&outputs)?;
+            (predictions, None)
         };
-        
-        let predictions = vec![
-            crate::Prediction {
-                class: format!("class_{}", max_idx),
-                confidence: *max_prob,
-                bbox: None,
-                metadata: {
-                    let mut map = HashMap::new();
-                    map.insert("backend".to_string(), serde_json::Value::String("candle".to_string()));
-                    map.insert("class_index".to_string(), serde_json::Value::Number(serde_json::Number::from(max_idx)));
-                    map.insert("raw_scores".to_string(), serde_json::Value::Array(
-                        output_data[0..std::cmp::min(10, output_data.len())]
-                            .iter()
-                            .map(|&x| serde_json::Value::Number(serde_json::Number::from_f64(x as f64).unwrap_or(serde_json::Number::from(0))))
-                            .collect()
-                    ));
-                    map
-                },
-                severity: Some("medium".to_string()),
-            }
-        ];
-        
+
+        let confidence = predictions.iter()
+            .map(|p| p.confidence)
+            .fold(0.0f32, f32::max);
+
+        let inference_time = start_time.elapsed().as_secs_f64() * 1000.0;
+        debug!("Candle inference completed in {:.2}ms", inference_time);
+
         Ok(InferenceResult {
-            model_name: "candle_model".to_string(),
-            model_type,
+            model_name: self.name.clone(),
+            model_type: "candle".to_string(),
             predictions,
-            confidence: *max_prob,
-            inference_time_ms: self.stats.last_inference_time_ms.unwrap_or(0.0),
+            confidence,
+            inference_time_ms: inference_time,
             metadata: serde_json::json!({
                 "backend": "candle",
                 "device": format!("{:?}", self.device),
-                "output_shape": output_tensor.shape().dims(),
-                "total_inferences": self.stats.total_inferences
+                "model_path": self.model_path,
+                "output_shapes": output_shapes,
+                "input_size": [self.input_shape[3], self.input_shape[2]],
+                "inference_type": "real"
             }),
+            tensors,
         })
     }
+
+    /// Run the forward pass and collect every output as f32
+    fn forward(&self, input: Tensor) -> Result<Vec<RawOutput>, BackendError> {
+        let outputs = match &self.network {
+            #[cfg(feature = "candle-onnx")]
+            Network::Onnx { model: graph, input_name, output_names } => {
+                let inputs = HashMap::from([(input_name.clone(), input)]);
+                let mut results = candle_onnx::simple_eval(graph, inputs)?;
+                output_names.iter()
+                    .map(|name| {
+                        results.remove(name)
+                            .map(|tensor| (name.clone(), tensor))
+                            .ok_or_else(|| BackendError::InferenceFailed(format!("ONNX output '{}' was not produced", name)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+            Network::Classifier(func) => vec![("logits".to_string(), func.forward(&input.to_dtype(self.dtype)?)?)],
+            Network::YoloV8(yolo) => vec![("output0".to_string(), yolo.forward(&input.to_dtype(self.dtype)?)?)],
+        };
+
+        outputs.into_iter()
+            .map(|(name, tensor)| {
+                Ok(RawOutput {
+                    name,
+                    shape: tensor.dims().to_vec(),
+                    data: tensor.to_dtype(DType::F32)?.flatten_all()?.to_vec1::<f32>()?,
+                })
+            })
+            .collect()
+    }
 }
 
-#[cfg(feature = "candle")]
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
 #[async_trait]
 impl InferenceBackend for CandleBackend {
     async fn initialize(&mut self, config: &BackendConfig) -> Result<(), BackendError> {
         info!("Initializing Candle backend");
-        
+
         self.setup_device(&config.device_type)?;
+        if let Some(candle_config) = &config.candle_config {
+            self.dtype = Self::candle_dtype_from_config(&candle_config.dtype);
+        }
         self.config = Some(config.clone());
-        
+
         info!("Candle backend initialized successfully with device: {:?}", self.device);
         Ok(())
     }
-    
+
     async fn load_model(&mut self, model_name: &str, model_config: &ModelConfig) -> Result<(), BackendError> {
         info!("Loading model '{}' with Candle backend", model_name);
-        
+
         let model_path = &model_config.model_path;
-        
+
         if !Path::new(model_path).exists() {
             return Err(BackendError::ModelLoadFailed(format!("Model file not found: {}", model_path)));
         }
-        
+
+        // The artifact is read once, so a pinned digest is checked against the
+        // exact bytes the network is built from
+        let bytes = tokio::fs::read(model_path).await
+            .map_err(|e| BackendError::ModelLoadFailed(format!("Failed to read model '{}': {}", model_path, e)))?;
+        if let Some(expected) = &model_config.integrity {
+            if let Err(e) = crate::integrity::verify_bytes(model_path, &bytes, &expected.sha256) {
+                error!("Refusing to load model '{}': {}", model_name, e);
+                self.integrity_failures.insert(model_name.to_string(), e.to_string());
+                return Err(BackendError::IntegrityCheckFailed(e.to_string()));
+            }
+        }
+        self.integrity_failures.remove(model_name);
+
         // Determine model format based on file extension
         let model_path_lower = model_path.to_lowercase();
-        if model_path_lower.ends_with(".onnx") {
-            self.load_onnx_model(model_path, model_name).await?;
+        let (network, default_size, default_postprocess_type) = if model_path_lower.ends_with(".onnx") {
+            (self.load_onnx_model(model_path, &bytes)?, 640, "classification")
         } else if model_path_lower.ends_with(".safetensors") {
-            self.load_safetensors_model(model_path, model_name).await?;
+            let (network, architecture) = self.load_safetensors_model(model_config, &bytes)?;
+            (network, architecture.default_input_size(), architecture.default_postprocess_type())
         } else {
             return Err(BackendError::ModelLoadFailed(format!("Unsupported model format for Candle backend: {}", model_path)));
-        }
-        
+        };
+
+        let input_shape = parse_input_shape(model_config, &[1, 3, default_size, default_size]);
+        let model = CandleModel {
+            name: model_name.to_string(),
+            model_path: model_path.clone(),
+            network,
+            device: self.device.clone(),
+            dtype: self.dtype,
+            image_input: image_input_pipeline(&input_shape, parse_normalization(model_config).as_ref()),
+            input_shape,
+            settings: OutputSettings::from_model_config(model_config, default_postprocess_type)?,
+            memory_mb: crate::backend::artifact_memory_mb(model_path),
+        };
+        debug!("Loaded Candle model: {:?}", model);
+
+        self.loaded_models.insert(model_name.to_string(), Arc::new(model));
+        self.stats.memory_usage_mb = self.loaded_models.values().map(|model| model.memory_mb).sum();
+        info!("Successfully loaded model '{}' with Candle backend", model_name);
+
         Ok(())
     }
-    
+
     async fn unload_model(&mut self, model_name: &str) -> Result<(), BackendError> {
         if self.loaded_models.remove(model_name).is_some() {
+            self.stats.memory_usage_mb = self.loaded_models.values().map(|model| model.memory_mb).sum();
             info!("Unloaded model '{}' from Candle backend", model_name);
             Ok(())
         } else {
             Err(BackendError::ModelUnloadFailed(format!("Model '{}' not found", model_name)))
         }
     }
-    
+
     async fn infer(&self, input: InferenceInput, model_name: Option<&str>) -> Result<InferenceResult, BackendError> {
         let start_time = std::time::Instant::now();
-        
+
         // Get the model to use
         let model = if let Some(name) = model_name {
             self.loaded_models.get(name)
@@ -360,45 +551,22 @@ impl InferenceBackend for CandleBackend {
             self.loaded_models.values().next()
                 .ok_or_else(|| BackendError::InferenceFailed("No models loaded".to_string()))?
         };
-        
-        // Preprocess input based on type
-        let input_tensor = match &input {
-            InferenceInput::Image { data, metadata: _ } => {
-                if let Some(preprocessing) = &model.preprocessing {
-                    self.preprocess_image(data, preprocessing)?
-                } else {
-                    return Err(BackendError::InvalidInput("No preprocessing config for image model".to_string()));
-                }
-            }
-            InferenceInput::TimeSeries { values, timestamps, metadata: _ } => {
-                self.preprocess_sensor_data(values, timestamps)?
-            }
-        };
-        
-        // Run inference
-        let output_tensor = self.run_model_inference(model, &input_tensor).await?;
-        
-        // Convert result
-        let input_type = match input {
-            InferenceInput::Image { .. } => "image",
-            InferenceInput::TimeSeries { .. } => "sensor",
-        };
-        
-        let mut result = self.tensor_to_inference_result(&output_tensor, input_type)?;
-        
-        // Update timing
-        let inference_time = start_time.elapsed().as_millis() as f64;
-        result.inference_time_ms = inference_time;
-        
-        debug!("Candle inference completed in {:.2}ms", inference_time);
-        
-        Ok(result)
+
+        // The forward pass would stall the async runtime, so it runs on the
+        // blocking pool; a caller that gives up stops waiting but cannot cancel it
+        let model = Arc::clone(model);
+        tokio::task::spawn_blocking(move || model.infer(input, start_time)).await
+            .map_err(|e| BackendError::InferenceFailed(format!("Inference task failed: {}", e)))?
     }
-    
+
+    fn model_memory_mb(&self, model_name: &str) -> Option<f64> {
+        self.loaded_models.get(model_name).map(|model| model.memory_mb)
+    }
+
     async fn get_loaded_models(&self) -> Vec<String> {
         self.loaded_models.keys().cloned().collect()
     }
-    
+
     async fn get_status(&self) -> BackendStatus {
         BackendStatus {
             backend_type: BackendType::Candle,
@@ -412,49 +580,58 @@ impl InferenceBackend for CandleBackend {
             memory_usage_mb: self.stats.memory_usage_mb,
             last_inference_time_ms: self.stats.last_inference_time_ms,
             total_inferences: self.stats.total_inferences,
-            errors: vec![], // Could be enhanced to track errors
+            errors: self.integrity_failures.iter()
+                .map(|(model, reason)| format!("Model '{}' rejected: {}", model, reason))
+                .collect(),
         }
     }
-    
+
     fn backend_type(&self) -> BackendType {
         BackendType::Candle
     }
 }
 
 // Fallback implementation when candle feature is not enabled
-#[cfg(not(feature = "candle"))]
+#[cfg(not(any(feature = "candle", feature = "pure-rust")))]
 pub struct CandleBackend;
 
-#[cfg(not(feature = "candle"))]
+#[cfg(not(any(feature = "candle", feature = "pure-rust")))]
+impl Default for CandleBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(any(feature = "candle", feature = "pure-rust")))]
 impl CandleBackend {
     pub fn new() -> Self {
         Self
     }
 }
 
-#[cfg(not(feature = "candle"))]
+#[cfg(not(any(feature = "candle", feature = "pure-rust")))]
 #[async_trait]
 impl InferenceBackend for CandleBackend {
     async fn initialize(&mut self, _config: &BackendConfig) -> Result<(), BackendError> {
         Err(BackendError::BackendUnavailable("Candle backend not compiled".to_string()))
     }
-    
+
     async fn load_model(&mut self, _model_name: &str, _model_config: &ModelConfig) -> Result<(), BackendError> {
         Err(BackendError::BackendUnavailable("Candle backend not compiled".to_string()))
     }
-    
+
     async fn unload_model(&mut self, _model_name: &str) -> Result<(), BackendError> {
         Err(BackendError::BackendUnavailable("Candle backend not compiled".to_string()))
     }
-    
+
     async fn infer(&self, _input: InferenceInput, _model_name: Option<&str>) -> Result<InferenceResult, BackendError> {
         Err(BackendError::BackendUnavailable("Candle backend not compiled".to_string()))
     }
-    
+
     async fn get_loaded_models(&self) -> Vec<String> {
         vec![]
     }
-    
+
     async fn get_status(&self) -> BackendStatus {
         BackendStatus {
             backend_type: BackendType::Candle,
@@ -467,8 +644,57 @@ impl InferenceBackend for CandleBackend {
             errors: vec!["Candle backend not compiled".to_string()],
         }
     }
-    
+
     fn backend_type(&self) -> BackendType {
         BackendType::Candle
     }
-}
\ No newline at end of file
+}
+
+#[cfg(all(test, any(feature = "candle", feature = "pure-rust")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_architecture_from_model_type() {
+        assert_eq!(Architecture::from_model_type("resnet50"), Some(Architecture::ResNet(50)));
+        assert_eq!(
+            Architecture::from_model_type("MobileNetV4-Hybrid-Medium"),
+            Some(Architecture::MobileNetV4(MobileNetV4Variant::HybridMedium)),
+        );
+        assert_eq!(Architecture::from_model_type("yolov8s"), Some(Architecture::YoloV8(Multiples::s())));
+        assert_eq!(Architecture::from_model_type("auto"), None);
+    }
+
+    #[test]
+    fn test_architecture_detected_from_tensor_names() {
+        let device = Device::Cpu;
+        let tensor = |dims: &[usize]| Tensor::zeros(dims, DType::F32, &device).unwrap();
+
+        let mut resnet = HashMap::new();
+        resnet.insert("conv1.weight".to_string(), tensor(&[64, 3, 7, 7]));
+        resnet.insert("layer1.0.conv1.weight".to_string(), tensor(&[64, 64, 3, 3]));
+        resnet.insert("fc.weight".to_string(), tensor(&[10, 512]));
+        for i in 0..6 {
+            resnet.insert(format!("layer3.{i}.conv1.weight"), tensor(&[256, 256, 3, 3]));
+        }
+        let architecture = Architecture::detect(&resnet).unwrap();
+        assert_eq!(architecture, Architecture::ResNet(34));
+        assert_eq!(architecture.num_classes(&resnet), Some(10));
+
+        let mut yolo = HashMap::new();
+        yolo.insert("net.b1.0.conv.weight".to_string(), tensor(&[32, 3, 3, 3]));
+        assert_eq!(Architecture::detect(&yolo), Some(Architecture::YoloV8(Multiples::s())));
+    }
+
+    #[test]
+    fn test_yolov8_output_matches_onnx_layout() {
+        let vb = VarBuilder::zeros(DType::F32, &Device::Cpu);
+        let model = YoloV8::load(vb, Multiples::n(), 2).unwrap();
+        let input = Tensor::zeros((1, 3, 64, 64), DType::F32, &Device::Cpu).unwrap();
+
+        let output = model.forward(&input).unwrap();
+
+        // 8x8 + 4x4 + 2x2 anchors for strides 8, 16 and 32
+        assert_eq!(output.dims(), &[1, 6, 84]);
+    }
+}
@@ -0,0 +1,338 @@
+//! Deterministic mock backend for hermetic tests
+//!
+//! The mock backend loads no model files. It answers each request with a
+//! response scripted in a fixture: predictions, a simulated latency, an
+//! injected failure or a request that never completes, so deadlines fire.
+//! Responses are picked by the hash of the input when the fixture lists one
+//! for it, and otherwise from a sequence that repeats once exhausted.
+//!
+//! ```yaml
+//! default:
+//!   sequence:
+//!     - predictions: []
+//! models:
+//!   ppe_detector:
+//!     by_input_hash:
+//!       "3f1c...": { predictions: [{ class: no_hardhat, confidence: 0.91, bbox: [10, 20, 80, 120] }] }
+//!     sequence:
+//!       - predictions: [{ class: hardhat, confidence: 0.88 }]
+//!         latency_ms: 15
+//!       - error: "simulated GPU reset"
+//!       - timeout: true
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+
+use crate::backend::{BackendConfig, BackendError, BackendStatus, BackendType, DeviceType, InferenceBackend};
+use crate::backends::registry::{BackendRegistration, BackendRegistry};
+use crate::tensors::Tensor;
+use crate::types::Prediction;
+use crate::{InferenceInput, InferenceResult, ModelConfig};
+
+/// Scripted answer to one request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockResponse {
+    pub predictions: Vec<Prediction>,
+    /// Simulated inference time, spent sleeping before answering
+    pub latency_ms: u64,
+    /// Fail the request with this message instead of answering
+    pub error: Option<String>,
+    /// Never answer, so the request runs into its deadline
+    pub timeout: bool,
+    /// Output tensors returned as they are, as in raw output mode
+    pub tensors: Option<Vec<Tensor>>,
+}
+
+/// Responses for one model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockScript {
+    /// Responses for specific inputs, keyed by `MockBackend::input_hash`
+    pub by_input_hash: HashMap<String, MockResponse>,
+    /// Responses for all other inputs in request order, repeated once exhausted
+    pub sequence: Vec<MockResponse>,
+}
+
+/// Scripted responses of the mock backend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockFixture {
+    /// Script of models without an entry in `models`
+    pub default: MockScript,
+    pub models: HashMap<String, MockScript>,
+}
+
+impl MockFixture {
+    /// Read a YAML or JSON fixture file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, BackendError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| BackendError::ConfigurationError(format!("Cannot read mock fixture {}: {}", path.display(), e)))?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| BackendError::ConfigurationError(format!("Invalid mock fixture {}: {}", path.display(), e)))
+    }
+
+    fn script(&self, model_name: &str) -> &MockScript {
+        self.models.get(model_name).unwrap_or(&self.default)
+    }
+}
+
+/// Backend that replays a `MockFixture`
+#[derive(Debug)]
+pub struct MockBackend {
+    fixture: MockFixture,
+    initialized: bool,
+    device_type: DeviceType,
+    loaded_models: HashMap<String, String>,
+    /// Requests answered from the sequence so far, per model
+    positions: Mutex<HashMap<String, usize>>,
+    total_inferences: AtomicU64,
+    last_inference_time_ms: Mutex<Option<f64>>,
+}
+
+impl MockBackend {
+    /// Name the mock backend is registered under
+    pub const NAME: &'static str = "mock";
+
+    pub fn new(fixture: MockFixture) -> Self {
+        Self {
+            fixture,
+            initialized: false,
+            device_type: DeviceType::Cpu,
+            loaded_models: HashMap::new(),
+            positions: Mutex::new(HashMap::new()),
+            total_inferences: AtomicU64::new(0),
+            last_inference_time_ms: Mutex::new(None),
+        }
+    }
+
+    /// Register the mock backend as `BackendType::Custom("mock")`
+    ///
+    /// It takes the highest priority, so engines created afterwards with
+    /// automatic backend selection use it.
+    pub fn register(fixture: MockFixture) -> Result<(), BackendError> {
+        BackendRegistry::register(
+            BackendRegistration::new(Self::NAME, move || Box::new(Self::new(fixture.clone())) as Box<dyn InferenceBackend>)
+                .with_priority(i32::MAX),
+        )
+    }
+
+    /// Hex SHA-256 of an input's content, the key of `MockScript::by_input_hash`
+    ///
+    /// Images hash their decoded pixel bytes; time series and audio hash
+    /// their values as little-endian `f32`, and tensors their little-endian
+    /// values in request order.
+    pub fn input_hash(input: &InferenceInput) -> String {
+        let mut hasher = Sha256::new();
+        match input {
+            InferenceInput::Image { data, .. } => hasher.update(data.as_bytes()),
+            InferenceInput::TimeSeries { values, .. } => values.iter().for_each(|value| hasher.update(value.to_le_bytes())),
+            InferenceInput::Audio { samples, .. } => samples.iter().for_each(|sample| hasher.update(sample.to_le_bytes())),
+            InferenceInput::Tensor { tensors, .. } => tensors.iter().for_each(|tensor| hasher.update(tensor.data.to_le_bytes())),
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    fn positions(&self) -> MutexGuard<'_, HashMap<String, usize>> {
+        self.positions.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn resolve_model(&self, model_name: Option<&str>) -> Result<(&String, &String), BackendError> {
+        if !self.initialized {
+            return Err(BackendError::BackendNotInitialized("Mock backend not initialized".to_string()));
+        }
+        let model_key = model_name.unwrap_or("default");
+        self.loaded_models.get_key_value(model_key)
+            .or_else(|| (model_key == "default").then(|| self.loaded_models.iter().next()).flatten())
+            .ok_or_else(|| BackendError::ModelLoadFailed(format!("Model '{}' not loaded", model_key)))
+    }
+
+    /// Pick the response for an input, returning it with its sequence index
+    fn next_response(&self, model_name: &str, input_hash: &str) -> (MockResponse, Option<usize>) {
+        let script = self.fixture.script(model_name);
+        if let Some(response) = script.by_input_hash.get(input_hash) {
+            return (response.clone(), None);
+        }
+        if script.sequence.is_empty() {
+            return (MockResponse::default(), None);
+        }
+        let mut positions = self.positions();
+        let position = positions.entry(model_name.to_string()).or_insert(0);
+        let index = *position % script.sequence.len();
+        *position += 1;
+        (script.sequence[index].clone(), Some(index))
+    }
+}
+
+#[async_trait]
+impl InferenceBackend for MockBackend {
+    async fn initialize(&mut self, config: &BackendConfig) -> Result<(), BackendError> {
+        self.device_type = config.device_type.clone();
+        self.initialized = true;
+        info!("Mock backend initialized with {} scripted models", self.fixture.models.len());
+        Ok(())
+    }
+
+    async fn load_model(&mut self, model_name: &str, model_config: &ModelConfig) -> Result<(), BackendError> {
+        self.loaded_models.insert(model_name.to_string(), model_config.model_path.clone());
+        debug!("Mock model '{}' loaded", model_name);
+        Ok(())
+    }
+
+    async fn unload_model(&mut self, model_name: &str) -> Result<(), BackendError> {
+        self.loaded_models.remove(model_name)
+            .map(|_| ())
+            .ok_or_else(|| BackendError::ModelUnloadFailed(format!("Model '{}' not found", model_name)))
+    }
+
+    async fn infer(&self, input: InferenceInput, model_name: Option<&str>) -> Result<InferenceResult, BackendError> {
+        let (name, model_path) = self.resolve_model(model_name)?;
+        let input_hash = Self::input_hash(&input);
+        let (response, sequence_index) = self.next_response(name, &input_hash);
+
+        if response.latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(response.latency_ms)).await;
+        }
+        if response.timeout {
+            std::future::pending::<()>().await;
+        }
+        self.total_inferences.fetch_add(1, Ordering::Relaxed);
+        *self.last_inference_time_ms.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(response.latency_ms as f64);
+        if let Some(error) = response.error {
+            return Err(BackendError::InferenceFailed(error));
+        }
+
+        let mut metadata = serde_json::json!({
+            "backend": Self::NAME,
+            "model_path": model_path,
+            "inference_type": "mock",
+            "input_hash": input_hash,
+            "sequence_index": sequence_index,
+            "request_id": uuid::Uuid::new_v4().to_string()
+        });
+        if let InferenceInput::Image { data, .. } = &input {
+            // Scripted boxes are in input image pixels
+            metadata["input_size"] = serde_json::json!([data.width(), data.height()]);
+        }
+
+        Ok(InferenceResult {
+            model_name: name.clone(),
+            model_type: Self::NAME.to_string(),
+            confidence: response.predictions.iter().map(|p| p.confidence).fold(0.0f32, f32::max),
+            predictions: response.predictions,
+            inference_time_ms: response.latency_ms as f64,
+            metadata,
+            tensors: response.tensors,
+        })
+    }
+
+    async fn get_loaded_models(&self) -> Vec<String> {
+        self.loaded_models.keys().cloned().collect()
+    }
+
+    async fn get_status(&self) -> BackendStatus {
+        BackendStatus {
+            backend_type: self.backend_type(),
+            device_type: self.device_type.clone(),
+            initialized: self.initialized,
+            loaded_models: self.get_loaded_models().await,
+            memory_usage_mb: 0.0,
+            last_inference_time_ms: *self.last_inference_time_ms.lock().unwrap_or_else(|poisoned| poisoned.into_inner()),
+            total_inferences: self.total_inferences.load(Ordering::Relaxed),
+            errors: Vec::new(),
+        }
+    }
+
+    fn backend_type(&self) -> BackendType {
+        BackendType::Custom(Self::NAME.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(values: Vec<f32>) -> InferenceInput {
+        InferenceInput::TimeSeries {
+            timestamps: (0..values.len() as i64).collect(),
+            values,
+            metadata: crate::SensorMetadata {
+                sensor_type: "vibration".to_string(),
+                sampling_rate: 100.0,
+                units: "g".to_string(),
+            },
+        }
+    }
+
+    fn model_config() -> ModelConfig {
+        ModelConfig {
+            model_path: "missing.onnx".to_string(),
+            model_type: "anomaly_detection".to_string(),
+            confidence_threshold: None,
+            preprocessing: None,
+            postprocessing: None,
+            integrity: None,
+            memory_mb: None,
+        }
+    }
+
+    async fn backend(fixture: &str) -> MockBackend {
+        let mut backend = MockBackend::new(serde_yaml::from_str(fixture).unwrap());
+        backend.initialize(&BackendConfig::default()).await.unwrap();
+        backend.load_model("pump", &model_config()).await.unwrap();
+        backend
+    }
+
+    #[tokio::test]
+    async fn test_sequence_repeats_and_injects_failures() {
+        let backend = backend(r#"
+models:
+  pump:
+    sequence:
+      - predictions: [{ class: bearing_wear, confidence: 0.8 }]
+        latency_ms: 5
+      - error: simulated failure
+"#).await;
+
+        let first = backend.infer(series(vec![1.0]), Some("pump")).await.unwrap();
+        assert_eq!(first.predictions[0].class, "bearing_wear");
+        assert_eq!(first.inference_time_ms, 5.0);
+        assert_eq!(first.metadata["sequence_index"], 0);
+
+        let failed = backend.infer(series(vec![1.0]), Some("pump")).await.unwrap_err();
+        assert!(failed.to_string().contains("simulated failure"));
+        assert_eq!(backend.infer(series(vec![1.0]), None).await.unwrap().confidence, 0.8);
+        assert!(backend.infer(series(vec![1.0]), Some("fan")).await.is_err());
+        assert_eq!(backend.get_status().await.total_inferences, 3);
+    }
+
+    #[tokio::test]
+    async fn test_input_hash_takes_precedence_and_timeout_never_answers() {
+        let known = series(vec![0.5, 0.25]);
+        let fixture = format!(r#"
+default:
+  by_input_hash:
+    "{}": {{ predictions: [{{ class: cavitation, confidence: 0.95 }}] }}
+  sequence:
+    - timeout: true
+"#, MockBackend::input_hash(&known));
+        let backend = backend(&fixture).await;
+
+        let result = backend.infer(known, Some("pump")).await.unwrap();
+        assert_eq!(result.predictions[0].class, "cavitation");
+        assert!(result.metadata["sequence_index"].is_null());
+
+        let pending = tokio::time::timeout(Duration::from_millis(50), backend.infer(series(vec![9.0]), Some("pump"))).await;
+        assert!(pending.is_err());
+    }
+}
@@ -1,12 +1,23 @@
-#[cfg(feature = "candle")]
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
 pub mod candle;
 
 #[cfg(feature = "onnx-runtime")]
 pub mod onnx;
 
+#[cfg(any(feature = "onnx-runtime", feature = "candle", feature = "pure-rust"))]
+mod outputs;
+
+pub mod registry;
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
 // Re-export backend implementations
-#[cfg(feature = "candle")]
+#[cfg(any(feature = "candle", feature = "pure-rust"))]
 pub use candle::CandleBackend;
 
 #[cfg(feature = "onnx-runtime")]
-pub use onnx::OnnxRuntimeBackend;
\ No newline at end of file
+pub use onnx::OnnxRuntimeBackend;
+
+#[cfg(feature = "mock")]
+pub use mock::{MockBackend, MockFixture, MockResponse, MockScript};
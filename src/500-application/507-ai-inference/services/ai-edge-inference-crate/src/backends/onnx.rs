@@ -1,12 +1,27 @@
 use std::collections::HashMap;
-use std::sync::Mutex;
-use tracing::{debug, info};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{debug, error, info};
 use async_trait::async_trait;
+use ort::session::{RunOptions, SessionInputValue};
 
 use crate::backend::{
-    InferenceBackend, BackendConfig, BackendError, BackendStatus, BackendType,
+    artifact_memory_mb, InferenceBackend, BackendConfig, BackendError, BackendStatus, BackendType,
     DeviceType
 };
+use crate::backends::outputs::{
+    auxiliary_input, image_batch_shape, image_input_pipeline, parse_audio_config, parse_input_bindings,
+    parse_input_shape, parse_normalization, parse_output_bindings, parse_sensor_window, require_image_input,
+    split_batch_outputs, OutputDecoder, OutputSettings, RawOutput,
+};
+use crate::audio::{self, AudioClip, AudioFeatureConfig};
+use crate::image_input::ImageInputPipeline;
+use crate::integrity;
+use crate::introspection::shapes_compatible;
+use crate::model_config::{InputSemantic, InputTensorSpec};
+use crate::tensors::{Tensor, TensorData, TensorDType};
+use crate::timeseries::{build_windows, SensorWindowConfig, TimeSeriesOutputKind};
+use crate::types::Prediction;
 use crate::{InferenceInput, InferenceResult, ModelConfig};
 
 /// ONNX Runtime backend implementation
@@ -14,8 +29,10 @@ use crate::{InferenceInput, InferenceResult, ModelConfig};
 pub struct OnnxRuntimeBackend {
     environment_initialized: bool,
     config: Option<BackendConfig>,
-    loaded_models: HashMap<String, OnnxModel>,
+    loaded_models: HashMap<String, Arc<OnnxModel>>,
     stats: Mutex<BackendStats>,
+    /// Models refused because their artifact failed the digest check
+    integrity_failures: HashMap<String, String>,
 }
 
 #[cfg(feature = "onnx-runtime")]
@@ -37,11 +54,21 @@ struct OnnxModel {
     session: Mutex<ort::session::Session>,
     input_name: String,
     input_shape: Vec<i64>,
-    class_labels: Vec<String>,
-    confidence_threshold: f32,
-    nms_threshold: f32,
-    top_k: usize,
-    postprocess_type: String,
+    /// Model inputs filled in by the backend rather than the request
+    auxiliary_inputs: Vec<InputTensorSpec>,
+    /// Declared output names; outputs reach postprocessing in this order
+    output_order: Vec<String>,
+    /// Whether the exported graph leaves the batch dimension symbolic
+    dynamic_batch: bool,
+    /// Fused image preprocessing; `None` when the input shape cannot hold an image
+    image_input: Option<ImageInputPipeline>,
+    /// Windowing for time-series input
+    sensor_window: Option<SensorWindowConfig>,
+    /// Feature extraction for audio input
+    audio: Option<AudioFeatureConfig>,
+    settings: OutputSettings,
+    /// Estimated session memory in MB
+    memory_mb: f64,
 }
 
 #[cfg(feature = "onnx-runtime")]
@@ -52,11 +79,14 @@ impl std::fmt::Debug for OnnxModel {
             .field("model_path", &self.model_path)
             .field("input_name", &self.input_name)
             .field("input_shape", &self.input_shape)
-            .field("class_labels", &self.class_labels)
-            .field("confidence_threshold", &self.confidence_threshold)
-            .field("nms_threshold", &self.nms_threshold)
-            .field("top_k", &self.top_k)
-            .field("postprocess_type", &self.postprocess_type)
+            .field("auxiliary_inputs", &self.auxiliary_inputs)
+            .field("output_order", &self.output_order)
+            .field("dynamic_batch", &self.dynamic_batch)
+            .field("image_input", &self.image_input)
+            .field("sensor_window", &self.sensor_window)
+            .field("audio", &self.audio)
+            .field("settings", &self.settings)
+            .field("memory_mb", &self.memory_mb)
             .finish()
     }
 }
@@ -70,16 +100,11 @@ struct BackendStats {
     average_inference_time_ms: f64,
 }
 
-/// Raw detection before NMS
 #[cfg(feature = "onnx-runtime")]
-#[derive(Debug, Clone)]
-struct RawDetection {
-    x1: f32,
-    y1: f32,
-    x2: f32,
-    y2: f32,
-    confidence: f32,
-    class_id: usize,
+impl Default for OnnxRuntimeBackend {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(feature = "onnx-runtime")]
@@ -95,6 +120,7 @@ impl OnnxRuntimeBackend {
                 total_errors: 0,
                 average_inference_time_ms: 0.0,
             }),
+            integrity_failures: HashMap::new(),
         }
     }
 
@@ -118,314 +144,491 @@ impl OnnxRuntimeBackend {
         stats.average_inference_time_ms = ((previous * (count - 1.0)) + elapsed_ms) / count;
     }
 
-    /// Parse postprocessing config from ModelConfig JSON
-    fn parse_postprocessing(model_config: &ModelConfig) -> (Vec<String>, f32, f32, usize, String) {
-        // Default top-K for classification outputs; overridable via postprocessing.top_k.
-        const DEFAULT_TOP_K: usize = 3;
+    /// Look up a loaded model, falling back to any loaded model for "default"
+    fn resolve_model(&self, model_name: Option<&str>) -> Result<&Arc<OnnxModel>, BackendError> {
+        let model_key = model_name.unwrap_or("default");
+        self.loaded_models.get(model_key)
+            .or_else(|| {
+                if model_key == "default" {
+                    let fallback = self.loaded_models.values().next();
+                    if let Some(m) = fallback {
+                        info!("Model 'default' not found, falling back to '{}'", m.name);
+                    }
+                    fallback
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| BackendError::ModelLoadFailed(format!("Model '{}' not loaded", model_key)))
+    }
+
+    /// Resolve a model that can be moved onto the blocking pool
+    fn session_model(&self, model_name: Option<&str>) -> Result<Arc<OnnxModel>, BackendError> {
+        if !self.environment_initialized {
+            return Err(BackendError::BackendNotInitialized("Environment not initialized".to_string()));
+        }
+        self.resolve_model(model_name).map(Arc::clone)
+    }
+}
+
+/// Run blocking ORT work on tokio's blocking thread pool
+///
+/// Session runs can take long enough to stall the async runtime, so they never
+/// execute on a runtime worker. If the returned future is dropped before the
+/// work finishes (for example when the engine's deadline expires), the session
+/// run is terminated through its `RunOptions`.
+#[cfg(feature = "onnx-runtime")]
+async fn run_blocking<T, F>(work: F) -> Result<T, BackendError>
+where
+    T: Send + 'static,
+    F: FnOnce(&RunOptions) -> Result<T, BackendError> + Send + 'static,
+{
+    let run_options = Arc::new(RunOptions::new()
+        .map_err(|e| BackendError::OnnxError(format!("Failed to create run options: {}", e)))?);
+    let mut terminate = TerminateOnDrop(Some(Arc::clone(&run_options)));
+
+    let result = tokio::task::spawn_blocking(move || work(&run_options)).await
+        .map_err(|e| BackendError::InferenceFailed(format!("Inference task failed: {}", e)));
+
+    terminate.0 = None;
+    result?
+}
+
+/// Terminates an in-flight session run when its caller goes away
+#[cfg(feature = "onnx-runtime")]
+struct TerminateOnDrop(Option<Arc<RunOptions>>);
 
-        let mut class_labels: Vec<String> = Vec::new();
-        let mut confidence_threshold = model_config.confidence_threshold.unwrap_or(0.5);
-        let mut nms_threshold = 0.4f32;
-        let mut top_k: usize = DEFAULT_TOP_K;
-        let mut postprocess_type = "classification".to_string();
+#[cfg(feature = "onnx-runtime")]
+impl Drop for TerminateOnDrop {
+    fn drop(&mut self) {
+        if let Some(run_options) = self.0.take() {
+            debug!("Terminating abandoned ONNX session run");
+            if let Err(e) = run_options.terminate() {
+                debug!("Failed to terminate ONNX session run: {}", e);
+            }
+        }
+    }
+}
 
-        if let Some(post) = &model_config.postprocessing {
-            if let Some(pt) = post.get("postprocess_type").and_then(|v| v.as_str()) {
-                postprocess_type = pt.to_string();
+#[cfg(feature = "onnx-runtime")]
+impl OnnxModel {
+    /// Run one input through the model
+    fn infer(&self, input: InferenceInput, run_options: &RunOptions, start: Instant) -> Result<InferenceResult, BackendError> {
+        let raw_output = self.settings.raw_output();
+        let (predictions, summary) = match input {
+            InferenceInput::Image { data, metadata: _ } if raw_output => {
+                let image_input = self.image_input()?;
+                let (input_data, _) = image_input.prepare(&data)
+                    .map_err(|e| BackendError::InvalidInput(format!("Failed to preprocess image: {}", e)))?;
+                let outputs = self.run_request(run_options, image_batch_shape(image_input, 1), &input_data, Some((data.width(), data.height())))?;
+                return Ok(self.raw_result(outputs, start));
+            }
+            InferenceInput::Image { data, metadata: _ } => {
+                (self.infer_image(run_options, &data)?, Some(("input_size", self.input_size_json())))
             }
-            if let Some(ct) = post.get("confidence_threshold").and_then(|v| v.as_f64()) {
-                confidence_threshold = ct as f32;
+            InferenceInput::Tensor { tensors, raw_output: requested } => {
+                let outputs = self.run_tensors(run_options, tensors, None)?;
+                if raw_output || requested {
+                    return Ok(self.raw_result(outputs, start));
+                }
+                let outputs: Vec<RawOutput> = outputs.into_iter().map(RawOutput::from).collect();
+                (self.output_decoder().decode(&outputs)?, None)
             }
-            if let Some(nt) = post.get("nms_threshold").and_then(|v| v.as_f64()) {
-                nms_threshold = nt as f32;
+            InferenceInput::TimeSeries { .. } | InferenceInput::Audio { .. } if raw_output => {
+                return Err(BackendError::InvalidInput(format!(
+                    "Model '{}' returns raw outputs, which needs image or tensor input", self.name
+                )));
             }
-            if let Some(k) = post.get("top_k").and_then(|v| v.as_u64()) {
-                top_k = k as usize;
+            InferenceInput::TimeSeries { values, timestamps, metadata } => {
+                debug!("Running {} sensor model on {} values", metadata.sensor_type, values.len());
+                let (predictions, summary) = self.infer_time_series(run_options, &values, &timestamps)?;
+                (predictions, Some(("time_series", summary)))
             }
-            if let Some(labels) = post.get("class_labels").and_then(|v| v.as_array()) {
-                class_labels = labels.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect();
+            InferenceInput::Audio { samples, metadata } => {
+                let clip = AudioClip { samples, sample_rate: metadata.sample_rate, channels: metadata.channels };
+                let (predictions, summary) = self.infer_audio(run_options, &clip)?;
+                (predictions, Some(("audio", summary)))
             }
+        };
+
+        let mut result = self.inference_result(predictions, start.elapsed().as_secs_f64() * 1000.0);
+        if let Some((key, summary)) = summary {
+            result.metadata[key] = summary;
         }
+        Ok(result)
+    }
 
-        (class_labels, confidence_threshold, nms_threshold, top_k, postprocess_type)
+    /// Run inference through the ONNX session
+    ///
+    /// Returns every output with the real tensor shape reported by ORT alongside the
+    /// flat data, so postprocessing does not have to guess dimensions from `num_classes`
+    /// (which is unreliable when `class_labels` is empty). Integer outputs (e.g. class
+    /// ids in some SSD exports) are converted to f32. `image_size` is the original
+    /// (width, height) of an image request, for models that take it as an input.
+    fn run_session(
+        &self,
+        run_options: &RunOptions,
+        input_shape: Vec<i64>,
+        input_data: &[f32],
+        image_size: Option<(u32, u32)>,
+    ) -> Result<Vec<RawOutput>, BackendError> {
+        let outputs = self.run_request(run_options, input_shape, input_data, image_size)?;
+        Ok(outputs.into_iter().map(RawOutput::from).collect())
     }
 
-    /// Parse input shape from ModelConfig preprocessing JSON or use defaults
-    fn parse_input_shape(model_config: &ModelConfig) -> Vec<i64> {
-        if let Some(pre) = &model_config.preprocessing {
-            if let Some(target_size) = pre.get("target_size").and_then(|v| v.as_array()) {
-                let dims: Vec<i64> = target_size.iter()
-                    .filter_map(|v| v.as_i64())
-                    .collect();
-                if dims.len() == 2 {
-                    return vec![1, 3, dims[0], dims[1]];
-                }
-            }
-            if let Some(shape) = pre.get("shape").and_then(|v| v.as_array()) {
-                let dims: Vec<i64> = shape.iter()
-                    .filter_map(|v| v.as_i64())
-                    .collect();
-                if dims.len() == 4 {
-                    return dims;
-                }
-            }
+    /// Run preprocessed request data, which ORT reads in place rather than copying
+    fn run_request(
+        &self,
+        run_options: &RunOptions,
+        input_shape: Vec<i64>,
+        input_data: &[f32],
+        image_size: Option<(u32, u32)>,
+    ) -> Result<Vec<Tensor>, BackendError> {
+        let request = ort::value::TensorRef::from_array_view((input_shape, input_data))
+            .map_err(|e| BackendError::InvalidInput(format!("Failed to create input tensor '{}': {}", self.input_name, e)))?;
+        let mut inputs = vec![(self.input_name.clone(), SessionInputValue::from(request))];
+        for spec in &self.auxiliary_inputs {
+            inputs.push(self.session_input(auxiliary_input(spec, image_size, self.input_size())?)?);
         }
-        // Default to YOLOv8 640x640 input
-        vec![1, 3, 640, 640]
+        self.run_values(run_options, inputs)
     }
 
-    /// Prepare NCHW float32 tensor data from a DynamicImage
-    /// Returns (shape, data) tuple suitable for ort::Tensor::from_array
-    fn prepare_input_from_image(
+    /// Run caller-provided tensors, keeping the outputs' element types
+    ///
+    /// A single unnamed tensor feeds the model's request input. Declared
+    /// auxiliary inputs the caller leaves out are filled in.
+    fn run_tensors(
         &self,
-        img: &image::DynamicImage,
-        input_shape: &[i64],
-    ) -> Result<(Vec<i64>, Vec<f32>), BackendError> {
-        if input_shape.len() != 4 {
-            return Err(BackendError::InferenceFailed(format!(
-                "invalid input shape {:?}: expected 4D NCHW",
-                input_shape,
-            )));
+        run_options: &RunOptions,
+        mut tensors: Vec<Tensor>,
+        image_size: Option<(u32, u32)>,
+    ) -> Result<Vec<Tensor>, BackendError> {
+        if tensors.len() > 1 && tensors.iter().any(|tensor| tensor.name.is_empty()) {
+            return Err(BackendError::InvalidInput("Every tensor needs a name when a request has several".to_string()));
         }
-        let channels = input_shape[1] as usize;
-        let height = input_shape[2] as u32;
-        let width = input_shape[3] as u32;
-
-        debug!("Resizing image to {}x{} for model input", width, height);
-        let resized = img.resize_exact(width, height, image::imageops::FilterType::Triangle);
-        let rgb = resized.to_rgb8();
-
-        let h = height as usize;
-        let w = width as usize;
-        let mut tensor_data = vec![0.0f32; channels * h * w];
-
-        for y in 0..h {
-            for x in 0..w {
-                let pixel = rgb.get_pixel(x as u32, y as u32);
-                for c in 0..channels.min(3) {
-                    tensor_data[c * h * w + y * w + x] = pixel[c] as f32 / 255.0;
-                }
+
+        for spec in &self.auxiliary_inputs {
+            if !tensors.iter().any(|tensor| tensor.name == spec.name) {
+                tensors.push(auxiliary_input(spec, image_size, self.input_size())?);
             }
         }
 
-        Ok((input_shape.to_vec(), tensor_data))
-    }
+        let inputs = tensors.into_iter()
+            .map(|tensor| self.session_input(tensor))
+            .collect::<Result<Vec<_>, BackendError>>()?;
 
-    /// Run inference through the ONNX session
-    ///
-    /// Returns the real output tensor shape reported by ORT alongside the flat data,
-    /// so postprocessing does not have to guess dimensions from `num_classes` (which
-    /// is unreliable when `class_labels` is empty).
-    fn run_session_inference(
-        &self,
-        model: &OnnxModel,
-        input_shape: Vec<i64>,
-        input_data: Vec<f32>,
-    ) -> Result<(Vec<usize>, Vec<f32>), BackendError> {
-        debug!("Running ONNX session inference for model '{}'", model.name);
+        self.run_values(run_options, inputs)
+    }
 
-        let tensor = ort::value::Tensor::from_array((input_shape, input_data))
-            .map_err(|e| BackendError::InferenceFailed(format!("Failed to create input tensor: {}", e)))?;
+    /// ORT input for a tensor; an unnamed tensor feeds the model's request input
+    fn session_input(&self, tensor: Tensor) -> Result<(String, SessionInputValue<'static>), BackendError> {
+        let name = if tensor.name.is_empty() { self.input_name.clone() } else { tensor.name };
+        let shape: Vec<i64> = tensor.shape.iter().map(|&d| d as i64).collect();
+        let value = match tensor.data {
+            TensorData::F32(values) => ort::value::Tensor::from_array((shape, values)).map(|t| t.upcast()),
+            TensorData::F64(values) => ort::value::Tensor::from_array((shape, values)).map(|t| t.upcast()),
+            TensorData::I32(values) => ort::value::Tensor::from_array((shape, values)).map(|t| t.upcast()),
+            TensorData::I64(values) => ort::value::Tensor::from_array((shape, values)).map(|t| t.upcast()),
+            TensorData::U8(values) => ort::value::Tensor::from_array((shape, values)).map(|t| t.upcast()),
+        }
+        .map_err(|e| BackendError::InvalidInput(format!("Failed to create input tensor '{}': {}", name, e)))?;
+        Ok((name, value.into()))
+    }
 
-        let inputs = ort::inputs![&model.input_name => tensor.upcast()];
+    /// Run the session on named inputs, returning every output with the
+    /// tensor shape reported by ORT
+    fn run_values(
+        &self,
+        run_options: &RunOptions,
+        inputs: Vec<(String, SessionInputValue<'_>)>,
+    ) -> Result<Vec<Tensor>, BackendError> {
+        debug!("Running ONNX session inference for model '{}'", self.name);
 
-        let mut session = model.session.lock()
+        let mut session = self.session.lock()
             .map_err(|e| BackendError::InferenceFailed(format!("Failed to lock session mutex: {}", e)))?;
 
         let outputs = session
-            .run(inputs)
+            .run_with_options(inputs, run_options)
             .map_err(|e| BackendError::InferenceFailed(format!("ONNX session run failed: {}", e)))?;
 
-        let (shape, data) = outputs[0]
-            .try_extract_tensor::<f32>()
-            .map_err(|e| BackendError::InferenceFailed(format!("Failed to extract output tensor: {}", e)))?;
-
-        debug!("Output tensor shape: {:?}", shape);
-        let shape_vec: Vec<usize> = shape.iter().map(|&d| d as usize).collect();
-        Ok((shape_vec, data.to_vec()))
-    }
+        let mut extracted = Vec::with_capacity(outputs.len());
+        for (name, value) in outputs.iter() {
+            let (shape, data) = if let Ok((shape, data)) = value.try_extract_tensor::<f32>() {
+                (shape.to_vec(), TensorData::F32(data.to_vec()))
+            } else if let Ok((shape, data)) = value.try_extract_tensor::<i64>() {
+                (shape.to_vec(), TensorData::I64(data.to_vec()))
+            } else if let Ok((shape, data)) = value.try_extract_tensor::<i32>() {
+                (shape.to_vec(), TensorData::I32(data.to_vec()))
+            } else if let Ok((shape, data)) = value.try_extract_tensor::<f64>() {
+                (shape.to_vec(), TensorData::F64(data.to_vec()))
+            } else if let Ok((shape, data)) = value.try_extract_tensor::<u8>() {
+                (shape.to_vec(), TensorData::U8(data.to_vec()))
+            } else {
+                return Err(BackendError::InferenceFailed(format!(
+                    "Failed to extract output tensor '{}': unsupported element type",
+                    name,
+                )));
+            };
 
-    /// Process YOLOv8 output tensor
-    /// Input shape: [1, 4+num_classes, num_detections] (raw from model)
-    /// Must transpose to iterate per-detection: [num_detections, 4+num_classes]
-    fn process_yolov8_output(
-        &self,
-        output_data: &[f32],
-        output_shape: &[usize],
-        model: &OnnxModel,
-    ) -> Result<Vec<crate::Prediction>, BackendError> {
-        if output_shape.len() != 3 || output_shape[0] != 1 {
-            return Err(BackendError::PostprocessingFailed(
-                format!("Unexpected YOLOv8 output shape: {:?}", output_shape),
-            ));
+            debug!("Output tensor '{}' shape: {:?}", name, shape);
+            extracted.push(Tensor {
+                name: name.to_string(),
+                shape: shape.iter().map(|&d| d as usize).collect(),
+                data,
+            });
         }
 
-        let rows = output_shape[1]; // 4 + num_classes (5 for single-class)
-        let cols = output_shape[2]; // num_detections (8400)
-        let num_classes = rows.saturating_sub(4);
-
-        if num_classes == 0 {
-            return Err(BackendError::PostprocessingFailed(
-                format!("Output has {} rows, need at least 5 (4 box + 1 class)", rows),
-            ));
+        if extracted.is_empty() {
+            return Err(BackendError::InferenceFailed("ONNX session returned no outputs".to_string()));
         }
 
-        // Validate against configured class labels. A mismatch usually means a
-        // model+config mismatch (e.g. COCO labels paired with a single-class model)
-        // which silently produces nonsense detections, so fail loudly instead.
-        if !model.class_labels.is_empty() && model.class_labels.len() != num_classes {
-            return Err(BackendError::PostprocessingFailed(format!(
-                "Model '{}' output has {} classes but {} class labels are configured; \
-                 check that postprocessing.class_labels matches the model",
-                model.name,
-                num_classes,
-                model.class_labels.len(),
-            )));
+        if !self.output_order.is_empty() {
+            extracted.sort_by_key(|output| {
+                self.output_order.iter().position(|name| *name == output.name).unwrap_or(usize::MAX)
+            });
         }
 
-        debug!("Processing YOLOv8 output: {} detections, {} classes", cols, num_classes);
-
-        let mut detections: Vec<RawDetection> = Vec::new();
-
-        for det_idx in 0..cols {
-            // Data is laid out as [1, rows, cols] in row-major:
-            // output_data[row * cols + det_idx]
-            let cx = output_data[det_idx];
-            let cy = output_data[cols + det_idx];
-            let w = output_data[2 * cols + det_idx];
-            let h = output_data[3 * cols + det_idx];
-
-            // Find best class
-            let mut best_conf = 0.0f32;
-            let mut best_class = 0usize;
-            for c in 0..num_classes {
-                let conf = output_data[(4 + c) * cols + det_idx];
-                if conf > best_conf {
-                    best_conf = conf;
-                    best_class = c;
-                }
-            }
+        Ok(extracted)
+    }
+
+    fn infer_image(&self, run_options: &RunOptions, image: &image::DynamicImage) -> Result<Vec<Prediction>, BackendError> {
+        // Prepare input tensor from image in a pooled buffer
+        let image_input = self.image_input()?;
+        let (input_data, _) = image_input.prepare(image)
+            .map_err(|e| BackendError::InvalidInput(format!("Failed to preprocess image: {}", e)))?;
+        let input_shape = image_batch_shape(image_input, 1);
+        debug!("Input tensor shape: {:?}", input_shape);
+
+        // Run real ONNX session inference (shapes are the authoritative shapes reported by ORT)
+        let outputs = self.run_session(run_options, input_shape, &input_data, Some((image.width(), image.height())))?;
+        self.output_decoder().decode(&outputs)
+    }
 
-            if best_conf >= model.confidence_threshold {
-                detections.push(RawDetection {
-                    x1: cx - w / 2.0,
-                    y1: cy - h / 2.0,
-                    x2: cx + w / 2.0,
-                    y2: cy + h / 2.0,
-                    confidence: best_conf,
-                    class_id: best_class,
-                });
+    /// Run stacked images through a single session call
+    ///
+    /// Images that fail preprocessing get their own error and are left out of the stack.
+    fn infer_image_batch(
+        &self,
+        run_options: &RunOptions,
+        images: &[&image::DynamicImage],
+    ) -> Vec<Result<Vec<Prediction>, BackendError>> {
+        let image_input = match self.image_input() {
+            Ok(image_input) => image_input,
+            Err(e) => return images.iter().map(|_| Err(BackendError::InferenceFailed(e.to_string()))).collect(),
+        };
+        let mut results: Vec<Result<Vec<Prediction>, BackendError>> = Vec::with_capacity(images.len());
+        let mut stacked_indices = Vec::with_capacity(images.len());
+
+        // Images are preprocessed straight into their slot of one pooled buffer
+        let item_len = image_input.tensor_len();
+        let mut stacked = image_input.buffer(images.len() * item_len);
+        for (index, image) in images.iter().enumerate() {
+            let offset = stacked_indices.len() * item_len;
+            match image_input.fill(image, &mut stacked[offset..offset + item_len]) {
+                Ok(_) => {
+                    stacked_indices.push(index);
+                    results.push(Ok(Vec::new()));
+                }
+                Err(e) => results.push(Err(BackendError::InvalidInput(format!("Failed to preprocess image: {}", e)))),
             }
         }
+        if stacked_indices.is_empty() {
+            return results;
+        }
 
-        debug!("Found {} detections above threshold {}", detections.len(), model.confidence_threshold);
-
-        // Apply NMS
-        let kept = Self::apply_nms(&mut detections, model.nms_threshold);
-        debug!("After NMS: {} detections", kept.len());
-
-        // Convert to Prediction
-        let predictions: Vec<crate::Prediction> = kept.into_iter().map(|det| {
-            let class_name = model.class_labels.get(det.class_id)
-                .cloned()
-                .unwrap_or_else(|| format!("class_{}", det.class_id));
-
-            crate::Prediction {
-                class: class_name,
-                confidence: det.confidence,
-                bbox: Some([det.x1, det.y1, det.x2, det.y2]),
-                metadata: {
-                    let mut map = HashMap::new();
-                    map.insert("backend".to_string(), serde_json::Value::String("onnx-runtime".to_string()));
-                    map.insert("class_index".to_string(), serde_json::Value::Number((det.class_id as u64).into()));
-                    map.insert("model_name".to_string(), serde_json::Value::String(model.name.clone()));
-                    map
-                },
-                severity: if det.confidence > 0.7 { Some("high".to_string()) }
-                         else if det.confidence > 0.4 { Some("medium".to_string()) }
-                         else { Some("low".to_string()) },
+        let batch_size = stacked_indices.len();
+        stacked.truncate(batch_size * item_len);
+        let input_shape = image_batch_shape(image_input, batch_size);
+        debug!("Batched input tensor shape: {:?}", input_shape);
+
+        let decoder = self.output_decoder();
+        let per_input = self.run_session(run_options, input_shape, &stacked, None)
+            .and_then(|outputs| split_batch_outputs(outputs, batch_size));
+        match per_input {
+            Ok(per_input) => {
+                for (index, outputs) in stacked_indices.into_iter().zip(per_input) {
+                    results[index] = decoder.decode(&outputs);
+                }
+            }
+            Err(e) => {
+                let message = e.to_string();
+                for index in stacked_indices {
+                    results[index] = Err(BackendError::InferenceFailed(message.clone()));
+                }
             }
-        }).collect();
+        }
 
-        Ok(predictions)
+        results
     }
 
-    /// Process classification-style output (flat logits/probabilities)
-    fn process_classification_output(
+    /// Run the model once per sensor window
+    ///
+    /// Forecasting models only see the most recent window, since earlier
+    /// windows would forecast into the observed past.
+    fn infer_time_series(
         &self,
-        output_data: &[f32],
-        model: &OnnxModel,
-    ) -> Result<Vec<crate::Prediction>, BackendError> {
-        let mut indexed: Vec<(usize, f32)> = output_data.iter().enumerate()
-            .map(|(i, &v)| (i, v))
-            .collect();
-        indexed.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-        let predictions: Vec<crate::Prediction> = indexed.iter()
-            .take(model.top_k)
-            .filter(|(_, conf)| *conf > model.confidence_threshold)
-            .map(|(class_idx, confidence)| {
-                let class_name = model.class_labels.get(*class_idx)
-                    .cloned()
-                    .unwrap_or_else(|| format!("class_{}", class_idx));
-
-                crate::Prediction {
-                    class: class_name,
-                    confidence: *confidence,
-                    bbox: None,
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("backend".to_string(), serde_json::Value::String("onnx-runtime".to_string()));
-                        map.insert("class_index".to_string(), serde_json::Value::Number((*class_idx as u64).into()));
-                        map.insert("model_name".to_string(), serde_json::Value::String(model.name.clone()));
-                        map
-                    },
-                    severity: if *confidence > 0.7 { Some("high".to_string()) }
-                             else if *confidence > 0.4 { Some("medium".to_string()) }
-                             else { Some("low".to_string()) },
-                }
+        run_options: &RunOptions,
+        values: &[f32],
+        timestamps: &[i64],
+    ) -> Result<(Vec<Prediction>, serde_json::Value), BackendError> {
+        let window_config = self.sensor_window.clone().unwrap_or_default();
+        let mut windows = build_windows(values, timestamps, &window_config)
+            .map_err(|e| BackendError::InvalidInput(format!("Failed to window sensor data: {}", e)))?;
+        if self.settings.time_series_output == Some(TimeSeriesOutputKind::Forecast) {
+            windows.drain(..windows.len() - 1);
+        }
+        debug!("Sensor input split into {} windows of shape {:?}", windows.len(), windows[0].shape);
+
+        let outputs = windows.iter()
+            .map(|window| self.run_session(run_options, window.shape.clone(), &window.data, None))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.output_decoder().decode_time_series(&windows, &outputs, &window_config, timestamps)
+    }
+
+    /// Run the model once per audio segment
+    fn infer_audio(&self, run_options: &RunOptions, clip: &AudioClip) -> Result<(Vec<Prediction>, serde_json::Value), BackendError> {
+        let config = self.audio.clone().unwrap_or_default();
+        let segments = audio::segment_clip(clip, &config)
+            .map_err(|e| BackendError::InvalidInput(format!("Failed to segment audio: {}", e)))?;
+        debug!("Audio input split into {} segments of {}s", segments.len(), config.segment_seconds);
+
+        let outputs = segments.iter()
+            .map(|segment| {
+                let (shape, data) = audio::extract_features(&segment.samples, &config)
+                    .map_err(|e| BackendError::InferenceFailed(format!("Audio feature extraction failed: {}", e)))?;
+                self.run_session(run_options, shape, &data, None)
             })
-            .collect();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(predictions)
+        self.output_decoder().decode_audio(&segments, &outputs)
     }
 
-    /// Greedy NMS: suppress overlapping detections
-    fn apply_nms(detections: &mut [RawDetection], nms_threshold: f32) -> Vec<RawDetection> {
-        detections.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
-        let mut keep: Vec<RawDetection> = Vec::new();
-        let mut suppressed = vec![false; detections.len()];
+    fn inference_result(&self, predictions: Vec<Prediction>, elapsed_ms: f64) -> InferenceResult {
+        let confidence = predictions.iter()
+            .map(|p| p.confidence)
+            .fold(0.0f32, f32::max);
+
+        InferenceResult {
+            model_name: self.name.clone(),
+            model_type: "onnx".to_string(),
+            predictions,
+            confidence,
+            inference_time_ms: elapsed_ms,
+            metadata: serde_json::json!({
+                "backend": "onnx-runtime",
+                "model_path": self.model_path,
+                "inference_type": "real",
+                "request_id": uuid::Uuid::new_v4().to_string()
+            }),
+            tensors: None,
+        }
+    }
 
-        for i in 0..detections.len() {
-            if suppressed[i] { continue; }
-            keep.push(detections[i].clone());
-            for j in (i + 1)..detections.len() {
-                if suppressed[j] { continue; }
-                if detections[i].class_id == detections[j].class_id
-                    && Self::iou(&detections[i], &detections[j]) > nms_threshold
-                {
-                    suppressed[j] = true;
-                }
-            }
+    fn raw_result(&self, outputs: Vec<Tensor>, start: Instant) -> InferenceResult {
+        let mut result = self.inference_result(Vec::new(), start.elapsed().as_secs_f64() * 1000.0);
+        result.metadata["output_mode"] = serde_json::json!("raw");
+        result.tensors = Some(outputs);
+        result
+    }
+
+    fn image_input(&self) -> Result<&ImageInputPipeline, BackendError> {
+        require_image_input(self.image_input.as_ref(), &self.input_shape)
+    }
+
+    /// Model input `[width, height]`, which detection boxes are relative to
+    fn input_size_json(&self) -> serde_json::Value {
+        serde_json::json!([self.input_shape[3], self.input_shape[2]])
+    }
+
+    /// Model input `(width, height)` in pixels
+    fn input_size(&self) -> (f32, f32) {
+        (self.input_shape[3] as f32, self.input_shape[2] as f32)
+    }
+
+    fn output_decoder(&self) -> OutputDecoder<'_> {
+        OutputDecoder {
+            settings: &self.settings,
+            model_name: &self.name,
+            backend: "onnx-runtime",
+            input_size: self.input_size(),
         }
-        keep
     }
+}
 
-    /// Intersection-over-Union for two bounding boxes
-    fn iou(a: &RawDetection, b: &RawDetection) -> f32 {
-        let inter_x1 = a.x1.max(b.x1);
-        let inter_y1 = a.y1.max(b.y1);
-        let inter_x2 = a.x2.min(b.x2);
-        let inter_y2 = a.y2.min(b.y2);
+/// Bind declared inputs and outputs to the session's signature
+///
+/// Returns the name of the input fed by the request. Declared tensors the
+/// model lacks, model inputs nothing feeds, and conflicting shapes or element
+/// types fail the load instead of the first inference.
+#[cfg(feature = "onnx-runtime")]
+fn bind_signature(
+    model_name: &str,
+    session: &ort::session::Session,
+    inputs: &[InputTensorSpec],
+    outputs: &[(String, Vec<i64>)],
+) -> Result<String, BackendError> {
+    let mismatch = |message: String| BackendError::ModelLoadFailed(format!("Model '{}': {}", model_name, message));
+    let input_names: Vec<&str> = session.inputs().iter().map(|i| i.name()).collect();
+    let output_names: Vec<&str> = session.outputs().iter().map(|o| o.name()).collect();
+
+    let request_input = if inputs.is_empty() {
+        if input_names.len() > 1 {
+            return Err(mismatch(format!(
+                "model has inputs {:?}; declare them under input.tensors", input_names,
+            )));
+        }
+        input_names.first().map(|name| name.to_string()).unwrap_or_else(|| "images".to_string())
+    } else {
+        for spec in inputs {
+            let outlet = session.inputs().iter().find(|i| i.name() == spec.name)
+                .ok_or_else(|| mismatch(format!("model has no input '{}'; its inputs are {:?}", spec.name, input_names)))?;
+            let shape: Option<Vec<i64>> = outlet.dtype().tensor_shape().map(|shape| shape.to_vec());
+            if let Some(shape) = shape.filter(|shape| !shapes_compatible(&spec.shape, shape)) {
+                return Err(mismatch(format!(
+                    "input '{}' is declared with shape {:?} but the model expects {:?}", spec.name, spec.shape, shape,
+                )));
+            }
+            let declared = spec.element_type().map_err(&mismatch)?;
+            if let Some(actual) = outlet.dtype().tensor_type().and_then(tensor_dtype).filter(|actual| *actual != declared) {
+                return Err(mismatch(format!(
+                    "input '{}' is declared as {:?} but the model expects {:?}", spec.name, declared, actual,
+                )));
+            }
+        }
+        if let Some(unbound) = input_names.iter().find(|name| !inputs.iter().any(|spec| spec.name == **name)) {
+            return Err(mismatch(format!("model input '{}' is not declared under input.tensors", unbound)));
+        }
+        inputs.iter()
+            .find(|spec| spec.semantic == InputSemantic::Input)
+            .map(|spec| spec.name.clone())
+            .unwrap_or_default()
+    };
+
+    for (name, declared_shape) in outputs {
+        let outlet = session.outputs().iter().find(|o| o.name() == name)
+            .ok_or_else(|| mismatch(format!("model has no output '{}'; its outputs are {:?}", name, output_names)))?;
+        if let Some(shape) = outlet.dtype().tensor_shape().filter(|shape| !shapes_compatible(declared_shape, shape)) {
+            return Err(mismatch(format!(
+                "output '{}' is declared with shape {:?} but the model produces {:?}", name, declared_shape, shape.to_vec(),
+            )));
+        }
+    }
 
-        let inter_area = (inter_x2 - inter_x1).max(0.0) * (inter_y2 - inter_y1).max(0.0);
-        let area_a = (a.x2 - a.x1) * (a.y2 - a.y1);
-        let area_b = (b.x2 - b.x1) * (b.y2 - b.y1);
-        let union_area = area_a + area_b - inter_area;
+    Ok(request_input)
+}
 
-        if union_area <= 0.0 { 0.0 } else { inter_area / union_area }
+/// Element types that requests and results can carry
+#[cfg(feature = "onnx-runtime")]
+fn tensor_dtype(element_type: ort::value::TensorElementType) -> Option<TensorDType> {
+    use ort::value::TensorElementType;
+    match element_type {
+        TensorElementType::Float32 => Some(TensorDType::F32),
+        TensorElementType::Float64 => Some(TensorDType::F64),
+        TensorElementType::Int32 => Some(TensorDType::I32),
+        TensorElementType::Int64 => Some(TensorDType::I64),
+        TensorElementType::Uint8 => Some(TensorDType::U8),
+        _ => None,
     }
 }
 
@@ -447,44 +650,85 @@ impl InferenceBackend for OnnxRuntimeBackend {
             return Err(BackendError::BackendNotInitialized("Environment not initialized".to_string()));
         }
 
+        // A pinned digest is checked against the exact bytes handed to the
+        // session, so the file cannot change between verification and loading
+        let verified_bytes = match &model_config.integrity {
+            Some(expected) => {
+                let bytes = tokio::fs::read(&model_config.model_path).await
+                    .map_err(|e| BackendError::ModelLoadFailed(format!("Failed to read ONNX model '{}': {}", model_config.model_path, e)))?;
+                if let Err(e) = integrity::verify_bytes(&model_config.model_path, &bytes, &expected.sha256) {
+                    error!("Refusing to load model '{}': {}", model_name, e);
+                    self.integrity_failures.insert(model_name.to_string(), e.to_string());
+                    return Err(BackendError::IntegrityCheckFailed(e.to_string()));
+                }
+                Some(bytes)
+            }
+            None => None,
+        };
+        self.integrity_failures.remove(model_name);
+
         // Build ort session from ONNX file
-        let session = ort::session::Session::builder()
-            .map_err(|e| BackendError::ModelLoadFailed(format!("Failed to create session builder: {}", e)))?
-            .commit_from_file(&model_config.model_path)
-            .map_err(|e| BackendError::ModelLoadFailed(format!("Failed to load ONNX model '{}': {}", model_config.model_path, e)))?;
-
-        // Extract input/output names from session metadata
-        let input_name = session.inputs().first()
-            .map(|i| i.name().to_string())
-            .unwrap_or_else(|| "images".to_string());
+        let mut builder = ort::session::Session::builder()
+            .map_err(|e| BackendError::ModelLoadFailed(format!("Failed to create session builder: {}", e)))?;
+        let session = match &verified_bytes {
+            Some(bytes) => builder.commit_from_memory(bytes),
+            None => builder.commit_from_file(&model_config.model_path),
+        }
+        .map_err(|e| BackendError::ModelLoadFailed(format!("Failed to load ONNX model '{}': {}", model_config.model_path, e)))?;
+
+        // Bind declared inputs and outputs by name against the session's signature
+        let input_bindings = parse_input_bindings(model_config)?;
+        let output_bindings = parse_output_bindings(model_config);
+        let input_name = bind_signature(model_name, &session, &input_bindings, &output_bindings)?;
         info!("Model input name: '{}'", input_name);
+        let auxiliary_inputs: Vec<InputTensorSpec> = input_bindings.into_iter()
+            .filter(|spec| spec.semantic != InputSemantic::Input)
+            .collect();
+
+        // A symbolic (negative) leading dimension lets requests be stacked into one run
+        let dynamic_batch = session.inputs().iter()
+            .find(|i| i.name() == input_name)
+            .and_then(|i| i.dtype().tensor_shape())
+            .and_then(|shape| shape.first().copied())
+            .is_some_and(|dim| dim < 0);
 
         // Parse postprocessing config from ModelConfig
-        let (class_labels, confidence_threshold, nms_threshold, top_k, postprocess_type) =
-            Self::parse_postprocessing(model_config);
+        let settings = OutputSettings::from_model_config(model_config, "classification")?;
 
-        // Parse input shape
-        let input_shape = Self::parse_input_shape(model_config);
+        // Parse input shape, defaulting to YOLOv8 640x640 input
+        let input_shape = parse_input_shape(model_config, &[1, 3, 640, 640]);
+        let image_input = image_input_pipeline(&input_shape, parse_normalization(model_config).as_ref());
+        let sensor_window = parse_sensor_window(model_config, &settings)?;
+        let audio = parse_audio_config(model_config)?;
 
         info!(
-            "Model '{}': postprocess={}, classes={:?}, conf_thresh={}, nms_thresh={}, top_k={}, input_shape={:?}",
-            model_name, postprocess_type, class_labels, confidence_threshold, nms_threshold, top_k, input_shape
+            "Model '{}': postprocess={}, classes={:?}, conf_thresh={}, suppression={:?}, nms_thresh={}, top_k={}, input_shape={:?}",
+            model_name, settings.postprocess_type, settings.class_labels, settings.confidence_threshold,
+            settings.suppression.method, settings.suppression.iou_threshold, settings.top_k, input_shape
         );
 
+        let memory_mb = match &verified_bytes {
+            Some(bytes) => bytes.len() as f64 / (1024.0 * 1024.0),
+            None => artifact_memory_mb(&model_config.model_path),
+        };
+
         let model = OnnxModel {
             name: model_name.to_string(),
             model_path: model_config.model_path.clone(),
             session: Mutex::new(session),
             input_name,
             input_shape,
-            class_labels,
-            confidence_threshold,
-            nms_threshold,
-            top_k,
-            postprocess_type,
+            auxiliary_inputs,
+            output_order: output_bindings.into_iter().map(|(name, _)| name).collect(),
+            dynamic_batch,
+            image_input,
+            sensor_window,
+            audio,
+            settings,
+            memory_mb,
         };
 
-        self.loaded_models.insert(model_name.to_string(), model);
+        self.loaded_models.insert(model_name.to_string(), Arc::new(model));
         match self.stats.lock() {
             Ok(mut stats) => {
                 stats.models_loaded += 1;
@@ -518,82 +762,88 @@ impl InferenceBackend for OnnxRuntimeBackend {
     }
 
     async fn infer(&self, input: InferenceInput, model_name: Option<&str>) -> Result<InferenceResult, BackendError> {
-        let start = std::time::Instant::now();
+        let start = Instant::now();
 
-        let result = (|| -> Result<InferenceResult, BackendError> {
-            if !self.environment_initialized {
-                return Err(BackendError::BackendNotInitialized("Environment not initialized".to_string()));
+        let result = match self.session_model(model_name) {
+            Ok(model) => {
+                debug!("Running ONNX inference with model '{}'", model.name);
+                run_blocking(move |run_options| model.infer(input, run_options, start)).await
             }
+            Err(e) => Err(e),
+        };
 
-            let model_key = model_name.unwrap_or("default");
-            debug!("Running ONNX inference with model '{}'", model_key);
-
-            let model = self.loaded_models.get(model_key)
-                .or_else(|| {
-                    if model_key == "default" {
-                        let fallback = self.loaded_models.values().next();
-                        if let Some(m) = fallback {
-                            info!("Model 'default' not found, falling back to '{}'", m.name);
-                        }
-                        fallback
-                    } else {
-                        None
-                    }
-                })
-                .ok_or_else(|| BackendError::ModelLoadFailed(format!("Model '{}' not loaded", model_key)))?;
-
-            // Extract DynamicImage from input
-            let image = match &input {
-                InferenceInput::Image { data, metadata: _ } => data,
-                InferenceInput::TimeSeries { .. } => {
-                    return Err(BackendError::InferenceFailed(
-                        "ONNX backend does not support time series input".to_string(),
-                    ));
-                }
-            };
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+        self.record_inference_stats(elapsed_ms, result.is_ok());
 
-            // Prepare input tensor from image
-            let (input_shape, input_data) = self.prepare_input_from_image(image, &model.input_shape)?;
-            debug!("Input tensor shape: {:?}", input_shape);
+        result
+    }
 
-            // Run real ONNX session inference (shape is the authoritative shape reported by ORT)
-            let (output_shape, output_data) = self.run_session_inference(model, input_shape, input_data)?;
-            debug!("Output shape: {:?}, postprocess_type: {}", output_shape, model.postprocess_type);
+    fn supports_batching(&self, model_name: Option<&str>) -> bool {
+        self.environment_initialized
+            && self.resolve_model(model_name).is_ok_and(|model| {
+                model.dynamic_batch && model.auxiliary_inputs.is_empty() && !model.settings.raw_output()
+            })
+    }
 
-            let predictions = match model.postprocess_type.as_str() {
-                "yolov8" | "yolo" | "yolov5" => {
-                    self.process_yolov8_output(&output_data, &output_shape, model)?
-                }
-                _ => {
-                    self.process_classification_output(&output_data, model)?
-                }
-            };
+    async fn infer_batch(&self, inputs: Vec<InferenceInput>, model_name: Option<&str>) -> Vec<Result<InferenceResult, BackendError>> {
+        let all_images = inputs.iter().all(|input| matches!(input, InferenceInput::Image { .. }));
+        if !all_images || !self.supports_batching(model_name) {
+            // Fixed batch dimension or non-image input: run each input on its own
+            let mut results = Vec::with_capacity(inputs.len());
+            for input in inputs {
+                results.push(self.infer(input, model_name).await);
+            }
+            return results;
+        }
 
-            let confidence = predictions.iter()
-                .map(|p| p.confidence)
-                .fold(0.0f32, f32::max);
-
-            let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
-
-            Ok(InferenceResult {
-                model_name: model.name.clone(),
-                model_type: "onnx".to_string(),
-                predictions,
-                confidence,
-                inference_time_ms: elapsed_ms,
-                metadata: serde_json::json!({
-                    "backend": "onnx-runtime",
-                    "model_path": model.model_path,
-                    "inference_type": "real",
-                    "request_id": uuid::Uuid::new_v4().to_string()
-                }),
+        let start = Instant::now();
+        let batch_size = inputs.len();
+        let images: Vec<image::DynamicImage> = inputs.into_iter()
+            .filter_map(|input| match input {
+                InferenceInput::Image { data, .. } => Some(data),
+                _ => None,
             })
-        })();
+            .collect();
+
+        let results = match self.session_model(model_name) {
+            Ok(model) => run_blocking(move |run_options| {
+                let images: Vec<&image::DynamicImage> = images.iter().collect();
+                let predictions = model.infer_image_batch(run_options, &images);
+                let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+                Ok(predictions.into_iter()
+                    .map(|predictions| predictions.map(|predictions| {
+                        let mut result = model.inference_result(predictions, elapsed_ms);
+                        result.metadata["batch_size"] = serde_json::json!(batch_size);
+                        result.metadata["input_size"] = model.input_size_json();
+                        result
+                    }))
+                    .collect::<Vec<_>>())
+            }).await,
+            Err(e) => Err(e),
+        };
 
         let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
-        self.record_inference_stats(elapsed_ms, result.is_ok());
+        match results {
+            Ok(results) => {
+                for result in &results {
+                    self.record_inference_stats(elapsed_ms, result.is_ok());
+                }
+                results
+            }
+            Err(e) => {
+                let message = e.to_string();
+                (0..batch_size)
+                    .map(|_| {
+                        self.record_inference_stats(elapsed_ms, false);
+                        Err(BackendError::InferenceFailed(message.clone()))
+                    })
+                    .collect()
+            }
+        }
+    }
 
-        result
+    fn model_memory_mb(&self, model_name: &str) -> Option<f64> {
+        self.loaded_models.get(model_name).map(|model| model.memory_mb)
     }
 
     async fn get_loaded_models(&self) -> Vec<String> {
@@ -613,10 +863,12 @@ impl InferenceBackend for OnnxRuntimeBackend {
             initialized: self.environment_initialized,
             loaded_models: self.loaded_models.keys().cloned().collect(),
             device_type: DeviceType::Cpu,
-            memory_usage_mb: 0.0,
+            memory_usage_mb: self.loaded_models.values().map(|model| model.memory_mb).sum(),
             last_inference_time_ms: None,
             total_inferences,
-            errors: vec![],
+            errors: self.integrity_failures.iter()
+                .map(|(model, reason)| format!("Model '{}' rejected: {}", model, reason))
+                .collect(),
         }
     }
 
@@ -0,0 +1,1070 @@
+//! Backend-neutral input preparation and output decoding
+//!
+//! The ONNX Runtime and Candle backends both run a forward pass over an NCHW
+//! float tensor and hand back flat f32 outputs. Preparing the input and decoding
+//! the outputs here keeps the two backends producing the same predictions for
+//! the same model and configuration.
+
+use std::collections::HashMap;
+use tracing::debug;
+
+use crate::backend::BackendError;
+use crate::calibration::ScoreCalibration;
+use crate::image_input::{
+    ImageInputConfig, ImageInputPipeline, InputFormat, NormalizationConfig, ResampleFilter, ResizeStrategy,
+};
+use crate::postprocessing::{
+    ClassificationConfig, KeypointConfig, ScoreActivation, SegmentationConfig, SegmentationDecoder, SsdConfig, SsdDecoder, SsdOutputs, SsdTensorRole,
+};
+use crate::suppression::{ScoredBox, SuppressionConfig};
+use crate::timeseries::TimeSeriesOutputKind;
+#[cfg(feature = "onnx-runtime")]
+use crate::timeseries::{self, SensorWindow, SensorWindowConfig};
+#[cfg(feature = "onnx-runtime")]
+use crate::audio::{AudioFeatureConfig, AudioSegment};
+#[cfg(feature = "onnx-runtime")]
+use crate::model_config::{InputSemantic, InputTensorSpec, SizeOrder};
+use crate::tensors::{Tensor, TensorData};
+#[cfg(feature = "onnx-runtime")]
+use crate::tensors::TensorDType;
+use crate::types::MaskEncoding;
+use crate::{ModelConfig, Prediction};
+
+/// Output tensor produced by a backend forward pass
+#[derive(Debug)]
+pub(crate) struct RawOutput {
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub data: Vec<f32>,
+}
+
+impl From<Tensor> for RawOutput {
+    fn from(tensor: Tensor) -> Self {
+        let data = match tensor.data {
+            TensorData::F32(values) => values,
+            other => other.to_f32(),
+        };
+        Self { data, name: tensor.name, shape: tensor.shape }
+    }
+}
+
+impl From<RawOutput> for Tensor {
+    fn from(output: RawOutput) -> Self {
+        Self { name: output.name, shape: output.shape, data: TensorData::F32(output.data) }
+    }
+}
+
+/// Per-channel normalization applied after scaling pixels to [0, 1]
+#[derive(Debug, Clone)]
+pub(crate) struct InputNormalization {
+    pub mean: Vec<f32>,
+    pub std: Vec<f32>,
+}
+
+/// Postprocessing settings parsed from the `ModelConfig` postprocessing JSON
+#[derive(Debug, Clone)]
+pub(crate) struct OutputSettings {
+    pub class_labels: Vec<String>,
+    pub confidence_threshold: f32,
+    /// Duplicate suppression for detection outputs, with its IoU threshold and caps
+    pub suppression: SuppressionConfig,
+    /// Confidence calibration and per-class thresholds
+    pub calibration: ScoreCalibration,
+    pub top_k: usize,
+    pub postprocess_type: String,
+    /// Output tensor name -> semantic, from the YAML output spec
+    pub output_semantics: HashMap<String, String>,
+    pub ssd_config: Option<SsdConfig>,
+    pub segmentation_config: Option<SegmentationConfig>,
+    /// Keypoint layout, for pose postprocess types
+    pub keypoint_config: Option<KeypointConfig>,
+    /// Multi-label and open-set options, for outputs decoded as class scores
+    pub classification_config: Option<ClassificationConfig>,
+    /// Interpretation of sensor model outputs, for time-series postprocess types
+    pub time_series_output: Option<TimeSeriesOutputKind>,
+    /// Anomaly score or reconstruction error above which a window is anomalous
+    pub anomaly_threshold: Option<f32>,
+}
+
+/// Raw detection before suppression
+#[derive(Debug, Clone)]
+struct RawDetection {
+    x1: f32,
+    y1: f32,
+    x2: f32,
+    y2: f32,
+    confidence: f32,
+    class_id: usize,
+    /// Values after the class scores: mask coefficients (YOLOv8-seg) or keypoints (YOLOv8-pose)
+    trailing: Vec<f32>,
+}
+
+impl OutputSettings {
+    /// Parse postprocessing settings, using `default_postprocess_type` when the
+    /// configuration does not name one
+    pub fn from_model_config(model_config: &ModelConfig, default_postprocess_type: &str) -> Result<Self, BackendError> {
+        // Default top-K for classification outputs; overridable via postprocessing.top_k.
+        const DEFAULT_TOP_K: usize = 3;
+
+        let mut settings = Self {
+            class_labels: Vec::new(),
+            confidence_threshold: model_config.confidence_threshold.unwrap_or(0.5),
+            suppression: SuppressionConfig::from_postprocessing(model_config.postprocessing.as_ref(), 0.4)
+                .map_err(|e| BackendError::ConfigurationError(e.to_string()))?,
+            calibration: ScoreCalibration::from_postprocessing(model_config.postprocessing.as_ref())
+                .map_err(|e| BackendError::ConfigurationError(e.to_string()))?,
+            top_k: DEFAULT_TOP_K,
+            postprocess_type: default_postprocess_type.to_string(),
+            output_semantics: HashMap::new(),
+            ssd_config: None,
+            segmentation_config: None,
+            keypoint_config: None,
+            classification_config: None,
+            time_series_output: None,
+            anomaly_threshold: None,
+        };
+
+        if let Some(post) = &model_config.postprocessing {
+            if let Some(pt) = post.get("postprocess_type").and_then(|v| v.as_str()) {
+                settings.postprocess_type = pt.to_string();
+            }
+            if let Some(ct) = post.get("confidence_threshold").and_then(|v| v.as_f64()) {
+                settings.confidence_threshold = ct as f32;
+            }
+            if let Some(k) = post.get("top_k").and_then(|v| v.as_u64()) {
+                settings.top_k = k as usize;
+            }
+            if let Some(labels) = post.get("class_labels").and_then(|v| v.as_array()) {
+                settings.class_labels = labels.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect();
+            }
+        }
+
+        settings.output_semantics = Self::parse_output_semantics(model_config);
+
+        let parameters = Self::postprocessing_parameters(model_config);
+        if is_ssd(&settings.postprocess_type) {
+            settings.ssd_config = Some(SsdConfig::from_parameters(&parameters).map_err(|e| {
+                BackendError::ConfigurationError(format!("Invalid SSD postprocessing config: {}", e))
+            })?);
+        }
+        if is_semantic_segmentation(&settings.postprocess_type) || is_instance_segmentation(&settings.postprocess_type) {
+            settings.segmentation_config = Some(
+                SegmentationConfig::from_parameters(settings.class_labels.clone(), &parameters).map_err(|e| {
+                    BackendError::ConfigurationError(format!("Invalid segmentation postprocessing config: {}", e))
+                })?,
+            );
+        }
+
+        if is_pose(&settings.postprocess_type) {
+            settings.keypoint_config = Some(KeypointConfig::from_parameters(&parameters).map_err(|e| {
+                BackendError::ConfigurationError(format!("Invalid keypoint postprocessing config: {}", e))
+            })?);
+        }
+
+        if is_classification(&settings.postprocess_type) {
+            // Backends have always reported classifier outputs as-is, so the
+            // activation stays off unless configured
+            settings.classification_config = Some(
+                ClassificationConfig::from_parameters(
+                    settings.class_labels.clone(),
+                    settings.score_threshold(),
+                    settings.top_k,
+                    ScoreActivation::None,
+                    &parameters,
+                ).map_err(|e| {
+                    BackendError::ConfigurationError(format!("Invalid classification postprocessing config: {}", e))
+                })?,
+            );
+        }
+
+        settings.time_series_output = TimeSeriesOutputKind::from_postprocess_type(&settings.postprocess_type);
+        settings.anomaly_threshold = ["anomaly_threshold", "reconstruction_threshold"].iter()
+            .find_map(|key| parameters.get(*key).and_then(|v| v.as_f64()))
+            .map(|t| t as f32);
+
+        Ok(settings)
+    }
+
+    /// Threshold on raw scores equivalent to `confidence_threshold` on calibrated confidences
+    fn score_threshold(&self) -> f32 {
+        self.calibration.raw_threshold(self.confidence_threshold)
+    }
+
+    /// Whether the model's outputs are returned as tensors instead of predictions
+    pub fn raw_output(&self) -> bool {
+        matches!(self.postprocess_type.as_str(), "raw" | "none")
+    }
+
+    /// Parse output tensor semantics (`output_tensors: [{name, semantic}]`) from postprocessing JSON
+    fn parse_output_semantics(model_config: &ModelConfig) -> HashMap<String, String> {
+        model_config.postprocessing.as_ref()
+            .and_then(|post| post.get("output_tensors"))
+            .and_then(|v| v.as_array())
+            .map(|tensors| {
+                tensors.iter()
+                    .filter_map(|t| {
+                        let name = t.get("name")?.as_str()?;
+                        let semantic = t.get("semantic").and_then(|v| v.as_str()).unwrap_or("");
+                        Some((name.to_string(), semantic.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Postprocessing parameters from the nested YAML `parameters` section when
+    /// present, otherwise from the top level of the postprocessing JSON
+    fn postprocessing_parameters(model_config: &ModelConfig) -> HashMap<String, serde_json::Value> {
+        model_config.postprocessing.as_ref()
+            .and_then(|post| {
+                post.get("parameters")
+                    .filter(|p| p.is_object())
+                    .or(Some(post))
+            })
+            .and_then(|v| v.as_object())
+            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default()
+    }
+}
+
+fn is_ssd(postprocess_type: &str) -> bool {
+    matches!(postprocess_type, "ssd" | "ssd_mobilenet" | "efficientdet")
+}
+
+fn is_semantic_segmentation(postprocess_type: &str) -> bool {
+    matches!(postprocess_type, "segmentation" | "semantic_segmentation")
+}
+
+fn is_instance_segmentation(postprocess_type: &str) -> bool {
+    matches!(postprocess_type, "yolov8-seg" | "yolov8_seg" | "instance_segmentation")
+}
+
+fn is_pose(postprocess_type: &str) -> bool {
+    matches!(postprocess_type, "yolov8-pose" | "yolov8_pose" | "pose" | "keypoints")
+}
+
+/// Postprocess types without a dedicated decoder, whose outputs are read as class scores
+fn is_classification(postprocess_type: &str) -> bool {
+    !(matches!(postprocess_type, "yolov8" | "yolo" | "yolov5")
+        || is_ssd(postprocess_type)
+        || is_instance_segmentation(postprocess_type)
+        || is_pose(postprocess_type)
+        || is_semantic_segmentation(postprocess_type))
+}
+
+/// Parse input shape from ModelConfig preprocessing JSON, falling back to `default_shape`
+pub(crate) fn parse_input_shape(model_config: &ModelConfig, default_shape: &[i64]) -> Vec<i64> {
+    if let Some(pre) = &model_config.preprocessing {
+        if let Some(target_size) = pre.get("target_size").and_then(|v| v.as_array()) {
+            let dims: Vec<i64> = target_size.iter()
+                .filter_map(|v| v.as_i64())
+                .collect();
+            if dims.len() == 2 {
+                return vec![1, 3, dims[0], dims[1]];
+            }
+        }
+        if let Some(shape) = pre.get("shape").and_then(|v| v.as_array()) {
+            let dims: Vec<i64> = shape.iter()
+                .filter_map(|v| v.as_i64())
+                .collect();
+            if dims.len() == 4 {
+                // Dynamic (non-positive) dimensions fall back to the default size
+                return dims.iter().zip(default_shape)
+                    .map(|(&dim, &default)| if dim > 0 { dim } else { default })
+                    .collect();
+            }
+        }
+    }
+    default_shape.to_vec()
+}
+
+/// Parse `normalization: {mean, std}` from ModelConfig preprocessing JSON
+pub(crate) fn parse_normalization(model_config: &ModelConfig) -> Option<InputNormalization> {
+    let normalization = model_config.preprocessing.as_ref()?.get("normalization")?;
+    let values = |key: &str| -> Option<Vec<f32>> {
+        normalization.get(key)?
+            .as_array()?
+            .iter()
+            .map(|v| v.as_f64().map(|f| f as f32))
+            .collect()
+    };
+
+    let (mean, std) = (values("mean")?, values("std")?);
+    if mean.len() != std.len() || std.contains(&0.0) {
+        return None;
+    }
+    Some(InputNormalization { mean, std })
+}
+
+/// Parse sensor windowing from ModelConfig preprocessing JSON
+///
+/// Returns `None` for models without time-series preprocessing, which then
+/// consume the whole series as a single-channel window.
+#[cfg(feature = "onnx-runtime")]
+pub(crate) fn parse_sensor_window(
+    model_config: &ModelConfig,
+    settings: &OutputSettings,
+) -> Result<Option<SensorWindowConfig>, BackendError> {
+    let Some(pre) = &model_config.preprocessing else {
+        return Ok(None);
+    };
+    if pre.get("window").is_none() && pre.get("channels").is_none() && settings.time_series_output.is_none() {
+        return Ok(None);
+    }
+    SensorWindowConfig::from_preprocessing(pre)
+        .map(Some)
+        .map_err(|e| BackendError::ConfigurationError(format!("Invalid sensor preprocessing config: {}", e)))
+}
+
+/// Parse `audio` feature extraction settings from ModelConfig preprocessing JSON
+#[cfg(feature = "onnx-runtime")]
+pub(crate) fn parse_audio_config(model_config: &ModelConfig) -> Result<Option<AudioFeatureConfig>, BackendError> {
+    let Some(audio) = model_config.preprocessing.as_ref().and_then(|pre| pre.get("audio")) else {
+        return Ok(None);
+    };
+    let config: AudioFeatureConfig = serde_json::from_value(audio.clone())
+        .map_err(|e| BackendError::ConfigurationError(format!("Invalid audio preprocessing config: {}", e)))?;
+    config.validate()
+        .map_err(|e| BackendError::ConfigurationError(format!("Invalid audio preprocessing config: {}", e)))?;
+    Ok(Some(config))
+}
+
+/// Parse named model inputs (`input_tensors`) from ModelConfig preprocessing JSON
+///
+/// Returns an empty list for single-input models, which feed the request to
+/// the model's only input.
+#[cfg(feature = "onnx-runtime")]
+pub(crate) fn parse_input_bindings(model_config: &ModelConfig) -> Result<Vec<InputTensorSpec>, BackendError> {
+    let Some(tensors) = model_config.preprocessing.as_ref().and_then(|pre| pre.get("input_tensors")) else {
+        return Ok(Vec::new());
+    };
+    let specs: Vec<InputTensorSpec> = serde_json::from_value(tensors.clone())
+        .map_err(|e| BackendError::ConfigurationError(format!("Invalid input tensors: {}", e)))?;
+    InputTensorSpec::validate_all(&specs).map_err(BackendError::ConfigurationError)?;
+    Ok(specs)
+}
+
+/// Parse declared output names and shapes (`output_tensors`) from ModelConfig postprocessing JSON
+#[cfg(feature = "onnx-runtime")]
+pub(crate) fn parse_output_bindings(model_config: &ModelConfig) -> Vec<(String, Vec<i64>)> {
+    model_config.postprocessing.as_ref()
+        .and_then(|post| post.get("output_tensors"))
+        .and_then(|v| v.as_array())
+        .map(|tensors| {
+            tensors.iter()
+                .filter_map(|t| {
+                    let name = t.get("name")?.as_str()?;
+                    let shape = t.get("shape").and_then(|v| v.as_array())
+                        .map(|dims| dims.iter().filter_map(|d| d.as_i64()).collect())
+                        .unwrap_or_default();
+                    Some((name.to_string(), shape))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Build an input that the request does not feed directly
+///
+/// `image_size` is the original image and `input_size` the model input, both
+/// as (width, height) in pixels. Size and scale inputs need an image request.
+#[cfg(feature = "onnx-runtime")]
+pub(crate) fn auxiliary_input(
+    spec: &InputTensorSpec,
+    image_size: Option<(u32, u32)>,
+    input_size: (f32, f32),
+) -> Result<Tensor, BackendError> {
+    let pair = |width: f64, height: f64| match spec.order {
+        SizeOrder::Hw => vec![height, width],
+        SizeOrder::Wh => vec![width, height],
+    };
+    let image_size = || {
+        image_size
+            .map(|(width, height)| (width as f64, height as f64))
+            .ok_or_else(|| BackendError::InvalidInput(format!("Model input '{}' needs an image request", spec.name)))
+    };
+    let (input_width, input_height) = (input_size.0 as f64, input_size.1 as f64);
+
+    let values = match spec.semantic {
+        InputSemantic::Input => {
+            return Err(BackendError::InvalidInput(format!("Model input '{}' must come from the request", spec.name)));
+        }
+        InputSemantic::ImageSize => {
+            let (width, height) = image_size()?;
+            pair(width, height)
+        }
+        InputSemantic::InputSize => pair(input_width, input_height),
+        InputSemantic::ScaleFactor => {
+            let (width, height) = image_size()?;
+            pair(input_width / width.max(1.0), input_height / height.max(1.0))
+        }
+        InputSemantic::Constant => spec.value.clone().unwrap_or_default(),
+    };
+
+    let shape = resolve_shape(&spec.shape, values.len()).ok_or_else(|| {
+        BackendError::ConfigurationError(format!(
+            "Model input '{}' with shape {:?} cannot hold {} values", spec.name, spec.shape, values.len(),
+        ))
+    })?;
+    let data = match spec.element_type().map_err(BackendError::ConfigurationError)? {
+        TensorDType::F32 => TensorData::F32(values.iter().map(|&v| v as f32).collect()),
+        TensorDType::F64 => TensorData::F64(values),
+        TensorDType::I32 => TensorData::I32(values.iter().map(|&v| v.round() as i32).collect()),
+        TensorDType::I64 => TensorData::I64(values.iter().map(|&v| v.round() as i64).collect()),
+        TensorDType::U8 => TensorData::U8(values.iter().map(|&v| v.round().clamp(0.0, 255.0) as u8).collect()),
+    };
+
+    Ok(Tensor { name: spec.name.clone(), shape, data })
+}
+
+/// Concrete shape for `len` values, filling in at most one dynamic dimension
+///
+/// Without a declared shape, values form a `[1, len]` row (or a scalar).
+#[cfg(feature = "onnx-runtime")]
+fn resolve_shape(declared: &[i64], len: usize) -> Option<Vec<usize>> {
+    if declared.is_empty() {
+        return Some(if len == 1 { Vec::new() } else { vec![1, len] });
+    }
+    let known: usize = declared.iter().filter(|&&d| d >= 0).map(|&d| d as usize).product();
+    let dynamic = declared.iter().filter(|&&d| d < 0).count();
+    let fill = match dynamic {
+        0 if known == len => 0,
+        1 if known > 0 && len.is_multiple_of(known) => len / known,
+        _ => return None,
+    };
+    Some(declared.iter().map(|&d| if d < 0 { fill } else { d as usize }).collect())
+}
+
+/// Image preprocessing for a backend's NCHW input
+///
+/// Images are stretched to the input size with a triangle filter, scaled to
+/// [0, 1] and normalized per channel when configured. Returns `None` when the
+/// input shape cannot hold an image; image requests then fail at inference.
+pub(crate) fn image_input_pipeline(
+    input_shape: &[i64],
+    normalization: Option<&InputNormalization>,
+) -> Option<ImageInputPipeline> {
+    let &[_, channels, height, width] = input_shape else {
+        return None;
+    };
+    if channels <= 0 || height <= 0 || width <= 0 {
+        return None;
+    }
+    let normalization = NormalizationConfig {
+        mean: normalization.map(|n| n.mean.clone()).unwrap_or_default(),
+        std: normalization.map(|n| n.std.clone()).unwrap_or_default(),
+        scale_to_unit: true,
+    };
+    Some(ImageInputPipeline::new(ImageInputConfig {
+        size: (width as u32, height as u32),
+        channels: channels as usize,
+        resize: ResizeStrategy::Stretch,
+        normalization,
+        format: InputFormat::NCHW,
+        filter: ResampleFilter::Triangle,
+    }))
+}
+
+/// The model's image pipeline, or the error image requests get without one
+pub(crate) fn require_image_input<'a>(
+    pipeline: Option<&'a ImageInputPipeline>,
+    input_shape: &[i64],
+) -> Result<&'a ImageInputPipeline, BackendError> {
+    pipeline.ok_or_else(|| BackendError::InferenceFailed(format!(
+        "invalid input shape {:?}: expected 4D NCHW",
+        input_shape,
+    )))
+}
+
+/// NCHW shape of `batch` preprocessed images
+pub(crate) fn image_batch_shape(pipeline: &ImageInputPipeline, batch: usize) -> Vec<i64> {
+    let mut shape: Vec<i64> = pipeline.shape().iter().map(|&d| d as i64).collect();
+    shape[0] = batch as i64;
+    shape
+}
+
+/// Split outputs of a stacked forward pass into per-input outputs
+///
+/// Every output must carry the batch in its leading dimension.
+#[cfg(feature = "onnx-runtime")]
+pub(crate) fn split_batch_outputs(outputs: Vec<RawOutput>, batch_size: usize) -> Result<Vec<Vec<RawOutput>>, BackendError> {
+    let mut per_input: Vec<Vec<RawOutput>> = (0..batch_size).map(|_| Vec::with_capacity(outputs.len())).collect();
+
+    for output in outputs {
+        if output.shape.first() != Some(&batch_size) || !output.data.len().is_multiple_of(batch_size) {
+            return Err(BackendError::InferenceFailed(format!(
+                "Output '{}' with shape {:?} does not have batch dimension {}",
+                output.name, output.shape, batch_size,
+            )));
+        }
+
+        let mut shape = output.shape.clone();
+        shape[0] = 1;
+        let item_len = output.data.len() / batch_size;
+        for (item, chunk) in per_input.iter_mut().zip(output.data.chunks(item_len.max(1))) {
+            item.push(RawOutput { name: output.name.clone(), shape: shape.clone(), data: chunk.to_vec() });
+        }
+    }
+
+    Ok(per_input)
+}
+
+/// Decodes raw outputs into predictions according to the model's postprocess type
+pub(crate) struct OutputDecoder<'a> {
+    pub settings: &'a OutputSettings,
+    pub model_name: &'a str,
+    /// Backend name recorded in prediction metadata
+    pub backend: &'static str,
+    /// Model input (width, height) in pixels
+    pub input_size: (f32, f32),
+}
+
+impl OutputDecoder<'_> {
+    /// Decode every output of a forward pass into predictions
+    pub fn decode(&self, outputs: &[RawOutput]) -> Result<Vec<Prediction>, BackendError> {
+        let primary = outputs.first()
+            .ok_or_else(|| BackendError::PostprocessingFailed("Model produced no outputs".to_string()))?;
+        debug!("Output shape: {:?}, postprocess_type: {}", primary.shape, self.settings.postprocess_type);
+
+        let mut predictions = match self.settings.postprocess_type.as_str() {
+            "yolov8" | "yolo" | "yolov5" => self.process_yolov8_output(&primary.data, &primary.shape)?,
+            pt if is_ssd(pt) => self.process_ssd_output(outputs)?,
+            pt if is_instance_segmentation(pt) => self.process_yolov8_seg_output(outputs)?,
+            pt if is_pose(pt) => self.process_yolov8_pose_output(primary)?,
+            pt if is_semantic_segmentation(pt) => self.process_semantic_segmentation_output(primary)?,
+            _ => self.process_classification_output(&primary.data),
+        };
+
+        if !self.settings.calibration.is_empty() {
+            self.settings.calibration.apply(&mut predictions);
+            for prediction in &mut predictions {
+                prediction.severity = severity(prediction.confidence);
+            }
+        }
+        Ok(predictions)
+    }
+
+    /// Process YOLOv8 output tensor
+    /// Input shape: [1, 4+num_classes, num_detections] (raw from model)
+    /// Must transpose to iterate per-detection: [num_detections, 4+num_classes]
+    fn process_yolov8_output(&self, output_data: &[f32], output_shape: &[usize]) -> Result<Vec<Prediction>, BackendError> {
+        let kept = self.decode_yolov8_detections(output_data, output_shape, 0)?;
+
+        Ok(kept.into_iter().map(|det| self.detection_prediction(&det, None)).collect())
+    }
+
+    /// Process YOLOv8-seg outputs
+    /// Detections: [1, 4+num_classes+num_masks, num_detections], prototypes: [1, num_masks, mask_h, mask_w]
+    fn process_yolov8_seg_output(&self, outputs: &[RawOutput]) -> Result<Vec<Prediction>, BackendError> {
+        let detections = outputs.iter().find(|o| o.shape.len() == 3);
+        let protos = outputs.iter().find(|o| o.shape.len() == 4);
+        let (Some(detections), Some(protos)) = (detections, protos) else {
+            return Err(BackendError::PostprocessingFailed(format!(
+                "Model '{}' must output a 3D detections tensor and a 4D prototype mask tensor for instance segmentation",
+                self.model_name,
+            )));
+        };
+
+        let proto_shape = (protos.shape[1], protos.shape[2], protos.shape[3]);
+        let kept = self.decode_yolov8_detections(&detections.data, &detections.shape, proto_shape.0)?;
+
+        let decoder = SegmentationDecoder::new(self.segmentation_config()?);
+
+        Ok(kept.into_iter().map(|det| {
+            let mask = decoder.decode_instance_mask(
+                &det.trailing,
+                &protos.data,
+                proto_shape,
+                [det.x1, det.y1, det.x2, det.y2],
+                self.input_size,
+            );
+            self.detection_prediction(&det, mask)
+        }).collect())
+    }
+
+    /// Process YOLOv8-pose output: [1, 4+num_classes+num_keypoints*dims, num_detections]
+    fn process_yolov8_pose_output(&self, output: &RawOutput) -> Result<Vec<Prediction>, BackendError> {
+        let keypoints = self.settings.keypoint_config.as_ref().ok_or_else(|| {
+            BackendError::PostprocessingFailed(format!("Model '{}' has no keypoint config", self.model_name))
+        })?;
+        let kept = self.decode_yolov8_detections(&output.data, &output.shape, keypoints.values_per_detection())?;
+
+        Ok(kept.into_iter().map(|det| {
+            let mut prediction = self.detection_prediction(&det, None);
+            prediction.keypoints = Some(keypoints.decode(|k| det.trailing[k]));
+            prediction
+        }).collect())
+    }
+
+    /// Decode and NMS-filter YOLOv8-layout detections, keeping `num_trailing`
+    /// values after the class scores per detection
+    fn decode_yolov8_detections(
+        &self,
+        output_data: &[f32],
+        output_shape: &[usize],
+        num_trailing: usize,
+    ) -> Result<Vec<RawDetection>, BackendError> {
+        if output_shape.len() != 3 || output_shape[0] != 1 {
+            return Err(BackendError::PostprocessingFailed(
+                format!("Unexpected YOLOv8 output shape: {:?}", output_shape),
+            ));
+        }
+
+        let rows = output_shape[1]; // 4 + num_classes (+ mask coefficients or keypoints)
+        let cols = output_shape[2]; // num_detections (8400)
+        let num_classes = rows.saturating_sub(4 + num_trailing);
+
+        if num_classes == 0 {
+            return Err(BackendError::PostprocessingFailed(
+                format!("Output has {} rows, need at least {} (4 box + 1 class + {} trailing)", rows, 5 + num_trailing, num_trailing),
+            ));
+        }
+
+        // Validate against configured class labels. A mismatch usually means a
+        // model+config mismatch (e.g. COCO labels paired with a single-class model)
+        // which silently produces nonsense detections, so fail loudly instead.
+        let class_labels = &self.settings.class_labels;
+        if !class_labels.is_empty() && class_labels.len() != num_classes {
+            return Err(BackendError::PostprocessingFailed(format!(
+                "Model '{}' output has {} classes but {} class labels are configured; \
+                 check that postprocessing.class_labels matches the model",
+                self.model_name,
+                num_classes,
+                class_labels.len(),
+            )));
+        }
+
+        debug!("Processing YOLOv8 output: {} detections, {} classes", cols, num_classes);
+
+        let score_threshold = self.settings.score_threshold();
+        let mut detections: Vec<RawDetection> = Vec::new();
+
+        for det_idx in 0..cols {
+            // Data is laid out as [1, rows, cols] in row-major:
+            // output_data[row * cols + det_idx]
+            let cx = output_data[det_idx];
+            let cy = output_data[cols + det_idx];
+            let w = output_data[2 * cols + det_idx];
+            let h = output_data[3 * cols + det_idx];
+
+            // Find best class
+            let mut best_conf = 0.0f32;
+            let mut best_class = 0usize;
+            for c in 0..num_classes {
+                let conf = output_data[(4 + c) * cols + det_idx];
+                if conf > best_conf {
+                    best_conf = conf;
+                    best_class = c;
+                }
+            }
+
+            if best_conf >= score_threshold {
+                detections.push(RawDetection {
+                    x1: cx - w / 2.0,
+                    y1: cy - h / 2.0,
+                    x2: cx + w / 2.0,
+                    y2: cy + h / 2.0,
+                    confidence: best_conf,
+                    class_id: best_class,
+                    trailing: (0..num_trailing)
+                        .map(|k| output_data[(4 + num_classes + k) * cols + det_idx])
+                        .collect(),
+                });
+            }
+        }
+
+        debug!("Found {} detections above threshold {}", detections.len(), self.settings.confidence_threshold);
+
+        let kept = suppress(detections, &self.settings.suppression);
+        debug!("After suppression: {} detections", kept.len());
+
+        Ok(kept)
+    }
+
+    /// Process semantic segmentation output: [1, num_classes, H, W] logits or a [1, H, W] map
+    fn process_semantic_segmentation_output(&self, output: &RawOutput) -> Result<Vec<Prediction>, BackendError> {
+        let shape = match output.shape.as_slice() {
+            [_, c, h, w] => (*c, *h, *w),
+            [1, h, w] => (1, *h, *w),
+            [c, h, w] => (*c, *h, *w),
+            other => {
+                return Err(BackendError::PostprocessingFailed(
+                    format!("Unexpected segmentation output shape: {:?}", other),
+                ));
+            }
+        };
+
+        let regions = SegmentationDecoder::new(self.segmentation_config()?)
+            .decode_semantic(&output.data, shape, self.input_size)
+            .map_err(|e| BackendError::PostprocessingFailed(format!("Segmentation decoding failed: {}", e)))?;
+
+        debug!("Found {} segmented classes", regions.len());
+
+        Ok(regions.into_iter().map(|region| Prediction {
+            class: region.class_name,
+            confidence: region.confidence,
+            bbox: Some(region.bbox),
+            metadata: self.prediction_metadata(region.class_id),
+            severity: severity(region.confidence),
+            mask: Some(region.mask),
+            keypoints: None,
+        }).collect())
+    }
+
+    /// Process classification-style output (flat logits/probabilities)
+    fn process_classification_output(&self, output_data: &[f32]) -> Vec<Prediction> {
+        let config = self.settings.classification_config.clone().unwrap_or_else(|| ClassificationConfig {
+            confidence_threshold: self.settings.score_threshold(),
+            top_k: self.settings.top_k,
+            class_labels: self.settings.class_labels.clone(),
+            activation: ScoreActivation::None,
+            multi_label: false,
+            open_set: None,
+        });
+
+        config.decode(output_data).into_iter()
+            .map(|score| {
+                let mut metadata = match score.class_id {
+                    Some(class_id) => self.prediction_metadata(class_id),
+                    None => self.open_set_metadata(),
+                };
+                if let Some(open_set_score) = score.open_set_score {
+                    metadata.insert("open_set_score".to_string(), serde_json::json!(open_set_score));
+                }
+                Prediction {
+                    class: score.class_name,
+                    confidence: score.confidence,
+                    bbox: None,
+                    metadata,
+                    severity: severity(score.confidence),
+                    mask: None,
+                    keypoints: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Process SSD-style outputs (separate boxes/scores/classes/num_detections tensors)
+    ///
+    /// Tensors are matched by the semantic configured for their name, then by their
+    /// name; boxes come back in model input pixel space like the YOLOv8 path.
+    fn process_ssd_output(&self, outputs: &[RawOutput]) -> Result<Vec<Prediction>, BackendError> {
+        let mut ssd_outputs = SsdOutputs::default();
+        for output in outputs {
+            let semantic = self.settings.output_semantics.get(&output.name).map(String::as_str).unwrap_or("");
+            if let Some(role) = SsdTensorRole::resolve(semantic, &output.name) {
+                ssd_outputs.insert(role, &output.data);
+            }
+        }
+
+        let decoder = SsdDecoder::new(self.settings.ssd_config.clone().unwrap_or_default());
+        let decoded = decoder
+            .decode(&ssd_outputs, self.input_size, self.settings.score_threshold(), &self.settings.class_labels)
+            .map_err(|e| BackendError::PostprocessingFailed(format!("SSD decoding failed: {}", e)))?;
+
+        debug!("Found {} SSD detections above threshold {}", decoded.len(), self.settings.confidence_threshold);
+
+        let detections: Vec<RawDetection> = decoded.iter()
+            .map(|det| RawDetection {
+                x1: det.x1,
+                y1: det.y1,
+                x2: det.x2,
+                y2: det.y2,
+                confidence: det.confidence,
+                class_id: det.class_id,
+                trailing: Vec::new(),
+            })
+            .collect();
+
+        let kept = suppress(detections, &self.settings.suppression);
+        debug!("After suppression: {} detections", kept.len());
+
+        Ok(kept.into_iter().map(|det| self.detection_prediction(&det, None)).collect())
+    }
+
+    fn segmentation_config(&self) -> Result<SegmentationConfig, BackendError> {
+        self.settings.segmentation_config.clone().ok_or_else(|| {
+            BackendError::PostprocessingFailed(format!("Model '{}' has no segmentation config", self.model_name))
+        })
+    }
+
+    /// Build a Prediction from a detection in model input pixel space
+    fn detection_prediction(&self, det: &RawDetection, mask: Option<MaskEncoding>) -> Prediction {
+        Prediction {
+            class: self.class_name(det.class_id),
+            confidence: det.confidence,
+            bbox: Some([det.x1, det.y1, det.x2, det.y2]),
+            metadata: self.prediction_metadata(det.class_id),
+            severity: severity(det.confidence),
+            mask,
+            keypoints: None,
+        }
+    }
+
+    fn class_name(&self, class_id: usize) -> String {
+        self.settings.class_labels.get(class_id)
+            .cloned()
+            .unwrap_or_else(|| format!("class_{}", class_id))
+    }
+
+    fn prediction_metadata(&self, class_id: usize) -> HashMap<String, serde_json::Value> {
+        let mut map = HashMap::new();
+        map.insert("backend".to_string(), serde_json::Value::String(self.backend.to_string()));
+        map.insert("class_index".to_string(), serde_json::Value::Number((class_id as u64).into()));
+        map.insert("model_name".to_string(), serde_json::Value::String(self.model_name.to_string()));
+        map
+    }
+
+    /// Metadata of an open-set rejection, which has no class index
+    fn open_set_metadata(&self) -> HashMap<String, serde_json::Value> {
+        let mut map = HashMap::new();
+        map.insert("backend".to_string(), serde_json::Value::String(self.backend.to_string()));
+        map.insert("model_name".to_string(), serde_json::Value::String(self.model_name.to_string()));
+        map.insert("open_set_rejected".to_string(), serde_json::Value::Bool(true));
+        map
+    }
+}
+
+#[cfg(feature = "onnx-runtime")]
+impl OutputDecoder<'_> {
+    /// Decode per-window outputs of a sensor model
+    ///
+    /// Returns one prediction per window plus a summary for the result metadata.
+    /// Models without a time-series postprocess type are decoded like any other
+    /// model, with window details added to each prediction.
+    pub fn decode_time_series(
+        &self,
+        windows: &[SensorWindow],
+        outputs: &[Vec<RawOutput>],
+        window_config: &SensorWindowConfig,
+        timestamps: &[i64],
+    ) -> Result<(Vec<Prediction>, serde_json::Value), BackendError> {
+        let threshold = self.settings.anomaly_threshold.unwrap_or(self.settings.confidence_threshold);
+        let mut predictions = Vec::new();
+        let mut anomalous_windows = 0usize;
+        let mut peak = 0.0f32;
+
+        for (window, window_outputs) in windows.iter().zip(outputs) {
+            let primary = window_outputs.first()
+                .ok_or_else(|| BackendError::PostprocessingFailed("Model produced no outputs".to_string()))?;
+
+            let Some(kind) = self.settings.time_series_output else {
+                for mut prediction in self.decode(window_outputs)? {
+                    prediction.metadata.extend(window_metadata(window));
+                    predictions.push(prediction);
+                }
+                continue;
+            };
+
+            let mut metadata = self.prediction_metadata(0);
+            metadata.remove("class_index");
+            metadata.extend(window_metadata(window));
+
+            let prediction = match kind {
+                TimeSeriesOutputKind::AnomalyScore => {
+                    let score = timeseries::anomaly_score(&primary.data).ok_or_else(|| {
+                        BackendError::PostprocessingFailed("Anomaly model produced an empty output".to_string())
+                    })?;
+                    metadata.insert("anomaly_score".to_string(), serde_json::json!(score));
+                    peak = peak.max(score);
+                    self.anomaly_prediction(score, score > threshold, metadata)
+                }
+                TimeSeriesOutputKind::Reconstruction => {
+                    let (error, channel_errors) = timeseries::reconstruction_error(window, &primary.data, window_config)
+                        .map_err(|e| BackendError::PostprocessingFailed(format!("Reconstruction decoding failed: {}", e)))?;
+                    metadata.insert("reconstruction_error".to_string(), serde_json::json!(error));
+                    metadata.insert("channel_errors".to_string(), serde_json::json!(channel_errors));
+                    peak = peak.max(error);
+                    // Maps the error onto [0, 1) with 0.5 at the threshold
+                    let confidence = if error + threshold > 0.0 { error / (error + threshold) } else { 0.0 };
+                    self.anomaly_prediction(confidence, error > threshold, metadata)
+                }
+                TimeSeriesOutputKind::Forecast => {
+                    let forecast = timeseries::decode_forecast(&primary.data, window_config)
+                        .map_err(|e| BackendError::PostprocessingFailed(format!("Forecast decoding failed: {}", e)))?;
+                    let forecast_timestamps = timeseries::forecast_timestamps(timestamps, window.end_timestamp, forecast.len());
+                    metadata.insert("horizon".to_string(), serde_json::json!(forecast.len()));
+                    metadata.insert("forecast".to_string(), serde_json::json!(forecast));
+                    metadata.insert("forecast_timestamps".to_string(), serde_json::json!(forecast_timestamps));
+                    Prediction {
+                        class: "forecast".to_string(),
+                        confidence: 1.0,
+                        bbox: None,
+                        metadata,
+                        severity: None,
+                        mask: None,
+                        keypoints: None,
+                    }
+                }
+            };
+
+            if prediction.class == "anomaly" {
+                anomalous_windows += 1;
+            }
+            predictions.push(prediction);
+        }
+
+        let summary = match self.settings.time_series_output {
+            Some(TimeSeriesOutputKind::AnomalyScore) => serde_json::json!({
+                "windows": windows.len(),
+                "anomalous_windows": anomalous_windows,
+                "max_anomaly_score": peak,
+                "threshold": threshold,
+            }),
+            Some(TimeSeriesOutputKind::Reconstruction) => serde_json::json!({
+                "windows": windows.len(),
+                "anomalous_windows": anomalous_windows,
+                "max_reconstruction_error": peak,
+                "threshold": threshold,
+            }),
+            Some(TimeSeriesOutputKind::Forecast) | None => serde_json::json!({
+                "windows": windows.len(),
+            }),
+        };
+
+        Ok((predictions, summary))
+    }
+
+    fn anomaly_prediction(
+        &self,
+        confidence: f32,
+        is_anomaly: bool,
+        mut metadata: HashMap<String, serde_json::Value>,
+    ) -> Prediction {
+        metadata.insert("is_anomaly".to_string(), serde_json::Value::Bool(is_anomaly));
+        Prediction {
+            class: if is_anomaly { "anomaly" } else { "normal" }.to_string(),
+            confidence,
+            bbox: None,
+            metadata,
+            severity: if is_anomaly { severity(confidence) } else { Some("low".to_string()) },
+            mask: None,
+            keypoints: None,
+        }
+    }
+
+    /// Decode per-segment outputs of an audio model
+    ///
+    /// Each segment is decoded like a single input and its predictions are
+    /// stamped with the segment time range. Consecutive segments whose top class
+    /// matches are merged into events for the result metadata.
+    pub fn decode_audio(
+        &self,
+        segments: &[AudioSegment],
+        outputs: &[Vec<RawOutput>],
+    ) -> Result<(Vec<Prediction>, serde_json::Value), BackendError> {
+        let mut predictions = Vec::new();
+        let mut events: Vec<AudioEvent> = Vec::new();
+
+        for (segment, segment_outputs) in segments.iter().zip(outputs) {
+            let segment_predictions = self.decode(segment_outputs)?;
+
+            if let Some(top) = segment_predictions.first() {
+                match events.last_mut() {
+                    Some(event) if event.class == top.class && event.last_segment + 1 == segment.index => {
+                        event.end_seconds = segment.end_seconds;
+                        event.max_confidence = event.max_confidence.max(top.confidence);
+                        event.last_segment = segment.index;
+                    }
+                    _ => events.push(AudioEvent {
+                        class: top.class.clone(),
+                        start_seconds: segment.start_seconds,
+                        end_seconds: segment.end_seconds,
+                        max_confidence: top.confidence,
+                        last_segment: segment.index,
+                    }),
+                }
+            }
+
+            for mut prediction in segment_predictions {
+                prediction.metadata.insert("segment_index".to_string(), serde_json::json!(segment.index));
+                prediction.metadata.insert("start_seconds".to_string(), serde_json::json!(segment.start_seconds));
+                prediction.metadata.insert("end_seconds".to_string(), serde_json::json!(segment.end_seconds));
+                predictions.push(prediction);
+            }
+        }
+
+        let summary = serde_json::json!({
+            "segments": segments.len(),
+            "duration_seconds": segments.last().map_or(0.0, |s| s.end_seconds),
+            "events": events.iter().map(|event| serde_json::json!({
+                "class": event.class,
+                "start_seconds": event.start_seconds,
+                "end_seconds": event.end_seconds,
+                "max_confidence": event.max_confidence,
+            })).collect::<Vec<_>>(),
+        });
+
+        Ok((predictions, summary))
+    }
+}
+
+/// Run of consecutive audio segments sharing a top class
+#[cfg(feature = "onnx-runtime")]
+struct AudioEvent {
+    class: String,
+    start_seconds: f32,
+    end_seconds: f32,
+    max_confidence: f32,
+    last_segment: usize,
+}
+
+#[cfg(feature = "onnx-runtime")]
+fn window_metadata(window: &SensorWindow) -> HashMap<String, serde_json::Value> {
+    HashMap::from([
+        ("window_index".to_string(), serde_json::json!(window.index)),
+        ("window_start".to_string(), serde_json::json!(window.start)),
+        ("start_timestamp".to_string(), serde_json::json!(window.start_timestamp)),
+        ("end_timestamp".to_string(), serde_json::json!(window.end_timestamp)),
+    ])
+}
+
+fn severity(confidence: f32) -> Option<String> {
+    if confidence > 0.7 { Some("high".to_string()) }
+    else if confidence > 0.4 { Some("medium".to_string()) }
+    else { Some("low".to_string()) }
+}
+
+/// Suppress overlapping detections and apply the detection caps, most confident first
+fn suppress(detections: Vec<RawDetection>, suppression: &SuppressionConfig) -> Vec<RawDetection> {
+    let boxes: Vec<ScoredBox> = detections.iter()
+        .map(|det| ScoredBox { bbox: [det.x1, det.y1, det.x2, det.y2], confidence: det.confidence, class_id: det.class_id })
+        .collect();
+
+    suppression.suppress(&boxes)
+        .into_iter()
+        .map(|kept| {
+            let mut det = detections[kept.index].clone();
+            [det.x1, det.y1, det.x2, det.y2] = kept.bbox;
+            det.confidence = kept.confidence;
+            det
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "onnx-runtime"))]
+mod tests {
+    use super::*;
+
+    fn spec(semantic: InputSemantic, shape: Vec<i64>, dtype: &str, order: SizeOrder) -> InputTensorSpec {
+        InputTensorSpec { name: "aux".to_string(), shape, dtype: dtype.to_string(), semantic, order, value: None }
+    }
+
+    #[test]
+    fn test_auxiliary_inputs_follow_image_and_model_size() {
+        let image_size = Some((1280, 720));
+        let input_size = (640.0, 640.0);
+
+        let sizes = auxiliary_input(&spec(InputSemantic::ImageSize, vec![1, 2], "int64", SizeOrder::Wh), image_size, input_size).unwrap();
+        assert_eq!(sizes.shape, vec![1, 2]);
+        assert_eq!(sizes.data, TensorData::I64(vec![1280, 720]));
+
+        let scale = auxiliary_input(&spec(InputSemantic::ScaleFactor, vec![-1, 2], "float32", SizeOrder::Hw), image_size, input_size).unwrap();
+        assert_eq!(scale.data, TensorData::F32(vec![640.0 / 720.0, 0.5]));
+
+        assert!(auxiliary_input(&spec(InputSemantic::ImageSize, vec![1, 2], "int64", SizeOrder::Hw), None, input_size).is_err());
+        assert!(auxiliary_input(&spec(InputSemantic::InputSize, vec![1, 3], "float32", SizeOrder::Hw), None, input_size).is_err());
+    }
+}
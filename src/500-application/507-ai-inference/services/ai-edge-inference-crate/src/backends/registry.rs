@@ -0,0 +1,166 @@
+//! Application-provided inference backends
+//!
+//! Applications can add their own `InferenceBackend` implementations (for
+//! example a TensorRT or vendor NPU runtime) without patching the crate.
+//! A registered backend is selected by name with `BackendType::Custom(name)`,
+//! and takes part in `BackendType::Auto` selection next to the built-in
+//! backends, ordered by priority and filtered by the devices it supports.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{BackendError, DeviceType, InferenceBackend};
+
+/// Creates a fresh, uninitialized backend instance
+pub type BackendConstructor = Arc<dyn Fn() -> Box<dyn InferenceBackend> + Send + Sync>;
+
+/// What a registered backend can run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackendCapabilities {
+    /// Devices the backend runs on; empty means any device
+    pub devices: Vec<DeviceType>,
+    /// Model file extensions it loads (e.g. "engine", "tflite"); empty means any
+    pub model_formats: Vec<String>,
+}
+
+impl BackendCapabilities {
+    /// Whether the backend can serve a requested device
+    ///
+    /// `DeviceType::Auto` is served by every backend.
+    pub fn supports_device(&self, device: &DeviceType) -> bool {
+        self.devices.is_empty() || *device == DeviceType::Auto || self.devices.contains(device)
+    }
+
+    /// Whether the backend loads the model file at `model_path`
+    pub fn supports_model(&self, model_path: &str) -> bool {
+        if self.model_formats.is_empty() {
+            return true;
+        }
+        let extension = Path::new(model_path).extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+        self.model_formats.iter().any(|format| format.trim_start_matches('.').eq_ignore_ascii_case(extension))
+    }
+}
+
+/// A backend implementation added by the application
+#[derive(Clone)]
+pub struct BackendRegistration {
+    /// Name selected with `BackendType::Custom(name)`
+    pub name: String,
+    /// Automatic selection tries higher priorities first
+    pub priority: i32,
+    pub capabilities: BackendCapabilities,
+    pub constructor: BackendConstructor,
+}
+
+impl BackendRegistration {
+    pub fn new<F>(name: impl Into<String>, constructor: F) -> Self
+    where
+        F: Fn() -> Box<dyn InferenceBackend> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            priority: 0,
+            capabilities: BackendCapabilities::default(),
+            constructor: Arc::new(constructor),
+        }
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_capabilities(mut self, capabilities: BackendCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+}
+
+impl fmt::Debug for BackendRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendRegistration")
+            .field("name", &self.name)
+            .field("priority", &self.priority)
+            .field("capabilities", &self.capabilities)
+            .finish_non_exhaustive()
+    }
+}
+
+static REGISTRY: OnceLock<RwLock<HashMap<String, BackendRegistration>>> = OnceLock::new();
+
+/// Process-wide registry of application backends
+pub struct BackendRegistry;
+
+impl BackendRegistry {
+    /// Auto-selection priority of the built-in ONNX Runtime backend
+    pub const ONNX_RUNTIME_PRIORITY: i32 = 100;
+    /// Auto-selection priority of the built-in Candle backend
+    pub const CANDLE_PRIORITY: i32 = 50;
+
+    fn read() -> RwLockReadGuard<'static, HashMap<String, BackendRegistration>> {
+        REGISTRY.get_or_init(Default::default).read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write() -> RwLockWriteGuard<'static, HashMap<String, BackendRegistration>> {
+        REGISTRY.get_or_init(Default::default).write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Register a backend, replacing an earlier registration of the same name
+    pub fn register(registration: BackendRegistration) -> Result<(), BackendError> {
+        let name = registration.name.trim();
+        if name.is_empty() {
+            return Err(BackendError::ConfigurationError("Backend name must not be empty".to_string()));
+        }
+        if ["ONNX Runtime", "Candle", "Auto"].iter().any(|builtin| builtin.eq_ignore_ascii_case(name)) {
+            return Err(BackendError::ConfigurationError(format!("'{}' is a built-in backend name", name)));
+        }
+        tracing::info!("Registered inference backend '{}' (priority {})", name, registration.priority);
+        Self::write().insert(name.to_string(), registration);
+        Ok(())
+    }
+
+    /// Remove a registration, returning whether it existed
+    ///
+    /// Backends already created from it keep running.
+    pub fn unregister(name: &str) -> bool {
+        Self::write().remove(name).is_some()
+    }
+
+    pub fn get(name: &str) -> Option<BackendRegistration> {
+        Self::read().get(name).cloned()
+    }
+
+    /// All registrations, highest priority first
+    pub fn registered() -> Vec<BackendRegistration> {
+        let mut registrations: Vec<BackendRegistration> = Self::read().values().cloned().collect();
+        registrations.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.name.cmp(&b.name)));
+        registrations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_match_devices_and_formats() {
+        let capabilities = BackendCapabilities {
+            devices: vec![DeviceType::Cuda(0)],
+            model_formats: vec![".engine".to_string(), "plan".to_string()],
+        };
+        assert!(capabilities.supports_device(&DeviceType::Cuda(0)));
+        assert!(capabilities.supports_device(&DeviceType::Auto));
+        assert!(!capabilities.supports_device(&DeviceType::Cpu));
+        assert!(capabilities.supports_model("models/yolo.ENGINE"));
+        assert!(capabilities.supports_model("yolo.plan"));
+        assert!(!capabilities.supports_model("yolo.onnx"));
+        assert!(BackendCapabilities::default().supports_model("anything.bin"));
+
+        let builtin = BackendRegistration::new("candle", || unreachable!());
+        assert!(BackendRegistry::register(builtin).is_err());
+    }
+}
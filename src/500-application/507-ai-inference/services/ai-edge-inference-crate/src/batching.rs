@@ -0,0 +1,394 @@
+//! Dynamic request batching
+//!
+//! Concurrent inference calls for the same model are queued and stacked into a
+//! single forward pass. A request arriving at an idle queue spawns a worker
+//! task for that model. The worker waits until the batch is full or the oldest
+//! request has waited `max_wait`, runs the batch and sends every caller its own
+//! result, and exits once the queue is drained. Callers only await their own
+//! reply, so a caller that gives up never takes a batch down with it.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Notify};
+use tracing::{debug, warn};
+
+use crate::backend::BackendError;
+use crate::config::PerformanceConfig;
+use crate::{InferenceInput, InferenceResult};
+
+/// Batch fill and queue wait metrics
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BatchingMetrics {
+    pub batches_executed: u64,
+    pub requests_batched: u64,
+    /// Mean ratio of batch size to the maximum batch size
+    pub average_fill_ratio: f64,
+    pub average_queue_wait_ms: f64,
+    pub max_queue_wait_ms: f64,
+}
+
+type BatchResults = Vec<Result<InferenceResult, BackendError>>;
+type BatchReply = oneshot::Sender<Result<InferenceResult, BackendError>>;
+type BatchRunner = Box<dyn FnOnce(Vec<InferenceInput>) -> BoxFuture<'static, BatchResults> + Send>;
+
+struct PendingRequest {
+    input: InferenceInput,
+    enqueued_at: Instant,
+    reply: BatchReply,
+    run_batch: BatchRunner,
+}
+
+#[derive(Default)]
+struct QueueState {
+    pending: VecDeque<PendingRequest>,
+    worker_active: bool,
+}
+
+/// Requests waiting for one model
+#[derive(Default)]
+struct ModelQueue {
+    state: Mutex<QueueState>,
+    /// Wakes the worker once a full batch is queued
+    full: Notify,
+}
+
+impl ModelQueue {
+    fn state(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Fails the queued requests if a worker panics, so the next request starts a new one
+struct WorkerGuard<'a> {
+    queue: &'a ModelQueue,
+}
+
+impl Drop for WorkerGuard<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            let mut state = self.queue.state();
+            state.worker_active = false;
+            state.pending.clear();
+        }
+    }
+}
+
+/// Batch limits and metrics shared with the queue workers
+struct BatchSettings {
+    max_batch_size: usize,
+    max_wait: Duration,
+    metrics: Mutex<BatchingMetrics>,
+}
+
+/// Queues concurrent requests per model and runs them as batches
+pub struct DynamicBatcher {
+    settings: Arc<BatchSettings>,
+    queues: Mutex<HashMap<String, Arc<ModelQueue>>>,
+}
+
+impl std::fmt::Debug for DynamicBatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicBatcher")
+            .field("max_batch_size", &self.settings.max_batch_size)
+            .field("max_wait", &self.settings.max_wait)
+            .finish()
+    }
+}
+
+impl DynamicBatcher {
+    pub fn new(max_batch_size: usize, max_wait: Duration) -> Self {
+        Self {
+            settings: Arc::new(BatchSettings {
+                max_batch_size: max_batch_size.max(1),
+                max_wait,
+                metrics: Mutex::new(BatchingMetrics::default()),
+            }),
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a batcher when the performance config asks for batches larger than one
+    pub fn from_config(config: &PerformanceConfig) -> Option<Self> {
+        (config.batch_size > 1)
+            .then(|| Self::new(config.batch_size, Duration::from_millis(config.max_batch_wait_ms)))
+    }
+
+    pub fn max_batch_size(&self) -> usize {
+        self.settings.max_batch_size
+    }
+
+    /// Queue `input` for `model_key` and wait for its result
+    ///
+    /// `run_batch` must return one result per input, in order. Every caller for
+    /// the same model must pass an equivalent `run_batch`, since the worker runs
+    /// each batch with the one from its first request. Dropping the returned
+    /// future only abandons this request; the batch it joined still runs.
+    pub async fn submit<F, Fut>(
+        &self,
+        model_key: &str,
+        input: InferenceInput,
+        run_batch: F,
+    ) -> Result<InferenceResult, BackendError>
+    where
+        F: FnOnce(Vec<InferenceInput>) -> Fut + Send + 'static,
+        Fut: Future<Output = BatchResults> + Send + 'static,
+    {
+        let queue = self.queue(model_key);
+        let (reply, receiver) = oneshot::channel();
+        let run_batch: BatchRunner = Box::new(move |inputs| Box::pin(run_batch(inputs)));
+
+        let start_worker = {
+            let mut state = queue.state();
+            state.pending.push_back(PendingRequest { input, enqueued_at: Instant::now(), reply, run_batch });
+            if state.pending.len() >= self.settings.max_batch_size {
+                queue.full.notify_one();
+            }
+            !std::mem::replace(&mut state.worker_active, true)
+        };
+        if start_worker {
+            tokio::spawn(Arc::clone(&self.settings).run_queue(queue));
+        }
+
+        receiver.await.unwrap_or_else(|_| Err(BackendError::InferenceFailed(
+            "Batch worker stopped before producing a result".to_string(),
+        )))
+    }
+
+    /// Snapshot of the batching metrics
+    pub fn metrics(&self) -> BatchingMetrics {
+        self.settings.metrics.lock().map(|metrics| metrics.clone()).unwrap_or_default()
+    }
+
+    pub fn reset_metrics(&self) {
+        if let Ok(mut metrics) = self.settings.metrics.lock() {
+            *metrics = BatchingMetrics::default();
+        }
+    }
+
+    fn queue(&self, model_key: &str) -> Arc<ModelQueue> {
+        let mut queues = self.queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Arc::clone(queues.entry(model_key.to_string()).or_default())
+    }
+}
+
+impl BatchSettings {
+    /// Worker loop: run batches until the queue is empty
+    async fn run_queue(self: Arc<Self>, queue: Arc<ModelQueue>) {
+        let _guard = WorkerGuard { queue: &queue };
+        while self.run_next_batch(&queue).await {}
+    }
+
+    /// Wait for a full batch or the oldest request's deadline, then run one batch
+    ///
+    /// Returns false, releasing the queue, once there is nothing left to run.
+    async fn run_next_batch(&self, queue: &ModelQueue) -> bool {
+        loop {
+            let deadline = {
+                let mut state = queue.state();
+                match state.pending.front() {
+                    None => {
+                        state.worker_active = false;
+                        return false;
+                    }
+                    Some(_) if state.pending.len() >= self.max_batch_size => break,
+                    Some(oldest) => oldest.enqueued_at + self.max_wait,
+                }
+            };
+            if Instant::now() >= deadline {
+                break;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline.into()) => break,
+                _ = queue.full.notified() => {}
+            }
+        }
+
+        let batch: Vec<PendingRequest> = {
+            let mut state = queue.state();
+            let take = state.pending.len().min(self.max_batch_size);
+            state.pending.drain(..take)
+                .filter(|request| !request.reply.is_closed())
+                .collect()
+        };
+
+        let dispatched_at = Instant::now();
+        let waits_ms: Vec<f64> = batch.iter()
+            .map(|request| dispatched_at.duration_since(request.enqueued_at).as_secs_f64() * 1000.0)
+            .collect();
+        let mut run_batch = None;
+        let (inputs, replies): (Vec<_>, Vec<_>) = batch.into_iter()
+            .map(|request| {
+                // Runners are equivalent, so the first request's runs the batch
+                run_batch.get_or_insert(request.run_batch);
+                (request.input, request.reply)
+            })
+            .unzip();
+        let Some(run_batch) = run_batch else {
+            return true;
+        };
+
+        debug!("Running batch of {}/{} requests", inputs.len(), self.max_batch_size);
+        let mut results = run_batch(inputs).await;
+        if results.len() != replies.len() {
+            warn!("Batch returned {} results for {} requests", results.len(), replies.len());
+            results.resize_with(replies.len(), || Err(BackendError::InferenceFailed(
+                "Batch returned no result for this request".to_string(),
+            )));
+        }
+
+        for (reply, result) in replies.into_iter().zip(results) {
+            // The caller may have gone away while the batch ran
+            let _ = reply.send(result);
+        }
+
+        self.record_batch(&waits_ms);
+        true
+    }
+
+    fn record_batch(&self, waits_ms: &[f64]) {
+        let Ok(mut metrics) = self.metrics.lock() else {
+            return;
+        };
+
+        let fill_ratio = waits_ms.len() as f64 / self.max_batch_size as f64;
+        let previous_requests = metrics.requests_batched as f64;
+        metrics.batches_executed += 1;
+        metrics.requests_batched += waits_ms.len() as u64;
+
+        let batches = metrics.batches_executed as f64;
+        metrics.average_fill_ratio += (fill_ratio - metrics.average_fill_ratio) / batches;
+        metrics.average_queue_wait_ms = (metrics.average_queue_wait_ms * previous_requests + waits_ms.iter().sum::<f64>())
+            / metrics.requests_batched as f64;
+        metrics.max_queue_wait_ms = waits_ms.iter().copied().fold(metrics.max_queue_wait_ms, f64::max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn series(value: f32) -> InferenceInput {
+        InferenceInput::TimeSeries {
+            values: vec![value],
+            timestamps: vec![0],
+            metadata: crate::SensorMetadata {
+                sensor_type: "test".to_string(),
+                sampling_rate: 1.0,
+                units: String::new(),
+            },
+        }
+    }
+
+    fn echo(input: &InferenceInput, batch_size: usize) -> InferenceResult {
+        let InferenceInput::TimeSeries { values, .. } = input else {
+            panic!("unexpected input");
+        };
+        InferenceResult {
+            model_name: "echo".to_string(),
+            model_type: "test".to_string(),
+            predictions: vec![],
+            confidence: values[0],
+            inference_time_ms: 0.0,
+            metadata: serde_json::json!({ "batch_size": batch_size }),
+            tensors: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_share_a_batch() {
+        let batcher = DynamicBatcher::new(4, Duration::from_millis(200));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let run = {
+            let calls = Arc::clone(&calls);
+            move |inputs: Vec<InferenceInput>| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let size = inputs.len();
+                async move { inputs.iter().map(|input| Ok(echo(input, size))).collect() }
+            }
+        };
+
+        let results = futures::future::join_all(
+            (0..4).map(|i| batcher.submit("model", series(i as f32), run.clone()))
+        ).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        for (i, result) in results.into_iter().enumerate() {
+            let result = result.unwrap();
+            assert_eq!(result.confidence, i as f32);
+            assert_eq!(result.metadata["batch_size"], 4);
+        }
+
+        let metrics = batcher.metrics();
+        assert_eq!(metrics.batches_executed, 1);
+        assert_eq!(metrics.requests_batched, 4);
+        assert!((metrics.average_fill_ratio - 1.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_partial_batch_runs_after_max_wait() {
+        let batcher = DynamicBatcher::new(8, Duration::from_millis(5));
+        let run = |inputs: Vec<InferenceInput>| {
+            let size = inputs.len();
+            async move { inputs.iter().map(|input| Ok(echo(input, size))).collect() }
+        };
+
+        let (a, b) = tokio::join!(
+            batcher.submit("model", series(1.0), run),
+            batcher.submit("model", series(2.0), run),
+        );
+        assert_eq!(a.unwrap().confidence, 1.0);
+        assert_eq!(b.unwrap().metadata["batch_size"], 2);
+
+        let metrics = batcher.metrics();
+        assert!((metrics.average_fill_ratio - 0.25).abs() < 1e-9);
+        assert!(metrics.max_queue_wait_ms >= 4.0);
+    }
+
+    #[tokio::test]
+    async fn test_overflow_requests_get_their_own_batch() {
+        let batcher = DynamicBatcher::new(2, Duration::from_millis(5));
+        let run = |inputs: Vec<InferenceInput>| {
+            let size = inputs.len();
+            async move { inputs.iter().map(|input| Ok(echo(input, size))).collect() }
+        };
+
+        let results = futures::future::join_all(
+            (0..3).map(|i| batcher.submit("model", series(i as f32), run))
+        ).await;
+
+        let sizes: Vec<_> = results.into_iter()
+            .map(|result| result.unwrap().metadata["batch_size"].as_u64().unwrap())
+            .collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+        assert_eq!(batcher.metrics().batches_executed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_caller_does_not_cancel_the_batch() {
+        let batcher = DynamicBatcher::new(3, Duration::from_millis(50));
+        let run = |inputs: Vec<InferenceInput>| async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let size = inputs.len();
+            inputs.iter().map(|input| Ok(echo(input, size))).collect()
+        };
+
+        // The first caller starts the batch and gives up while it runs
+        let (dropped, b, c) = tokio::join!(
+            tokio::time::timeout(Duration::from_millis(5), batcher.submit("model", series(0.0), run)),
+            batcher.submit("model", series(1.0), run),
+            batcher.submit("model", series(2.0), run),
+        );
+        assert!(dropped.is_err());
+        assert_eq!(b.unwrap().confidence, 1.0);
+        let c = c.unwrap();
+        assert_eq!(c.confidence, 2.0);
+        assert_eq!(c.metadata["batch_size"], 3);
+        assert_eq!(batcher.metrics().batches_executed, 1);
+    }
+}
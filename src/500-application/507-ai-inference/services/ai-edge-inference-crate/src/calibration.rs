@@ -0,0 +1,389 @@
+//! Confidence calibration and per-class thresholds
+//!
+//! Raw sigmoid and softmax scores of different models are not comparable: one
+//! detector's 0.6 may be right more often than another's 0.9. A calibration
+//! maps each model's scores onto the probability that the prediction is
+//! correct, so alert levels and thresholds mean the same for every model.
+//!
+//! Both calibrations work on the logit of a score, one class at a time:
+//!
+//! - temperature: `sigmoid(logit(p) / temperature)`
+//! - Platt scaling: `sigmoid(a * logit(p) + b)`
+//!
+//! Parameters are fitted from a labeled validation set with [`CalibrationFit`];
+//! `evaluation::calibration_samples` collects the samples from an evaluation run.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::Prediction;
+
+/// Scores are clamped away from 0 and 1 before taking their logit
+const EPSILON: f64 = 1e-6;
+/// Bins of the expected calibration error
+const ECE_BINS: usize = 10;
+/// Fewer labeled scores than this cannot support a fit
+const MIN_SAMPLES: usize = 20;
+
+/// Errors in a calibration configuration or fit
+#[derive(Error, Debug)]
+pub enum CalibrationError {
+    #[error("Temperature must be positive, got {0}")]
+    InvalidTemperature(f32),
+    #[error("Platt parameters must be finite with a positive slope, got a={a}, b={b}")]
+    InvalidPlatt { a: f32, b: f32 },
+    #[error("Threshold for class '{class}' must be in [0, 1], got {value}")]
+    InvalidThreshold { class: String, value: f32 },
+    #[error("Calibration needs at least {MIN_SAMPLES} labeled scores with both correct and incorrect predictions, got {0}")]
+    NotEnoughSamples(usize),
+    #[error("Invalid calibration config: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Mapping from raw scores to calibrated confidences
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Calibration {
+    /// Divide the logit by a temperature; above 1 softens overconfident scores
+    Temperature { temperature: f32 },
+    /// Logistic regression on the logit
+    Platt { a: f32, b: f32 },
+}
+
+/// Calibration to fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationMethod {
+    Temperature,
+    Platt,
+}
+
+impl Calibration {
+    pub fn apply(&self, confidence: f32) -> f32 {
+        let logit = logit(confidence as f64);
+        let scaled = match *self {
+            Self::Temperature { temperature } => logit / temperature as f64,
+            Self::Platt { a, b } => a as f64 * logit + b as f64,
+        };
+        sigmoid(scaled) as f32
+    }
+
+    /// Raw score that calibrates to `confidence`; both calibrations are increasing
+    pub fn invert(&self, confidence: f32) -> f32 {
+        if confidence <= 0.0 {
+            return 0.0;
+        }
+        let logit = logit(confidence as f64);
+        let raw = match *self {
+            Self::Temperature { temperature } => logit * temperature as f64,
+            Self::Platt { a, b } => (logit - b as f64) / a as f64,
+        };
+        sigmoid(raw) as f32
+    }
+
+    pub fn validate(&self) -> Result<(), CalibrationError> {
+        match *self {
+            Self::Temperature { temperature } if !(temperature > 0.0 && temperature.is_finite()) => {
+                Err(CalibrationError::InvalidTemperature(temperature))
+            }
+            // A negative slope would rank predictions in reverse
+            Self::Platt { a, b } if !(a > 0.0 && a.is_finite() && b.is_finite()) => {
+                Err(CalibrationError::InvalidPlatt { a, b })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Calibration and per-class thresholds applied to a model's predictions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScoreCalibration {
+    pub calibration: Option<Calibration>,
+    /// Minimum calibrated confidence by class label
+    pub class_thresholds: BTreeMap<String, f32>,
+}
+
+impl ScoreCalibration {
+    pub fn new(calibration: Option<Calibration>, class_thresholds: BTreeMap<String, f32>) -> Result<Self, CalibrationError> {
+        let config = Self { calibration, class_thresholds };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Read `calibration` and `class_thresholds` from a model's postprocessing JSON
+    pub fn from_postprocessing(postprocessing: Option<&serde_json::Value>) -> Result<Self, CalibrationError> {
+        let entry = |key: &str| postprocessing.and_then(|post| post.get(key)).filter(|v| !v.is_null()).cloned();
+        let calibration = entry("calibration").map(serde_json::from_value).transpose()?;
+        let class_thresholds = entry("class_thresholds").map(serde_json::from_value).transpose()?.unwrap_or_default();
+        Self::new(calibration, class_thresholds)
+    }
+
+    pub fn validate(&self) -> Result<(), CalibrationError> {
+        if let Some(calibration) = &self.calibration {
+            calibration.validate()?;
+        }
+        match self.class_thresholds.iter().find(|(_, value)| !(0.0..=1.0).contains(*value)) {
+            Some((class, value)) => Err(CalibrationError::InvalidThreshold { class: class.clone(), value: *value }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calibration.is_none() && self.class_thresholds.is_empty()
+    }
+
+    /// Raw score threshold that keeps exactly the predictions whose calibrated
+    /// confidence reaches `confidence_threshold`
+    ///
+    /// Decoders filter raw scores before calibration runs, so they compare
+    /// against this instead of the configured threshold.
+    pub fn raw_threshold(&self, confidence_threshold: f32) -> f32 {
+        self.calibration.map_or(confidence_threshold, |calibration| calibration.invert(confidence_threshold))
+    }
+
+    /// Calibrate every prediction's confidence, then drop those below their class threshold
+    ///
+    /// The uncalibrated score is kept in the `raw_confidence` metadata entry so
+    /// calibration can be refitted from live results.
+    pub fn apply(&self, predictions: &mut Vec<Prediction>) {
+        if let Some(calibration) = &self.calibration {
+            for prediction in predictions.iter_mut() {
+                prediction.metadata.insert("raw_confidence".to_string(), serde_json::json!(prediction.confidence));
+                prediction.confidence = calibration.apply(prediction.confidence);
+            }
+        }
+        if !self.class_thresholds.is_empty() {
+            predictions.retain(|prediction| {
+                self.class_thresholds.get(&prediction.class).is_none_or(|threshold| prediction.confidence >= *threshold)
+            });
+        }
+    }
+}
+
+/// Raw score of a prediction, before any calibration
+pub fn raw_confidence(prediction: &Prediction) -> f32 {
+    prediction.metadata.get("raw_confidence")
+        .and_then(|value| value.as_f64())
+        .map_or(prediction.confidence, |value| value as f32)
+}
+
+/// Fitted calibration and how well scores match accuracy before and after it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationFit {
+    pub calibration: Calibration,
+    /// Labeled scores the fit used
+    pub samples: usize,
+    /// Expected calibration error of the raw scores
+    pub ece_before: f64,
+    /// Expected calibration error of the calibrated scores
+    pub ece_after: f64,
+}
+
+impl CalibrationFit {
+    /// Fit a calibration to `(raw score, prediction was correct)` pairs by
+    /// minimizing their negative log-likelihood
+    pub fn fit(method: CalibrationMethod, samples: &[(f32, bool)]) -> Result<Self, CalibrationError> {
+        let positives = samples.iter().filter(|(_, correct)| *correct).count();
+        if samples.len() < MIN_SAMPLES || positives == 0 || positives == samples.len() {
+            return Err(CalibrationError::NotEnoughSamples(samples.len()));
+        }
+
+        // Platt's smoothed targets keep the fit finite on separable data
+        let negatives = samples.len() - positives;
+        let (high, low) = (
+            (positives as f64 + 1.0) / (positives as f64 + 2.0),
+            1.0 / (negatives as f64 + 2.0),
+        );
+        let points: Vec<(f64, f64)> = samples.iter()
+            .map(|&(score, correct)| (logit(score as f64), if correct { high } else { low }))
+            .collect();
+
+        let calibration = match method {
+            CalibrationMethod::Temperature => {
+                let (a, _) = fit_logistic(&points, false);
+                Calibration::Temperature { temperature: (1.0 / a) as f32 }
+            }
+            CalibrationMethod::Platt => {
+                let (a, b) = fit_logistic(&points, true);
+                Calibration::Platt { a: a as f32, b: b as f32 }
+            }
+        };
+        calibration.validate()?;
+
+        let calibrated: Vec<(f32, bool)> = samples.iter()
+            .map(|&(score, correct)| (calibration.apply(score), correct))
+            .collect();
+        Ok(Self {
+            calibration,
+            samples: samples.len(),
+            ece_before: expected_calibration_error(samples),
+            ece_after: expected_calibration_error(&calibrated),
+        })
+    }
+}
+
+/// Mean gap between confidence and accuracy over equal-width confidence bins,
+/// weighted by the share of scores in each bin
+pub fn expected_calibration_error(samples: &[(f32, bool)]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    // (confidence sum, correct count, count) per bin
+    let mut bins = [(0.0f64, 0usize, 0usize); ECE_BINS];
+    for &(score, correct) in samples {
+        let bin = &mut bins[((score.clamp(0.0, 1.0) * ECE_BINS as f32) as usize).min(ECE_BINS - 1)];
+        bin.0 += score as f64;
+        bin.1 += usize::from(correct);
+        bin.2 += 1;
+    }
+    bins.iter()
+        .filter(|(_, _, count)| *count > 0)
+        .map(|&(confidence, correct, count)| {
+            (confidence / count as f64 - correct as f64 / count as f64).abs() * count as f64 / samples.len() as f64
+        })
+        .sum()
+}
+
+/// Logistic regression `sigmoid(a * x + b)` by Newton's method with step
+/// halving; `b` stays 0 without an intercept
+fn fit_logistic(points: &[(f64, f64)], intercept: bool) -> (f64, f64) {
+    let (mut a, mut b) = (1.0f64, 0.0f64);
+    let mut loss = negative_log_likelihood(points, a, b);
+    for _ in 0..100 {
+        let (mut ga, mut gb, mut haa, mut hab, mut hbb) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for &(x, target) in points {
+            let p = sigmoid(a * x + b);
+            let weight = (p * (1.0 - p)).max(1e-12);
+            ga += (p - target) * x;
+            gb += p - target;
+            haa += weight * x * x;
+            hab += weight * x;
+            hbb += weight;
+        }
+
+        let (step_a, step_b) = if intercept {
+            let determinant = haa * hbb - hab * hab;
+            if determinant.abs() < 1e-12 {
+                break;
+            }
+            ((hbb * ga - hab * gb) / determinant, (haa * gb - hab * ga) / determinant)
+        } else if haa > 1e-12 {
+            (ga / haa, 0.0)
+        } else {
+            break;
+        };
+
+        // Full Newton steps can overshoot far from the optimum
+        let mut scale = 1.0;
+        let improved = loop {
+            let (next_a, next_b) = (a - scale * step_a, b - scale * step_b);
+            let next_loss = negative_log_likelihood(points, next_a, next_b);
+            if next_loss <= loss {
+                break Some((next_a, next_b, next_loss));
+            }
+            scale /= 2.0;
+            if scale < 1e-6 {
+                break None;
+            }
+        };
+        let Some((next_a, next_b, next_loss)) = improved else {
+            break;
+        };
+        let converged = (loss - next_loss).abs() < 1e-12;
+        (a, b, loss) = (next_a, next_b, next_loss);
+        if converged {
+            break;
+        }
+    }
+    (a, b)
+}
+
+fn negative_log_likelihood(points: &[(f64, f64)], a: f64, b: f64) -> f64 {
+    points.iter()
+        .map(|&(x, target)| {
+            let p = sigmoid(a * x + b).clamp(1e-15, 1.0 - 1e-15);
+            -(target * p.ln() + (1.0 - target) * (1.0 - p).ln())
+        })
+        .sum()
+}
+
+fn logit(p: f64) -> f64 {
+    let p = p.clamp(EPSILON, 1.0 - EPSILON);
+    (p / (1.0 - p)).ln()
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn prediction(class: &str, confidence: f32) -> Prediction {
+        Prediction {
+            class: class.to_string(),
+            confidence,
+            bbox: None,
+            metadata: HashMap::new(),
+            severity: None,
+            mask: None,
+            keypoints: None,
+        }
+    }
+
+    #[test]
+    fn test_calibration_and_class_thresholds_filter_predictions() {
+        let post = serde_json::json!({
+            "calibration": { "method": "temperature", "temperature": 2.0 },
+            "class_thresholds": { "person": 0.65 },
+        });
+        let calibration = ScoreCalibration::from_postprocessing(Some(&post)).unwrap();
+
+        let mut predictions = vec![prediction("person", 0.9), prediction("person", 0.7), prediction("forklift", 0.7)];
+        calibration.apply(&mut predictions);
+
+        // logit(0.9) / 2 -> 0.75 stays; logit(0.7) / 2 -> 0.60 drops below the person threshold
+        assert_eq!(predictions.len(), 2);
+        assert!((predictions[0].confidence - 0.75).abs() < 1e-3);
+        assert_eq!(raw_confidence(&predictions[0]), 0.9);
+        assert_eq!(predictions[1].class, "forklift");
+
+        let platt = Calibration::Platt { a: 1.0, b: 0.0 };
+        assert!((platt.apply(0.3) - 0.3).abs() < 1e-6);
+        let platt = Calibration::Platt { a: 1.5, b: 0.8 };
+        assert!((platt.apply(platt.invert(0.5)) - 0.5).abs() < 1e-5);
+        assert!((calibration.raw_threshold(0.75) - 0.9).abs() < 1e-3);
+        assert_eq!(ScoreCalibration::default().raw_threshold(0.75), 0.75);
+        let invalid = serde_json::json!({ "calibration": { "method": "platt", "a": -1.0, "b": 0.0 } });
+        assert!(ScoreCalibration::from_postprocessing(Some(&invalid)).is_err());
+        let invalid = serde_json::json!({ "class_thresholds": { "person": 1.5 } });
+        assert!(ScoreCalibration::from_postprocessing(Some(&invalid)).is_err());
+    }
+
+    #[test]
+    fn test_fit_softens_overconfident_scores() {
+        // Scores of 0.95 that are right 70% of the time, 0.6 right half the time
+        let mut samples = Vec::new();
+        for i in 0..100 {
+            samples.push((0.95, i % 10 < 7));
+            samples.push((0.6, i % 2 == 0));
+        }
+
+        let temperature = CalibrationFit::fit(CalibrationMethod::Temperature, &samples).unwrap();
+        let Calibration::Temperature { temperature: t } = temperature.calibration else {
+            panic!("expected a temperature calibration");
+        };
+        assert!(t > 1.0);
+        assert!(temperature.ece_after < temperature.ece_before);
+
+        let platt = CalibrationFit::fit(CalibrationMethod::Platt, &samples).unwrap();
+        assert!(platt.ece_after < 0.02);
+        assert!((platt.calibration.apply(0.95) - 0.7).abs() < 0.02);
+
+        assert!(CalibrationFit::fit(CalibrationMethod::Platt, &samples[..10]).is_err());
+    }
+}
@@ -2,6 +2,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use crate::types::ModelType;
+use crate::integrity::IntegrityConfig;
+use crate::tracking::TrackingConfig;
+use crate::analytics::AnalyticsConfig;
+use crate::model_cache::ModelCacheConfig;
 
 /// Configuration for the AI inference engine
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -16,6 +20,12 @@ pub struct InferenceConfig {
     pub monitoring: MonitoringConfig,
     /// Industrial site context
     pub site_context: SiteContext,
+    /// Multi-object tracking across frames of a stream
+    #[serde(default)]
+    pub tracking: TrackingConfig,
+    /// Zone, tripwire and separation rules per camera
+    #[serde(default)]
+    pub analytics: AnalyticsConfig,
 }
 
 /// Configuration for AI models
@@ -31,6 +41,12 @@ pub struct ModelsConfig {
     pub global_confidence_threshold: f32,
     /// Maximum predictions per inference
     pub max_predictions_per_model: usize,
+    /// Artifact digest and signature policy for model loading
+    #[serde(default)]
+    pub integrity: IntegrityConfig,
+    /// Model memory budget, lazy loading and pinned models
+    #[serde(default)]
+    pub cache: ModelCacheConfig,
 }
 
 /// Definition of an AI model
@@ -74,8 +90,11 @@ pub struct PerformanceConfig {
     pub num_threads: Option<usize>,
     /// Enable parallel processing of multiple inputs
     pub enable_parallel_processing: bool,
-    /// Batch size for processing multiple inputs
+    /// Batch size for processing multiple inputs; values above 1 enable dynamic batching
     pub batch_size: usize,
+    /// Longest time a queued request waits for its batch to fill (milliseconds)
+    #[serde(default = "default_max_batch_wait_ms")]
+    pub max_batch_wait_ms: u64,
     /// Timeout for inference operations (milliseconds)
     pub inference_timeout_ms: u64,
     /// Enable model caching to reduce load times
@@ -147,6 +166,8 @@ impl Default for ModelsConfig {
             model_configs: HashMap::new(),
             global_confidence_threshold: 0.5,
             max_predictions_per_model: 10,
+            integrity: IntegrityConfig::default(),
+            cache: ModelCacheConfig::default(),
         }
     }
 }
@@ -170,6 +191,7 @@ impl Default for PerformanceConfig {
             num_threads: None, // Use system default
             enable_parallel_processing: true,
             batch_size: 1,
+            max_batch_wait_ms: default_max_batch_wait_ms(),
             inference_timeout_ms: 5000,
             enable_model_caching: true,
             memory_pool_size_mb: Some(512),
@@ -177,6 +199,10 @@ impl Default for PerformanceConfig {
     }
 }
 
+fn default_max_batch_wait_ms() -> u64 {
+    5
+}
+
 impl Default for HardwareConfig {
     fn default() -> Self {
         Self {
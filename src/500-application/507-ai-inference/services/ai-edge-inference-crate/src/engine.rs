@@ -1,25 +1,53 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::future::Future;
+use std::time::{Duration, Instant};
 use std::sync::RwLock;
 use std::path::{Path, PathBuf};
 use tracing::{info, warn, debug};
+use tokio::sync::{oneshot, OwnedRwLockReadGuard, OwnedRwLockWriteGuard};
 use base64::{Engine as _, engine::general_purpose};
 use serde_json;
 
 use crate::types::{InferenceRequest, InferenceResult};
 use crate::error::InferenceError;
 use crate::config::InferenceConfig;
-use crate::backend::{Backend, BackendFactory, BackendConfig, BackendType, DeviceType, OptimizationLevel};
-use crate::{InferenceInput, ModelConfig};
-use crate::model_config::{ModelConfigManager, ModelConfiguration, ModelSummary};
+use crate::batching::{BatchingMetrics, DynamicBatcher};
+use crate::versioning::{version_key, CanarySplit, ModelVersionRouter, VersionRoute, VersionStatus};
+use crate::backend::{Backend, BackendError, BackendFactory, BackendConfig, BackendType, DeviceType, OptimizationLevel};
+use crate::backends::registry::BackendRegistry;
+use crate::{audio, InferenceInput, ModelConfig};
+use crate::model_config::{ModelConfigError, ModelConfigManager, ModelConfiguration, ModelSummary};
+use crate::integrity::TrustStore;
+use crate::pipeline::{self, PipelineConfiguration, PipelineMetrics, StageRun, StageSummary};
+use crate::tiling::{self, TilingConfig};
+use crate::tensors::TensorRequest;
+use crate::model_cache::{CacheLookup, ModelCache, ModelCacheEntry, ModelCacheMetrics};
+use crate::tracking::TrackerRegistry;
+use crate::analytics::ZoneAnalytics;
 
 /// Core AI inference engine that processes requests using pluggable ML backends
 pub struct InferenceEngine {
-    backend: Backend,
+    /// Locked so models can be loaded and evicted while requests run; shared
+    /// with batch workers, whose read guards keep batched models resident
+    backend: Arc<tokio::sync::RwLock<Backend>>,
+    /// Memory-budgeted residency of backend models
+    model_cache: Arc<ModelCache>,
     config: InferenceConfig,
     metrics: Arc<RwLock<InferenceMetrics>>,
     model_config_manager: Option<ModelConfigManager>,
+    /// Stacks concurrent image requests when `performance.batch_size` is above one
+    batcher: Option<Arc<DynamicBatcher>>,
+    /// Routes logical model names to side-by-side loaded versions
+    versions: Arc<ModelVersionRouter>,
+    /// Multi-stage pipelines addressed by name like a model
+    pipelines: HashMap<String, PipelineConfiguration>,
+    /// Tiling settings by backend model key, for models that slice large images
+    tiling: HashMap<String, TilingConfig>,
+    /// Per-stream object trackers when `tracking.enabled` is set
+    tracker: Option<TrackerRegistry>,
+    /// Per-camera zone, tripwire and separation rules
+    analytics: Option<ZoneAnalytics>,
 }
 
 /// Performance and usage metrics for the inference engine
@@ -32,6 +60,9 @@ pub struct InferenceMetrics {
     pub total_inference_time_ms: f64,
     pub model_usage_count: HashMap<String, u64>,
     pub error_count_by_type: HashMap<String, u64>,
+    pub batching: BatchingMetrics,
+    pub pipelines: HashMap<String, PipelineMetrics>,
+    pub model_cache: ModelCacheMetrics,
     pub last_reset: chrono::DateTime<chrono::Utc>,
 }
 
@@ -44,9 +75,9 @@ impl InferenceEngine {
         let available_backends = BackendFactory::available_backends();
         info!("Available backends: {:?}", available_backends);
 
-        if available_backends.is_empty() {
+        if available_backends.is_empty() && BackendRegistry::registered().is_empty() {
             return Err(InferenceError::configuration(
-                "No ML backends available. Check that onnx-runtime or candle features are enabled.".to_string()
+                "No ML backends available. Enable the onnx-runtime or candle features, or register a backend.".to_string()
             ));
         }
 
@@ -55,7 +86,7 @@ impl InferenceEngine {
             backend_type: BackendType::Auto, // Auto-select
             device_type: DeviceType::Cpu, // Force CPU
             model_directory: config.models.models_directory.to_string_lossy().to_string(),
-            cache_size_mb: 512, // Reduced cache
+            cache_size_mb: backend_cache_size_mb(&config, 512), // Reduced cache
             enable_optimization: false, // Disable optimization for debugging
             optimization_level: OptimizationLevel::None,
             parallel_execution: false, // Disable parallel execution
@@ -75,7 +106,15 @@ impl InferenceEngine {
             .map_err(|e| InferenceError::configuration(format!("Failed to create backend: {}", e)))?;
 
         Ok(Self {
-            backend,
+            backend: Arc::new(tokio::sync::RwLock::new(backend)),
+            model_cache: Arc::new(ModelCache::new(config.models.cache.clone())),
+            batcher: DynamicBatcher::from_config(&config.performance).map(Arc::new),
+            versions: Arc::new(ModelVersionRouter::new()),
+            pipelines: HashMap::new(),
+            tiling: HashMap::new(),
+            tracker: TrackerRegistry::from_config(&config.tracking),
+            analytics: ZoneAnalytics::from_config(&config.analytics)
+                .map_err(|e| InferenceError::configuration(format!("Invalid analytics rules: {}", e)))?,
             config,
             metrics: Arc::new(RwLock::new(InferenceMetrics::default())),
             model_config_manager: None,
@@ -91,7 +130,7 @@ impl InferenceEngine {
             backend_type: backend_type.clone(),
             device_type: DeviceType::Auto,
             model_directory: config.models.models_directory.to_string_lossy().to_string(),
-            cache_size_mb: 1024,
+            cache_size_mb: backend_cache_size_mb(&config, 1024),
             enable_optimization: true,
             optimization_level: OptimizationLevel::Basic,
             parallel_execution: true,
@@ -104,7 +143,15 @@ impl InferenceEngine {
             .map_err(|e| InferenceError::configuration(format!("Failed to create {} backend: {}", backend_type, e)))?;
 
         Ok(Self {
-            backend,
+            backend: Arc::new(tokio::sync::RwLock::new(backend)),
+            model_cache: Arc::new(ModelCache::new(config.models.cache.clone())),
+            batcher: DynamicBatcher::from_config(&config.performance).map(Arc::new),
+            versions: Arc::new(ModelVersionRouter::new()),
+            pipelines: HashMap::new(),
+            tiling: HashMap::new(),
+            tracker: TrackerRegistry::from_config(&config.tracking),
+            analytics: ZoneAnalytics::from_config(&config.analytics)
+                .map_err(|e| InferenceError::configuration(format!("Invalid analytics rules: {}", e)))?,
             config,
             metrics: Arc::new(RwLock::new(InferenceMetrics::default())),
             model_config_manager: None,
@@ -124,7 +171,7 @@ impl InferenceEngine {
             backend_type: BackendType::Auto, // Use detected backend
             device_type: DeviceType::Auto,
             model_directory: self.config.models.models_directory.to_string_lossy().to_string(),
-            cache_size_mb: 1024,
+            cache_size_mb: backend_cache_size_mb(&self.config, 1024),
             enable_optimization: true,
             optimization_level: OptimizationLevel::Basic,
             parallel_execution: true,
@@ -132,21 +179,25 @@ impl InferenceEngine {
             candle_config: Some(Default::default()),
         };
 
-        self.backend.initialize(&backend_config).await
+        self.backend.write().await.initialize(&backend_config).await
             .map_err(|e| InferenceError::configuration(format!("Backend initialization failed: {}", e)))?;
 
         // Load default models if specified
-        if let Some(default_models) = &self.config.models.default_models {
-            for (model_name, model_path) in default_models {
+        if let Some(default_models) = self.config.models.default_models.clone() {
+            for (model_name, model_path) in &default_models {
                 let model_config = ModelConfig {
                     model_path: model_path.clone(),
                     model_type: "auto".to_string(),
                     confidence_threshold: Some(0.5),
                     preprocessing: None,
                     postprocessing: None,
+                    integrity: None,
+                    memory_mb: None,
                 };
 
-                if let Err(e) = self.backend.load_model(model_name, &model_config).await {
+                if let Err(e) = self.check_integrity_policy(model_name, &model_config) {
+                    warn!("Skipping default model '{}': {}", model_name, e);
+                } else if let Err(e) = self.load_backend_model(model_name, &model_config).await {
                     warn!("Failed to load default model '{}': {}", model_name, e);
                 } else {
                     info!("Loaded default model: {}", model_name);
@@ -155,11 +206,11 @@ impl InferenceEngine {
         }
 
         // Initialize metrics
-        let mut metrics = self.metrics.write()
-            .map_err(|error| InferenceError::internal(format!("Metrics lock poisoned during initialization: {}", error)))?;
-        metrics.last_reset = chrono::Utc::now();
+        self.metrics.write()
+            .map_err(|error| InferenceError::internal(format!("Metrics lock poisoned during initialization: {}", error)))?
+            .last_reset = chrono::Utc::now();
 
-        info!("AI inference engine initialized successfully with {} backend", self.backend.backend_type());
+        info!("AI inference engine initialized successfully with {} backend", self.backend.read().await.backend_type());
         Ok(())
     }
 
@@ -176,8 +227,11 @@ impl InferenceEngine {
         // Convert request to backend input format
         let inference_input = self.convert_request_to_input(&request).await?;
 
-        // Run inference (simplified without timeout for now)
-        let result = self.backend.infer(inference_input, request.model_name.as_deref()).await;
+        let pipeline = request.model_name.as_deref().and_then(|name| self.pipelines.get(name));
+        let result = match pipeline {
+            Some(pipeline) => self.run_pipeline(pipeline, inference_input, &request).await,
+            None => self.run_model(request.model_name.as_deref(), inference_input, &request).await,
+        };
 
         let inference_result = match result {
             Ok(mut result) => {
@@ -194,6 +248,13 @@ impl InferenceEngine {
                     );
                 }
 
+                if let Some(tracker) = &self.tracker {
+                    tracker.track(&request, &mut result);
+                }
+                if let Some(analytics) = &self.analytics {
+                    analytics.evaluate(&request, &mut result);
+                }
+
                 if let Err(metrics_error) = self.update_success_metrics(&result.model_name, start_time).await {
                     warn!(
                         request_id = %request_id,
@@ -203,8 +264,7 @@ impl InferenceEngine {
                 }
                 Ok(result)
             }
-            Err(e) => {
-                let inference_error = InferenceError::execution(e.to_string());
+            Err(inference_error) => {
                 if let Err(metrics_error) = self.update_error_metrics(&inference_error).await {
                     warn!(
                         request_id = %request_id,
@@ -220,6 +280,251 @@ impl InferenceEngine {
         inference_result
     }
 
+    /// Run one model by its logical name
+    ///
+    /// Versioned models are routed to the served version, with the shadow
+    /// version run alongside in the background when one is configured.
+    async fn run_model(
+        &self,
+        model_name: Option<&str>,
+        inference_input: InferenceInput,
+        request: &InferenceRequest,
+    ) -> Result<InferenceResult, InferenceError> {
+        let start_time = Instant::now();
+        let request_id = request.request_id.as_str();
+
+        // Route versioned models to the served (and shadow) version
+        let route = model_name.and_then(|name| self.versions.route(name, request_id));
+        let backend_model = route.as_ref()
+            .map(|route| route.served.backend_key.as_str())
+            .or(model_name);
+        let timeout_ms = self.request_timeout_ms(request, model_name, route.as_ref());
+
+        let served_tx = route.as_ref()
+            .filter(|route| route.shadow.is_some())
+            .and_then(|route| self.spawn_shadow(route.clone(), inference_input.clone(), timeout_ms, request_id));
+        let tiling = backend_model.and_then(|name| self.tiling.get(name));
+        let result = self.runner().run_with_deadline(inference_input, backend_model, tiling, timeout_ms, request_id).await;
+        if let (Some(served_tx), Ok(result)) = (served_tx, &result) {
+            // The shadow task may already have given up on the comparison
+            let _ = served_tx.send((result.clone(), start_time.elapsed().as_secs_f64() * 1000.0));
+        }
+
+        if let Some(route) = &route {
+            let elapsed_ms = start_time.elapsed().as_secs_f64() * 1000.0;
+            self.versions.record(&route.model_name, &route.served.version, elapsed_ms, result.is_ok());
+        }
+        result.map(|mut result| {
+            if let Some(route) = &route {
+                result.model_name = route.model_name.clone();
+                if let Some(metadata) = result.metadata.as_object_mut() {
+                    metadata.insert("model_version".to_string(), serde_json::json!(route.served.version));
+                }
+            }
+            result
+        })
+    }
+
+    /// Run the shadow version of `route` on a detached task with its own deadline
+    ///
+    /// The served result and latency are sent on the returned channel for the
+    /// shadow comparison; the served response never waits for the shadow. The
+    /// shadow is skipped while the model has too many shadow runs in flight,
+    /// and when its version is not loaded: shadow traffic never loads or
+    /// evicts models.
+    fn spawn_shadow(
+        &self,
+        route: VersionRoute,
+        input: InferenceInput,
+        timeout_ms: Option<u64>,
+        request_id: &str,
+    ) -> Option<oneshot::Sender<(InferenceResult, f64)>> {
+        let permit = self.versions.shadow_permit(&route)?;
+        let (served_tx, served_rx) = oneshot::channel::<(InferenceResult, f64)>();
+        let tiling = route.shadow.as_ref().and_then(|shadow| self.tiling.get(&shadow.backend_key).cloned());
+        let (runner, versions, request_id) = (self.runner(), Arc::clone(&self.versions), request_id.to_string());
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let Some(shadow) = &route.shadow else {
+                return;
+            };
+            let started = Instant::now();
+            let Some(shadow_result) = runner.run_if_loaded(
+                input, &shadow.backend_key, tiling.as_ref(), timeout_ms, &request_id,
+            ).await else {
+                versions.record_shadow_skip(&route);
+                return;
+            };
+            let shadow_ms = started.elapsed().as_secs_f64() * 1000.0;
+            versions.record(&route.model_name, &shadow.version, shadow_ms, shadow_result.is_ok());
+
+            // Dropped without a value when the served request failed or was cancelled
+            let served = served_rx.await.ok();
+            versions.record_shadow(
+                &route,
+                served.as_ref().map(|(result, served_ms)| (result, *served_ms)),
+                shadow_result.as_ref().ok().map(|result| (result, shadow_ms)),
+            );
+        });
+        Some(served_tx)
+    }
+
+    /// Run a pipeline's stages in order, cropping each stage's input from the request image
+    async fn run_pipeline(
+        &self,
+        pipeline: &PipelineConfiguration,
+        inference_input: InferenceInput,
+        request: &InferenceRequest,
+    ) -> Result<InferenceResult, InferenceError> {
+        let start_time = Instant::now();
+        let image = match &inference_input {
+            InferenceInput::Image { data, .. } => Some(data.clone()),
+            _ if pipeline.stages.len() > 1 => {
+                return Err(InferenceError::invalid_input(format!(
+                    "Pipeline '{}' crops its input and needs an image", pipeline.name
+                )));
+            }
+            _ => None,
+        };
+        let image_region = image.as_ref().map_or((0, 0, 0, 0), |image| (0, 0, image.width(), image.height()));
+
+        let root = &pipeline.stages[0];
+        let stage_start = Instant::now();
+        let root_result = self.run_model(Some(&root.model), inference_input, request).await;
+        let mut summaries = vec![StageSummary {
+            name: root.name.clone(),
+            model: root.model.clone(),
+            runs: 1,
+            failures: usize::from(root_result.is_err()),
+            predictions: root_result.as_ref().map_or(0, |result| result.predictions.len()),
+            time_ms: stage_start.elapsed().as_secs_f64() * 1000.0,
+        }];
+        let mut root_result = match root_result {
+            Ok(result) => result,
+            Err(e) => {
+                self.record_pipeline(&pipeline.name, &summaries, false);
+                return Err(e);
+            }
+        };
+        if image.is_some() {
+            pipeline::to_image_coordinates(&mut root_result, image_region);
+            if let Some(metadata) = root_result.metadata.as_object_mut() {
+                metadata.insert("input_size".to_string(), serde_json::json!([image_region.2, image_region.3]));
+            }
+        }
+
+        let sources = pipeline.source_indices();
+        let mut runs = vec![vec![StageRun { parent: None, predictions: std::mem::take(&mut root_result.predictions) }]];
+
+        for (stage, source) in pipeline.stages.iter().zip(&sources).skip(1) {
+            let (Some(image), Some(source)) = (&image, *source) else {
+                runs.push(Vec::new());
+                continue;
+            };
+
+            let crops: Vec<_> = runs[source].iter()
+                .enumerate()
+                .flat_map(|(run_index, run)| {
+                    pipeline::crop_regions(&run.predictions, &stage.crop, (image.width(), image.height()))
+                        .into_iter()
+                        .map(move |(prediction_index, region)| ((run_index, prediction_index), region))
+                })
+                .collect();
+
+            let stage_start = Instant::now();
+            let results = futures::future::join_all(crops.iter().map(|(_, (x, y, width, height))| {
+                let input = InferenceInput::Image {
+                    data: image.crop_imm(*x, *y, *width, *height),
+                    metadata: crate::ImageMetadata {
+                        width: *width,
+                        height: *height,
+                        channels: 3,
+                        format: "RGB".to_string(),
+                    },
+                };
+                self.run_model(Some(&stage.model), input, request)
+            })).await;
+
+            let mut stage_runs = Vec::with_capacity(crops.len());
+            let mut failures = 0;
+            for ((parent, region), result) in crops.into_iter().zip(results) {
+                match result {
+                    Ok(mut result) => {
+                        pipeline::to_image_coordinates(&mut result, region);
+                        stage_runs.push(StageRun { parent: Some(parent), predictions: result.predictions });
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        warn!(request_id = %request.request_id, stage = %stage.name, "Pipeline stage run failed: {}", e);
+                    }
+                }
+            }
+
+            summaries.push(StageSummary {
+                name: stage.name.clone(),
+                model: stage.model.clone(),
+                runs: stage_runs.len() + failures,
+                failures,
+                predictions: stage_runs.iter().map(|run| run.predictions.len()).sum(),
+                time_ms: stage_start.elapsed().as_secs_f64() * 1000.0,
+            });
+            runs.push(stage_runs);
+        }
+
+        self.record_pipeline(&pipeline.name, &summaries, true);
+
+        root_result.predictions = pipeline::attach_children(pipeline, runs);
+        root_result.model_name = pipeline.name.clone();
+        root_result.inference_time_ms = start_time.elapsed().as_secs_f64() * 1000.0;
+        if let Some(metadata) = root_result.metadata.as_object_mut() {
+            metadata.insert("pipeline".to_string(), serde_json::json!({
+                "name": pipeline.name,
+                "stages": summaries,
+            }));
+        }
+        Ok(root_result)
+    }
+
+    fn record_pipeline(&self, pipeline_name: &str, summaries: &[StageSummary], success: bool) {
+        match self.metrics.write() {
+            Ok(mut metrics) => metrics.pipelines.entry(pipeline_name.to_string())
+                .or_default()
+                .record(summaries, success),
+            Err(error) => warn!("Failed to record pipeline metrics due to poisoned lock: {}", error),
+        }
+    }
+
+    /// Handles for running inputs on the backend outside the engine borrow
+    fn runner(&self) -> BackendRunner {
+        BackendRunner {
+            backend: Arc::clone(&self.backend),
+            model_cache: Arc::clone(&self.model_cache),
+            batcher: self.batcher.clone(),
+        }
+    }
+
+    /// Deadline for a request's backend call in milliseconds
+    ///
+    /// A `timeout_ms` entry in the request metadata takes precedence over the
+    /// model's YAML `timeout_ms`, which takes precedence over
+    /// `performance.inference_timeout_ms`. Zero disables the deadline.
+    fn request_timeout_ms(&self, request: &InferenceRequest, model_name: Option<&str>, route: Option<&VersionRoute>) -> Option<u64> {
+        let model_timeout_ms = self.model_config_manager.as_ref()
+            .and_then(|manager| match route {
+                Some(route) => manager.get_version_config(&route.model_name, &route.served.version),
+                None => manager.get_config(model_name?),
+            })
+            .and_then(|config| config.model.timeout_ms);
+
+        let timeout_ms = request.metadata.get("timeout_ms")
+            .and_then(|value| value.as_u64())
+            .or(model_timeout_ms)
+            .unwrap_or(self.config.performance.inference_timeout_ms);
+
+        (timeout_ms > 0).then_some(timeout_ms)
+    }
+
     /// Process multiple inference requests in batch
     pub async fn infer_batch(&self, requests: Vec<InferenceRequest>) -> Vec<InferenceResult> {
         if requests.is_empty() {
@@ -277,15 +582,20 @@ impl InferenceEngine {
                 let sensor_data: serde_json::Value = serde_json::from_str(&request.input_data)
                     .map_err(|e| InferenceError::preprocessing(format!("Failed to parse sensor data: {}", e)))?;
 
-                let values = sensor_data["values"].as_array()
-                    .ok_or_else(|| InferenceError::preprocessing("Missing 'values' field in sensor data".to_string()))?
-                    .iter()
-                    .map(|v| v.as_f64().unwrap_or(0.0) as f32)
+                let samples = sensor_data["values"].as_array()
+                    .ok_or_else(|| InferenceError::preprocessing("Missing 'values' field in sensor data".to_string()))?;
+
+                // Multi-channel samples arrive as nested arrays and are flattened frame-major
+                let values = samples.iter()
+                    .flat_map(|v| match v.as_array() {
+                        Some(channels) => channels.iter().map(|c| c.as_f64().unwrap_or(0.0) as f32).collect(),
+                        None => vec![v.as_f64().unwrap_or(0.0) as f32],
+                    })
                     .collect::<Vec<f32>>();
 
                 let timestamps = sensor_data["timestamps"].as_array()
                     .map(|arr| arr.iter().map(|v| v.as_i64().unwrap_or(0)).collect())
-                    .unwrap_or_else(|| (0..values.len() as i64).collect());
+                    .unwrap_or_else(|| (0..samples.len() as i64).collect());
 
                 Ok(InferenceInput::TimeSeries {
                     values,
@@ -297,30 +607,228 @@ impl InferenceEngine {
                     },
                 })
             }
+            "audio" => Self::convert_audio_input(&request.input_data).await,
+            "tensor" => {
+                let tensor_request = TensorRequest::parse(&request.input_data)
+                    .map_err(|e| InferenceError::invalid_input(e.to_string()))?;
+                Ok(InferenceInput::Tensor {
+                    tensors: tensor_request.tensors,
+                    raw_output: tensor_request.raw_output,
+                })
+            }
             _ => Err(InferenceError::invalid_input(format!("Unsupported input type: {}", request.input_type)))
         }
     }
 
+    /// Decode audio request data into mono samples
+    ///
+    /// `input_data` is either a base64 WAV file or a JSON object with `data`
+    /// (base64) or `path`, plus `format` ("wav" or a PCM encoding such as
+    /// "pcm_s16le") and, for PCM, `sample_rate` and `channels`.
+    async fn convert_audio_input(input_data: &str) -> Result<InferenceInput, InferenceError> {
+        let descriptor = serde_json::from_str::<serde_json::Value>(input_data)
+            .ok()
+            .filter(|value| value.is_object());
+
+        let (bytes, format) = match &descriptor {
+            Some(descriptor) => {
+                let format = descriptor["format"].as_str().unwrap_or("wav").to_lowercase();
+                let bytes = if let Some(data) = descriptor["data"].as_str() {
+                    general_purpose::STANDARD.decode(data)
+                        .map_err(|e| InferenceError::audio(format!("Failed to decode base64 audio: {}", e)))?
+                } else if let Some(path) = descriptor["path"].as_str() {
+                    tokio::fs::read(path).await
+                        .map_err(|e| InferenceError::audio(format!("Failed to read audio file '{}': {}", path, e)))?
+                } else {
+                    return Err(InferenceError::audio("Audio input needs a 'data' or 'path' field".to_string()));
+                };
+                (bytes, format)
+            }
+            None => {
+                let bytes = general_purpose::STANDARD.decode(input_data.trim())
+                    .map_err(|e| InferenceError::audio(format!("Failed to decode base64 audio: {}", e)))?;
+                (bytes, "wav".to_string())
+            }
+        };
+
+        let clip = if format == "wav" {
+            audio::decode_wav(&bytes)
+        } else {
+            let pcm_format = audio::PcmFormat::parse(&format)
+                .ok_or_else(|| InferenceError::audio(format!("Unsupported audio format: {}", format)))?;
+            let descriptor = descriptor.as_ref();
+            let sample_rate = descriptor.and_then(|d| d["sample_rate"].as_u64())
+                .ok_or_else(|| InferenceError::audio("PCM audio needs a 'sample_rate'".to_string()))?;
+            let channels = descriptor.and_then(|d| d["channels"].as_u64()).unwrap_or(1);
+            audio::decode_pcm(&bytes, pcm_format, sample_rate as u32, channels as u16)
+        }
+        .map_err(|e| InferenceError::audio(e.to_string()))?;
+
+        debug!("Decoded {:.2}s of {} audio at {} Hz", clip.duration_seconds(), format, clip.sample_rate);
+
+        Ok(InferenceInput::Audio {
+            samples: clip.samples,
+            metadata: crate::AudioMetadata {
+                sample_rate: clip.sample_rate,
+                channels: clip.channels,
+                format,
+            },
+        })
+    }
+
     /// Load a model into the backend
     pub async fn load_model(&mut self, model_name: &str, model_config: &ModelConfig) -> Result<(), InferenceError> {
-        self.backend.load_model(model_name, model_config).await
-            .map_err(|e| InferenceError::model(format!("Backend model loading failed: {}", e)))
+        self.check_integrity_policy(model_name, model_config)?;
+        let tiling = tiling_config(model_name, model_config)?;
+        self.load_backend_model(model_name, model_config).await
+            .map_err(model_load_error)?;
+        self.set_tiling(model_name, tiling);
+        Ok(())
+    }
+
+    /// Load a model into the backend, or only register it when lazy loading
+    /// defers it to its first request
+    async fn load_backend_model(&mut self, backend_key: &str, model_config: &ModelConfig) -> Result<(), BackendError> {
+        if self.model_cache.defers_load(backend_key) {
+            self.model_cache.register(backend_key, model_config);
+            info!("Registered model '{}' to load on its first request", backend_key);
+            return Ok(());
+        }
+        Self::load_into(&mut *self.backend.write().await, &self.model_cache, backend_key, model_config, false).await
+    }
+
+    /// Load a model, record it in the cache and evict models over the memory budget
+    async fn load_into(
+        backend: &mut Backend,
+        model_cache: &ModelCache,
+        backend_key: &str,
+        model_config: &ModelConfig,
+        lazy: bool,
+    ) -> Result<(), BackendError> {
+        let started = Instant::now();
+        if let Err(e) = backend.load_model(backend_key, model_config).await {
+            model_cache.record_load_failure();
+            return Err(e);
+        }
+        let load_time_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let memory_mb = model_config.memory_mb
+            .or_else(|| backend.model_memory_mb(backend_key))
+            .unwrap_or(0.0);
+        model_cache.record_load(backend_key, model_config, memory_mb, load_time_ms, lazy);
+        info!("Loaded model '{}' ({:.1} MB) in {:.1} ms", backend_key, memory_mb, load_time_ms);
+
+        for evicted in model_cache.evict_for_budget(backend_key) {
+            match backend.unload_model(&evicted).await {
+                Ok(()) => info!("Evicted model '{}' to stay within the model memory budget", evicted),
+                Err(e) => warn!("Failed to evict model '{}': {}", evicted, e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Unload a model from the backend unless it was registered but never loaded
+    async fn unload_backend_model(&mut self, backend_key: &str) -> Result<(), InferenceError> {
+        if self.model_cache.remove(backend_key) != Some(false) {
+            self.backend.write().await.unload_model(backend_key).await
+                .map_err(|e| InferenceError::model(format!("Backend model unloading failed: {}", e)))?;
+        }
+        self.tiling.remove(backend_key);
+        Ok(())
+    }
+
+    /// Apply the integrity policy to a model that was not loaded from YAML
+    ///
+    /// Signatures cover the model YAML, so a signed-only policy accepts
+    /// nothing but YAML-configured models.
+    fn check_integrity_policy(&self, model_name: &str, model_config: &ModelConfig) -> Result<(), InferenceError> {
+        let policy = &self.config.models.integrity;
+        if policy.require_signature {
+            return Err(InferenceError::integrity(format!(
+                "Model '{}' must be loaded from a signed YAML configuration", model_name
+            )));
+        }
+        if policy.require_digest && model_config.integrity.is_none() {
+            return Err(InferenceError::integrity(format!(
+                "Model '{}' does not declare an artifact digest", model_name
+            )));
+        }
+        Ok(())
     }
 
     /// Unload a model from the backend
     pub async fn unload_model(&mut self, model_name: &str) -> Result<(), InferenceError> {
-        self.backend.unload_model(model_name).await
-            .map_err(|e| InferenceError::model(format!("Backend model unloading failed: {}", e)))
+        self.unload_backend_model(model_name).await
+    }
+
+    /// Load a model version next to any versions already serving
+    ///
+    /// The first version loaded for a model becomes its stable version. Later
+    /// versions receive traffic only once promoted, given a canary split or
+    /// set as the shadow.
+    pub async fn load_model_version(&mut self, model_name: &str, version: &str, model_config: &ModelConfig) -> Result<(), InferenceError> {
+        self.check_integrity_policy(model_name, model_config)?;
+        self.register_model_version(model_name, version, model_config).await
+    }
+
+    async fn register_model_version(&mut self, model_name: &str, version: &str, model_config: &ModelConfig) -> Result<(), InferenceError> {
+        let backend_key = version_key(model_name, version);
+        let tiling = tiling_config(&backend_key, model_config)?;
+        self.load_backend_model(&backend_key, model_config).await
+            .map_err(model_load_error)?;
+        self.set_tiling(&backend_key, tiling);
+        self.versions.register(model_name, version);
+        info!("Loaded version '{}' of model '{}'", version, model_name);
+        Ok(())
+    }
+
+    /// Unload a model version that no longer receives traffic
+    pub async fn unload_model_version(&mut self, model_name: &str, version: &str) -> Result<(), InferenceError> {
+        self.versions.remove(model_name, version)
+            .map_err(|e| InferenceError::model(e.to_string()))?;
+        self.unload_backend_model(&version_key(model_name, version)).await
+    }
+
+    /// Atomically switch a model's stable version
+    pub fn promote_model_version(&self, model_name: &str, version: &str) -> Result<(), InferenceError> {
+        self.versions.promote(model_name, version)
+            .map_err(|e| InferenceError::model(e.to_string()))?;
+        info!("Promoted version '{}' of model '{}' to stable", version, model_name);
+        Ok(())
+    }
+
+    /// Atomically switch back to the previous stable version, returning it
+    pub fn rollback_model_version(&self, model_name: &str) -> Result<String, InferenceError> {
+        let version = self.versions.rollback(model_name)
+            .map_err(|e| InferenceError::model(e.to_string()))?;
+        warn!("Rolled model '{}' back to version '{}'", model_name, version);
+        Ok(version)
+    }
+
+    /// Set the canary split and shadow version of a model (None clears them)
+    pub fn set_model_traffic(&self, model_name: &str, canary: Option<CanarySplit>, shadow: Option<String>) -> Result<(), InferenceError> {
+        self.versions.set_canary(model_name, canary)
+            .and_then(|_| self.versions.set_shadow(model_name, shadow))
+            .map_err(|e| InferenceError::model(e.to_string()))
+    }
+
+    /// Routing state, per-version latency and shadow agreement for a model
+    pub fn model_version_status(&self, model_name: &str) -> Option<VersionStatus> {
+        self.versions.status(model_name)
     }
 
     /// Get list of loaded models
     pub async fn get_loaded_models(&self) -> Vec<String> {
-        self.backend.get_loaded_models().await
+        self.backend.read().await.get_loaded_models().await
     }
 
     /// Get backend status
     pub async fn get_backend_status(&self) -> crate::backend::BackendStatus {
-        self.backend.get_status().await
+        self.backend.read().await.get_status().await
+    }
+
+    /// Residency, pinning and memory of every model the cache manages, most recently used first
+    pub fn model_cache_entries(&self) -> Vec<ModelCacheEntry> {
+        self.model_cache.entries()
     }
 
     /// Create an error result for failed inference
@@ -335,6 +843,7 @@ impl InferenceEngine {
                 "error": error.to_string(),
                 "error_type": match error {
                     InferenceError::Model { .. } => "model_error",
+                    InferenceError::Integrity { .. } => "integrity_error",
                     InferenceError::InvalidInput { .. } => "invalid_input",
                     InferenceError::Timeout { .. } => "timeout",
                     InferenceError::Configuration { .. } => "configuration_error",
@@ -351,6 +860,7 @@ impl InferenceEngine {
                     InferenceError::OnnxRuntime(_) => "onnx_runtime_error",
                 }
             }),
+            tensors: None,
         }
     }
 
@@ -364,7 +874,7 @@ impl InferenceEngine {
             return Err(InferenceError::invalid_input("Input data cannot be empty".to_string()));
         }
 
-        if !["image", "sensor", "time_series"].contains(&request.input_type.as_str()) {
+        if !["image", "sensor", "time_series", "audio", "tensor"].contains(&request.input_type.as_str()) {
             return Err(InferenceError::invalid_input(format!("Unsupported input type: {}", request.input_type)));
         }
 
@@ -396,6 +906,7 @@ impl InferenceEngine {
 
         let error_type = match error {
             InferenceError::Model { .. } => "model_error",
+            InferenceError::Integrity { .. } => "integrity_error",
             InferenceError::InvalidInput { .. } => "invalid_input",
             InferenceError::Timeout { .. } => "timeout",
             InferenceError::Configuration { .. } => "configuration_error",
@@ -419,13 +930,18 @@ impl InferenceEngine {
 
     /// Get current performance metrics
     pub async fn get_metrics(&self) -> InferenceMetrics {
-        match self.metrics.read() {
+        let mut metrics = match self.metrics.read() {
             Ok(metrics) => metrics.clone(),
             Err(error) => {
                 warn!("Failed to read metrics due to poisoned lock: {}", error);
                 InferenceMetrics::default()
             }
+        };
+        if let Some(batcher) = &self.batcher {
+            metrics.batching = batcher.metrics();
         }
+        metrics.model_cache = self.model_cache.metrics();
+        metrics
     }
 
     /// Reset performance metrics
@@ -439,13 +955,60 @@ impl InferenceEngine {
                 warn!("Failed to reset metrics due to poisoned lock: {}", error);
             }
         }
+        if let Some(batcher) = &self.batcher {
+            batcher.reset_metrics();
+        }
+        self.model_cache.reset_metrics();
+    }
+
+    /// Register a pipeline so requests can address it by name
+    ///
+    /// Stage models are resolved per request, so they can be loaded before or
+    /// after the pipeline is registered. A pipeline shadows a model of the same name.
+    pub fn register_pipeline(&mut self, pipeline: PipelineConfiguration) -> Result<(), InferenceError> {
+        pipeline.validate()
+            .map_err(|e| InferenceError::configuration(e.to_string()))?;
+        info!("Registered pipeline '{}' with {} stages", pipeline.name, pipeline.stages.len());
+        self.pipelines.insert(pipeline.name.clone(), pipeline);
+        Ok(())
+    }
+
+    /// Remove a registered pipeline
+    pub fn remove_pipeline(&mut self, pipeline_name: &str) -> Option<PipelineConfiguration> {
+        self.pipelines.remove(pipeline_name)
+    }
+
+    /// List registered pipeline names
+    pub fn list_pipelines(&self) -> Vec<String> {
+        self.pipelines.keys().cloned().collect()
+    }
+
+    fn set_tiling(&mut self, backend_key: &str, tiling: Option<TilingConfig>) {
+        match tiling {
+            Some(tiling) => {
+                info!("Model '{}' runs tiled inference over {:?} tiles", backend_key, tiling.tile_size);
+                self.tiling.insert(backend_key.to_string(), tiling);
+            }
+            None => {
+                self.tiling.remove(backend_key);
+            }
+        }
+    }
+
+    /// Forget the object tracks of a camera or stream, e.g. after it was moved
+    pub fn reset_tracking(&self, stream_id: &str) {
+        if let Some(tracker) = &self.tracker {
+            tracker.reset_stream(stream_id);
+        }
     }
 
     // ========= YAML Configuration System Methods =========
 
     /// Initialize YAML-based model configuration system
     pub fn initialize_yaml_config_system(&mut self, models_base_dir: PathBuf) -> Result<(), InferenceError> {
-        let manager = ModelConfigManager::new(models_base_dir.clone());
+        let trust_store = TrustStore::from_config(&self.config.models.integrity)
+            .map_err(|e| InferenceError::configuration(format!("Invalid model integrity configuration: {}", e)))?;
+        let manager = ModelConfigManager::with_trust_store(models_base_dir.clone(), trust_store);
         self.model_config_manager = Some(manager);
         info!("YAML model configuration system initialized with base directory: {}", models_base_dir.display());
         Ok(())
@@ -457,22 +1020,67 @@ impl InferenceEngine {
             .ok_or_else(|| InferenceError::configuration("YAML config system not initialized. Call initialize_yaml_config_system() first.".to_string()))?;
 
         let model_name = manager.load_config(yaml_path).await
-            .map_err(|e| InferenceError::configuration(format!("Failed to load YAML config: {}", e)))?;
+            .map_err(config_load_error)?;
 
         // Get the loaded configuration (clone it to avoid borrowing issues)
         let yaml_config = manager.get_config(&model_name)
             .ok_or_else(|| InferenceError::configuration("Model config was loaded but not found".to_string()))?
             .clone();
+        let base_dir = manager.base_dir.clone();
+
+        self.load_yaml_model(&model_name, yaml_config, &base_dir).await?;
 
+        info!("Successfully loaded model '{}' from YAML configuration", model_name);
+        Ok(model_name)
+    }
+
+    /// Load a model from its YAML configuration with its tiling and pipeline
+    ///
+    /// A tiling or pipeline configuration that does not validate fails the load.
+    async fn load_yaml_model(
+        &mut self,
+        model_name: &str,
+        yaml_config: ModelConfiguration,
+        base_dir: &Path,
+    ) -> Result<(), InferenceError> {
         // Convert YAML config to legacy ModelConfig for backend compatibility
-        let model_config = Self::convert_yaml_to_model_config(&yaml_config, &manager.base_dir)?;
+        let model_config = Self::convert_yaml_to_model_config(&yaml_config, base_dir)?;
 
         // Load the model using the existing backend system
-        self.backend.load_model(&model_name, &model_config).await
-            .map_err(|e| InferenceError::model(format!("Backend model loading failed: {}", e)))?;
+        let tiling = tiling_config(model_name, &model_config)?;
+        self.load_backend_model(model_name, &model_config).await
+            .map_err(model_load_error)?;
+        self.set_tiling(model_name, tiling);
 
-        info!("Successfully loaded model '{}' from YAML configuration", model_name);
-        Ok(model_name)
+        if let Some(pipeline) = yaml_config.pipeline {
+            self.register_pipeline(pipeline)?;
+        }
+        Ok(())
+    }
+
+    /// Load a model version from a YAML configuration file
+    ///
+    /// Unlike `load_model_from_yaml`, the model is keyed by its name and
+    /// `model.version`, so it can be loaded while another version is serving.
+    /// Returns the model name and version.
+    pub async fn load_model_version_from_yaml(&mut self, yaml_path: &str) -> Result<(String, String), InferenceError> {
+        let manager = self.model_config_manager.as_mut()
+            .ok_or_else(|| InferenceError::configuration("YAML config system not initialized. Call initialize_yaml_config_system() first.".to_string()))?;
+
+        let model_name = manager.load_config(yaml_path).await
+            .map_err(config_load_error)?;
+
+        let yaml_config = manager.get_config(&model_name)
+            .ok_or_else(|| InferenceError::configuration("Model config was loaded but not found".to_string()))?
+            .clone();
+        let model_config = Self::convert_yaml_to_model_config(&yaml_config, &manager.base_dir)?;
+
+        let version = yaml_config.model.version.clone();
+        self.register_model_version(&model_name, &version, &model_config).await?;
+        if let Some(pipeline) = yaml_config.pipeline {
+            self.register_pipeline(pipeline)?;
+        }
+        Ok((model_name, version))
     }
 
     /// Load all YAML model configurations from a directory
@@ -483,29 +1091,22 @@ impl InferenceEngine {
         let loaded_models = manager.load_from_directory(directory_path).await
             .map_err(|e| InferenceError::configuration(format!("Failed to load models from directory: {}", e)))?;
 
-        // Get base directory for conversion
         let base_dir = manager.base_dir.clone();
+        // Clone the configs so the models can be loaded through the engine
+        let yaml_configs: Vec<(String, ModelConfiguration)> = loaded_models.iter()
+            .filter_map(|model_name| manager.get_config(model_name).map(|config| (model_name.clone(), config.clone())))
+            .collect();
 
         // Load each model into the backend
         let mut successfully_loaded = Vec::new();
-        for model_name in &loaded_models {
-            if let Some(yaml_config) = manager.get_config(model_name) {
-                let yaml_config = yaml_config.clone(); // Clone to avoid borrowing issues
-                match Self::convert_yaml_to_model_config(&yaml_config, &base_dir) {
-                    Ok(model_config) => {
-                        match self.backend.load_model(model_name, &model_config).await {
-                            Ok(_) => {
-                                successfully_loaded.push(model_name.clone());
-                                info!("Successfully loaded model '{}' from directory", model_name);
-                            }
-                            Err(e) => {
-                                warn!("Failed to load model '{}': {}", model_name, e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to convert YAML config for model '{}': {}", model_name, e);
-                    }
+        for (model_name, yaml_config) in yaml_configs {
+            match self.load_yaml_model(&model_name, yaml_config, &base_dir).await {
+                Ok(()) => {
+                    info!("Successfully loaded model '{}' from directory", model_name);
+                    successfully_loaded.push(model_name);
+                }
+                Err(e) => {
+                    warn!("Failed to load model '{}': {}", model_name, e);
                 }
             }
         }
@@ -545,11 +1146,20 @@ impl InferenceEngine {
             .to_string();
 
         // Convert preprocessing to JSON if available
-        let preprocessing = yaml_config.preprocessing.as_ref()
+        let mut preprocessing = yaml_config.preprocessing.as_ref()
             .map(serde_json::to_value)
             .transpose()
             .map_err(|e| InferenceError::configuration(format!("Failed to serialize preprocessing: {}", e)))?;
 
+        // Multi-input models bind their inputs by name
+        if !yaml_config.input.tensors.is_empty() {
+            let input_tensors = serde_json::to_value(&yaml_config.input.tensors)
+                .map_err(|e| InferenceError::configuration(format!("Failed to serialize input tensors: {}", e)))?;
+            if let Some(object) = preprocessing.get_or_insert_with(|| serde_json::json!({})).as_object_mut() {
+                object.entry("input_tensors".to_string()).or_insert(input_tensors);
+            }
+        }
+
         // Convert postprocessing to JSON if available
         let postprocessing = yaml_config.postprocessing.as_ref()
             .map(serde_json::to_value)
@@ -573,6 +1183,28 @@ impl InferenceEngine {
                 obj.entry("nms_threshold".to_string())
                     .or_insert_with(|| serde_json::json!(nms_threshold));
             }
+            if let Some(max_detections) = yaml_config.output.max_detections {
+                obj.entry("max_detections".to_string())
+                    .or_insert_with(|| serde_json::json!(max_detections));
+            }
+            if let Some(suppression) = &yaml_config.output.suppression {
+                obj.entry("suppression".to_string())
+                    .or_insert_with(|| serde_json::json!(suppression));
+            }
+            if let Some(calibration) = &yaml_config.output.calibration {
+                obj.entry("calibration".to_string())
+                    .or_insert_with(|| serde_json::json!(calibration));
+            }
+            if let Some(class_thresholds) = &yaml_config.output.class_thresholds {
+                obj.entry("class_thresholds".to_string())
+                    .or_insert_with(|| serde_json::json!(class_thresholds));
+            }
+            // Multi-tensor postprocessors (e.g. SSD) map outputs to roles by semantic,
+            // and backends check names and shapes against the model's signature
+            obj.entry("output_tensors".to_string())
+                .or_insert_with(|| serde_json::json!(yaml_config.output.tensors.iter()
+                    .map(|t| serde_json::json!({ "name": t.name, "semantic": t.semantic, "shape": t.shape }))
+                    .collect::<Vec<_>>()));
         };
 
         match postprocessing {
@@ -594,6 +1226,306 @@ impl InferenceEngine {
             confidence_threshold: yaml_config.output.confidence_threshold,
             preprocessing,
             postprocessing,
+            integrity: yaml_config.model.integrity.clone(),
+            memory_mb: yaml_config.model.performance.as_ref()
+                .and_then(|performance| performance.memory_usage_mb)
+                .map(f64::from),
         })
     }
 }
+
+/// Shared handles for running inputs on the backend, cheap to clone into
+/// detached tasks such as shadow runs
+#[derive(Clone)]
+struct BackendRunner {
+    backend: Arc<tokio::sync::RwLock<Backend>>,
+    model_cache: Arc<ModelCache>,
+    batcher: Option<Arc<DynamicBatcher>>,
+}
+
+impl BackendRunner {
+    /// Run one input on the backend, failing with a timeout once the deadline passes
+    ///
+    /// Expiry only abandons this request: a batch it joined still runs for the
+    /// other callers, and backend work already on the blocking pool is left to
+    /// finish (ONNX Runtime terminates the session run).
+    async fn run_with_deadline(
+        &self,
+        input: InferenceInput,
+        model_name: Option<&str>,
+        tiling: Option<&TilingConfig>,
+        timeout_ms: Option<u64>,
+        request_id: &str,
+    ) -> Result<InferenceResult, InferenceError> {
+        let backend_call = async {
+            let backend = self.resident_backend(model_name).await?;
+            self.run_input(backend, input, model_name, tiling).await
+        };
+        with_deadline(backend_call, timeout_ms, request_id).await
+    }
+
+    /// Like `run_with_deadline`, but only when `model_key` is already loaded
+    ///
+    /// Returns `None` instead of loading the model, and leaves its place in
+    /// the eviction order unchanged.
+    async fn run_if_loaded(
+        &self,
+        input: InferenceInput,
+        model_key: &str,
+        tiling: Option<&TilingConfig>,
+        timeout_ms: Option<u64>,
+        request_id: &str,
+    ) -> Option<Result<InferenceResult, InferenceError>> {
+        let backend = Arc::clone(&self.backend).read_owned().await;
+        if matches!(self.model_cache.peek(model_key), CacheLookup::Unloaded(_)) {
+            return None;
+        }
+        Some(with_deadline(self.run_input(backend, input, Some(model_key), tiling), timeout_ms, request_id).await)
+    }
+
+    /// Run one input, slicing images into tiles when the model has tiling configured
+    async fn run_input(
+        &self,
+        backend: OwnedRwLockReadGuard<Backend>,
+        input: InferenceInput,
+        model_name: Option<&str>,
+        tiling: Option<&TilingConfig>,
+    ) -> Result<InferenceResult, InferenceError> {
+        let result = match (tiling, input) {
+            (Some(tiling), InferenceInput::Image { data, .. }) if tiling.applies_to((data.width(), data.height())) => {
+                self.run_tiled(&backend, &data, model_name, tiling).await
+            }
+            (_, input) => self.run_backend(backend, input, model_name).await,
+        };
+        result.map_err(|e| InferenceError::execution(e.to_string()))
+    }
+
+    /// Read access to the backend with `model_name` resident
+    ///
+    /// A model that was registered lazily or evicted is loaded first, which may
+    /// evict others; a failed load is reported like an eager one. The returned
+    /// guard keeps the model from being evicted while the request runs.
+    async fn resident_backend(&self, model_name: Option<&str>) -> Result<OwnedRwLockReadGuard<Backend>, InferenceError> {
+        let backend = Arc::clone(&self.backend).read_owned().await;
+        let Some(model_key) = model_name else {
+            return Ok(backend);
+        };
+        let model_config = match self.model_cache.touch(model_key) {
+            CacheLookup::Resident | CacheLookup::Unknown => return Ok(backend),
+            CacheLookup::Unloaded(model_config) => model_config,
+        };
+        drop(backend);
+
+        let mut backend = Arc::clone(&self.backend).write_owned().await;
+        // Another request may have loaded the model while this one waited
+        if matches!(self.model_cache.touch(model_key), CacheLookup::Unloaded(_)) {
+            InferenceEngine::load_into(&mut backend, &self.model_cache, model_key, &model_config, true).await
+                .map_err(model_load_error)?;
+        }
+        Ok(OwnedRwLockWriteGuard::downgrade(backend))
+    }
+
+    /// Run an image as overlapping tiles and merge their detections in image pixels
+    ///
+    /// Tiles are sent to the backend `tiling.batch_size` at a time, as one
+    /// batch when the model supports batching. Any failed tile fails the image.
+    async fn run_tiled(
+        &self,
+        backend: &Backend,
+        image: &image::DynamicImage,
+        model_name: Option<&str>,
+        tiling: &TilingConfig,
+    ) -> Result<InferenceResult, BackendError> {
+        let start_time = Instant::now();
+        let image_size = (image.width(), image.height());
+        let mut regions = tiling.tile_regions(image_size);
+        let tile_count = regions.len();
+        if tiling.full_frame {
+            regions.push((0, 0, image_size.0, image_size.1));
+        }
+
+        let mut results = Vec::with_capacity(regions.len());
+        for chunk in regions.chunks(tiling.batch_size.max(1)) {
+            let inputs: Vec<InferenceInput> = chunk.iter()
+                .map(|&(x, y, width, height)| InferenceInput::Image {
+                    data: image.crop_imm(x, y, width, height),
+                    metadata: crate::ImageMetadata {
+                        width,
+                        height,
+                        channels: 3,
+                        format: "RGB".to_string(),
+                    },
+                })
+                .collect();
+            if backend.supports_batching(model_name) {
+                results.extend(backend.infer_batch(inputs, model_name).await);
+            } else {
+                let calls = inputs.into_iter().map(|input| backend.infer(input, model_name));
+                results.extend(futures::future::join_all(calls).await);
+            }
+        }
+
+        let mut merged: Option<InferenceResult> = None;
+        let mut predictions = Vec::new();
+        for (result, region) in results.into_iter().zip(&regions) {
+            let mut result = result?;
+            pipeline::to_image_coordinates(&mut result, *region);
+            predictions.append(&mut result.predictions);
+            merged.get_or_insert(result);
+        }
+        let mut result = merged
+            .ok_or_else(|| BackendError::InferenceFailed("Tiled inference produced no results".to_string()))?;
+
+        let raw_predictions = predictions.len();
+        result.predictions = tiling::merge_predictions(predictions, tiling);
+        result.inference_time_ms = start_time.elapsed().as_secs_f64() * 1000.0;
+        if let Some(metadata) = result.metadata.as_object_mut() {
+            metadata.insert("input_size".to_string(), serde_json::json!([image_size.0, image_size.1]));
+            metadata.insert("tiling".to_string(), serde_json::json!({
+                "tiles": tile_count,
+                "tile_size": tiling.tile_size,
+                "overlap": tiling.overlap,
+                "full_frame": tiling.full_frame,
+                "merge": tiling.merge,
+                "raw_predictions": raw_predictions,
+            }));
+        }
+        Ok(result)
+    }
+
+    /// Run one input, joining a dynamic batch when the model supports it
+    ///
+    /// A batched request hands its backend guard to the batch worker, so the
+    /// batch keeps its model resident even if this caller goes away.
+    async fn run_backend(
+        &self,
+        backend: OwnedRwLockReadGuard<Backend>,
+        input: InferenceInput,
+        model_name: Option<&str>,
+    ) -> Result<InferenceResult, BackendError> {
+        match &self.batcher {
+            Some(batcher) if matches!(input, InferenceInput::Image { .. }) && backend.supports_batching(model_name) => {
+                let model_key = model_name.unwrap_or("default");
+                let model = model_name.map(str::to_string);
+                batcher.submit(model_key, input, move |inputs| async move {
+                    backend.infer_batch(inputs, model.as_deref()).await
+                }).await
+            }
+            _ => backend.infer(input, model_name).await,
+        }
+    }
+}
+
+/// Backend cache size: the model memory budget when one is set
+fn backend_cache_size_mb(config: &InferenceConfig, default_mb: usize) -> usize {
+    match config.models.cache.memory_budget_mb {
+        0 => default_mb,
+        budget_mb => budget_mb,
+    }
+}
+
+/// Tiling settings from a model's preprocessing config
+fn tiling_config(model_name: &str, model_config: &ModelConfig) -> Result<Option<TilingConfig>, InferenceError> {
+    TilingConfig::from_preprocessing(model_config.preprocessing.as_ref())
+        .map_err(|e| InferenceError::configuration(format!("Invalid tiling config for model '{}': {}", model_name, e)))
+}
+
+/// Await a backend call, failing with a timeout once the deadline passes
+async fn with_deadline(
+    backend_call: impl Future<Output = Result<InferenceResult, InferenceError>>,
+    timeout_ms: Option<u64>,
+    request_id: &str,
+) -> Result<InferenceResult, InferenceError> {
+    match timeout_ms {
+        Some(timeout_ms) => match tokio::time::timeout(Duration::from_millis(timeout_ms), backend_call).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!(request_id = %request_id, timeout_ms, "Inference deadline exceeded");
+                Err(InferenceError::timeout(timeout_ms))
+            }
+        },
+        None => backend_call.await,
+    }
+}
+
+fn model_load_error(error: BackendError) -> InferenceError {
+    match error {
+        BackendError::IntegrityCheckFailed(message) => InferenceError::integrity(message),
+        error => InferenceError::model(format!("Backend model loading failed: {}", error)),
+    }
+}
+
+fn config_load_error(error: ModelConfigError) -> InferenceError {
+    match error {
+        ModelConfigError::Integrity(error) => InferenceError::integrity(error.to_string()),
+        error => InferenceError::configuration(format!("Failed to load YAML config: {}", error)),
+    }
+}
+
+#[cfg(all(test, any(feature = "onnx-runtime", feature = "candle", feature = "pure-rust")))]
+mod tests {
+    use super::*;
+
+    fn request(metadata: HashMap<String, serde_json::Value>) -> InferenceRequest {
+        InferenceRequest {
+            request_id: "timeout-test".to_string(),
+            model_name: None,
+            input_data: "data".to_string(),
+            input_type: "image".to_string(),
+            metadata,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_precedence() {
+        let mut config = InferenceConfig::default();
+        config.performance.inference_timeout_ms = 2000;
+        let engine = InferenceEngine::new(config).await.unwrap();
+
+        assert_eq!(engine.request_timeout_ms(&request(HashMap::new()), None, None), Some(2000));
+
+        let per_request = HashMap::from([("timeout_ms".to_string(), serde_json::json!(150))]);
+        assert_eq!(engine.request_timeout_ms(&request(per_request), None, None), Some(150));
+
+        let disabled = HashMap::from([("timeout_ms".to_string(), serde_json::json!(0))]);
+        assert_eq!(engine.request_timeout_ms(&request(disabled), None, None), None);
+    }
+
+    #[tokio::test]
+    async fn test_lazy_load_integrity_failure_is_an_integrity_error() {
+        use base64::{engine::general_purpose, Engine as _};
+        use std::io::Write;
+
+        let mut artifact = tempfile::Builder::new().suffix(".onnx").tempfile().unwrap();
+        artifact.write_all(b"not the pinned model").unwrap();
+        let model_config = ModelConfig {
+            model_path: artifact.path().to_string_lossy().to_string(),
+            model_type: "object_detection".to_string(),
+            confidence_threshold: Some(0.5),
+            preprocessing: None,
+            postprocessing: None,
+            integrity: Some(crate::integrity::ArtifactIntegrity {
+                sha256: "0".repeat(64),
+                signature: None,
+                key_id: None,
+            }),
+            memory_mb: None,
+        };
+
+        let mut config = InferenceConfig::default();
+        config.models.cache.lazy_loading = true;
+        let mut engine = InferenceEngine::new(config).await.unwrap();
+        engine.load_model("detector", &model_config).await.unwrap();
+
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgb8(8, 8)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let mut request = request(HashMap::new());
+        request.model_name = Some("detector".to_string());
+        request.input_data = general_purpose::STANDARD.encode(png);
+
+        let error = engine.infer(request).await.unwrap_err();
+        assert!(matches!(error, InferenceError::Integrity { .. }), "unexpected error: {}", error);
+    }
+}
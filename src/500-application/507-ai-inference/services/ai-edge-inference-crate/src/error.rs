@@ -51,6 +51,10 @@ pub enum InferenceError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    /// Model artifact failed digest or signature verification
+    #[error("Integrity check failed: {message}")]
+    Integrity { message: String },
+
     /// Timeout errors for long-running operations
     #[error("Operation timed out after {timeout_ms}ms")]
     Timeout { timeout_ms: u64 },
@@ -114,6 +118,13 @@ impl InferenceError {
         }
     }
 
+    /// Create a new integrity error
+    pub fn integrity<S: Into<String>>(message: S) -> Self {
+        Self::Integrity {
+            message: message.into(),
+        }
+    }
+
     /// Create a new timeout error
     pub fn timeout(timeout_ms: u64) -> Self {
         Self::Timeout { timeout_ms }
@@ -160,6 +171,7 @@ impl InferenceError {
             // Permanent errors that won't resolve with retry
             InferenceError::Configuration { .. } 
             | InferenceError::Model { .. }
+            | InferenceError::Integrity { .. }
             | InferenceError::InvalidInput { .. } => false,
             
             // Temporary errors that might resolve
@@ -192,6 +204,7 @@ impl InferenceError {
             #[cfg(feature = "onnx-runtime")]
             InferenceError::OnnxRuntime(_) => "runtime",
             InferenceError::Model { .. } => "model",
+            InferenceError::Integrity { .. } => "integrity",
             InferenceError::InvalidInput { .. } => "input",
             InferenceError::Configuration { .. } => "configuration", 
             InferenceError::Gpu { .. } => "gpu",
@@ -0,0 +1,946 @@
+//! Accuracy evaluation against labeled datasets
+//!
+//! Runs a loaded model through `InferenceEngine` over a local dataset and
+//! measures what it actually achieves on this device and backend:
+//!
+//! - detection (COCO JSON or YOLO txt labels): COCO mAP@[.5:.95], mAP@.5 and
+//!   mAP@.75, per-class AP, precision and recall, and a confusion matrix
+//! - classification (one directory per class): top-1 and top-5 accuracy,
+//!   per-class precision and recall, and a confusion matrix
+//! - latency percentiles over every request
+//!
+//! The report serializes to JSON, can be checked against an `EvaluationGate`,
+//! and can replace the hand-typed `performance` section of a model YAML. The
+//! predictions of a run also supply the labeled scores for fitting a
+//! confidence calibration.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::calibration::{self, CalibrationError};
+use crate::engine::InferenceEngine;
+use crate::error::InferenceError;
+use crate::model_config::{ModelConfiguration, PerformanceMetrics};
+use crate::pipeline;
+use crate::suppression::iou;
+use crate::types::{InferenceRequest, Prediction};
+
+/// COCO IoU thresholds .50:.05:.95
+const IOU_THRESHOLDS: [f32; 10] = [0.5, 0.55, 0.6, 0.65, 0.7, 0.75, 0.8, 0.85, 0.9, 0.95];
+/// Column and row of the confusion matrix for missed and spurious detections
+const BACKGROUND: &str = "background";
+/// Inference errors kept in the report
+const MAX_REPORTED_ERRORS: usize = 10;
+const IMAGE_EXTENSIONS: [&str; 5] = ["jpg", "jpeg", "png", "bmp", "webp"];
+
+/// Errors raised while loading a dataset or evaluating a model
+#[derive(Error, Debug)]
+pub enum EvaluationError {
+    #[error("Invalid dataset: {0}")]
+    Dataset(String),
+    #[error("Inference failed: {0}")]
+    Inference(#[from] InferenceError),
+    #[error("Calibration failed: {0}")]
+    Calibration(#[from] CalibrationError),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("YAML error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Layout of a labeled dataset on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetFormat {
+    /// COCO annotation JSON with `[x, y, width, height]` boxes
+    Coco,
+    /// One `class cx cy w h` txt file per image, normalized to [0, 1]
+    Yolo,
+    /// One subdirectory of images per class
+    ImageFolder,
+}
+
+/// Where a dataset lives and how to read it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetSpec {
+    pub format: DatasetFormat,
+    /// Image directory; for `image_folder`, the directory of class subdirectories
+    pub images: PathBuf,
+    /// COCO annotation JSON, or the YOLO label directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<PathBuf>,
+    /// YOLO class names by class index
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub class_names: Vec<String>,
+    /// Ultralytics `data.yaml` to take YOLO class names from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_yaml: Option<PathBuf>,
+    /// Evaluate only the first images, in file name order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Labeled box in source image pixels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroundTruthBox {
+    pub class: String,
+    /// `[x1, y1, x2, y2]`
+    pub bbox: [f32; 4],
+}
+
+/// One labeled image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sample {
+    pub image_path: PathBuf,
+    /// Detection labels
+    #[serde(default)]
+    pub boxes: Vec<GroundTruthBox>,
+    /// Classification label
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Labeled images ready for evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dataset {
+    pub format: DatasetFormat,
+    /// Class names in label order
+    pub classes: Vec<String>,
+    pub samples: Vec<Sample>,
+}
+
+impl Dataset {
+    /// Load a dataset described by `spec`
+    pub fn load(spec: &DatasetSpec) -> Result<Self, EvaluationError> {
+        let mut dataset = match spec.format {
+            DatasetFormat::Coco => Self::load_coco(spec)?,
+            DatasetFormat::Yolo => Self::load_yolo(spec)?,
+            DatasetFormat::ImageFolder => Self::load_image_folder(&spec.images)?,
+        };
+        if let Some(limit) = spec.limit {
+            dataset.samples.truncate(limit);
+        }
+        if dataset.samples.is_empty() {
+            return Err(EvaluationError::Dataset(format!("No images found under {}", spec.images.display())));
+        }
+        Ok(dataset)
+    }
+
+    fn load_coco(spec: &DatasetSpec) -> Result<Self, EvaluationError> {
+        #[derive(Deserialize)]
+        struct CocoImage { id: u64, file_name: String }
+        #[derive(Deserialize)]
+        struct CocoAnnotation {
+            image_id: u64,
+            category_id: u64,
+            bbox: [f32; 4],
+            #[serde(default)]
+            iscrowd: u8,
+        }
+        #[derive(Deserialize)]
+        struct CocoCategory { id: u64, name: String }
+        #[derive(Deserialize)]
+        struct CocoFile { images: Vec<CocoImage>, annotations: Vec<CocoAnnotation>, categories: Vec<CocoCategory> }
+
+        let path = spec.annotations.as_ref()
+            .ok_or_else(|| EvaluationError::Dataset("COCO datasets need an `annotations` JSON file".to_string()))?;
+        let coco: CocoFile = serde_json::from_slice(&std::fs::read(path)?)?;
+
+        let mut categories = coco.categories;
+        categories.sort_by_key(|category| category.id);
+        let names: HashMap<u64, &str> = categories.iter().map(|c| (c.id, c.name.as_str())).collect();
+
+        let mut boxes: HashMap<u64, Vec<GroundTruthBox>> = HashMap::new();
+        for annotation in coco.annotations.iter().filter(|a| a.iscrowd == 0) {
+            let class = names.get(&annotation.category_id).ok_or_else(|| {
+                EvaluationError::Dataset(format!("Annotation uses unknown category {}", annotation.category_id))
+            })?;
+            let [x, y, width, height] = annotation.bbox;
+            boxes.entry(annotation.image_id).or_default().push(GroundTruthBox {
+                class: class.to_string(),
+                bbox: [x, y, x + width, y + height],
+            });
+        }
+
+        let mut images = coco.images;
+        images.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        let samples = images.into_iter()
+            .map(|image| Sample {
+                image_path: spec.images.join(&image.file_name),
+                boxes: boxes.remove(&image.id).unwrap_or_default(),
+                label: None,
+            })
+            .collect();
+
+        Ok(Self {
+            format: DatasetFormat::Coco,
+            classes: categories.into_iter().map(|c| c.name).collect(),
+            samples,
+        })
+    }
+
+    fn load_yolo(spec: &DatasetSpec) -> Result<Self, EvaluationError> {
+        let labels = spec.annotations.as_ref()
+            .ok_or_else(|| EvaluationError::Dataset("YOLO datasets need an `annotations` label directory".to_string()))?;
+        let classes = match &spec.data_yaml {
+            Some(data_yaml) => yolo_class_names(data_yaml)?,
+            None => spec.class_names.clone(),
+        };
+
+        let mut samples = Vec::new();
+        for image_path in list_images(&spec.images)? {
+            let label_path = image_path.file_stem()
+                .map(|stem| labels.join(stem).with_extension("txt"))
+                .unwrap_or_default();
+            let boxes = match std::fs::read_to_string(&label_path) {
+                Ok(text) => {
+                    let (width, height) = image::image_dimensions(&image_path)?;
+                    parse_yolo_labels(&text, &classes, width as f32, height as f32)
+                        .map_err(|e| EvaluationError::Dataset(format!("{}: {}", label_path.display(), e)))?
+                }
+                // Images without a label file have no objects
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                Err(e) => return Err(e.into()),
+            };
+            samples.push(Sample { image_path, boxes, label: None });
+        }
+
+        Ok(Self { format: DatasetFormat::Yolo, classes, samples })
+    }
+
+    fn load_image_folder(root: &Path) -> Result<Self, EvaluationError> {
+        let mut classes = Vec::new();
+        let mut samples = Vec::new();
+        let mut directories: Vec<PathBuf> = std::fs::read_dir(root)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect();
+        directories.sort();
+
+        for directory in directories {
+            let class = directory.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            for image_path in list_images(&directory)? {
+                samples.push(Sample { image_path, boxes: Vec::new(), label: Some(class.clone()) });
+            }
+            classes.push(class);
+        }
+
+        Ok(Self { format: DatasetFormat::ImageFolder, classes, samples })
+    }
+}
+
+/// Image files in a directory, sorted by name
+fn list_images(directory: &Path) -> Result<Vec<PathBuf>, EvaluationError> {
+    let mut images: Vec<PathBuf> = std::fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| IMAGE_EXTENSIONS.iter().any(|known| known.eq_ignore_ascii_case(ext)))
+        })
+        .collect();
+    images.sort();
+    Ok(images)
+}
+
+/// Class names from an Ultralytics `data.yaml` (`names` as a list or an index map)
+fn yolo_class_names(data_yaml: &Path) -> Result<Vec<String>, EvaluationError> {
+    let data: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(data_yaml)?)?;
+    let names = match data.get("names") {
+        Some(serde_yaml::Value::Sequence(names)) => {
+            names.iter().map(|name| name.as_str().unwrap_or_default().to_string()).collect()
+        }
+        Some(serde_yaml::Value::Mapping(names)) => {
+            let mut indexed: Vec<(u64, String)> = names.iter()
+                .filter_map(|(index, name)| Some((index.as_u64()?, name.as_str()?.to_string())))
+                .collect();
+            indexed.sort_by_key(|(index, _)| *index);
+            indexed.into_iter().map(|(_, name)| name).collect()
+        }
+        _ => return Err(EvaluationError::Dataset(format!("{} has no `names`", data_yaml.display()))),
+    };
+    Ok(names)
+}
+
+/// Parse YOLO label lines into pixel boxes
+fn parse_yolo_labels(text: &str, classes: &[String], width: f32, height: f32) -> Result<Vec<GroundTruthBox>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let values: Vec<&str> = line.split_whitespace().collect();
+            let [class, cx, cy, w, h] = values[..] else {
+                return Err(format!("expected 'class cx cy w h', found '{}'", line));
+            };
+            let class_id: usize = class.parse().map_err(|_| format!("invalid class id '{}'", class))?;
+            let number = |value: &str| value.parse::<f32>().map_err(|_| format!("invalid coordinate '{}'", value));
+            let (cx, cy, w, h) = (number(cx)? * width, number(cy)? * height, number(w)? * width, number(h)? * height);
+            Ok(GroundTruthBox {
+                class: classes.get(class_id).cloned().unwrap_or_else(|| class_id.to_string()),
+                bbox: [cx - w / 2.0, cy - h / 2.0, cx + w / 2.0, cy + h / 2.0],
+            })
+        })
+        .collect()
+}
+
+/// Settings for an evaluation run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationOptions {
+    /// Requests run before measuring, to keep session setup out of the latency
+    #[serde(default = "default_warmup")]
+    pub warmup: usize,
+    /// Confidence below which predictions are ignored
+    #[serde(default)]
+    pub min_confidence: f32,
+}
+
+fn default_warmup() -> usize {
+    1
+}
+
+impl Default for EvaluationOptions {
+    fn default() -> Self {
+        Self { warmup: default_warmup(), min_confidence: 0.0 }
+    }
+}
+
+/// Minimum accuracy and maximum latency a model must reach
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvaluationGate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_map: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_map50: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_top1: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_top5: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_p95_ms: Option<f64>,
+}
+
+/// Evaluation file: the dataset, run settings and the gate to check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationSpec {
+    pub dataset: DatasetSpec,
+    #[serde(default)]
+    pub options: EvaluationOptions,
+    #[serde(default)]
+    pub gate: EvaluationGate,
+}
+
+impl EvaluationSpec {
+    /// Read an evaluation YAML; relative dataset paths resolve against its directory
+    pub fn load(path: &Path) -> Result<Self, EvaluationError> {
+        let mut spec: Self = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+        let base = path.parent().unwrap_or(Path::new("."));
+        let dataset = &mut spec.dataset;
+        dataset.images = base.join(&dataset.images);
+        dataset.annotations = dataset.annotations.as_ref().map(|p| base.join(p));
+        dataset.data_yaml = dataset.data_yaml.as_ref().map(|p| base.join(p));
+        Ok(spec)
+    }
+}
+
+/// Accuracy of one class
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassMetrics {
+    pub class: String,
+    /// Labeled instances
+    pub ground_truth: usize,
+    /// Predicted instances
+    pub predicted: usize,
+    pub true_positives: usize,
+    pub precision: f64,
+    pub recall: f64,
+    /// AP@[.5:.95]; detection only, absent for classes without labels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ap: Option<f64>,
+    /// AP@.5; detection only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ap50: Option<f64>,
+}
+
+/// Counts of labeled class (rows) against predicted class (columns)
+///
+/// The last row and column are `background`: missed labels and predictions
+/// that match nothing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfusionMatrix {
+    pub labels: Vec<String>,
+    pub counts: Vec<Vec<usize>>,
+}
+
+impl ConfusionMatrix {
+    fn new(mut labels: Vec<String>) -> Self {
+        labels.push(BACKGROUND.to_string());
+        let size = labels.len();
+        Self { labels, counts: vec![vec![0; size]; size] }
+    }
+
+    fn index(&mut self, class: &str) -> usize {
+        if let Some(index) = self.labels.iter().position(|label| label == class) {
+            return index;
+        }
+        // Classes the dataset does not know go before background
+        let index = self.labels.len() - 1;
+        self.labels.insert(index, class.to_string());
+        for row in &mut self.counts {
+            row.insert(index, 0);
+        }
+        self.counts.insert(index, vec![0; self.labels.len()]);
+        index
+    }
+
+    fn add(&mut self, actual: &str, predicted: &str) {
+        let row = self.index(actual);
+        let column = self.index(predicted);
+        self.counts[row][column] += 1;
+    }
+}
+
+/// Detection accuracy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionMetrics {
+    /// COCO mAP@[.5:.95]
+    pub map: f64,
+    pub map50: f64,
+    pub map75: f64,
+    pub per_class: Vec<ClassMetrics>,
+    /// At IoU 0.5
+    pub confusion_matrix: ConfusionMatrix,
+}
+
+/// Classification accuracy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassificationMetrics {
+    pub top1: f64,
+    pub top5: f64,
+    pub per_class: Vec<ClassMetrics>,
+    /// Of top-1 predictions
+    pub confusion_matrix: ConfusionMatrix,
+}
+
+/// Request latency in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    pub fn from_samples(samples: &[f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        // Nearest-rank percentile
+        let percentile = |p: f64| sorted[((p / 100.0 * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+        Self {
+            count: sorted.len(),
+            mean_ms: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50_ms: percentile(50.0),
+            p90_ms: percentile(90.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+            max_ms: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Measured accuracy and latency of one model on one dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationReport {
+    pub model_name: String,
+    pub dataset_format: DatasetFormat,
+    pub images: usize,
+    /// Requests that failed; they count as images without predictions
+    pub failures: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detection: Option<DetectionMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<ClassificationMetrics>,
+    pub latency: LatencyStats,
+}
+
+impl EvaluationReport {
+    /// Headline accuracy: mAP@[.5:.95] for detection, top-1 for classification
+    pub fn accuracy(&self) -> Option<f64> {
+        self.detection.as_ref().map(|d| d.map)
+            .or_else(|| self.classification.as_ref().map(|c| c.top1))
+    }
+
+    /// Every way the report misses `gate`
+    pub fn check(&self, gate: &EvaluationGate) -> Vec<String> {
+        let mut failures = Vec::new();
+        let mut minimum = |name: &str, limit: Option<f64>, value: Option<f64>| {
+            if let Some(limit) = limit {
+                match value {
+                    Some(value) if value >= limit => {}
+                    Some(value) => failures.push(format!("{} {:.4} is below {:.4}", name, value, limit)),
+                    None => failures.push(format!("{} was not measured", name)),
+                }
+            }
+        };
+        minimum("mAP", gate.min_map, self.detection.as_ref().map(|d| d.map));
+        minimum("mAP50", gate.min_map50, self.detection.as_ref().map(|d| d.map50));
+        minimum("top-1", gate.min_top1, self.classification.as_ref().map(|c| c.top1));
+        minimum("top-5", gate.min_top5, self.classification.as_ref().map(|c| c.top5));
+        if let Some(limit) = gate.max_p95_ms.filter(|&limit| self.latency.p95_ms > limit) {
+            failures.push(format!("p95 latency {:.1} ms exceeds {:.1} ms", self.latency.p95_ms, limit));
+        }
+        failures
+    }
+
+    /// Write the measured accuracy and mean latency into a model's `performance` section
+    pub fn update_performance(&self, config: &mut ModelConfiguration) {
+        let performance = config.model.performance.get_or_insert(PerformanceMetrics {
+            avg_inference_ms: None,
+            memory_usage_mb: None,
+            accuracy: None,
+            target_hardware: None,
+        });
+        performance.avg_inference_ms = Some(self.latency.mean_ms as f32);
+        if let Some(accuracy) = self.accuracy() {
+            performance.accuracy = Some(accuracy as f32);
+        }
+    }
+}
+
+/// Predictions and timings of one pass over a dataset
+#[derive(Debug, Clone, Default)]
+pub struct EvaluationRun {
+    /// Per sample, in source image pixels; empty for failed requests
+    pub predictions: Vec<Vec<Prediction>>,
+    pub latencies_ms: Vec<f64>,
+    pub failures: usize,
+    pub errors: Vec<String>,
+}
+
+impl EvaluationRun {
+    /// Score the run against the dataset's labels
+    pub fn report(&self, model_name: &str, dataset: &Dataset) -> EvaluationReport {
+        let (detection, classification) = match dataset.format {
+            DatasetFormat::ImageFolder => (None, Some(classification_metrics(&dataset.classes, &dataset.samples, &self.predictions))),
+            DatasetFormat::Coco | DatasetFormat::Yolo => (Some(detection_metrics(&dataset.classes, &dataset.samples, &self.predictions)), None),
+        };
+
+        EvaluationReport {
+            model_name: model_name.to_string(),
+            dataset_format: dataset.format,
+            images: dataset.samples.len(),
+            failures: self.failures,
+            errors: self.errors.clone(),
+            detection,
+            classification,
+            latency: LatencyStats::from_samples(&self.latencies_ms),
+        }
+    }
+}
+
+/// Run `model_name` over every sample and measure it
+///
+/// Detection datasets compare boxes in source image pixels; classification
+/// datasets compare the highest-confidence predictions with the label.
+pub async fn evaluate(
+    engine: &InferenceEngine,
+    model_name: &str,
+    dataset: &Dataset,
+    options: &EvaluationOptions,
+) -> Result<EvaluationReport, EvaluationError> {
+    Ok(run(engine, model_name, dataset, options).await?.report(model_name, dataset))
+}
+
+/// Run `model_name` over every sample, keeping the predictions
+pub async fn run(
+    engine: &InferenceEngine,
+    model_name: &str,
+    dataset: &Dataset,
+    options: &EvaluationOptions,
+) -> Result<EvaluationRun, EvaluationError> {
+    for sample in dataset.samples.iter().cycle().take(options.warmup.min(dataset.samples.len())) {
+        let _ = engine.infer(image_request(model_name, sample, "warmup")?).await;
+    }
+
+    let mut run = EvaluationRun::default();
+    for (index, sample) in dataset.samples.iter().enumerate() {
+        let request = image_request(model_name, sample, &format!("eval-{}", index))?;
+        let start = Instant::now();
+        let result = engine.infer(request).await;
+        run.latencies_ms.push(start.elapsed().as_secs_f64() * 1000.0);
+
+        match result {
+            Ok(mut result) => {
+                if dataset.format != DatasetFormat::ImageFolder {
+                    let (width, height) = image::image_dimensions(&sample.image_path)?;
+                    pipeline::to_image_coordinates(&mut result, (0, 0, width, height));
+                }
+                result.predictions.retain(|p| p.confidence >= options.min_confidence);
+                run.predictions.push(result.predictions);
+            }
+            Err(e) => {
+                run.failures += 1;
+                if run.errors.len() < MAX_REPORTED_ERRORS {
+                    run.errors.push(format!("{}: {}", sample.image_path.display(), e));
+                }
+                run.predictions.push(Vec::new());
+            }
+        }
+    }
+
+    Ok(run)
+}
+
+/// Raw score of every prediction and whether it was correct, for fitting a calibration
+///
+/// A detection is correct when it matches an unmatched label of its class at
+/// IoU 0.5, most confident detections first; a classification is correct when
+/// its class is the image's label.
+pub fn calibration_samples(dataset: &Dataset, predictions: &[Vec<Prediction>]) -> Vec<(f32, bool)> {
+    let mut samples = Vec::new();
+    for (sample, image_predictions) in dataset.samples.iter().zip(predictions) {
+        if dataset.format == DatasetFormat::ImageFolder {
+            samples.extend(image_predictions.iter()
+                .map(|p| (calibration::raw_confidence(p), sample.label.as_deref() == Some(p.class.as_str()))));
+            continue;
+        }
+
+        let mut ranked: Vec<&Prediction> = image_predictions.iter().filter(|p| p.bbox.is_some()).collect();
+        ranked.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        let mut matched = vec![false; sample.boxes.len()];
+        for prediction in ranked {
+            let bbox = prediction.bbox.unwrap_or_default();
+            let best = sample.boxes.iter().enumerate()
+                .filter(|(index, label)| !matched[*index] && label.class == prediction.class)
+                .map(|(index, label)| (index, iou(&bbox, &label.bbox)))
+                .filter(|(_, overlap)| *overlap >= IOU_THRESHOLDS[0])
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((index, _)) = best {
+                matched[index] = true;
+            }
+            samples.push((calibration::raw_confidence(prediction), best.is_some()));
+        }
+    }
+    samples
+}
+
+fn image_request(model_name: &str, sample: &Sample, request_id: &str) -> Result<InferenceRequest, EvaluationError> {
+    Ok(InferenceRequest {
+        request_id: request_id.to_string(),
+        model_name: Some(model_name.to_string()),
+        input_data: general_purpose::STANDARD.encode(std::fs::read(&sample.image_path)?),
+        input_type: "image".to_string(),
+        metadata: HashMap::new(),
+    })
+}
+
+/// COCO-style detection metrics over per-image predictions in source image pixels
+pub fn detection_metrics(classes: &[String], samples: &[Sample], predictions: &[Vec<Prediction>]) -> DetectionMetrics {
+    let mut class_names: Vec<String> = classes.to_vec();
+    for prediction in predictions.iter().flatten().filter(|p| p.bbox.is_some()) {
+        if !class_names.contains(&prediction.class) {
+            class_names.push(prediction.class.clone());
+        }
+    }
+
+    // Per class and IoU threshold: (confidence, true positive) of every detection
+    let mut scored: Vec<Vec<Vec<(f32, bool)>>> = vec![vec![Vec::new(); IOU_THRESHOLDS.len()]; class_names.len()];
+    let mut ground_truth = vec![0usize; class_names.len()];
+    let mut predicted = vec![0usize; class_names.len()];
+    let mut confusion_matrix = ConfusionMatrix::new(classes.to_vec());
+
+    for (sample, image_predictions) in samples.iter().zip(predictions) {
+        let detections: Vec<(&Prediction, [f32; 4])> = image_predictions.iter()
+            .filter_map(|p| p.bbox.map(|bbox| (p, bbox)))
+            .collect();
+
+        for (class_index, class) in class_names.iter().enumerate() {
+            let labels: Vec<[f32; 4]> = sample.boxes.iter().filter(|b| &b.class == class).map(|b| b.bbox).collect();
+            let mut class_detections: Vec<(f32, [f32; 4])> = detections.iter()
+                .filter(|(p, _)| &p.class == class)
+                .map(|(p, bbox)| (p.confidence, *bbox))
+                .collect();
+            class_detections.sort_by(|a, b| b.0.total_cmp(&a.0));
+            ground_truth[class_index] += labels.len();
+            predicted[class_index] += class_detections.len();
+
+            for (threshold_index, &threshold) in IOU_THRESHOLDS.iter().enumerate() {
+                let mut matched = vec![false; labels.len()];
+                for (confidence, bbox) in &class_detections {
+                    let best = labels.iter().enumerate()
+                        .filter(|(label_index, _)| !matched[*label_index])
+                        .map(|(label_index, label)| (label_index, iou(bbox, label)))
+                        .filter(|(_, overlap)| *overlap >= threshold)
+                        .max_by(|a, b| a.1.total_cmp(&b.1));
+                    if let Some((label_index, _)) = best {
+                        matched[label_index] = true;
+                    }
+                    scored[class_index][threshold_index].push((*confidence, best.is_some()));
+                }
+            }
+        }
+
+        // Class-agnostic matching at IoU 0.5, best overlaps first
+        let mut pairs: Vec<(usize, usize, f32)> = Vec::new();
+        for (label_index, label) in sample.boxes.iter().enumerate() {
+            for (detection_index, (_, bbox)) in detections.iter().enumerate() {
+                let overlap = iou(bbox, &label.bbox);
+                if overlap >= IOU_THRESHOLDS[0] {
+                    pairs.push((label_index, detection_index, overlap));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.2.total_cmp(&a.2));
+        let mut label_used = vec![false; sample.boxes.len()];
+        let mut detection_used = vec![false; detections.len()];
+        for (label_index, detection_index, _) in pairs {
+            if !label_used[label_index] && !detection_used[detection_index] {
+                label_used[label_index] = true;
+                detection_used[detection_index] = true;
+                confusion_matrix.add(&sample.boxes[label_index].class, &detections[detection_index].0.class);
+            }
+        }
+        for (label, _) in sample.boxes.iter().zip(&label_used).filter(|(_, used)| !**used) {
+            confusion_matrix.add(&label.class, BACKGROUND);
+        }
+        for ((prediction, _), _) in detections.iter().zip(&detection_used).filter(|(_, used)| !**used) {
+            confusion_matrix.add(BACKGROUND, &prediction.class);
+        }
+    }
+
+    let per_class: Vec<ClassMetrics> = class_names.iter().enumerate()
+        .map(|(class_index, class)| {
+            let aps: Vec<Option<f64>> = scored[class_index].iter()
+                .map(|detections| average_precision(detections.clone(), ground_truth[class_index]))
+                .collect();
+            let true_positives = scored[class_index][0].iter().filter(|(_, hit)| *hit).count();
+            ClassMetrics {
+                class: class.clone(),
+                ground_truth: ground_truth[class_index],
+                predicted: predicted[class_index],
+                true_positives,
+                precision: ratio(true_positives, predicted[class_index]),
+                recall: ratio(true_positives, ground_truth[class_index]),
+                ap: aps.iter().copied().collect::<Option<Vec<f64>>>().map(|aps| mean(&aps)),
+                ap50: aps[0],
+            }
+        })
+        .collect();
+
+    let mean_ap = |threshold_index: usize| {
+        let aps: Vec<f64> = (0..class_names.len())
+            .filter_map(|class_index| average_precision(scored[class_index][threshold_index].clone(), ground_truth[class_index]))
+            .collect();
+        mean(&aps)
+    };
+    let map = mean(&(0..IOU_THRESHOLDS.len()).map(mean_ap).collect::<Vec<_>>());
+
+    DetectionMetrics {
+        map,
+        map50: mean_ap(0),
+        map75: mean_ap(5),
+        per_class,
+        confusion_matrix,
+    }
+}
+
+/// Top-k accuracy of predictions ranked by confidence
+pub fn classification_metrics(classes: &[String], samples: &[Sample], predictions: &[Vec<Prediction>]) -> ClassificationMetrics {
+    let mut confusion_matrix = ConfusionMatrix::new(classes.to_vec());
+    let mut counts: HashMap<&str, (usize, usize, usize)> = HashMap::new(); // (ground truth, predicted, true positives)
+    let (mut top1, mut top5, mut labeled) = (0usize, 0usize, 0usize);
+
+    for (sample, image_predictions) in samples.iter().zip(predictions) {
+        let Some(label) = sample.label.as_deref() else {
+            continue;
+        };
+        labeled += 1;
+        let mut ranked: Vec<&Prediction> = image_predictions.iter().collect();
+        ranked.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+        let rank = ranked.iter().position(|p| p.class == label);
+        top1 += usize::from(rank == Some(0));
+        top5 += usize::from(rank.is_some_and(|rank| rank < 5));
+
+        counts.entry(label).or_default().0 += 1;
+        let predicted = ranked.first().map_or(BACKGROUND, |p| p.class.as_str());
+        if predicted != BACKGROUND {
+            counts.entry(predicted).or_default().1 += 1;
+        }
+        if predicted == label {
+            counts.entry(label).or_default().2 += 1;
+        }
+        confusion_matrix.add(label, predicted);
+    }
+
+    let mut class_names: Vec<&str> = classes.iter().map(String::as_str).collect();
+    let mut extra: Vec<&str> = counts.keys().copied().filter(|class| !class_names.contains(class)).collect();
+    extra.sort();
+    class_names.extend(extra);
+
+    let per_class = class_names.into_iter()
+        .map(|class| {
+            let (ground_truth, predicted, true_positives) = counts.get(class).copied().unwrap_or_default();
+            ClassMetrics {
+                class: class.to_string(),
+                ground_truth,
+                predicted,
+                true_positives,
+                precision: ratio(true_positives, predicted),
+                recall: ratio(true_positives, ground_truth),
+                ap: None,
+                ap50: None,
+            }
+        })
+        .collect();
+
+    ClassificationMetrics {
+        top1: ratio(top1, labeled),
+        top5: ratio(top5, labeled),
+        per_class,
+        confusion_matrix,
+    }
+}
+
+/// COCO 101-point interpolated average precision; `None` without labels
+fn average_precision(mut detections: Vec<(f32, bool)>, ground_truth: usize) -> Option<f64> {
+    if ground_truth == 0 {
+        return None;
+    }
+    detections.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut true_positives = 0usize;
+    let mut precision = Vec::with_capacity(detections.len());
+    let mut recall = Vec::with_capacity(detections.len());
+    for (index, (_, hit)) in detections.iter().enumerate() {
+        true_positives += usize::from(*hit);
+        precision.push(true_positives as f64 / (index + 1) as f64);
+        recall.push(true_positives as f64 / ground_truth as f64);
+    }
+    // Precision envelope: best precision at this recall or higher
+    for index in (0..precision.len().saturating_sub(1)).rev() {
+        precision[index] = precision[index].max(precision[index + 1]);
+    }
+
+    let sum: f64 = (0..=100)
+        .map(|step| {
+            let threshold = step as f64 / 100.0;
+            let index = recall.partition_point(|&r| r < threshold);
+            precision.get(index).copied().unwrap_or(0.0)
+        })
+        .sum();
+    Some(sum / 101.0)
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 { 0.0 } else { numerator as f64 / denominator as f64 }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detection(class: &str, confidence: f32, bbox: [f32; 4]) -> Prediction {
+        Prediction {
+            class: class.to_string(),
+            confidence,
+            bbox: Some(bbox),
+            metadata: HashMap::new(),
+            severity: None,
+            mask: None,
+            keypoints: None,
+        }
+    }
+
+    fn labeled(boxes: &[(&str, [f32; 4])]) -> Sample {
+        Sample {
+            image_path: PathBuf::from("image.jpg"),
+            boxes: boxes.iter().map(|(class, bbox)| GroundTruthBox { class: class.to_string(), bbox: *bbox }).collect(),
+            label: None,
+        }
+    }
+
+    #[test]
+    fn test_detection_metrics_score_matches_and_misses() {
+        let classes = vec!["person".to_string(), "forklift".to_string()];
+        let samples = vec![
+            labeled(&[("person", [0.0, 0.0, 10.0, 10.0]), ("forklift", [20.0, 20.0, 40.0, 40.0])]),
+            labeled(&[("person", [50.0, 50.0, 60.0, 60.0])]),
+        ];
+        let predictions = vec![
+            vec![
+                detection("person", 0.9, [0.0, 0.0, 10.0, 10.0]),
+                detection("person", 0.8, [20.0, 20.0, 40.0, 40.0]),
+            ],
+            vec![detection("person", 0.7, [50.0, 50.0, 60.0, 60.0])],
+        ];
+
+        let metrics = detection_metrics(&classes, &samples, &predictions);
+        let person = &metrics.per_class[0];
+        assert_eq!((person.ground_truth, person.predicted, person.true_positives), (2, 3, 2));
+        assert!((person.recall - 1.0).abs() < 1e-9);
+        assert!(person.ap.unwrap() > 0.6 && person.ap.unwrap() < 1.0);
+        assert_eq!(metrics.per_class[1].ap, Some(0.0));
+        assert!((metrics.map - (person.ap.unwrap() / 2.0)).abs() < 1e-9);
+
+        // The forklift was detected as a person
+        let matrix = &metrics.confusion_matrix;
+        assert_eq!(matrix.labels, vec!["person", "forklift", "background"]);
+        assert_eq!(matrix.counts[1][0], 1);
+        assert_eq!(matrix.counts[0][0], 2);
+    }
+
+    #[test]
+    fn test_classification_top_k_and_latency_percentiles() {
+        let classes = vec!["normal".to_string(), "leak".to_string()];
+        let sample = |label: &str| Sample { image_path: PathBuf::new(), boxes: Vec::new(), label: Some(label.to_string()) };
+        let ranked = |classes: &[(&str, f32)]| -> Vec<Prediction> {
+            classes.iter().map(|(class, confidence)| Prediction { bbox: None, ..detection(class, *confidence, [0.0; 4]) }).collect()
+        };
+        let metrics = classification_metrics(
+            &classes,
+            &[sample("normal"), sample("leak"), sample("leak")],
+            &[ranked(&[("normal", 0.9)]), ranked(&[("normal", 0.6), ("leak", 0.4)]), Vec::new()],
+        );
+        assert!((metrics.top1 - 1.0 / 3.0).abs() < 1e-9);
+        assert!((metrics.top5 - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(metrics.confusion_matrix.counts[1], vec![1, 0, 1]);
+
+        let latency = LatencyStats::from_samples(&(1..=100).map(f64::from).collect::<Vec<_>>());
+        assert_eq!((latency.p50_ms, latency.p95_ms, latency.max_ms), (50.0, 95.0, 100.0));
+
+        let report = EvaluationReport {
+            model_name: "leak".to_string(),
+            dataset_format: DatasetFormat::ImageFolder,
+            images: 3,
+            failures: 0,
+            errors: Vec::new(),
+            detection: None,
+            classification: Some(metrics),
+            latency,
+        };
+        let gate = EvaluationGate { min_top1: Some(0.9), max_p95_ms: Some(100.0), ..Default::default() };
+        assert_eq!(report.check(&gate).len(), 1);
+    }
+}
@@ -0,0 +1,303 @@
+//! Model artifact integrity and signature verification
+//!
+//! A model YAML can pin the SHA-256 digest of its artifact and name a detached
+//! Ed25519 signature file. The signature covers `SHA-256(artifact) ||
+//! SHA-256(config YAML)`, so neither the model nor its configuration can be
+//! replaced on its own. Signatures are checked against the trusted public keys
+//! in [`IntegrityConfig`].
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use base64::{Engine as _, engine::general_purpose};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Errors raised while verifying a model artifact
+#[derive(Error, Debug)]
+pub enum IntegrityError {
+    #[error("SHA-256 mismatch for '{path}': expected {expected}, found {actual}")]
+    DigestMismatch { path: String, expected: String, actual: String },
+    #[error("Malformed SHA-256 digest '{0}'")]
+    InvalidDigest(String),
+    #[error("Model '{0}' does not declare an artifact digest")]
+    MissingDigest(String),
+    #[error("Model '{0}' is not signed")]
+    MissingSignature(String),
+    #[error("Model '{model}' is signed with untrusted key '{key_id}'")]
+    UntrustedKey { model: String, key_id: String },
+    #[error("Invalid trusted key '{key_id}': {reason}")]
+    InvalidKey { key_id: String, reason: String },
+    #[error("Malformed signature: {0}")]
+    MalformedSignature(String),
+    #[error("Signature verification failed for model '{0}'")]
+    BadSignature(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Expected digest and optional signature declared by a model YAML
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactIntegrity {
+    /// Hex-encoded SHA-256 digest of the model artifact
+    pub sha256: String,
+    /// Detached signature file (base64 or raw 64 bytes), relative to the models directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Trusted key that produced the signature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+}
+
+/// Trusted signing keys and load policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityConfig {
+    /// Base64-encoded Ed25519 public keys by key id
+    #[serde(default)]
+    pub trusted_keys: HashMap<String, String>,
+    /// Reject models whose configuration does not declare a digest
+    #[serde(default)]
+    pub require_digest: bool,
+    /// Reject models that are not signed by a trusted key
+    #[serde(default)]
+    pub require_signature: bool,
+}
+
+/// How thoroughly a loaded model was verified
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    #[default]
+    Unverified,
+    DigestVerified,
+    SignatureVerified,
+}
+
+/// Parsed trusted keys plus the load policy they enforce
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    keys: HashMap<String, VerifyingKey>,
+    require_digest: bool,
+    require_signature: bool,
+}
+
+impl TrustStore {
+    pub fn from_config(config: &IntegrityConfig) -> Result<Self, IntegrityError> {
+        let keys = config.trusted_keys.iter()
+            .map(|(key_id, encoded)| parse_public_key(key_id, encoded).map(|key| (key_id.clone(), key)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            keys,
+            require_digest: config.require_digest || config.require_signature,
+            require_signature: config.require_signature,
+        })
+    }
+
+    pub fn require_digest(&self) -> bool {
+        self.require_digest
+    }
+
+    pub fn require_signature(&self) -> bool {
+        self.require_signature
+    }
+
+    /// Verify a model artifact against its declared digest and signature
+    ///
+    /// `signature` holds the contents of the detached signature file, when the
+    /// configuration names one. Blocking: hashes the whole artifact.
+    pub fn verify(
+        &self,
+        model_name: &str,
+        integrity: Option<&ArtifactIntegrity>,
+        artifact: &Path,
+        config_yaml: &[u8],
+        signature: Option<&[u8]>,
+    ) -> Result<IntegrityStatus, IntegrityError> {
+        let Some(integrity) = integrity else {
+            return if self.require_digest {
+                Err(IntegrityError::MissingDigest(model_name.to_string()))
+            } else {
+                Ok(IntegrityStatus::Unverified)
+            };
+        };
+
+        let artifact_digest = sha256_file(artifact)?;
+        check_digest(&artifact.display().to_string(), &artifact_digest, &integrity.sha256)?;
+
+        let Some(signature) = signature else {
+            return if self.require_signature {
+                Err(IntegrityError::MissingSignature(model_name.to_string()))
+            } else {
+                Ok(IntegrityStatus::DigestVerified)
+            };
+        };
+
+        let key_id = integrity.key_id.as_deref().unwrap_or_default();
+        let key = self.keys.get(key_id).ok_or_else(|| IntegrityError::UntrustedKey {
+            model: model_name.to_string(),
+            key_id: key_id.to_string(),
+        })?;
+
+        let signature = parse_signature(signature)?;
+        key.verify_strict(&signed_message(&artifact_digest, config_yaml), &signature)
+            .map_err(|_| IntegrityError::BadSignature(model_name.to_string()))?;
+
+        Ok(IntegrityStatus::SignatureVerified)
+    }
+}
+
+/// Message covered by a model signature
+pub fn signed_message(artifact_digest: &[u8; 32], config_yaml: &[u8]) -> Vec<u8> {
+    let mut message = artifact_digest.to_vec();
+    message.extend_from_slice(&Sha256::digest(config_yaml));
+    message
+}
+
+/// SHA-256 of a file, read in chunks so large models are not held in memory
+pub fn sha256_file(path: &Path) -> Result<[u8; 32], IntegrityError> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Compare a computed digest with an expected hex digest
+pub fn check_digest(path: &str, actual: &[u8], expected_hex: &str) -> Result<(), IntegrityError> {
+    let expected = hex::decode(expected_hex.trim())
+        .ok()
+        .filter(|bytes| bytes.len() == 32)
+        .ok_or_else(|| IntegrityError::InvalidDigest(expected_hex.to_string()))?;
+
+    if expected != actual {
+        return Err(IntegrityError::DigestMismatch {
+            path: path.to_string(),
+            expected: expected_hex.trim().to_lowercase(),
+            actual: hex::encode(actual),
+        });
+    }
+    Ok(())
+}
+
+/// Verify in-memory artifact bytes against an expected hex digest
+pub fn verify_bytes(path: &str, bytes: &[u8], expected_hex: &str) -> Result<(), IntegrityError> {
+    check_digest(path, &Sha256::digest(bytes), expected_hex)
+}
+
+fn parse_public_key(key_id: &str, encoded: &str) -> Result<VerifyingKey, IntegrityError> {
+    let invalid = |reason: String| IntegrityError::InvalidKey { key_id: key_id.to_string(), reason };
+
+    let bytes = general_purpose::STANDARD.decode(encoded.trim())
+        .map_err(|e| invalid(e.to_string()))?;
+    let bytes: [u8; 32] = bytes.try_into()
+        .map_err(|bytes: Vec<u8>| invalid(format!("expected 32 bytes, got {}", bytes.len())))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| invalid(e.to_string()))
+}
+
+fn parse_signature(contents: &[u8]) -> Result<Signature, IntegrityError> {
+    let bytes = if contents.len() == Signature::BYTE_SIZE {
+        contents.to_vec()
+    } else {
+        let text = std::str::from_utf8(contents)
+            .map_err(|_| IntegrityError::MalformedSignature("expected base64 or 64 raw bytes".to_string()))?;
+        general_purpose::STANDARD.decode(text.trim())
+            .map_err(|e| IntegrityError::MalformedSignature(e.to_string()))?
+    };
+    Signature::from_slice(&bytes).map_err(|e| IntegrityError::MalformedSignature(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use std::io::Write;
+
+    const CONFIG: &[u8] = b"model:\n  name: detector\n";
+
+    fn artifact(contents: &[u8]) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(contents).unwrap();
+        file
+    }
+
+    fn signed_store() -> (TrustStore, SigningKey) {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let config = IntegrityConfig {
+            trusted_keys: [(
+                "release".to_string(),
+                general_purpose::STANDARD.encode(signing_key.verifying_key().as_bytes()),
+            )].into_iter().collect(),
+            require_digest: false,
+            require_signature: true,
+        };
+        (TrustStore::from_config(&config).unwrap(), signing_key)
+    }
+
+    fn integrity(contents: &[u8]) -> ArtifactIntegrity {
+        ArtifactIntegrity {
+            sha256: hex::encode(Sha256::digest(contents)),
+            signature: Some("detector.sig".to_string()),
+            key_id: Some("release".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_digest_mismatch_is_rejected() {
+        let file = artifact(b"truncated");
+        let store = TrustStore::default();
+
+        let status = store.verify("detector", Some(&integrity(b"truncated")), file.path(), CONFIG, None).unwrap();
+        assert_eq!(status, IntegrityStatus::DigestVerified);
+
+        let error = store.verify("detector", Some(&integrity(b"original")), file.path(), CONFIG, None).unwrap_err();
+        assert!(matches!(error, IntegrityError::DigestMismatch { .. }));
+    }
+
+    #[test]
+    fn test_signature_covers_artifact_and_config() {
+        let file = artifact(b"weights");
+        let (store, signing_key) = signed_store();
+        let digest = sha256_file(file.path()).unwrap();
+        let signature = general_purpose::STANDARD.encode(signing_key.sign(&signed_message(&digest, CONFIG)).to_bytes());
+
+        let status = store.verify("detector", Some(&integrity(b"weights")), file.path(), CONFIG, Some(signature.as_bytes())).unwrap();
+        assert_eq!(status, IntegrityStatus::SignatureVerified);
+
+        let error = store.verify("detector", Some(&integrity(b"weights")), file.path(), b"model: {}", Some(signature.as_bytes())).unwrap_err();
+        assert!(matches!(error, IntegrityError::BadSignature(_)));
+    }
+
+    #[test]
+    fn test_policy_requires_trusted_signature() {
+        let file = artifact(b"weights");
+        let (store, _) = signed_store();
+
+        let error = store.verify("detector", None, file.path(), CONFIG, None).unwrap_err();
+        assert!(matches!(error, IntegrityError::MissingDigest(_)));
+
+        let error = store.verify("detector", Some(&integrity(b"weights")), file.path(), CONFIG, None).unwrap_err();
+        assert!(matches!(error, IntegrityError::MissingSignature(_)));
+
+        let untrusted = SigningKey::from_bytes(&[9u8; 32]);
+        let digest = sha256_file(file.path()).unwrap();
+        let signature = untrusted.sign(&signed_message(&digest, CONFIG)).to_bytes();
+        let mut unknown_key = integrity(b"weights");
+        unknown_key.key_id = Some("someone-else".to_string());
+        let error = store.verify("detector", Some(&unknown_key), file.path(), CONFIG, Some(&signature)).unwrap_err();
+        assert!(matches!(error, IntegrityError::UntrustedKey { .. }));
+
+        let error = store.verify("detector", Some(&integrity(b"weights")), file.path(), CONFIG, Some(&signature)).unwrap_err();
+        assert!(matches!(error, IntegrityError::BadSignature(_)));
+    }
+}
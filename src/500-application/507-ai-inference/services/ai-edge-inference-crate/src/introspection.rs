@@ -0,0 +1,576 @@
+//! ONNX model signature introspection
+//!
+//! Reads the input/output names, shapes and element types of an ONNX graph
+//! together with its metadata properties, then either drafts a complete
+//! `ModelConfiguration` or lists every way an existing configuration
+//! disagrees with the model. Ultralytics exports carry `names`, `imgsz`,
+//! `task` and `kpt_shape` properties, which fill in class labels, the input
+//! size and the postprocessor.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::model_config::{
+    InputConfiguration, InputSemantic, InputTensorSpec, ModelConfiguration, ModelMetadata, NormalizationConfig,
+    OutputConfiguration, OutputTensorSpec, PostprocessingConfiguration, PreprocessingConfiguration, SizeOrder,
+};
+use crate::tensors::TensorDType;
+
+/// Errors raised while reading a model signature
+#[derive(Error, Debug)]
+pub enum IntrospectionError {
+    #[error("Failed to read ONNX model '{path}': {reason}")]
+    Onnx { path: String, reason: String },
+    #[error("Model '{0}' has no inputs")]
+    NoInputs(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Name, shape and element type of a graph input or output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TensorSignature {
+    pub name: String,
+    /// Dimensions; dynamic dimensions are negative
+    pub shape: Vec<i64>,
+    /// Element type (e.g. "float32", "int64")
+    pub dtype: String,
+}
+
+/// Everything an ONNX file declares about its interface
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelSignature {
+    pub inputs: Vec<TensorSignature>,
+    pub outputs: Vec<TensorSignature>,
+    /// Custom metadata properties (e.g. Ultralytics `names`, `imgsz`, `task`)
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+    pub producer: Option<String>,
+    pub description: Option<String>,
+}
+
+/// One way a configuration disagrees with the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureDiscrepancy {
+    /// Configuration field at fault (e.g. "output.tensors[0].shape")
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for SignatureDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Model task, from the Ultralytics `task` property or the output layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Task {
+    Detection,
+    Classification,
+    InstanceSegmentation,
+    SemanticSegmentation,
+    Pose,
+    Unknown,
+}
+
+impl Task {
+    fn model_type(self) -> &'static str {
+        match self {
+            Task::Detection => "object_detection",
+            Task::Classification => "image_classification",
+            Task::InstanceSegmentation => "instance_segmentation",
+            Task::SemanticSegmentation => "semantic_segmentation",
+            Task::Pose => "pose_estimation",
+            Task::Unknown => "custom",
+        }
+    }
+
+    fn postprocess_type(self) -> &'static str {
+        match self {
+            Task::Detection => "yolov8",
+            Task::Classification => "classification",
+            Task::InstanceSegmentation => "yolov8-seg",
+            Task::SemanticSegmentation => "segmentation",
+            Task::Pose => "yolov8-pose",
+            Task::Unknown => "raw",
+        }
+    }
+
+    fn output_format(self) -> &'static str {
+        match self {
+            Task::Detection | Task::Pose => "detection",
+            Task::Classification => "classification",
+            Task::InstanceSegmentation | Task::SemanticSegmentation => "segmentation",
+            Task::Unknown => "raw",
+        }
+    }
+}
+
+impl ModelSignature {
+    /// Read the signature of an ONNX file through ONNX Runtime
+    #[cfg(feature = "onnx-runtime")]
+    pub fn from_onnx_file(path: impl AsRef<std::path::Path>) -> Result<Self, IntrospectionError> {
+        let path = path.as_ref();
+        let onnx_error = |reason: String| IntrospectionError::Onnx { path: path.display().to_string(), reason };
+
+        let session = ort::session::Session::builder()
+            .and_then(|mut builder| builder.commit_from_file(path))
+            .map_err(|e| onnx_error(e.to_string()))?;
+
+        let tensor = |name: &str, dtype: &ort::value::ValueType| TensorSignature {
+            name: name.to_string(),
+            shape: dtype.tensor_shape().map(|shape| shape.to_vec()).unwrap_or_default(),
+            dtype: dtype.tensor_type()
+                .map(|ty| format!("{:?}", ty).to_lowercase())
+                .unwrap_or_else(|| "non-tensor".to_string()),
+        };
+        let inputs = session.inputs().iter().map(|i| tensor(i.name(), i.dtype())).collect();
+        let outputs = session.outputs().iter().map(|o| tensor(o.name(), o.dtype())).collect();
+
+        let metadata = session.metadata().map_err(|e| onnx_error(e.to_string()))?;
+        let properties = metadata.custom_keys()
+            .map_err(|e| onnx_error(e.to_string()))?
+            .into_iter()
+            .filter_map(|key| metadata.custom(&key).map(|value| (key, value)))
+            .collect();
+
+        Ok(Self {
+            inputs,
+            outputs,
+            properties,
+            producer: metadata.producer().filter(|p| !p.is_empty()),
+            description: metadata.description().filter(|d| !d.is_empty()),
+        })
+    }
+
+    /// Class labels from the Ultralytics `names` property, ordered by index
+    pub fn class_labels(&self) -> Option<Vec<String>> {
+        let names = parse_names(self.properties.get("names")?);
+        (!names.is_empty()).then_some(names)
+    }
+
+    /// Input `[height, width]` from the Ultralytics `imgsz` property
+    pub fn image_size(&self) -> Option<[i64; 2]> {
+        let dims = parse_integers(self.properties.get("imgsz")?);
+        match dims.as_slice() {
+            [size] => Some([*size, *size]),
+            [height, width] => Some([*height, *width]),
+            _ => None,
+        }
+    }
+
+    /// The input that receives the image: the first 4D input, or the first input
+    fn primary_input(&self) -> Option<&TensorSignature> {
+        self.inputs.iter().find(|input| input.shape.len() == 4).or_else(|| self.inputs.first())
+    }
+
+    /// Primary input shape with dynamic dimensions filled in from `imgsz`
+    fn concrete_input_shape(&self) -> Vec<i64> {
+        let Some(input) = self.primary_input() else {
+            return Vec::new();
+        };
+        let mut shape = input.shape.clone();
+        if let (4, Some([height, width])) = (shape.len(), self.image_size()) {
+            let (h, w) = if channels_last(&shape) { (1, 2) } else { (2, 3) };
+            if shape[h] < 0 {
+                shape[h] = height;
+            }
+            if shape[w] < 0 {
+                shape[w] = width;
+            }
+        }
+        if shape.first().is_some_and(|&batch| batch < 0) {
+            shape[0] = 1;
+        }
+        shape
+    }
+
+    fn task(&self) -> Task {
+        match self.properties.get("task").map(|task| task.trim().to_lowercase()).as_deref() {
+            Some("detect") => return Task::Detection,
+            Some("classify") => return Task::Classification,
+            Some("segment") => return Task::InstanceSegmentation,
+            Some("pose") => return Task::Pose,
+            _ => {}
+        }
+        match self.outputs.as_slice() {
+            [output] => match output.shape.len() {
+                2 => Task::Classification,
+                3 if output.shape[2] > output.shape[1] && output.shape[1] > 4 => Task::Detection,
+                4 => Task::SemanticSegmentation,
+                _ => Task::Unknown,
+            },
+            [detections, protos] if detections.shape.len() == 3 && protos.shape.len() == 4 => Task::InstanceSegmentation,
+            _ => Task::Unknown,
+        }
+    }
+
+    /// Draft a configuration for the model at `model_path`
+    ///
+    /// The draft is complete enough to load, but thresholds, normalization
+    /// and the description are defaults worth reviewing.
+    pub fn draft_configuration(&self, name: &str, model_path: PathBuf) -> Result<ModelConfiguration, IntrospectionError> {
+        let primary = self.primary_input().ok_or_else(|| IntrospectionError::NoInputs(name.to_string()))?;
+        let task = self.task();
+        let class_labels = self.class_labels();
+        let input_shape = self.concrete_input_shape();
+        let is_image = input_shape.len() == 4;
+        let layout_nhwc = is_image && channels_last(&input_shape);
+
+        let input_tensors = if self.inputs.len() > 1 {
+            self.inputs.iter()
+                .map(|input| draft_input_tensor(input, input.name == primary.name))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let outputs = self.outputs.iter()
+            .map(|output| OutputTensorSpec {
+                name: output.name.clone(),
+                shape: output.shape.clone(),
+                dtype: output.dtype.clone(),
+                semantic: output_semantic(task, output),
+            })
+            .collect();
+
+        let target_size = is_image.then(|| {
+            if layout_nhwc { [input_shape[1], input_shape[2]] } else { [input_shape[2], input_shape[3]] }
+        });
+
+        let mut parameters = HashMap::new();
+        if let (Task::Pose, Some(kpt_shape)) = (task, self.properties.get("kpt_shape")) {
+            if let [count, dims] = parse_integers(kpt_shape).as_slice() {
+                parameters.insert("num_keypoints".to_string(), serde_yaml::Value::from(*count));
+                parameters.insert("keypoint_dims".to_string(), serde_yaml::Value::from(*dims));
+            }
+        }
+
+        let description = self.description.clone()
+            .or_else(|| self.properties.get("description").cloned())
+            .unwrap_or_else(|| format!("Draft configuration generated from {}", model_path.display()));
+        let detection = matches!(task, Task::Detection | Task::InstanceSegmentation | Task::Pose);
+
+        Ok(ModelConfiguration {
+            model: ModelMetadata {
+                name: name.to_string(),
+                version: self.properties.get("version").cloned().unwrap_or_else(|| "1.0.0".to_string()),
+                model_type: task.model_type().to_string(),
+                description,
+                path: model_path,
+                size_mb: None,
+                backends: vec!["onnx".to_string()],
+                performance: None,
+                timeout_ms: None,
+                integrity: None,
+            },
+            input: InputConfiguration {
+                shape: input_shape,
+                dtype: primary.dtype.clone(),
+                format: if !is_image { "flat" } else if layout_nhwc { "NHWC" } else { "NCHW" }.to_string(),
+                value_range: vec![0.0, 1.0],
+                color_space: is_image.then(|| "RGB".to_string()),
+                input_type: if is_image { "image" } else { "tensor" }.to_string(),
+                tensors: input_tensors,
+            },
+            output: OutputConfiguration {
+                tensors: outputs,
+                postprocess_type: task.postprocess_type().to_string(),
+                confidence_threshold: (task != Task::Unknown).then_some(if detection { 0.25 } else { 0.5 }),
+                nms_threshold: detection.then_some(0.45),
+                max_detections: detection.then_some(300),
+                class_labels,
+                suppression: None,
+                calibration: None,
+                class_thresholds: None,
+            },
+            preprocessing: is_image.then(|| PreprocessingConfiguration {
+                resize_strategy: if detection { "letterbox" } else { "stretch" }.to_string(),
+                target_size,
+                normalization: Some(NormalizationConfig {
+                    mean: vec![0.0, 0.0, 0.0],
+                    std: vec![1.0, 1.0, 1.0],
+                    scale_to_unit: true,
+                }),
+                steps: None,
+                audio: None,
+                tiling: None,
+            }),
+            postprocessing: Some(PostprocessingConfiguration {
+                postprocess_type: task.postprocess_type().to_string(),
+                parameters: (!parameters.is_empty()).then_some(parameters),
+                output_format: task.output_format().to_string(),
+            }),
+            parameters: None,
+            pipeline: None,
+        })
+    }
+
+    /// Every way `config` disagrees with this signature
+    pub fn validate(&self, config: &ModelConfiguration) -> Vec<SignatureDiscrepancy> {
+        let mut issues = Vec::new();
+        let mut report = |field: String, message: String| issues.push(SignatureDiscrepancy { field, message });
+        let input_names: Vec<&str> = self.inputs.iter().map(|i| i.name.as_str()).collect();
+        let output_names: Vec<&str> = self.outputs.iter().map(|o| o.name.as_str()).collect();
+
+        // Inputs
+        if config.input.tensors.is_empty() {
+            if self.inputs.len() > 1 {
+                report("input.tensors".to_string(), format!("model has inputs {:?}, which must all be declared", input_names));
+            }
+            // Dynamic dimensions are compared against the trained `imgsz`
+            if let Some(input) = self.primary_input() {
+                let concrete = TensorSignature { shape: self.concrete_input_shape(), ..input.clone() };
+                check_tensor(&mut report, "input", &config.input.shape, &config.input.dtype, &concrete);
+            }
+        } else {
+            for (index, spec) in config.input.tensors.iter().enumerate() {
+                let field = format!("input.tensors[{}]", index);
+                match self.inputs.iter().find(|i| i.name == spec.name) {
+                    Some(input) => check_tensor(&mut report, &field, &spec.shape, &spec.dtype, input),
+                    None => report(format!("{}.name", field), format!("model has no input '{}'; its inputs are {:?}", spec.name, input_names)),
+                }
+            }
+            for input in &self.inputs {
+                if !config.input.tensors.iter().any(|spec| spec.name == input.name) {
+                    report("input.tensors".to_string(), format!("model input '{}' is not declared", input.name));
+                }
+            }
+            if let Err(message) = config.input.validate_tensors() {
+                report("input.tensors".to_string(), message);
+            }
+            if let Some(input) = config.input.tensors.iter()
+                .find(|spec| spec.semantic == InputSemantic::Input)
+                .and_then(|spec| self.inputs.iter().find(|i| i.name == spec.name))
+            {
+                check_tensor(&mut report, "input", &config.input.shape, &config.input.dtype, input);
+            }
+        }
+
+        let model_shape = self.concrete_input_shape();
+        if let (Some([height, width]), 4) = (config.preprocessing.as_ref().and_then(|pre| pre.target_size), model_shape.len()) {
+            let (h, w) = if channels_last(&model_shape) { (model_shape[1], model_shape[2]) } else { (model_shape[2], model_shape[3]) };
+            if (h >= 0 && h != height) || (w >= 0 && w != width) {
+                report("preprocessing.target_size".to_string(), format!("[{}, {}] does not match the model input [{}, {}]", height, width, h, w));
+            }
+        }
+
+        // Outputs
+        for (index, spec) in config.output.tensors.iter().enumerate() {
+            let field = format!("output.tensors[{}]", index);
+            match self.outputs.iter().find(|o| o.name == spec.name) {
+                Some(output) => check_tensor(&mut report, &field, &spec.shape, &spec.dtype, output),
+                None => report(format!("{}.name", field), format!("model has no output '{}'; its outputs are {:?}", spec.name, output_names)),
+            }
+        }
+        for output in &self.outputs {
+            if !config.output.tensors.iter().any(|spec| spec.name == output.name) {
+                report("output.tensors".to_string(), format!("model output '{}' is not declared", output.name));
+            }
+        }
+
+        // Class labels
+        let configured = config.output.class_labels.as_deref().unwrap_or_default();
+        if let Some(embedded) = self.class_labels() {
+            if configured.is_empty() {
+                report("output.class_labels".to_string(), format!("model embeds {} class names but none are configured", embedded.len()));
+            } else if configured != embedded.as_slice() {
+                let first = configured.iter().zip(&embedded).position(|(a, b)| a != b);
+                let message = match first {
+                    Some(index) => format!("label {} is '{}' but the model names it '{}'", index, configured[index], embedded[index]),
+                    None => format!("{} labels are configured but the model names {} classes", configured.len(), embedded.len()),
+                };
+                report("output.class_labels".to_string(), message);
+            }
+        }
+        if !configured.is_empty() && matches!(config.output.postprocess_type.as_str(), "yolov8" | "yolo" | "yolov5") {
+            if let Some(rows) = self.outputs.first().filter(|o| o.shape.len() == 3).map(|o| o.shape[1]).filter(|&rows| rows > 4) {
+                let classes = rows - 4;
+                if classes != configured.len() as i64 {
+                    report("output.class_labels".to_string(), format!("{} labels are configured but the output has {} classes", configured.len(), classes));
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+/// Compare a declared shape and dtype against the model's
+fn check_tensor(
+    report: &mut impl FnMut(String, String),
+    field: &str,
+    declared_shape: &[i64],
+    declared_dtype: &str,
+    actual: &TensorSignature,
+) {
+    if !shapes_compatible(declared_shape, &actual.shape) {
+        report(format!("{}.shape", field), format!("{:?} does not match the model's {:?}", declared_shape, actual.shape));
+    }
+    if !same_dtype(declared_dtype, &actual.dtype) {
+        report(format!("{}.dtype", field), format!("'{}' does not match the model's '{}'", declared_dtype, actual.dtype));
+    }
+}
+
+/// Whether a declared shape agrees with the shape a model reports
+///
+/// Negative dimensions on either side are dynamic and match anything; an
+/// empty declared shape is not checked.
+pub(crate) fn shapes_compatible(declared: &[i64], actual: &[i64]) -> bool {
+    declared.is_empty()
+        || (declared.len() == actual.len()
+            && declared.iter().zip(actual).all(|(&d, &a)| d < 0 || a < 0 || d == a))
+}
+
+/// Compare element type names, accepting aliases such as "float" for "float32"
+fn same_dtype(declared: &str, actual: &str) -> bool {
+    let parse = |dtype: &str| serde_json::from_value::<TensorDType>(serde_json::json!(dtype.to_lowercase())).ok();
+    match (parse(declared), parse(actual)) {
+        (Some(declared), Some(actual)) => declared == actual,
+        _ => declared.eq_ignore_ascii_case(actual),
+    }
+}
+
+fn channels_last(shape: &[i64]) -> bool {
+    shape.len() == 4 && matches!(shape[3], 1 | 3) && !matches!(shape[1], 1 | 3)
+}
+
+/// Guess what feeds an extra input from its name
+fn draft_input_tensor(input: &TensorSignature, primary: bool) -> InputTensorSpec {
+    let name = input.name.to_lowercase();
+    let semantic = if primary {
+        InputSemantic::Input
+    } else if name.contains("scale") {
+        InputSemantic::ScaleFactor
+    } else if name.contains("im_shape") || name.contains("input_size") {
+        InputSemantic::InputSize
+    } else if name.contains("size") || name.contains("shape") {
+        InputSemantic::ImageSize
+    } else {
+        InputSemantic::Constant
+    };
+    let value = (semantic == InputSemantic::Constant).then(|| {
+        let len = input.shape.iter().map(|&d| d.max(1)).product::<i64>().max(1);
+        vec![0.0; len as usize]
+    });
+    InputTensorSpec {
+        name: input.name.clone(),
+        shape: input.shape.clone(),
+        dtype: input.dtype.clone(),
+        semantic,
+        order: if name.contains("orig") { SizeOrder::Wh } else { SizeOrder::Hw },
+        value,
+    }
+}
+
+fn output_semantic(task: Task, output: &TensorSignature) -> String {
+    let name = output.name.to_lowercase();
+    let semantic = match task {
+        Task::Detection | Task::Pose => "detections",
+        Task::Classification => "logits",
+        Task::InstanceSegmentation if output.shape.len() == 4 => "prototypes",
+        Task::InstanceSegmentation => "detections",
+        Task::SemanticSegmentation => "segmentation",
+        Task::Unknown => ["boxes", "scores", "classes", "labels"].into_iter()
+            .find(|semantic| name.contains(semantic.trim_end_matches('s')))
+            .unwrap_or("output"),
+    };
+    semantic.to_string()
+}
+
+/// Parse a Python dict literal such as `{0: 'person', 1: "traffic light"}` into names ordered by key
+fn parse_names(raw: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = raw.trim().trim_start_matches('{');
+    while let Some(colon) = rest.find(':') {
+        let key = rest[..colon].trim().trim_start_matches(',').trim();
+        let value = rest[colon + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| *c == '\'' || *c == '"') else {
+            break;
+        };
+        let Some(end) = value[1..].find(quote) else {
+            break;
+        };
+        if let Ok(index) = key.parse::<usize>() {
+            names.push((index, value[1..end + 1].to_string()));
+        }
+        rest = &value[end + 2..];
+    }
+    names.sort_by_key(|(index, _)| *index);
+    names.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Parse the integers of a list literal such as `[640, 640]` or `640`
+fn parse_integers(raw: &str) -> Vec<i64> {
+    raw.split(|c: char| !c.is_ascii_digit() && c != '-')
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yolov8n_signature() -> ModelSignature {
+        ModelSignature {
+            inputs: vec![TensorSignature { name: "images".to_string(), shape: vec![-1, 3, -1, -1], dtype: "float32".to_string() }],
+            outputs: vec![TensorSignature { name: "output0".to_string(), shape: vec![1, 6, 8400], dtype: "float32".to_string() }],
+            properties: HashMap::from([
+                ("names".to_string(), "{0: 'hardhat', 1: \"no hardhat\"}".to_string()),
+                ("imgsz".to_string(), "[640, 640]".to_string()),
+                ("task".to_string(), "detect".to_string()),
+            ]),
+            producer: Some("pytorch".to_string()),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_declared_shapes_allow_dynamic_dimensions() {
+        assert!(shapes_compatible(&[1, 84, 8400], &[-1, 84, 8400]));
+        assert!(shapes_compatible(&[-1, 300, 4], &[1, 300, 4]));
+        assert!(shapes_compatible(&[], &[1, 1000]));
+        assert!(!shapes_compatible(&[1, 84, 8400], &[1, 84]));
+        assert!(!shapes_compatible(&[1, 1000], &[1, 1001]));
+    }
+
+    #[test]
+    fn test_draft_from_ultralytics_metadata() {
+        let config = yolov8n_signature().draft_configuration("ppe", PathBuf::from("ppe.onnx")).unwrap();
+
+        assert_eq!(config.input.shape, vec![1, 3, 640, 640]);
+        assert_eq!(config.input.format, "NCHW");
+        assert_eq!(config.output.postprocess_type, "yolov8");
+        assert_eq!(config.output.class_labels, Some(vec!["hardhat".to_string(), "no hardhat".to_string()]));
+        assert_eq!(config.output.tensors[0].name, "output0");
+        assert_eq!(config.preprocessing.as_ref().unwrap().target_size, Some([640, 640]));
+        assert!(yolov8n_signature().validate(&config).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_every_discrepancy() {
+        let signature = yolov8n_signature();
+        let mut config = signature.draft_configuration("ppe", PathBuf::from("ppe.onnx")).unwrap();
+        config.input.shape = vec![1, 3, 320, 320];
+        config.output.tensors[0].name = "detections".to_string();
+        config.output.class_labels = Some(vec!["hardhat".to_string()]);
+
+        let fields: Vec<String> = signature.validate(&config).into_iter().map(|issue| issue.field).collect();
+        assert_eq!(fields, vec![
+            "input.shape",
+            "output.tensors[0].name",
+            "output.tensors",
+            "output.class_labels",
+            "output.class_labels",
+        ]);
+
+        config.preprocessing.as_mut().unwrap().target_size = Some([320, 320]);
+        let issues = signature.validate(&config);
+        assert!(issues.iter().any(|issue| issue.field == "preprocessing.target_size"));
+    }
+}
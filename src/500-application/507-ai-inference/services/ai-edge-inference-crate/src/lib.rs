@@ -13,7 +13,7 @@
 //! - Comprehensive error handling with recovery suggestions
 //! - Structured data types optimized for JSON serialization
 //! - Performance metrics and monitoring
-//! - Support for multiple model types (Vision, Sensor data)
+//! - Support for multiple model types (Vision, Sensor data, Audio)
 //! - Industrial site context enrichment
 //! - Dynamic model loading/unloading
 //! - Batch processing and parallel inference
@@ -40,6 +40,8 @@
 //!         confidence_threshold: Some(0.7),
 //!         preprocessing: None,
 //!         postprocessing: None,
+//!         integrity: None,
+//!         memory_mb: None,
 //!     };
 //!     engine.load_model("safety-detector", &model_config).await?;
 //!
@@ -78,18 +80,35 @@ pub mod types;
 pub mod error;
 pub mod config;
 pub mod models;
+pub mod model_cache;
 pub mod engine;
+pub mod evaluation;
+pub mod batching;
+pub mod calibration;
+pub mod versioning;
+pub mod integrity;
+pub mod introspection;
+pub mod pipeline;
+pub mod suppression;
+pub mod tiling;
+pub mod tracking;
+pub mod analytics;
 pub mod backend;
 pub mod backends;
 pub mod model_config;
-#[cfg(any(feature = "onnx", feature = "onnx-runtime"))]
+pub mod masks;
+pub mod audio;
+pub mod timeseries;
+pub mod tensors;
+pub mod image_input;
+#[cfg(any(feature = "onnx", feature = "onnx-runtime", feature = "candle", feature = "pure-rust"))]
 pub mod preprocessing;
-#[cfg(any(feature = "onnx", feature = "onnx-runtime"))]
+#[cfg(any(feature = "onnx", feature = "onnx-runtime", feature = "candle", feature = "pure-rust"))]
 pub mod postprocessing;
 
 // Re-export main types for easy access
 pub use types::{
-    InferenceRequest, InferenceResult, MqttInferenceResult, Prediction, ModelType
+    InferenceRequest, InferenceResult, MqttInferenceResult, Prediction, ModelType, MaskEncoding, Keypoint
 };
 pub use error::InferenceError;
 pub use config::{
@@ -97,28 +116,55 @@ pub use config::{
     MonitoringConfig, SiteContext, ModelDefinition, ModelParameters
 };
 pub use models::{ModelRegistry, ModelMetadata, LoadedModel};
+pub use model_cache::{CacheLookup, ModelCache, ModelCacheConfig, ModelCacheEntry, ModelCacheMetrics};
 pub use engine::{InferenceEngine, InferenceMetrics};
+pub use batching::{BatchingMetrics, DynamicBatcher};
+pub use calibration::{Calibration, CalibrationError, CalibrationFit, CalibrationMethod, ScoreCalibration};
+pub use evaluation::{
+    ClassMetrics, ClassificationMetrics, ConfusionMatrix, Dataset, DatasetFormat, DatasetSpec,
+    DetectionMetrics, EvaluationError, EvaluationGate, EvaluationOptions, EvaluationReport,
+    EvaluationRun, EvaluationSpec, LatencyStats
+};
+pub use versioning::{CanarySplit, ModelVersionRouter, VersionError, VersionStatus};
+pub use integrity::{ArtifactIntegrity, IntegrityConfig, IntegrityError, IntegrityStatus, TrustStore};
+pub use introspection::{IntrospectionError, ModelSignature, SignatureDiscrepancy, TensorSignature};
+pub use pipeline::{CropConfig, PipelineConfiguration, PipelineError, PipelineMetrics, PipelineStage, StageMetrics};
+pub use suppression::{OverlapMetric, SuppressionConfig, SuppressionError, SuppressionMethod};
+pub use tiling::{MergeStrategy, TilingConfig, TilingError};
+pub use tensors::{Tensor, TensorData, TensorDType, TensorError, TensorRequest};
+pub use image_input::{
+    ImageInputConfig, ImageInputError, ImageInputPipeline, InputGeometry, PooledTensor, ResampleFilter, TensorPool
+};
+pub use tracking::{StreamTracker, TrackerRegistry, TrackingConfig};
+pub use analytics::{
+    AnalyticsConfig, AnalyticsError, AnalyticsEvent, AnalyticsEventType, CameraRules,
+    CrossingDirection, LineRule, SeparationRule, ZoneAnalytics, ZoneRule
+};
 pub use model_config::{
     ModelConfiguration, ModelConfigManager, ModelConfigError,
     ModelMetadata as YamlModelMetadata, ModelSummary
 };
 
 // Re-export universal processing types
-#[cfg(any(feature = "onnx", feature = "onnx-runtime"))]
+#[cfg(any(feature = "onnx", feature = "onnx-runtime", feature = "candle", feature = "pure-rust"))]
 pub use preprocessing::{
     UniversalImagePreprocessor, PreprocessedImage, PreprocessingError,
     ResizeStrategy, NormalizationConfig, InputFormat
 };
-#[cfg(any(feature = "onnx", feature = "onnx-runtime"))]
+#[cfg(any(feature = "onnx", feature = "onnx-runtime", feature = "candle", feature = "pure-rust"))]
 pub use postprocessing::{
-    UniversalPostprocessor, PostprocessingError, DetectionBox
+    UniversalPostprocessor, PostprocessingError, DetectionBox, SsdConfig, SsdDecoder,
+    SegmentationConfig, SegmentationDecoder, KeypointConfig, KeypointDetectionConfig
 };
 
 // Re-export backend types
 pub use backend::{
     InferenceBackend, BackendFactory, BackendConfig, BackendError,
-    BackendStatus, BackendType, DeviceType
+    BackendStatus, BackendType, CustomBackend, DeviceType
 };
+pub use backends::registry::{BackendCapabilities, BackendConstructor, BackendRegistration, BackendRegistry};
+#[cfg(feature = "mock")]
+pub use backends::mock::{MockBackend, MockFixture, MockResponse, MockScript};
 
 // New unified input/output types for backend abstraction
 #[derive(Debug, Clone)]
@@ -132,6 +178,17 @@ pub enum InferenceInput {
         timestamps: Vec<i64>,
         metadata: SensorMetadata,
     },
+    Audio {
+        /// Mono samples in [-1, 1]
+        samples: Vec<f32>,
+        metadata: AudioMetadata,
+    },
+    /// Model inputs passed to the backend as they are
+    Tensor {
+        tensors: Vec<Tensor>,
+        /// Return the output tensors instead of postprocessed predictions
+        raw_output: bool,
+    },
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -149,6 +206,15 @@ pub struct SensorMetadata {
     pub units: String,
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AudioMetadata {
+    pub sample_rate: u32,
+    /// Channel count of the source before downmixing
+    pub channels: u16,
+    /// Source encoding ("wav", "pcm_s16le", ...)
+    pub format: String,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ModelConfig {
     pub model_path: String,
@@ -156,6 +222,12 @@ pub struct ModelConfig {
     pub confidence_threshold: Option<f32>,
     pub preprocessing: Option<serde_json::Value>,
     pub postprocessing: Option<serde_json::Value>,
+    /// Expected artifact digest, checked before the backend creates a session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<ArtifactIntegrity>,
+    /// Memory the loaded model holds, in MB, in place of the backend's estimate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<f64>,
 }
 
 /// Library version
@@ -0,0 +1,313 @@
+//! Compact binary mask encodings
+//!
+//! Segmentation results are published over MQTT, where raw mask tensors are far
+//! too large. This module turns binary masks into run-length encodings and
+//! simplified polygon contours that downstream consumers can rebuild.
+
+use crate::types::MaskEncoding;
+
+/// Which encodings to produce for a mask
+#[derive(Debug, Clone)]
+pub struct MaskEncodingOptions {
+    /// Emit run-length encoding over the mask grid
+    pub rle: bool,
+    /// Emit polygon contours of each connected region
+    pub polygons: bool,
+    /// Douglas-Peucker tolerance for polygon simplification, in grid cells
+    pub polygon_epsilon: f32,
+}
+
+impl Default for MaskEncodingOptions {
+    fn default() -> Self {
+        Self {
+            rle: true,
+            polygons: true,
+            polygon_epsilon: 1.0,
+        }
+    }
+}
+
+/// Encode a row-major binary mask of `width` x `height` cells
+///
+/// Polygon vertices are cell centers multiplied by `scale` (x, y), so callers can
+/// emit them in model input pixel space when the mask grid is smaller. Regions
+/// whose contour has fewer than 3 cells only appear in the RLE.
+pub fn encode_mask(
+    mask: &[bool],
+    width: usize,
+    height: usize,
+    scale: (f32, f32),
+    options: &MaskEncodingOptions,
+) -> MaskEncoding {
+    let covered = mask.iter().filter(|&&m| m).count();
+    let total = (width * height).max(1);
+
+    MaskEncoding {
+        size: [height as u32, width as u32],
+        rle: options.rle.then(|| encode_rle(mask)),
+        polygons: options.polygons.then(|| {
+            trace_contours(mask, width, height)
+                .into_iter()
+                .map(|contour| {
+                    // Thin regions can collapse to a line; keep the raw contour for those
+                    let simplified = simplify_polygon(&contour, options.polygon_epsilon);
+                    if simplified.len() >= 3 { simplified } else { contour }
+                })
+                .filter(|polygon| polygon.len() >= 3)
+                .map(|polygon| {
+                    polygon.into_iter()
+                        .flat_map(|(x, y)| [(x + 0.5) * scale.0, (y + 0.5) * scale.1])
+                        .collect()
+                })
+                .collect()
+        }),
+        area_ratio: covered as f32 / total as f32,
+    }
+}
+
+/// Run-length encode a row-major binary mask
+///
+/// Counts alternate between unset and set cells, always starting with an
+/// unset run (which may be zero).
+pub fn encode_rle(mask: &[bool]) -> Vec<u32> {
+    let mut counts = Vec::new();
+    let mut current = false;
+    let mut run = 0u32;
+
+    for &value in mask {
+        if value != current {
+            counts.push(run);
+            run = 0;
+            current = value;
+        }
+        run += 1;
+    }
+    counts.push(run);
+
+    counts
+}
+
+/// Decode a run-length encoding produced by [`encode_rle`]
+pub fn decode_rle(counts: &[u32]) -> Vec<bool> {
+    let mut mask = Vec::with_capacity(counts.iter().map(|&c| c as usize).sum());
+    for (index, &count) in counts.iter().enumerate() {
+        mask.extend(std::iter::repeat_n(index % 2 == 1, count as usize));
+    }
+    mask
+}
+
+/// Neighbor offsets in clockwise order starting from west
+const NEIGHBORS: [(i64, i64); 8] = [
+    (-1, 0), (-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1),
+];
+
+/// Trace the outer boundary of every 8-connected region using Moore-neighbor tracing
+///
+/// Returns one contour per region as grid cell coordinates.
+pub fn trace_contours(mask: &[bool], width: usize, height: usize) -> Vec<Vec<(f32, f32)>> {
+    let mut labels = vec![0u32; mask.len()];
+    let mut contours = Vec::new();
+    let mut next_label = 0u32;
+
+    for start in 0..mask.len().min(width * height) {
+        if !mask[start] || labels[start] != 0 {
+            continue;
+        }
+
+        next_label += 1;
+        label_region(mask, width, height, start, next_label, &mut labels);
+        contours.push(trace_region(&labels, width, height, start, next_label));
+    }
+
+    contours
+}
+
+/// Flood fill an 8-connected region with `label`
+fn label_region(mask: &[bool], width: usize, height: usize, start: usize, label: u32, labels: &mut [u32]) {
+    let mut stack = vec![start];
+    labels[start] = label;
+
+    while let Some(index) = stack.pop() {
+        let (x, y) = ((index % width) as i64, (index / width) as i64);
+        for (dx, dy) in NEIGHBORS {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || ny < 0 || nx >= width as i64 || ny >= height as i64 {
+                continue;
+            }
+            let neighbor = ny as usize * width + nx as usize;
+            if mask[neighbor] && labels[neighbor] == 0 {
+                labels[neighbor] = label;
+                stack.push(neighbor);
+            }
+        }
+    }
+}
+
+/// Moore-neighbor boundary tracing with Jacob's stopping criterion
+///
+/// `start` must be the first cell of the region in raster order, so its west
+/// neighbor is guaranteed to be outside the region.
+fn trace_region(labels: &[u32], width: usize, height: usize, start: usize, label: u32) -> Vec<(f32, f32)> {
+    let inside = |x: i64, y: i64| {
+        x >= 0 && y >= 0 && x < width as i64 && y < height as i64
+            && labels[y as usize * width + x as usize] == label
+    };
+
+    let start_cell = ((start % width) as i64, (start / width) as i64);
+    let mut contour = vec![(start_cell.0 as f32, start_cell.1 as f32)];
+    let mut current = start_cell;
+    // Direction (index into NEIGHBORS) pointing from the current cell to the backtrack cell
+    let mut backtrack = 0usize;
+    let mut first_step: Option<(i64, i64)> = None;
+    let max_steps = 4 * width * height + 8;
+
+    for _ in 0..max_steps {
+        let mut next = None;
+        for i in 1..=8 {
+            let direction = (backtrack + i) % 8;
+            let (dx, dy) = NEIGHBORS[direction];
+            if inside(current.0 + dx, current.1 + dy) {
+                next = Some((direction, (current.0 + dx, current.1 + dy)));
+                break;
+            }
+        }
+
+        let Some((direction, cell)) = next else {
+            // Isolated single cell
+            break;
+        };
+
+        if current == start_cell {
+            match first_step {
+                None => first_step = Some(cell),
+                Some(first) if first == cell => break,
+                Some(_) => {},
+            }
+        }
+
+        // The last background cell examined becomes the new backtrack point
+        let (bx, by) = NEIGHBORS[(direction + 7) % 8];
+        let background = (current.0 + bx, current.1 + by);
+        let offset = (background.0 - cell.0, background.1 - cell.1);
+        backtrack = NEIGHBORS.iter().position(|&o| o == offset).unwrap_or(0);
+
+        current = cell;
+        if current != start_cell {
+            contour.push((current.0 as f32, current.1 as f32));
+        }
+    }
+
+    contour
+}
+
+/// Simplify a closed polygon with the Douglas-Peucker algorithm
+pub fn simplify_polygon(points: &[(f32, f32)], epsilon: f32) -> Vec<(f32, f32)> {
+    if points.len() <= 3 || epsilon <= 0.0 {
+        return points.to_vec();
+    }
+
+    // Split the closed ring at the point farthest from the start so both halves are open polylines
+    let start = points[0];
+    let (split, _) = points.iter().enumerate().fold((0, 0.0f32), |best, (i, &p)| {
+        let distance = (p.0 - start.0).powi(2) + (p.1 - start.1).powi(2);
+        if distance > best.1 { (i, distance) } else { best }
+    });
+
+    if split == 0 {
+        return vec![start];
+    }
+
+    let mut first_half = points[..=split].to_vec();
+    let mut second_half = points[split..].to_vec();
+    second_half.push(start);
+
+    first_half = douglas_peucker(&first_half, epsilon);
+    second_half = douglas_peucker(&second_half, epsilon);
+
+    // Drop the duplicated split point and closing point
+    first_half.pop();
+    second_half.pop();
+    first_half.extend(second_half);
+    first_half
+}
+
+fn douglas_peucker(points: &[(f32, f32)], epsilon: f32) -> Vec<(f32, f32)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let (index, distance) = points[1..points.len() - 1].iter().enumerate().fold((0, 0.0f32), |best, (i, &p)| {
+        let d = perpendicular_distance(p, first, last);
+        if d > best.1 { (i + 1, d) } else { best }
+    });
+
+    if distance > epsilon {
+        let mut left = douglas_peucker(&points[..=index], epsilon);
+        let right = douglas_peucker(&points[index..], epsilon);
+        left.pop();
+        left.extend(right);
+        left
+    } else {
+        vec![first, last]
+    }
+}
+
+fn perpendicular_distance(point: (f32, f32), line_start: (f32, f32), line_end: (f32, f32)) -> f32 {
+    let (dx, dy) = (line_end.0 - line_start.0, line_end.1 - line_start.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return ((point.0 - line_start.0).powi(2) + (point.1 - line_start.1).powi(2)).sqrt();
+    }
+    (dy * point.0 - dx * point.1 + line_end.0 * line_start.1 - line_end.1 * line_start.0).abs() / length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square_mask(width: usize, height: usize, x0: usize, y0: usize, size: usize) -> Vec<bool> {
+        (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                x >= x0 && x < x0 + size && y >= y0 && y < y0 + size
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rle_round_trip() {
+        let mask = square_mask(8, 8, 2, 3, 3);
+        let counts = encode_rle(&mask);
+
+        assert_eq!(counts[0], 3 * 8 + 2);
+        assert_eq!(decode_rle(&counts), mask);
+    }
+
+    #[test]
+    fn test_square_contour_simplifies_to_corners() {
+        let mask = square_mask(10, 10, 2, 2, 5);
+        let contours = trace_contours(&mask, 10, 10);
+
+        assert_eq!(contours.len(), 1);
+        let polygon = simplify_polygon(&contours[0], 0.5);
+        assert_eq!(polygon.len(), 4);
+        assert!(polygon.contains(&(2.0, 2.0)));
+        assert!(polygon.contains(&(6.0, 6.0)));
+    }
+
+    #[test]
+    fn test_encode_mask_separates_regions() {
+        let mut mask = square_mask(12, 12, 0, 0, 3);
+        for (i, value) in square_mask(12, 12, 7, 7, 4).into_iter().enumerate() {
+            mask[i] |= value;
+        }
+
+        let encoding = encode_mask(&mask, 12, 12, (2.0, 2.0), &MaskEncodingOptions::default());
+
+        assert_eq!(encoding.size, [12, 12]);
+        assert_eq!(encoding.polygons.as_ref().unwrap().len(), 2);
+        approx::assert_abs_diff_eq!(encoding.area_ratio, 25.0 / 144.0, epsilon = 1e-6);
+    }
+}
@@ -0,0 +1,340 @@
+//! Memory-budgeted model residency
+//!
+//! The cache keeps the bookkeeping for every model the engine knows about:
+//! its load configuration, an estimate of the memory its backend session holds
+//! and when it was last used. With a memory budget set, loading a model that pushes the
+//! resident total over the budget evicts the least recently used models that
+//! are not pinned. With lazy loading, models are only registered when they are
+//! configured and are loaded by the first request that needs them. An evicted
+//! model is loaded again the same way.
+//!
+//! The cache only decides; the engine performs the backend loads and unloads.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ModelConfig;
+
+/// Model residency settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelCacheConfig {
+    /// Memory that loaded model sessions may hold, in MB; 0 disables eviction
+    pub memory_budget_mb: usize,
+    /// Register models when configured and load them on their first request
+    pub lazy_loading: bool,
+    /// Models (or `name@version` keys) loaded up front and never evicted
+    pub pinned: Vec<String>,
+}
+
+/// Residency of a model known to the cache
+#[derive(Debug, Clone)]
+pub enum CacheLookup {
+    /// Loaded in the backend
+    Resident,
+    /// Registered but not loaded; load it with this configuration
+    Unloaded(Box<ModelConfig>),
+    /// Not managed by the cache
+    Unknown,
+}
+
+/// Model cache load and eviction metrics
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ModelCacheMetrics {
+    pub memory_budget_mb: usize,
+    /// Estimated memory of the resident models: declared footprints, or
+    /// artifact sizes for models that do not declare one
+    pub estimated_resident_memory_mb: f64,
+    pub resident_models: usize,
+    pub registered_models: usize,
+    pub loads: u64,
+    /// Loads triggered by a request for a model that was not resident
+    pub lazy_loads: u64,
+    pub load_failures: u64,
+    pub evictions: u64,
+    pub average_load_time_ms: f64,
+    pub max_load_time_ms: f64,
+    pub last_load_time_ms: Option<f64>,
+}
+
+/// Residency of one model as reported by `ModelCache::entries`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCacheEntry {
+    pub model: String,
+    pub resident: bool,
+    pub pinned: bool,
+    /// Estimated memory of the loaded model
+    pub memory_mb: f64,
+    /// Position in the use order; higher was used more recently
+    pub last_used: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    model_config: ModelConfig,
+    resident: bool,
+    memory_mb: f64,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+    /// Logical clock advanced on every use, so ties cannot happen
+    clock: u64,
+    metrics: ModelCacheMetrics,
+}
+
+/// LRU residency tracker for backend models
+#[derive(Debug)]
+pub struct ModelCache {
+    config: ModelCacheConfig,
+    state: Mutex<CacheState>,
+}
+
+impl ModelCache {
+    pub fn new(config: ModelCacheConfig) -> Self {
+        let metrics = ModelCacheMetrics { memory_budget_mb: config.memory_budget_mb, ..Default::default() };
+        Self {
+            config,
+            state: Mutex::new(CacheState { metrics, ..Default::default() }),
+        }
+    }
+
+    pub fn config(&self) -> &ModelCacheConfig {
+        &self.config
+    }
+
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Whether a model or model version is pinned
+    ///
+    /// Pinning a model name pins all of its versions.
+    pub fn is_pinned(&self, model_key: &str) -> bool {
+        let model_name = model_key.split('@').next().unwrap_or(model_key);
+        self.config.pinned.iter().any(|pinned| pinned == model_key || pinned == model_name)
+    }
+
+    /// Whether a newly configured model should wait for its first request
+    pub fn defers_load(&self, model_key: &str) -> bool {
+        self.config.lazy_loading && !self.is_pinned(model_key)
+    }
+
+    /// Record a configured model that is not loaded yet
+    pub fn register(&self, model_key: &str, model_config: &ModelConfig) {
+        let mut state = self.state();
+        state.entries.insert(model_key.to_string(), Entry {
+            model_config: model_config.clone(),
+            resident: false,
+            memory_mb: 0.0,
+            last_used: 0,
+        });
+        state.refresh_totals();
+    }
+
+    /// Look up a model for a request, marking it as most recently used
+    pub fn touch(&self, model_key: &str) -> CacheLookup {
+        let mut state = self.state();
+        state.clock += 1;
+        let clock = state.clock;
+        match state.entries.get_mut(model_key) {
+            Some(entry) if entry.resident => {
+                entry.last_used = clock;
+                CacheLookup::Resident
+            }
+            Some(entry) => CacheLookup::Unloaded(Box::new(entry.model_config.clone())),
+            None => CacheLookup::Unknown,
+        }
+    }
+
+    /// Look up a model without marking it as used
+    pub fn peek(&self, model_key: &str) -> CacheLookup {
+        match self.state().entries.get(model_key) {
+            Some(entry) if entry.resident => CacheLookup::Resident,
+            Some(entry) => CacheLookup::Unloaded(Box::new(entry.model_config.clone())),
+            None => CacheLookup::Unknown,
+        }
+    }
+
+    /// Record a successful backend load
+    pub fn record_load(&self, model_key: &str, model_config: &ModelConfig, memory_mb: f64, load_time_ms: f64, lazy: bool) {
+        let mut state = self.state();
+        state.clock += 1;
+        let clock = state.clock;
+        state.entries.insert(model_key.to_string(), Entry {
+            model_config: model_config.clone(),
+            resident: true,
+            memory_mb,
+            last_used: clock,
+        });
+
+        let metrics = &mut state.metrics;
+        metrics.loads += 1;
+        if lazy {
+            metrics.lazy_loads += 1;
+        }
+        metrics.average_load_time_ms += (load_time_ms - metrics.average_load_time_ms) / metrics.loads as f64;
+        metrics.max_load_time_ms = metrics.max_load_time_ms.max(load_time_ms);
+        metrics.last_load_time_ms = Some(load_time_ms);
+        state.refresh_totals();
+    }
+
+    pub fn record_load_failure(&self) {
+        self.state().metrics.load_failures += 1;
+    }
+
+    /// Pick the models to unload so the resident total fits the budget
+    ///
+    /// Least recently used models go first. Pinned models and `keep` are never
+    /// chosen, so the total can stay over budget when nothing else is left.
+    /// The chosen models are marked as registered but not resident.
+    pub fn evict_for_budget(&self, keep: &str) -> Vec<String> {
+        let budget = self.config.memory_budget_mb as f64;
+        if budget <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut state = self.state();
+        let mut candidates: Vec<(u64, String)> = state.entries.iter()
+            .filter(|(key, entry)| entry.resident && key.as_str() != keep && !self.is_pinned(key))
+            .map(|(key, entry)| (entry.last_used, key.clone()))
+            .collect();
+        candidates.sort();
+
+        let mut resident_mb: f64 = state.entries.values().filter(|entry| entry.resident).map(|entry| entry.memory_mb).sum();
+        let mut evicted = Vec::new();
+        for (_, key) in candidates {
+            if resident_mb <= budget {
+                break;
+            }
+            if let Some(entry) = state.entries.get_mut(&key) {
+                entry.resident = false;
+                resident_mb -= entry.memory_mb;
+                evicted.push(key);
+            }
+        }
+
+        state.metrics.evictions += evicted.len() as u64;
+        state.refresh_totals();
+        evicted
+    }
+
+    /// Forget a model that was unloaded on request
+    ///
+    /// Returns whether the model was resident, or `None` if it was unknown.
+    pub fn remove(&self, model_key: &str) -> Option<bool> {
+        let mut state = self.state();
+        let resident = state.entries.remove(model_key).map(|entry| entry.resident);
+        state.refresh_totals();
+        resident
+    }
+
+    pub fn metrics(&self) -> ModelCacheMetrics {
+        self.state().metrics.clone()
+    }
+
+    pub fn reset_metrics(&self) {
+        let mut state = self.state();
+        state.metrics = ModelCacheMetrics { memory_budget_mb: self.config.memory_budget_mb, ..Default::default() };
+        state.refresh_totals();
+    }
+
+    /// Residency of every known model, most recently used first
+    pub fn entries(&self) -> Vec<ModelCacheEntry> {
+        let state = self.state();
+        let mut entries: Vec<ModelCacheEntry> = state.entries.iter()
+            .map(|(key, entry)| ModelCacheEntry {
+                model: key.clone(),
+                resident: entry.resident,
+                pinned: self.is_pinned(key),
+                memory_mb: entry.memory_mb,
+                last_used: entry.last_used,
+            })
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.last_used));
+        entries
+    }
+}
+
+impl CacheState {
+    fn refresh_totals(&mut self) {
+        let resident = self.entries.values().filter(|entry| entry.resident);
+        self.metrics.resident_models = resident.clone().count();
+        self.metrics.estimated_resident_memory_mb = resident.map(|entry| entry.memory_mb).sum();
+        self.metrics.registered_models = self.entries.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model_config(path: &str) -> ModelConfig {
+        ModelConfig {
+            model_path: path.to_string(),
+            model_type: "object_detection".to_string(),
+            confidence_threshold: None,
+            preprocessing: None,
+            postprocessing: None,
+            integrity: None,
+            memory_mb: None,
+        }
+    }
+
+    #[test]
+    fn test_lru_eviction_skips_pinned_and_current_model() {
+        let cache = ModelCache::new(ModelCacheConfig {
+            memory_budget_mb: 250,
+            lazy_loading: true,
+            pinned: vec!["ppe".to_string()],
+        });
+        assert!(!cache.defers_load("ppe@2.0.0"));
+        assert!(cache.defers_load("defects"));
+
+        cache.record_load("ppe@2.0.0", &model_config("ppe.onnx"), 100.0, 40.0, false);
+        cache.record_load("defects", &model_config("defects.onnx"), 100.0, 20.0, true);
+        cache.record_load("forklifts", &model_config("forklifts.onnx"), 100.0, 30.0, true);
+
+        // 300 MB resident: the pinned model and the model just loaded stay
+        assert_eq!(cache.evict_for_budget("forklifts"), vec!["defects".to_string()]);
+        assert!(matches!(cache.touch("defects"), CacheLookup::Unloaded(config) if config.model_path == "defects.onnx"));
+        assert!(matches!(cache.touch("ppe@2.0.0"), CacheLookup::Resident));
+        assert!(matches!(cache.touch("unknown"), CacheLookup::Unknown));
+
+        // Reloading `defects` pushes out `forklifts`, now the least recently used
+        cache.record_load("defects", &model_config("defects.onnx"), 100.0, 10.0, true);
+        assert_eq!(cache.evict_for_budget("defects"), vec!["forklifts".to_string()]);
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.evictions, 2);
+        assert_eq!(metrics.loads, 4);
+        assert_eq!(metrics.lazy_loads, 3);
+        assert_eq!(metrics.resident_models, 2);
+        assert_eq!(metrics.registered_models, 3);
+        assert_eq!(metrics.estimated_resident_memory_mb, 200.0);
+        assert_eq!(metrics.max_load_time_ms, 40.0);
+        assert_eq!(metrics.average_load_time_ms, 25.0);
+    }
+
+    #[test]
+    fn test_pinned_models_can_exceed_budget() {
+        let cache = ModelCache::new(ModelCacheConfig {
+            memory_budget_mb: 100,
+            lazy_loading: false,
+            pinned: vec!["a".to_string(), "b".to_string()],
+        });
+        cache.record_load("a", &model_config("a.onnx"), 80.0, 1.0, false);
+        cache.record_load("b", &model_config("b.onnx"), 80.0, 1.0, false);
+        assert!(cache.evict_for_budget("b").is_empty());
+
+        // Without a budget nothing is ever evicted
+        let unbounded = ModelCache::new(ModelCacheConfig::default());
+        unbounded.record_load("c", &model_config("c.onnx"), 1e6, 1.0, false);
+        unbounded.record_load("d", &model_config("d.onnx"), 1e6, 1.0, false);
+        assert!(unbounded.evict_for_budget("d").is_empty());
+    }
+}
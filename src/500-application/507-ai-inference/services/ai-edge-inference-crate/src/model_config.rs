@@ -6,10 +6,21 @@
 //! and configuration YAML, without code changes.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::audio::AudioFeatureConfig;
+use crate::calibration::{Calibration, ScoreCalibration};
+use crate::integrity::{ArtifactIntegrity, IntegrityError, IntegrityStatus, TrustStore};
+use crate::introspection::IntrospectionError;
+#[cfg(feature = "onnx-runtime")]
+use crate::introspection::{ModelSignature, SignatureDiscrepancy};
+use crate::pipeline::PipelineConfiguration;
+use crate::suppression::SuppressionConfig;
+use crate::tiling::TilingConfig;
+use crate::versioning::version_key;
+
 /// Errors related to model configuration
 #[derive(Error, Debug)]
 pub enum ModelConfigError {
@@ -21,6 +32,10 @@ pub enum ModelConfigError {
     InvalidConfig(String),
     #[error("Model not found: {0}")]
     ModelNotFound(String),
+    #[error("Model integrity check failed: {0}")]
+    Integrity(#[from] IntegrityError),
+    #[error("Model introspection failed: {0}")]
+    Introspection(#[from] IntrospectionError),
 }
 
 /// Top-level model configuration loaded from YAML
@@ -38,6 +53,9 @@ pub struct ModelConfiguration {
     pub postprocessing: Option<PostprocessingConfiguration>,
     /// Optional model-specific parameters
     pub parameters: Option<HashMap<String, serde_yaml::Value>>,
+    /// Optional multi-stage pipeline that starts from this or other loaded models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<PipelineConfiguration>,
 }
 
 /// Model metadata and identification
@@ -59,6 +77,12 @@ pub struct ModelMetadata {
     pub backends: Vec<String>,
     /// Model performance characteristics
     pub performance: Option<PerformanceMetrics>,
+    /// Inference deadline in milliseconds, overriding the engine-wide timeout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Expected artifact digest and optional detached signature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<ArtifactIntegrity>,
 }
 
 /// Performance metrics for the model
@@ -89,6 +113,102 @@ pub struct InputConfiguration {
     pub color_space: Option<String>,
     /// Expected input type ("image", "tensor", "audio", "text")
     pub input_type: String,
+    /// Named input tensors, for models with more than one input
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tensors: Vec<InputTensorSpec>,
+}
+
+impl InputConfiguration {
+    /// Check the named input tensors: unique names, one request input,
+    /// known dtypes and values for constants
+    pub fn validate_tensors(&self) -> Result<(), String> {
+        InputTensorSpec::validate_all(&self.tensors)
+    }
+}
+
+/// Specification for an input tensor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputTensorSpec {
+    /// Tensor name in the model graph
+    pub name: String,
+    /// Tensor shape (may contain dynamic dimensions as -1)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shape: Vec<i64>,
+    /// Data type (e.g., "float32", "int64")
+    #[serde(default = "default_input_dtype")]
+    pub dtype: String,
+    /// What feeds the tensor
+    pub semantic: InputSemantic,
+    /// Order of size and scale pairs
+    #[serde(default)]
+    pub order: SizeOrder,
+    /// Values of a constant input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Vec<f64>>,
+}
+
+fn default_input_dtype() -> String {
+    "float32".to_string()
+}
+
+impl InputTensorSpec {
+    /// Validate a model's set of input specifications
+    pub fn validate_all(specs: &[InputTensorSpec]) -> Result<(), String> {
+        if specs.is_empty() {
+            return Ok(());
+        }
+        let request_inputs = specs.iter().filter(|spec| spec.semantic == InputSemantic::Input).count();
+        if request_inputs != 1 {
+            return Err(format!("Exactly one input tensor must have semantic 'input', found {}", request_inputs));
+        }
+        for (index, spec) in specs.iter().enumerate() {
+            if spec.name.is_empty() {
+                return Err("Input tensor names cannot be empty".to_string());
+            }
+            if specs[..index].iter().any(|other| other.name == spec.name) {
+                return Err(format!("Input tensor '{}' is declared twice", spec.name));
+            }
+            spec.element_type()?;
+            if spec.semantic == InputSemantic::Constant && spec.value.as_ref().is_none_or(Vec::is_empty) {
+                return Err(format!("Constant input tensor '{}' needs a value", spec.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Element type named by `dtype`
+    pub fn element_type(&self) -> Result<crate::tensors::TensorDType, String> {
+        serde_json::from_value(serde_json::json!(self.dtype))
+            .map_err(|_| format!("Input tensor '{}' has unsupported dtype '{}'", self.name, self.dtype))
+    }
+}
+
+/// Source of an input tensor's values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputSemantic {
+    /// The preprocessed request: image, sensor window or audio features
+    #[serde(alias = "image")]
+    Input,
+    /// Original image size (e.g. `orig_target_sizes` in RT-DETR)
+    ImageSize,
+    /// Model input size (e.g. `im_shape` in PP-YOLOE)
+    InputSize,
+    /// Model input size divided by the original image size
+    ScaleFactor,
+    /// Fixed values from `value`
+    Constant,
+}
+
+/// Order of the two values in size and scale inputs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeOrder {
+    /// Height first
+    #[default]
+    Hw,
+    /// Width first
+    Wh,
 }
 
 /// Output configuration for the model
@@ -106,6 +226,15 @@ pub struct OutputConfiguration {
     pub max_detections: Option<usize>,
     /// Class labels
     pub class_labels: Option<Vec<String>>,
+    /// Duplicate suppression strategy for detection models (default: class-aware NMS)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppression: Option<SuppressionConfig>,
+    /// Mapping from raw scores to calibrated confidences
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<Calibration>,
+    /// Minimum calibrated confidence by class label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_thresholds: Option<BTreeMap<String, f32>>,
 }
 
 /// Specification for an output tensor
@@ -124,7 +253,8 @@ pub struct OutputTensorSpec {
 /// Preprocessing configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreprocessingConfiguration {
-    /// Resize strategy ("letterbox", "crop", "stretch")
+    /// Resize strategy ("letterbox", "crop", "stretch"); unused by audio models
+    #[serde(default)]
     pub resize_strategy: String,
     /// Target size for resize
     pub target_size: Option<[i64; 2]>,
@@ -132,6 +262,12 @@ pub struct PreprocessingConfiguration {
     pub normalization: Option<NormalizationConfig>,
     /// Additional preprocessing steps
     pub steps: Option<Vec<PreprocessingStep>>,
+    /// Audio resampling, segmentation and feature extraction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioFeatureConfig>,
+    /// Sliced inference over overlapping tiles for high-resolution images
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tiling: Option<TilingConfig>,
 }
 
 /// Normalization configuration
@@ -171,18 +307,38 @@ pub struct ModelConfigManager {
     pub base_dir: PathBuf,
     /// Loaded configurations
     configurations: HashMap<String, ModelConfiguration>,
+    /// Every loaded configuration keyed by `name@version`
+    versions: HashMap<String, ModelConfiguration>,
+    /// Trusted signing keys and digest/signature policy
+    trust_store: TrustStore,
+    /// Verification outcome per model name
+    integrity: HashMap<String, IntegrityStatus>,
+    /// Configurations rejected by the integrity check, with the reason
+    rejected: HashMap<String, String>,
 }
 
 impl ModelConfigManager {
     /// Create a new model configuration manager
     pub fn new(base_dir: PathBuf) -> Self {
+        Self::with_trust_store(base_dir, TrustStore::default())
+    }
+
+    /// Create a manager that verifies model artifacts against `trust_store`
+    pub fn with_trust_store(base_dir: PathBuf, trust_store: TrustStore) -> Self {
         Self {
             base_dir,
             configurations: HashMap::new(),
+            versions: HashMap::new(),
+            trust_store,
+            integrity: HashMap::new(),
+            rejected: HashMap::new(),
         }
     }
 
     /// Load a model configuration from a YAML file
+    ///
+    /// When the configuration declares `model.integrity`, the artifact digest
+    /// and signature are verified before the configuration is accepted.
     pub async fn load_config(&mut self, config_path: &str) -> Result<String, ModelConfigError> {
         let full_path = self.base_dir.join(config_path);
         let yaml_content = tokio::fs::read_to_string(&full_path).await?;
@@ -191,7 +347,19 @@ impl ModelConfigManager {
         // Validate the configuration
         self.validate_config(&config)?;
 
+        let status = match self.verify_integrity(&config, yaml_content.into_bytes()).await {
+            Ok(status) => status,
+            Err(error) => {
+                tracing::error!("Rejected model config {}: {}", config_path, error);
+                self.rejected.insert(config_path.to_string(), error.to_string());
+                return Err(error.into());
+            }
+        };
+        self.rejected.remove(config_path);
+
         let model_name = config.model.name.clone();
+        self.integrity.insert(model_name.clone(), status);
+        self.versions.insert(version_key(&model_name, &config.model.version), config.clone());
         self.configurations.insert(model_name.clone(), config);
 
         Ok(model_name)
@@ -202,6 +370,21 @@ impl ModelConfigManager {
         self.configurations.get(model_name)
     }
 
+    /// Get the configuration of a specific model version
+    pub fn get_version_config(&self, model_name: &str, version: &str) -> Option<&ModelConfiguration> {
+        self.versions.get(&version_key(model_name, version))
+    }
+
+    /// Verification outcome of a loaded model
+    pub fn integrity_status(&self, model_name: &str) -> IntegrityStatus {
+        self.integrity.get(model_name).copied().unwrap_or_default()
+    }
+
+    /// Configurations rejected by the integrity check, keyed by config path
+    pub fn rejected_configs(&self) -> &HashMap<String, String> {
+        &self.rejected
+    }
+
     /// List all loaded model names
     pub fn list_models(&self) -> Vec<String> {
         self.configurations.keys().cloned().collect()
@@ -252,6 +435,25 @@ impl ModelConfigManager {
             return Err(ModelConfigError::InvalidConfig("Output tensors cannot be empty".to_string()));
         }
 
+        config.input.validate_tensors().map_err(ModelConfigError::InvalidConfig)?;
+
+        if let Some(suppression) = &config.output.suppression {
+            let suppression = suppression.clone()
+                .with_limits(config.output.nms_threshold.unwrap_or(suppression.iou_threshold), config.output.max_detections);
+            suppression.validate().map_err(|e| ModelConfigError::InvalidConfig(e.to_string()))?;
+        }
+
+        ScoreCalibration::new(config.output.calibration, config.output.class_thresholds.clone().unwrap_or_default())
+            .map_err(|e| ModelConfigError::InvalidConfig(e.to_string()))?;
+
+        if let Some(tiling) = config.preprocessing.as_ref().and_then(|pre| pre.tiling.as_ref()) {
+            tiling.validate().map_err(|e| ModelConfigError::InvalidConfig(e.to_string()))?;
+        }
+
+        if let Some(pipeline) = &config.pipeline {
+            pipeline.validate().map_err(|e| ModelConfigError::InvalidConfig(e.to_string()))?;
+        }
+
         // Check if model file exists
         let model_path = self.base_dir.join(&config.model.path);
         if !model_path.exists() {
@@ -263,6 +465,69 @@ impl ModelConfigManager {
         Ok(())
     }
 
+    /// Check the artifact digest and signature declared by a configuration
+    async fn verify_integrity(&self, config: &ModelConfiguration, config_yaml: Vec<u8>) -> Result<IntegrityStatus, IntegrityError> {
+        let integrity = config.model.integrity.clone();
+        let signature = match integrity.as_ref().and_then(|integrity| integrity.signature.as_ref()) {
+            Some(signature_path) => Some(tokio::fs::read(self.base_dir.join(signature_path)).await?),
+            None => None,
+        };
+
+        let trust_store = self.trust_store.clone();
+        let model_name = config.model.name.clone();
+        let artifact = self.base_dir.join(&config.model.path);
+        tokio::task::spawn_blocking(move || {
+            trust_store.verify(&model_name, integrity.as_ref(), &artifact, &config_yaml, signature.as_deref())
+        })
+        .await
+        .map_err(|e| IntegrityError::Io(std::io::Error::other(e)))?
+    }
+
+    /// Draft a configuration for an ONNX model from its signature and metadata
+    ///
+    /// `model_path` is relative to the base directory, as in `model.path`. The
+    /// model name defaults to the file stem.
+    #[cfg(feature = "onnx-runtime")]
+    pub async fn draft_config(&self, model_path: &str, model_name: Option<&str>) -> Result<ModelConfiguration, ModelConfigError> {
+        let signature = self.read_signature(model_path).await?;
+        let name = model_name.map(str::to_string).unwrap_or_else(|| {
+            std::path::Path::new(model_path).file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+        });
+        let mut config = signature.draft_configuration(&name, PathBuf::from(model_path))?;
+        config.model.size_mb = tokio::fs::metadata(self.base_dir.join(model_path)).await
+            .ok()
+            .map(|m| m.len() as f32 / (1024.0 * 1024.0));
+        Ok(config)
+    }
+
+    /// Write a configuration as YAML, relative to the base directory
+    pub async fn save_config(&self, config: &ModelConfiguration, config_path: &str) -> Result<(), ModelConfigError> {
+        let yaml = serde_yaml::to_string(config)?;
+        tokio::fs::write(self.base_dir.join(config_path), yaml).await?;
+        Ok(())
+    }
+
+    /// Compare a loaded configuration with the signature of its ONNX model
+    ///
+    /// Returns every discrepancy, so all of them can be fixed before deployment.
+    #[cfg(feature = "onnx-runtime")]
+    pub async fn validate_against_model(&self, model_name: &str) -> Result<Vec<SignatureDiscrepancy>, ModelConfigError> {
+        let config = self.get_config(model_name)
+            .ok_or_else(|| ModelConfigError::ModelNotFound(model_name.to_string()))?;
+        let signature = self.read_signature(&config.model.path.to_string_lossy()).await?;
+        Ok(signature.validate(config))
+    }
+
+    /// Read an ONNX signature off the async runtime
+    #[cfg(feature = "onnx-runtime")]
+    async fn read_signature(&self, model_path: &str) -> Result<ModelSignature, ModelConfigError> {
+        let path = self.base_dir.join(model_path);
+        tokio::task::spawn_blocking(move || ModelSignature::from_onnx_file(path))
+            .await
+            .map_err(|e| IntrospectionError::Io(std::io::Error::other(e)))?
+            .map_err(ModelConfigError::from)
+    }
+
     /// Get model configuration as a summary for health endpoints
     pub fn get_model_summary(&self, model_name: &str) -> Option<ModelSummary> {
         self.get_config(model_name).map(|config| ModelSummary {
@@ -274,6 +539,7 @@ impl ModelConfigManager {
             output_count: config.output.tensors.len(),
             backends: config.model.backends.clone(),
             performance: config.model.performance.clone(),
+            integrity: self.integrity_status(model_name),
         })
     }
 
@@ -296,6 +562,7 @@ pub struct ModelSummary {
     pub output_count: usize,
     pub backends: Vec<String>,
     pub performance: Option<PerformanceMetrics>,
+    pub integrity: IntegrityStatus,
 }
 
 /// Helper function to create default configurations for common model types
@@ -319,6 +586,8 @@ pub mod defaults {
                     accuracy: Some(0.37),
                     target_hardware: Some("cpu".to_string()),
                 }),
+                timeout_ms: None,
+                integrity: None,
             },
             input: InputConfiguration {
                 shape: vec![1, 3, 640, 640],
@@ -327,6 +596,7 @@ pub mod defaults {
                 value_range: vec![0.0, 1.0],
                 color_space: Some("RGB".to_string()),
                 input_type: "image".to_string(),
+                tensors: Vec::new(),
             },
             output: OutputConfiguration {
                 tensors: vec![OutputTensorSpec {
@@ -346,6 +616,9 @@ pub mod defaults {
                     "traffic light".to_string(),
                     // Add more COCO classes as needed...
                 ]),
+                suppression: None,
+                calibration: None,
+                class_thresholds: None,
             },
             preprocessing: Some(PreprocessingConfiguration {
                 resize_strategy: "letterbox".to_string(),
@@ -356,6 +629,8 @@ pub mod defaults {
                     scale_to_unit: true,
                 }),
                 steps: None,
+                audio: None,
+                tiling: None,
             }),
             postprocessing: Some(PostprocessingConfiguration {
                 postprocess_type: "yolov8".to_string(),
@@ -363,6 +638,7 @@ pub mod defaults {
                 output_format: "detection".to_string(),
             }),
             parameters: None,
+            pipeline: None,
         }
     }
 
@@ -383,6 +659,8 @@ pub mod defaults {
                     accuracy: Some(0.72),
                     target_hardware: Some("cpu".to_string()),
                 }),
+                timeout_ms: None,
+                integrity: None,
             },
             input: InputConfiguration {
                 shape: vec![1, 3, 224, 224],
@@ -391,6 +669,7 @@ pub mod defaults {
                 value_range: vec![-1.0, 1.0],
                 color_space: Some("RGB".to_string()),
                 input_type: "image".to_string(),
+                tensors: Vec::new(),
             },
             output: OutputConfiguration {
                 tensors: vec![OutputTensorSpec {
@@ -404,6 +683,9 @@ pub mod defaults {
                 nms_threshold: None,
                 max_detections: Some(5),
                 class_labels: None, // Would load ImageNet labels
+                suppression: None,
+                calibration: None,
+                class_thresholds: None,
             },
             preprocessing: Some(PreprocessingConfiguration {
                 resize_strategy: "crop".to_string(),
@@ -414,6 +696,8 @@ pub mod defaults {
                     scale_to_unit: true,
                 }),
                 steps: None,
+                audio: None,
+                tiling: None,
             }),
             postprocessing: Some(PostprocessingConfiguration {
                 postprocess_type: "classification".to_string(),
@@ -421,6 +705,7 @@ pub mod defaults {
                 output_format: "classification".to_string(),
             }),
             parameters: None,
+            pipeline: None,
         }
     }
 }
@@ -485,4 +770,75 @@ postprocessing:
         assert_eq!(model_name, "test-model");
         assert!(manager.get_config("test-model").is_some());
     }
+
+    #[tokio::test]
+    async fn test_tampered_artifact_is_rejected() {
+        use sha2::{Digest, Sha256};
+
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().to_path_buf();
+        let digest = hex::encode(Sha256::digest(b"original weights"));
+        let config_yaml = format!(r#"
+model:
+  name: "pinned-model"
+  version: "1.0.0"
+  model_type: "image_classification"
+  description: "Pinned model"
+  path: "pinned-model.onnx"
+  backends: ["onnx"]
+  integrity:
+    sha256: "{digest}"
+
+input:
+  shape: [1, 3, 224, 224]
+  dtype: "float32"
+  format: "NCHW"
+  value_range: [0.0, 1.0]
+  input_type: "image"
+
+output:
+  tensors:
+    - name: "logits"
+      shape: [1, 1000]
+      dtype: "float32"
+      semantic: "logits"
+  postprocess_type: "classification"
+"#);
+        tokio::fs::write(base_path.join("pinned.yaml"), config_yaml).await.unwrap();
+
+        let mut manager = ModelConfigManager::new(base_path.clone());
+        tokio::fs::write(base_path.join("pinned-model.onnx"), b"original weights").await.unwrap();
+        manager.load_config("pinned.yaml").await.unwrap();
+        assert_eq!(manager.integrity_status("pinned-model"), IntegrityStatus::DigestVerified);
+
+        tokio::fs::write(base_path.join("pinned-model.onnx"), b"original wei").await.unwrap();
+        let mut manager = ModelConfigManager::new(base_path);
+        let error = manager.load_config("pinned.yaml").await.unwrap_err();
+        assert!(matches!(error, ModelConfigError::Integrity(IntegrityError::DigestMismatch { .. })));
+        assert!(manager.get_config("pinned-model").is_none());
+        assert!(manager.rejected_configs().contains_key("pinned.yaml"));
+    }
+
+    #[test]
+    fn test_input_tensors_need_one_request_input() {
+        let mut input: InputConfiguration = serde_yaml::from_str(r#"
+shape: [1, 3, 640, 640]
+dtype: "float32"
+format: "NCHW"
+value_range: [0.0, 1.0]
+input_type: "image"
+tensors:
+  - { name: images, shape: [1, 3, 640, 640], semantic: image }
+  - { name: orig_target_sizes, shape: [1, 2], dtype: int64, semantic: image_size, order: wh }
+"#).unwrap();
+        assert!(input.validate_tensors().is_ok());
+        assert_eq!(input.tensors[0].semantic, InputSemantic::Input);
+        assert_eq!(input.tensors[1].order, SizeOrder::Wh);
+
+        input.tensors[0].semantic = InputSemantic::Constant;
+        assert!(input.validate_tensors().unwrap_err().contains("semantic 'input'"));
+        input.tensors[0].semantic = InputSemantic::Input;
+        input.tensors[1].dtype = "bfloat16".to_string();
+        assert!(input.validate_tensors().unwrap_err().contains("unsupported dtype"));
+    }
 }
@@ -0,0 +1,403 @@
+//! Declarative multi-stage model pipelines
+//!
+//! A pipeline chains models inside a single request. The first stage runs on
+//! the request input. Every later stage names the stage it reads `from` and runs
+//! once per crop of that stage's detections, e.g. person detector → crop →
+//! PPE classifier. Child predictions are attached to the parent prediction
+//! under `metadata[<stage name>]`, and every bounding box in a pipeline result
+//! is `[x1, y1, x2, y2]` in source image pixels.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::Prediction;
+use crate::InferenceResult;
+
+/// Errors in a pipeline definition
+#[derive(Error, Debug)]
+pub enum PipelineError {
+    #[error("Pipeline '{0}' has no stages")]
+    Empty(String),
+    #[error("Pipeline '{pipeline}' defines stage '{stage}' more than once")]
+    DuplicateStage { pipeline: String, stage: String },
+    #[error("First stage '{stage}' of pipeline '{pipeline}' runs on the request input and cannot read from another stage")]
+    RootHasSource { pipeline: String, stage: String },
+    #[error("Stage '{stage}' of pipeline '{pipeline}' must read from an earlier stage")]
+    MissingSource { pipeline: String, stage: String },
+    #[error("Stage '{stage}' of pipeline '{pipeline}' reads from unknown or later stage '{from}'")]
+    UnknownSource { pipeline: String, stage: String, from: String },
+}
+
+/// Pipeline definition from the `pipeline` section of a model YAML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineConfiguration {
+    /// Name requests use as their `model_name`
+    pub name: String,
+    /// Stages in execution order
+    pub stages: Vec<PipelineStage>,
+}
+
+/// One model in a pipeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStage {
+    /// Stage name, used as the metadata key for its child predictions
+    pub name: String,
+    /// Model (or versioned model) the stage runs
+    pub model: String,
+    /// Stage whose detections are cropped for this stage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// How detections of the `from` stage are turned into crops
+    #[serde(default)]
+    pub crop: CropConfig,
+}
+
+/// Selection and padding of the crops fed to a stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CropConfig {
+    /// Parent classes to crop; empty crops every detection
+    #[serde(default)]
+    pub classes: Vec<String>,
+    /// Padding added on each side, as a fraction of the box width and height
+    #[serde(default = "default_padding")]
+    pub padding: f32,
+    /// Parent detections below this confidence are not cropped
+    #[serde(default)]
+    pub min_confidence: f32,
+    /// Crops narrower or shorter than this many pixels are skipped
+    #[serde(default = "default_min_size")]
+    pub min_size: u32,
+    /// Most confident detections cropped per parent run
+    #[serde(default = "default_max_crops")]
+    pub max_crops: usize,
+}
+
+fn default_padding() -> f32 {
+    0.1
+}
+
+fn default_min_size() -> u32 {
+    8
+}
+
+fn default_max_crops() -> usize {
+    32
+}
+
+impl Default for CropConfig {
+    fn default() -> Self {
+        Self {
+            classes: Vec::new(),
+            padding: default_padding(),
+            min_confidence: 0.0,
+            min_size: default_min_size(),
+            max_crops: default_max_crops(),
+        }
+    }
+}
+
+impl PipelineConfiguration {
+    /// Check that stages form a tree rooted at the first stage
+    pub fn validate(&self) -> Result<(), PipelineError> {
+        let Some(root) = self.stages.first() else {
+            return Err(PipelineError::Empty(self.name.clone()));
+        };
+        if root.from.is_some() {
+            return Err(PipelineError::RootHasSource { pipeline: self.name.clone(), stage: root.name.clone() });
+        }
+
+        let mut seen = HashSet::new();
+        for stage in &self.stages {
+            if let Some(from) = &stage.from {
+                if !seen.contains(from.as_str()) {
+                    return Err(PipelineError::UnknownSource {
+                        pipeline: self.name.clone(),
+                        stage: stage.name.clone(),
+                        from: from.clone(),
+                    });
+                }
+            } else if !seen.is_empty() {
+                return Err(PipelineError::MissingSource { pipeline: self.name.clone(), stage: stage.name.clone() });
+            }
+            if !seen.insert(stage.name.as_str()) {
+                return Err(PipelineError::DuplicateStage { pipeline: self.name.clone(), stage: stage.name.clone() });
+            }
+        }
+        Ok(())
+    }
+
+    /// Index of the stage each stage reads from (`None` for the first stage)
+    pub fn source_indices(&self) -> Vec<Option<usize>> {
+        self.stages.iter()
+            .map(|stage| stage.from.as_ref()
+                .and_then(|from| self.stages.iter().position(|candidate| &candidate.name == from)))
+            .collect()
+    }
+}
+
+/// Per-stage totals across pipeline executions
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StageMetrics {
+    /// Pipeline executions that reached this stage
+    pub executions: u64,
+    /// Model runs, one per crop after the first stage
+    pub runs: u64,
+    pub failures: u64,
+    pub predictions: u64,
+    pub total_time_ms: f64,
+    /// Mean stage wall time per execution
+    pub average_time_ms: f64,
+}
+
+/// Totals for one pipeline
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PipelineMetrics {
+    pub executions: u64,
+    pub failures: u64,
+    pub stages: HashMap<String, StageMetrics>,
+}
+
+impl PipelineMetrics {
+    pub fn record(&mut self, summaries: &[StageSummary], success: bool) {
+        self.executions += 1;
+        if !success {
+            self.failures += 1;
+        }
+        for summary in summaries {
+            let stage = self.stages.entry(summary.name.clone()).or_default();
+            stage.executions += 1;
+            stage.runs += summary.runs as u64;
+            stage.failures += summary.failures as u64;
+            stage.predictions += summary.predictions as u64;
+            stage.total_time_ms += summary.time_ms;
+            stage.average_time_ms = stage.total_time_ms / stage.executions as f64;
+        }
+    }
+}
+
+/// What one stage did for one request, reported in `metadata.pipeline.stages`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageSummary {
+    pub name: String,
+    pub model: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub predictions: usize,
+    /// Wall time of the stage; crops within a stage run concurrently
+    pub time_ms: f64,
+}
+
+/// One model run inside a stage
+#[derive(Debug, Clone)]
+pub struct StageRun {
+    /// Run and prediction index in the source stage this crop was cut from
+    pub parent: Option<(usize, usize)>,
+    pub predictions: Vec<Prediction>,
+}
+
+/// Crop rectangle `(x, y, width, height)` in source image pixels
+pub type CropRegion = (u32, u32, u32, u32);
+
+/// Pick and pad the detections of a run that a stage should crop
+///
+/// Returns the prediction index and crop rectangle, most confident first.
+pub fn crop_regions(predictions: &[Prediction], crop: &CropConfig, image_size: (u32, u32)) -> Vec<(usize, CropRegion)> {
+    let (image_width, image_height) = (image_size.0 as f32, image_size.1 as f32);
+
+    let mut candidates: Vec<(usize, &Prediction)> = predictions.iter()
+        .enumerate()
+        .filter(|(_, prediction)| prediction.bbox.is_some() && prediction.confidence >= crop.min_confidence)
+        .filter(|(_, prediction)| crop.classes.is_empty() || crop.classes.contains(&prediction.class))
+        .collect();
+    candidates.sort_by(|a, b| b.1.confidence.total_cmp(&a.1.confidence));
+
+    candidates.into_iter()
+        .filter_map(|(index, prediction)| {
+            let [x1, y1, x2, y2] = prediction.bbox?;
+            let pad_x = (x2 - x1) * crop.padding;
+            let pad_y = (y2 - y1) * crop.padding;
+            let left = (x1 - pad_x).clamp(0.0, image_width).floor();
+            let top = (y1 - pad_y).clamp(0.0, image_height).floor();
+            let right = (x2 + pad_x).clamp(0.0, image_width).ceil();
+            let bottom = (y2 + pad_y).clamp(0.0, image_height).ceil();
+
+            let (width, height) = ((right - left) as u32, (bottom - top) as u32);
+            (width >= crop.min_size.max(1) && height >= crop.min_size.max(1))
+                .then_some((index, (left as u32, top as u32, width, height)))
+        })
+        .take(crop.max_crops)
+        .collect()
+}
+
+/// Model input size `(width, height)` reported by the backend for image results
+fn result_input_size(result: &InferenceResult) -> Option<(f32, f32)> {
+    let size = result.metadata.get("input_size")?.as_array()?;
+    Some((size.first()?.as_f64()? as f32, size.get(1)?.as_f64()? as f32))
+}
+
+/// Move a result's boxes and keypoints from model input pixels into source image pixels
+///
+/// `region` is where the model's input was cut from in the source image.
+/// Without a reported `input_size` the boxes are assumed to be in region
+/// pixels already.
+pub fn to_image_coordinates(result: &mut InferenceResult, region: CropRegion) {
+    let (x, y, width, height) = region;
+    let (scale_x, scale_y) = match result_input_size(result) {
+        Some((input_width, input_height)) if input_width > 0.0 && input_height > 0.0 => {
+            (width as f32 / input_width, height as f32 / input_height)
+        }
+        _ => (1.0, 1.0),
+    };
+
+    for prediction in &mut result.predictions {
+        if let Some(bbox) = prediction.bbox.as_mut() {
+            *bbox = [
+                x as f32 + bbox[0] * scale_x,
+                y as f32 + bbox[1] * scale_y,
+                x as f32 + bbox[2] * scale_x,
+                y as f32 + bbox[3] * scale_y,
+            ];
+        }
+        for keypoint in prediction.keypoints.iter_mut().flatten() {
+            keypoint.x = x as f32 + keypoint.x * scale_x;
+            keypoint.y = y as f32 + keypoint.y * scale_y;
+        }
+    }
+}
+
+/// Attach each stage's predictions to the parent predictions they were cropped from
+///
+/// Stages are folded last to first, so grandchildren are already nested in
+/// their parents when those are attached. Returns the first stage's predictions.
+pub fn attach_children(pipeline: &PipelineConfiguration, mut runs: Vec<Vec<StageRun>>) -> Vec<Prediction> {
+    let sources = pipeline.source_indices();
+
+    for stage_index in (1..runs.len()).rev() {
+        let Some(source_index) = sources[stage_index] else {
+            continue;
+        };
+        let stage_name = &pipeline.stages[stage_index].name;
+
+        for run in std::mem::take(&mut runs[stage_index]) {
+            let Some((run_index, prediction_index)) = run.parent else {
+                continue;
+            };
+            let Some(parent) = runs[source_index].get_mut(run_index)
+                .and_then(|parent| parent.predictions.get_mut(prediction_index)) else {
+                continue;
+            };
+
+            let children = parent.metadata.entry(stage_name.clone())
+                .or_insert_with(|| serde_json::json!([]));
+            if let Some(children) = children.as_array_mut() {
+                children.extend(run.predictions.iter().filter_map(|child| serde_json::to_value(child).ok()));
+            }
+        }
+    }
+
+    runs.into_iter()
+        .next()
+        .and_then(|root| root.into_iter().next())
+        .map(|root| root.predictions)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prediction(class: &str, confidence: f32, bbox: Option<[f32; 4]>) -> Prediction {
+        Prediction {
+            class: class.to_string(),
+            confidence,
+            bbox,
+            metadata: HashMap::new(),
+            severity: None,
+            mask: None,
+            keypoints: None,
+        }
+    }
+
+    fn pipeline(yaml: &str) -> PipelineConfiguration {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    const PPE_PIPELINE: &str = r#"
+name: ppe-compliance
+stages:
+  - name: people
+    model: person-detector
+  - name: ppe
+    model: ppe-classifier
+    from: people
+    crop:
+      classes: [person]
+      padding: 0.5
+"#;
+
+    #[test]
+    fn test_validate_rejects_bad_graphs() {
+        assert!(pipeline(PPE_PIPELINE).validate().is_ok());
+
+        let forward_reference = pipeline(r#"
+name: broken
+stages:
+  - name: a
+    model: m
+  - name: b
+    model: m
+    from: c
+  - name: c
+    model: m
+    from: a
+"#);
+        assert!(matches!(forward_reference.validate(), Err(PipelineError::UnknownSource { .. })));
+
+        let second_root = pipeline("name: broken\nstages:\n  - {name: a, model: m}\n  - {name: b, model: m}\n");
+        assert!(matches!(second_root.validate(), Err(PipelineError::MissingSource { .. })));
+    }
+
+    #[test]
+    fn test_crop_regions_pad_filter_and_clamp() {
+        let crop = pipeline(PPE_PIPELINE).stages[1].crop.clone();
+        let predictions = vec![
+            prediction("person", 0.6, Some([10.0, 10.0, 30.0, 50.0])),
+            prediction("forklift", 0.9, Some([0.0, 0.0, 100.0, 100.0])),
+            prediction("person", 0.8, Some([80.0, 60.0, 100.0, 100.0])),
+        ];
+
+        let regions = crop_regions(&predictions, &crop, (100, 100));
+        assert_eq!(regions, vec![(2, (70, 40, 30, 60)), (0, (0, 0, 40, 70))]);
+    }
+
+    #[test]
+    fn test_children_attach_to_parent_boxes_in_image_pixels() {
+        let config = pipeline(PPE_PIPELINE);
+        let mut child = InferenceResult {
+            model_name: "ppe-classifier".to_string(),
+            model_type: "onnx".to_string(),
+            predictions: vec![prediction("helmet", 0.7, Some([0.0, 0.0, 32.0, 32.0]))],
+            confidence: 0.7,
+            inference_time_ms: 0.0,
+            metadata: serde_json::json!({ "input_size": [64, 64] }),
+            tensors: None,
+        };
+        to_image_coordinates(&mut child, (100, 50, 20, 40));
+        assert_eq!(child.predictions[0].bbox, Some([100.0, 50.0, 110.0, 70.0]));
+
+        let runs = vec![
+            vec![StageRun {
+                parent: None,
+                predictions: vec![prediction("person", 0.9, Some([90.0, 40.0, 130.0, 100.0]))],
+            }],
+            vec![StageRun { parent: Some((0, 0)), predictions: child.predictions }],
+        ];
+
+        let predictions = attach_children(&config, runs);
+        let children = predictions[0].metadata["ppe"].as_array().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0]["class"], "helmet");
+    }
+}
@@ -10,9 +10,12 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
 
-use crate::types::Prediction;
+use crate::calibration::ScoreCalibration;
+use crate::masks::{encode_mask, MaskEncodingOptions};
+use crate::types::{Keypoint, MaskEncoding, Prediction};
 use crate::model_config::{OutputConfiguration, PostprocessingConfiguration};
 use crate::preprocessing::PreprocessedImage;
+use crate::suppression::{ScoredBox, SuppressionConfig};
 
 /// Errors that can occur during postprocessing
 #[derive(Error, Debug)]
@@ -37,6 +40,8 @@ pub struct UniversalPostprocessor {
     /// Configuration parameters
     #[allow(dead_code)]
     config: PostprocessingConfig,
+    /// Confidence calibration and per-class thresholds
+    calibration: ScoreCalibration,
 }
 
 /// Supported postprocessing types
@@ -48,6 +53,8 @@ pub enum PostprocessorType {
     Classification(ClassificationConfig),
     /// Segmentation
     Segmentation(SegmentationConfig),
+    /// Pose estimation: detection boxes with skeleton keypoints (YOLOv8-pose)
+    Keypoints(KeypointDetectionConfig),
     /// Custom postprocessing
     Custom(CustomConfig),
 }
@@ -57,16 +64,18 @@ pub enum PostprocessorType {
 pub struct ObjectDetectionConfig {
     /// Confidence threshold for filtering detections
     pub confidence_threshold: f32,
-    /// NMS (Non-Maximum Suppression) threshold
-    pub nms_threshold: f32,
-    /// Maximum number of detections to return
-    pub max_detections: usize,
+    /// Duplicate suppression strategy, IoU threshold and detection caps
+    pub suppression: SuppressionConfig,
     /// Class labels
     pub class_labels: Vec<String>,
     /// Detection format (e.g., "yolov8", "yolov5", "ssd")
     pub detection_format: String,
     /// Number of classes
     pub num_classes: usize,
+    /// Multi-tensor decoding options for SSD-style detectors
+    pub ssd: Option<SsdConfig>,
+    /// Prototype mask decoding options for instance segmentation (YOLOv8-seg)
+    pub instance_masks: Option<SegmentationConfig>,
 }
 
 /// Configuration for image classification postprocessing
@@ -78,8 +87,45 @@ pub struct ClassificationConfig {
     pub top_k: usize,
     /// Class labels
     pub class_labels: Vec<String>,
-    /// Apply softmax to output logits
-    pub apply_softmax: bool,
+    /// Activation turning output logits into scores
+    pub activation: ScoreActivation,
+    /// Report every class whose score passes the threshold instead of the top-k of one
+    pub multi_label: bool,
+    /// Reject inputs that look like none of the known classes
+    pub open_set: Option<OpenSetConfig>,
+}
+
+/// Open-set rejection for classifiers
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenSetConfig {
+    /// Score measuring how familiar an input looks
+    pub score: OpenSetScore,
+    /// Inputs scoring below this are reported as `label`
+    pub threshold: f32,
+    /// Energy temperature
+    pub temperature: f32,
+    /// Class reported for rejected inputs
+    pub label: String,
+}
+
+/// Familiarity score computed from a classifier's raw logits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenSetScore {
+    /// Largest logit
+    MaxLogit,
+    /// Negative free energy, `temperature * logsumexp(logits / temperature)`
+    Energy,
+}
+
+/// Class picked by a classification decoder
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassScore {
+    /// `None` for an open-set rejection
+    pub class_id: Option<usize>,
+    pub class_name: String,
+    pub confidence: f32,
+    /// Open-set score of the input, when rejection is configured
+    pub open_set_score: Option<f32>,
 }
 
 /// Configuration for segmentation postprocessing
@@ -91,6 +137,36 @@ pub struct SegmentationConfig {
     pub apply_argmax: bool,
     /// Class labels
     pub class_labels: Vec<String>,
+    /// Probability a pixel needs to be part of a binary or instance mask
+    pub mask_threshold: f32,
+    /// Class id that is not reported (e.g. "background" or "floor")
+    pub background_class: Option<usize>,
+    /// Regions covering less than this fraction of the mask are dropped
+    pub min_area_ratio: f32,
+    /// Activation applied to semantic logits (single channel outputs use sigmoid for softmax)
+    pub activation: ScoreActivation,
+    /// Mask encodings to emit
+    pub encoding: MaskEncodingOptions,
+}
+
+/// Configuration for pose estimation postprocessing
+#[derive(Debug, Clone)]
+pub struct KeypointDetectionConfig {
+    /// Box decoding, thresholds and NMS for the detected instances
+    pub detection: ObjectDetectionConfig,
+    /// Layout and names of the keypoints following each box
+    pub keypoints: KeypointConfig,
+}
+
+/// Keypoint layout of a pose model output
+#[derive(Debug, Clone)]
+pub struct KeypointConfig {
+    /// Skeleton point names in output order
+    pub names: Vec<String>,
+    /// Values per keypoint: 3 for (x, y, visibility), 2 for (x, y)
+    pub dims: usize,
+    /// Keypoints with a lower visibility score are omitted
+    pub min_score: f32,
 }
 
 /// Configuration for custom postprocessing
@@ -127,6 +203,9 @@ pub struct DetectionBox {
     pub confidence: f32,
     pub class_id: usize,
     pub class_name: String,
+    /// Skeleton points for pose models, in the same space as the box
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keypoints: Vec<Keypoint>,
 }
 
 /// Segmentation mask
@@ -136,53 +215,193 @@ pub struct SegmentationMask {
     pub class_labels: Vec<String>,
 }
 
+/// Box coordinate layout for detectors that emit a dedicated boxes tensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxFormat {
+    /// [ymin, xmin, ymax, xmax] (TensorFlow Object Detection API)
+    Yxyx,
+    /// [xmin, ymin, xmax, ymax]
+    Xyxy,
+    /// [center_x, center_y, width, height]
+    Cxcywh,
+}
+
+/// Activation applied to raw per-class score tensors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreActivation {
+    None,
+    Sigmoid,
+    Softmax,
+}
+
+/// Configuration for SSD-style multi-tensor detection decoding
+#[derive(Debug, Clone)]
+pub struct SsdConfig {
+    /// Layout of the boxes tensor
+    pub box_format: BoxFormat,
+    /// Whether box coordinates are normalized to [0, 1] of the model input
+    pub normalized_coordinates: bool,
+    /// Offset subtracted from class ids (TF exports use 1-based ids)
+    pub label_offset: i64,
+    /// Column of raw score tensors that holds the background class
+    pub background_class: Option<usize>,
+    /// Activation applied to raw per-class score tensors
+    pub score_activation: ScoreActivation,
+    /// Anchors for models that emit raw box regressions instead of boxes
+    pub anchors: Option<AnchorConfig>,
+}
+
+/// Anchor boxes and box coder scale factors for raw SSD outputs
+#[derive(Debug, Clone)]
+pub struct AnchorConfig {
+    /// Anchors as normalized [center_y, center_x, height, width]
+    pub anchors: Vec<[f32; 4]>,
+    /// Box coder scale factors [y, x, h, w] (TF default: 10, 10, 5, 5)
+    pub scale_factors: [f32; 4],
+}
+
+/// Role of an output tensor in an SSD-style detector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SsdTensorRole {
+    Boxes,
+    Scores,
+    Classes,
+    NumDetections,
+}
+
+/// Borrowed SSD output tensors, flattened in row-major order
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SsdOutputs<'a> {
+    /// Boxes tensor, 4 values per detection
+    pub boxes: Option<&'a [f32]>,
+    /// Scores tensor, either 1 value per detection or 1 per class per detection
+    pub scores: Option<&'a [f32]>,
+    /// Class id tensor, 1 value per detection
+    pub classes: Option<&'a [f32]>,
+    /// Number of valid detections (TF post-processed exports)
+    pub num_detections: Option<&'a [f32]>,
+}
+
+/// Decoder shared by the YAML-driven postprocessor and the ONNX backend
+#[derive(Debug, Clone)]
+pub struct SsdDecoder {
+    config: SsdConfig,
+}
+
 impl UniversalPostprocessor {
     /// Create a new postprocessor from YAML configuration
     pub fn from_yaml_config(
         output_config: &OutputConfiguration,
         postprocessing_config: Option<&PostprocessingConfiguration>,
     ) -> Result<Self, PostprocessingError> {
+        let calibration = ScoreCalibration::new(
+            output_config.calibration,
+            output_config.class_thresholds.clone().unwrap_or_default(),
+        ).map_err(|e| PostprocessingError::ConfigError(e.to_string()))?;
+        // The configured threshold applies to calibrated confidences
+        let confidence_threshold = calibration.raw_threshold(output_config.confidence_threshold.unwrap_or(0.5));
+
         let processor_type = match output_config.postprocess_type.as_str() {
             "yolo" | "yolov8" | "yolov5" => {
                 let config = ObjectDetectionConfig {
-                    confidence_threshold: output_config.confidence_threshold.unwrap_or(0.5),
-                    nms_threshold: output_config.nms_threshold.unwrap_or(0.4),
-                    max_detections: output_config.max_detections.unwrap_or(100),
+                    confidence_threshold,
+                    suppression: suppression_config(output_config, 0.4)?,
                     class_labels: output_config.class_labels.clone().unwrap_or_default(),
                     detection_format: output_config.postprocess_type.clone(),
                     num_classes: output_config.class_labels.as_ref().map(|l| l.len()).unwrap_or(80),
+                    ssd: None,
+                    instance_masks: None,
                 };
                 PostprocessorType::ObjectDetection(config)
             },
 
-            "classification" => {
-                let config = ClassificationConfig {
-                    confidence_threshold: output_config.confidence_threshold.unwrap_or(0.1),
-                    top_k: output_config.max_detections.unwrap_or(5),
-                    class_labels: output_config.class_labels.clone().unwrap_or_default(),
-                    apply_softmax: true,
+            "yolov8-seg" | "yolov8_seg" | "instance_segmentation" => {
+                let parameters = postprocessing_config
+                    .and_then(|p| p.parameters.as_ref())
+                    .map(yaml_parameters_to_json)
+                    .unwrap_or_default();
+                let class_labels = output_config.class_labels.clone().unwrap_or_default();
+                let config = ObjectDetectionConfig {
+                    confidence_threshold,
+                    suppression: suppression_config(output_config, 0.4)?,
+                    class_labels: class_labels.clone(),
+                    detection_format: "yolov8-seg".to_string(),
+                    num_classes: output_config.class_labels.as_ref().map(|l| l.len()).unwrap_or(80),
+                    ssd: None,
+                    instance_masks: Some(SegmentationConfig::from_parameters(class_labels, &parameters)?),
                 };
-                PostprocessorType::Classification(config)
+                PostprocessorType::ObjectDetection(config)
             },
 
-            "segmentation" => {
-                let config = SegmentationConfig {
-                    num_classes: output_config.class_labels.as_ref().map(|l| l.len()).unwrap_or(1),
-                    apply_argmax: true,
+            "ssd" | "ssd_mobilenet" | "efficientdet" => {
+                let parameters = postprocessing_config
+                    .and_then(|p| p.parameters.as_ref())
+                    .map(yaml_parameters_to_json)
+                    .unwrap_or_default();
+                let config = ObjectDetectionConfig {
+                    confidence_threshold,
+                    suppression: suppression_config(output_config, 0.4)?,
                     class_labels: output_config.class_labels.clone().unwrap_or_default(),
+                    detection_format: "ssd".to_string(),
+                    num_classes: output_config.class_labels.as_ref().map(|l| l.len()).unwrap_or(90),
+                    ssd: Some(SsdConfig::from_parameters(&parameters)?),
+                    instance_masks: None,
+                };
+                PostprocessorType::ObjectDetection(config)
+            },
+
+            "yolov8-pose" | "yolov8_pose" | "pose" | "keypoints" => {
+                let parameters = postprocessing_config
+                    .and_then(|p| p.parameters.as_ref())
+                    .map(yaml_parameters_to_json)
+                    .unwrap_or_default();
+                let config = KeypointDetectionConfig {
+                    detection: ObjectDetectionConfig {
+                        confidence_threshold,
+                        suppression: suppression_config(output_config, 0.45)?,
+                        class_labels: output_config.class_labels.clone().unwrap_or_else(|| vec!["person".to_string()]),
+                        detection_format: "yolov8-pose".to_string(),
+                        num_classes: output_config.class_labels.as_ref().map(|l| l.len()).unwrap_or(1),
+                        ssd: None,
+                        instance_masks: None,
+                    },
+                    keypoints: KeypointConfig::from_parameters(&parameters)?,
                 };
+                PostprocessorType::Keypoints(config)
+            },
+
+            "classification" => {
+                let parameters = postprocessing_config
+                    .and_then(|p| p.parameters.as_ref())
+                    .map(yaml_parameters_to_json)
+                    .unwrap_or_default();
+                let config = ClassificationConfig::from_parameters(
+                    output_config.class_labels.clone().unwrap_or_default(),
+                    calibration.raw_threshold(output_config.confidence_threshold.unwrap_or(0.1)),
+                    output_config.max_detections.unwrap_or(5),
+                    ScoreActivation::Softmax,
+                    &parameters,
+                )?;
+                PostprocessorType::Classification(config)
+            },
+
+            "segmentation" | "semantic_segmentation" => {
+                let parameters = postprocessing_config
+                    .and_then(|p| p.parameters.as_ref())
+                    .map(yaml_parameters_to_json)
+                    .unwrap_or_default();
+                let config = SegmentationConfig::from_parameters(
+                    output_config.class_labels.clone().unwrap_or_default(),
+                    &parameters,
+                )?;
                 PostprocessorType::Segmentation(config)
             },
 
             _ => {
                 let config = CustomConfig {
                     parameters: postprocessing_config
-                        .and_then(|p| p.parameters.clone())
-                        .map(|params| {
-                            // Convert from serde_yaml::Value to serde_json::Value
-                            let json_str = serde_yaml::to_string(&params).unwrap_or_default();
-                            serde_json::from_str(&json_str).unwrap_or_default()
-                        })
+                        .and_then(|p| p.parameters.as_ref())
+                        .map(yaml_parameters_to_json)
                         .unwrap_or_default(),
                 };
                 PostprocessorType::Custom(config)
@@ -196,18 +415,15 @@ impl UniversalPostprocessor {
                 semantic_meaning: t.semantic.clone(),
             }).collect(),
             parameters: postprocessing_config
-                .and_then(|p| p.parameters.clone())
-                .map(|params| {
-                    // Convert from serde_yaml::Value to serde_json::Value
-                    let json_str = serde_yaml::to_string(&params).unwrap_or_default();
-                    serde_json::from_str(&json_str).unwrap_or_default()
-                })
+                .and_then(|p| p.parameters.as_ref())
+                .map(yaml_parameters_to_json)
                 .unwrap_or_default(),
         };
 
         Ok(Self {
             processor_type,
             config,
+            calibration,
         })
     }
 
@@ -217,20 +433,26 @@ impl UniversalPostprocessor {
         outputs: &[Array3<f32>],
         preprocessing_info: &PreprocessedImage,
     ) -> Result<Vec<Prediction>, PostprocessingError> {
-        match &self.processor_type {
+        let mut predictions = match &self.processor_type {
             PostprocessorType::ObjectDetection(config) => {
-                self.process_object_detection(outputs, preprocessing_info, config)
+                self.process_object_detection(outputs, preprocessing_info, config)?
             },
             PostprocessorType::Classification(config) => {
-                self.process_classification(outputs, config)
+                self.process_classification(outputs, config)?
             },
             PostprocessorType::Segmentation(config) => {
-                self.process_segmentation(outputs, config)
+                self.process_segmentation(outputs, preprocessing_info, config)?
+            },
+            PostprocessorType::Keypoints(config) => {
+                self.process_keypoints(outputs, preprocessing_info, config)?
             },
             PostprocessorType::Custom(config) => {
-                self.process_custom(outputs, config)
+                return self.process_custom(outputs, config);
             },
-        }
+        };
+
+        self.calibration.apply(&mut predictions);
+        Ok(predictions)
     }
 
     /// Process object detection outputs
@@ -244,10 +466,14 @@ impl UniversalPostprocessor {
             return Ok(Vec::new());
         }
 
+        if let Some(mask_config) = &config.instance_masks {
+            return self.process_instance_segmentation(outputs, preprocessing_info, config, mask_config);
+        }
+
         let detections = match config.detection_format.as_str() {
             "yolov8" | "yolo" => self.process_yolov8_output(&outputs[0], config)?,
             "yolov5" => self.process_yolov5_output(&outputs[0], config)?,
-            "ssd" => self.process_ssd_output(&outputs[0], config)?,
+            "ssd" => self.process_ssd_output(outputs, preprocessing_info, config)?,
             _ => {
                 return Err(PostprocessingError::UnsupportedOutputFormat(
                     format!("Unsupported detection format: {}", config.detection_format)
@@ -261,19 +487,20 @@ impl UniversalPostprocessor {
             preprocessing_info,
         );
 
-        // Apply NMS (Non-Maximum Suppression)
-        let nms_detections = self.apply_nms(converted_detections, config.nms_threshold);
+        // Suppress duplicate boxes and apply the detection caps
+        let kept_detections = self.apply_nms(converted_detections, &config.suppression);
 
         // Convert to Prediction format
-        let predictions: Vec<Prediction> = nms_detections
+        let predictions: Vec<Prediction> = kept_detections
             .into_iter()
-            .take(config.max_detections)
             .map(|det| Prediction {
                 class: det.class_name,
                 confidence: det.confidence,
                 bbox: Some([det.x1, det.y1, det.x2, det.y2]),
                 severity: Some("medium".to_string()), // Default severity
                 metadata: HashMap::new(),
+                mask: None,
+                keypoints: None,
             })
             .collect();
 
@@ -344,6 +571,7 @@ impl UniversalPostprocessor {
                     confidence: max_conf,
                     class_id: max_class,
                     class_name,
+                    keypoints: Vec::new(),
                 });
             }
         }
@@ -361,17 +589,58 @@ impl UniversalPostprocessor {
         self.process_yolov8_output(output, config)
     }
 
-    /// Process SSD style output (different format)
+    /// Process SSD style output (separate boxes/scores/classes/num_detections tensors)
+    ///
+    /// Tensors are matched to their role through the `semantic` (or `name`) of the
+    /// output spec at the same position, falling back to the TF Object Detection API
+    /// order when no spec is configured.
     fn process_ssd_output(
         &self,
-        _output: &Array3<f32>,
-        _config: &ObjectDetectionConfig,
+        outputs: &[Array3<f32>],
+        preprocessing_info: &PreprocessedImage,
+        config: &ObjectDetectionConfig,
     ) -> Result<Vec<DetectionBox>, PostprocessingError> {
-        // TODO: Implement SSD-specific postprocessing
-        // SSD typically has separate outputs for boxes, scores, and classes
-        Err(PostprocessingError::UnsupportedOutputFormat(
-            "SSD postprocessing not yet implemented".to_string()
-        ))
+        const DEFAULT_ROLES: [SsdTensorRole; 4] = [
+            SsdTensorRole::Boxes,
+            SsdTensorRole::Scores,
+            SsdTensorRole::Classes,
+            SsdTensorRole::NumDetections,
+        ];
+
+        let ssd_config = config.ssd.clone().unwrap_or_default();
+
+        let flattened: Vec<Vec<f32>> = outputs.iter()
+            .map(|output| output.iter().copied().collect())
+            .collect();
+
+        let mut ssd_outputs = SsdOutputs::default();
+        for (index, data) in flattened.iter().enumerate() {
+            let role = match self.config.output_tensors.get(index) {
+                Some(spec) => SsdTensorRole::resolve(&spec.semantic_meaning, &spec.name),
+                None => DEFAULT_ROLES.get(index).copied(),
+            };
+            if let Some(role) = role {
+                ssd_outputs.insert(role, data);
+            }
+        }
+
+        // tensor_shape is always reported as [N, C, H, W]
+        let input_size = match preprocessing_info.tensor_shape.as_slice() {
+            [_, _, h, w] => (*w as f32, *h as f32),
+            other => {
+                return Err(PostprocessingError::InvalidTensorShape {
+                    expected: vec![1, 3, 0, 0],
+                    actual: other.to_vec(),
+                });
+            }
+        };
+
+        SsdDecoder::new(ssd_config).decode(
+            &ssd_outputs,
+            input_size,
+            config.confidence_threshold,
+            &config.class_labels,
+        )
     }
 
     /// Process classification outputs
@@ -408,70 +677,238 @@ impl UniversalPostprocessor {
             });
         };
 
-        // Apply softmax if requested
-        let probabilities = if config.apply_softmax {
-            self.apply_softmax_vec(&logits_vec)
-        } else {
-            logits_vec
-        };
-
-        // Get top-k predictions
-        let mut predictions = Vec::new();
-        let mut indexed_probs: Vec<(usize, f32)> = probabilities
-            .iter()
-            .enumerate()
-            .map(|(i, &prob)| (i, prob))
+        let predictions = config.decode(&logits_vec)
+            .into_iter()
+            .map(|score| Prediction {
+                class: score.class_name,
+                confidence: score.confidence,
+                bbox: None, // No bounding box for classification
+                severity: Some("info".to_string()), // Default severity
+                metadata: score.open_set_score
+                    .map(|open_set_score| HashMap::from([("open_set_score".to_string(), serde_json::json!(open_set_score))]))
+                    .unwrap_or_default(),
+                mask: None,
+                keypoints: None,
+            })
             .collect();
 
-        // Sort by confidence descending
-        indexed_probs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(predictions)
+    }
+
+    /// Process YOLOv8-seg style outputs
+    ///
+    /// Expects the detections tensor (1, num_boxes, 4+num_classes+num_masks) followed by
+    /// the prototype masks (num_masks, mask_height, mask_width).
+    fn process_instance_segmentation(
+        &self,
+        outputs: &[Array3<f32>],
+        preprocessing_info: &PreprocessedImage,
+        config: &ObjectDetectionConfig,
+        mask_config: &SegmentationConfig,
+    ) -> Result<Vec<Prediction>, PostprocessingError> {
+        if outputs.len() < 2 {
+            return Err(PostprocessingError::UnsupportedOutputFormat(
+                "Instance segmentation needs detection and prototype mask outputs".to_string()
+            ));
+        }
+
+        let output = &outputs[0];
+        let (num_masks, mask_height, mask_width) = outputs[1].dim();
+        let shape = output.shape();
+        if shape[0] != 1 || shape[2] <= 4 + num_masks {
+            return Err(PostprocessingError::InvalidTensorShape {
+                expected: vec![1, 8400, 4 + config.num_classes + num_masks],
+                actual: shape.to_vec(),
+            });
+        }
+        let num_classes = shape[2] - 4 - num_masks;
+
+        let mut candidates = Vec::new();
+        let mut coefficients = Vec::new();
+        for box_idx in 0..shape[1] {
+            let (best_class, best_conf) = (0..num_classes)
+                .map(|c| (c, output[[0, box_idx, 4 + c]]))
+                .fold((0, 0.0f32), |best, cur| if cur.1 > best.1 { cur } else { best });
+
+            if best_conf < config.confidence_threshold {
+                continue;
+            }
+
+            let cx = output[[0, box_idx, 0]];
+            let cy = output[[0, box_idx, 1]];
+            let w = output[[0, box_idx, 2]];
+            let h = output[[0, box_idx, 3]];
 
-        // Take top-k above threshold
-        for (class_idx, confidence) in indexed_probs.into_iter().take(config.top_k) {
-            if confidence >= config.confidence_threshold {
-                let class_name = config.class_labels.get(class_idx)
+            candidates.push(DetectionBox {
+                x1: cx - w / 2.0,
+                y1: cy - h / 2.0,
+                x2: cx + w / 2.0,
+                y2: cy + h / 2.0,
+                confidence: best_conf,
+                class_id: best_class,
+                class_name: config.class_labels.get(best_class)
                     .cloned()
-                    .unwrap_or_else(|| format!("class_{}", class_idx));
+                    .unwrap_or_else(|| format!("class_{}", best_class)),
+                keypoints: Vec::new(),
+            });
+            coefficients.push(
+                (0..num_masks).map(|k| output[[0, box_idx, 4 + num_classes + k]]).collect::<Vec<f32>>()
+            );
+        }
 
-                predictions.push(Prediction {
-                    class: class_name,
-                    confidence,
-                    bbox: None, // No bounding box for classification
-                    severity: Some("info".to_string()), // Default severity
+        let protos: Vec<f32> = outputs[1].iter().copied().collect();
+        let input_size = Self::input_size(preprocessing_info);
+        let decoder = SegmentationDecoder::new(mask_config.clone());
+
+        let predictions = self.suppress(&candidates, &config.suppression)
+            .into_iter()
+            .map(|(index, det)| {
+                let mask = decoder
+                    .decode_instance_mask(
+                        &coefficients[index],
+                        &protos,
+                        (num_masks, mask_height, mask_width),
+                        [det.x1, det.y1, det.x2, det.y2],
+                        input_size,
+                    )
+                    .map(|mask| self.convert_mask_to_original(mask, preprocessing_info));
+                let det = self.convert_coordinates_to_original(vec![det], preprocessing_info).remove(0);
+
+                Prediction {
+                    class: det.class_name,
+                    confidence: det.confidence,
+                    bbox: Some([det.x1, det.y1, det.x2, det.y2]),
+                    severity: Some("medium".to_string()),
                     metadata: HashMap::new(),
-                });
+                    mask,
+                    keypoints: None,
+                }
+            })
+            .collect();
+
+        Ok(predictions)
+    }
+
+    /// Process pose estimation outputs (1, num_boxes, 4+num_classes+num_keypoints*dims)
+    ///
+    /// Boxes go through NMS like plain detections; each kept box carries its
+    /// named keypoints mapped back to the original image.
+    fn process_keypoints(
+        &self,
+        outputs: &[Array3<f32>],
+        preprocessing_info: &PreprocessedImage,
+        config: &KeypointDetectionConfig,
+    ) -> Result<Vec<Prediction>, PostprocessingError> {
+        let Some(output) = outputs.first() else {
+            return Ok(Vec::new());
+        };
+        let detection = &config.detection;
+        let keypoint_values = config.keypoints.values_per_detection();
+        let shape = output.shape();
+        if shape[0] != 1 || shape[2] <= 4 + keypoint_values {
+            return Err(PostprocessingError::InvalidTensorShape {
+                expected: vec![1, 8400, 4 + detection.num_classes + keypoint_values],
+                actual: shape.to_vec(),
+            });
+        }
+        let num_classes = shape[2] - 4 - keypoint_values;
+
+        let mut detections = Vec::new();
+        for box_idx in 0..shape[1] {
+            let (best_class, best_conf) = (0..num_classes)
+                .map(|c| (c, output[[0, box_idx, 4 + c]]))
+                .fold((0, 0.0f32), |best, cur| if cur.1 > best.1 { cur } else { best });
+
+            if best_conf < detection.confidence_threshold {
+                continue;
             }
+
+            let cx = output[[0, box_idx, 0]];
+            let cy = output[[0, box_idx, 1]];
+            let w = output[[0, box_idx, 2]];
+            let h = output[[0, box_idx, 3]];
+
+            detections.push(DetectionBox {
+                x1: cx - w / 2.0,
+                y1: cy - h / 2.0,
+                x2: cx + w / 2.0,
+                y2: cy + h / 2.0,
+                confidence: best_conf,
+                class_id: best_class,
+                class_name: detection.class_labels.get(best_class)
+                    .cloned()
+                    .unwrap_or_else(|| format!("class_{}", best_class)),
+                keypoints: config.keypoints.decode(|k| output[[0, box_idx, 4 + num_classes + k]]),
+            });
         }
 
+        let detections = self.convert_coordinates_to_original(detections, preprocessing_info);
+
+        let predictions = self.apply_nms(detections, &detection.suppression)
+            .into_iter()
+            .map(|det| Prediction {
+                class: det.class_name,
+                confidence: det.confidence,
+                bbox: Some([det.x1, det.y1, det.x2, det.y2]),
+                severity: Some("medium".to_string()),
+                metadata: HashMap::new(),
+                mask: None,
+                keypoints: Some(det.keypoints),
+            })
+            .collect();
+
         Ok(predictions)
     }
 
-    /// Process segmentation outputs
+    /// Process semantic segmentation outputs (channels, height, width)
     fn process_segmentation(
         &self,
         outputs: &[Array3<f32>],
+        preprocessing_info: &PreprocessedImage,
         config: &SegmentationConfig,
     ) -> Result<Vec<Prediction>, PostprocessingError> {
         if outputs.is_empty() {
             return Ok(Vec::new());
         }
 
-        // For now, return a simple segmentation result
-        // Full segmentation postprocessing would be more complex
-        let prediction = Prediction {
-            class: "segmentation".to_string(),
-            confidence: 1.0,
-            bbox: None,
-            severity: Some("info".to_string()),
-            metadata: {
-                let mut meta = HashMap::new();
-                meta.insert("output_type".to_string(), serde_json::Value::String("segmentation".to_string()));
-                meta.insert("num_classes".to_string(), serde_json::Value::Number(config.num_classes.into()));
-                meta
-            },
-        };
+        let output = &outputs[0];
+        let logits: Vec<f32> = output.iter().copied().collect();
+        let regions = SegmentationDecoder::new(config.clone())
+            .decode_semantic(&logits, output.dim(), Self::input_size(preprocessing_info))?;
 
-        Ok(vec![prediction])
+        let predictions = regions
+            .into_iter()
+            .map(|region| {
+                let [x1, y1, x2, y2] = region.bbox;
+                let bbox = self.convert_coordinates_to_original(
+                    vec![DetectionBox {
+                        x1, y1, x2, y2,
+                        confidence: region.confidence,
+                        class_id: region.class_id,
+                        class_name: String::new(),
+                        keypoints: Vec::new(),
+                    }],
+                    preprocessing_info,
+                ).remove(0);
+
+                Prediction {
+                    class: region.class_name,
+                    confidence: region.confidence,
+                    bbox: Some([bbox.x1, bbox.y1, bbox.x2, bbox.y2]),
+                    severity: Some("info".to_string()),
+                    metadata: {
+                        let mut meta = HashMap::new();
+                        meta.insert("output_type".to_string(), serde_json::Value::String("segmentation".to_string()));
+                        meta.insert("class_index".to_string(), serde_json::Value::Number(region.class_id.into()));
+                        meta
+                    },
+                    mask: Some(self.convert_mask_to_original(region.mask, preprocessing_info)),
+                    keypoints: None,
+                }
+            })
+            .collect();
+
+        Ok(predictions)
     }
 
     /// Process custom outputs
@@ -487,6 +924,8 @@ impl UniversalPostprocessor {
             bbox: None,
             severity: Some("info".to_string()),
             metadata: HashMap::new(),
+            mask: None,
+            keypoints: None,
         };
 
         Ok(vec![prediction])
@@ -523,69 +962,67 @@ impl UniversalPostprocessor {
                 det.x2 = det.x2.max(0.0).min(orig_w as f32);
                 det.y2 = det.y2.max(0.0).min(orig_h as f32);
 
+                for keypoint in &mut det.keypoints {
+                    keypoint.x = ((keypoint.x - pad_left as f32) / scale_x).max(0.0).min(orig_w as f32);
+                    keypoint.y = ((keypoint.y - pad_top as f32) / scale_y).max(0.0).min(orig_h as f32);
+                }
+
                 det
             })
             .collect()
     }
 
-    /// Apply Non-Maximum Suppression to filter overlapping detections
-    fn apply_nms(&self, mut detections: Vec<DetectionBox>, nms_threshold: f32) -> Vec<DetectionBox> {
-        if detections.is_empty() {
-            return detections;
-        }
-
-        // Sort by confidence descending
-        detections.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
-
-        let mut keep = Vec::new();
-        let mut suppressed = vec![false; detections.len()];
-
-        for i in 0..detections.len() {
-            if suppressed[i] {
-                continue;
-            }
-
-            keep.push(detections[i].clone());
-
-            for j in (i + 1)..detections.len() {
-                if suppressed[j] {
-                    continue;
-                }
+    /// Polygon vertices are model input pixels; map them back like detection boxes
+    fn convert_mask_to_original(&self, mut mask: MaskEncoding, preprocessing_info: &PreprocessedImage) -> MaskEncoding {
+        let (scale_x, scale_y) = preprocessing_info.scale_factors;
+        let (pad_left, pad_top, _, _) = preprocessing_info.padding;
+        let (orig_w, orig_h) = preprocessing_info.original_size;
 
-                // Only suppress detections of the same class
-                if detections[i].class_id == detections[j].class_id {
-                    let iou = self.calculate_iou(&detections[i], &detections[j]);
-                    if iou > nms_threshold {
-                        suppressed[j] = true;
-                    }
-                }
+        for polygon in mask.polygons.iter_mut().flatten() {
+            for point in polygon.chunks_exact_mut(2) {
+                point[0] = ((point[0] - pad_left as f32) / scale_x).max(0.0).min(orig_w as f32);
+                point[1] = ((point[1] - pad_top as f32) / scale_y).max(0.0).min(orig_h as f32);
             }
         }
 
-        keep
+        mask
     }
 
-    /// Calculate Intersection over Union (IoU) between two boxes
-    fn calculate_iou(&self, box1: &DetectionBox, box2: &DetectionBox) -> f32 {
-        let x1 = box1.x1.max(box2.x1);
-        let y1 = box1.y1.max(box2.y1);
-        let x2 = box1.x2.min(box2.x2);
-        let y2 = box1.y2.min(box2.y2);
+    /// Model input (width, height) from the preprocessed tensor shape [N, C, H, W]
+    fn input_size(preprocessing_info: &PreprocessedImage) -> (f32, f32) {
+        let shape = &preprocessing_info.tensor_shape;
+        (shape[3] as f32, shape[2] as f32)
+    }
 
-        if x2 <= x1 || y2 <= y1 {
-            return 0.0;
-        }
+    /// Suppress overlapping detections and apply the detection caps
+    fn apply_nms(&self, detections: Vec<DetectionBox>, suppression: &SuppressionConfig) -> Vec<DetectionBox> {
+        self.suppress(&detections, suppression)
+            .into_iter()
+            .map(|(_, det)| det)
+            .collect()
+    }
 
-        let intersection = (x2 - x1) * (y2 - y1);
-        let area1 = (box1.x2 - box1.x1) * (box1.y2 - box1.y1);
-        let area2 = (box2.x2 - box2.x1) * (box2.y2 - box2.y1);
-        let union = area1 + area2 - intersection;
+    /// Detections kept by suppression with the index they came from, highest confidence first
+    ///
+    /// Kept boxes carry their Soft-NMS confidence and WBF-fused coordinates.
+    fn suppress(&self, detections: &[DetectionBox], suppression: &SuppressionConfig) -> Vec<(usize, DetectionBox)> {
+        let boxes: Vec<ScoredBox> = detections.iter()
+            .map(|det| ScoredBox {
+                bbox: [det.x1, det.y1, det.x2, det.y2],
+                confidence: det.confidence,
+                class_id: det.class_id,
+            })
+            .collect();
 
-        if union <= 0.0 {
-            0.0
-        } else {
-            intersection / union
-        }
+        suppression.suppress(&boxes)
+            .into_iter()
+            .map(|kept| {
+                let mut det = detections[kept.index].clone();
+                [det.x1, det.y1, det.x2, det.y2] = kept.bbox;
+                det.confidence = kept.confidence;
+                (kept.index, det)
+            })
+            .collect()
     }
 
     /// Apply softmax activation to 2D array
@@ -596,71 +1033,999 @@ impl UniversalPostprocessor {
         let sum_exp = exp_logits.sum();
         exp_logits / sum_exp
     }
+}
 
-    /// Apply softmax activation to Vec<f32>
-    fn apply_softmax_vec(&self, logits: &[f32]) -> Vec<f32> {
-        let max_val = logits.iter().fold(f32::NEG_INFINITY, |acc, &x| acc.max(x));
-        let exp_logits: Vec<f32> = logits.iter().map(|&x| (x - max_val).exp()).collect();
-        let sum_exp: f32 = exp_logits.iter().sum();
-        exp_logits.into_iter().map(|x| x / sum_exp).collect()
+/// Suppression settings of a detection model's output configuration
+fn suppression_config(output_config: &OutputConfiguration, default_iou_threshold: f32) -> Result<SuppressionConfig, PostprocessingError> {
+    let suppression = output_config.suppression.clone().unwrap_or_default().with_limits(
+        output_config.nms_threshold.unwrap_or(default_iou_threshold),
+        Some(output_config.max_detections.unwrap_or(100)),
+    );
+    suppression.validate().map_err(|e| PostprocessingError::ConfigError(e.to_string()))?;
+    Ok(suppression)
+}
+
+/// Convert YAML postprocessing parameters to the JSON map used by the postprocessors
+fn yaml_parameters_to_json(
+    params: &HashMap<String, serde_yaml::Value>,
+) -> HashMap<String, serde_json::Value> {
+    params.iter()
+        .filter_map(|(key, value)| serde_json::to_value(value).ok().map(|v| (key.clone(), v)))
+        .collect()
+}
+
+impl Default for SsdConfig {
+    fn default() -> Self {
+        Self {
+            box_format: BoxFormat::Yxyx,
+            normalized_coordinates: true,
+            label_offset: 0,
+            background_class: None,
+            score_activation: ScoreActivation::None,
+            anchors: None,
+        }
     }
 }
 
-/// Create preset postprocessors for common model types
-pub mod presets {
-    use super::*;
+impl SsdConfig {
+    /// Parse SSD options from postprocessing parameters
+    ///
+    /// Recognized keys: `box_format` ("yxyx", "xyxy", "cxcywh"), `normalized_coordinates`,
+    /// `label_offset`, `background_class`, `score_activation` ("none", "sigmoid", "softmax"),
+    /// `scale_factors`, and either inline `anchors` or an `anchor_generator` section.
+    pub fn from_parameters(
+        parameters: &HashMap<String, serde_json::Value>,
+    ) -> Result<Self, PostprocessingError> {
+        let mut config = Self::default();
+
+        if let Some(format) = parameters.get("box_format").and_then(|v| v.as_str()) {
+            config.box_format = match format.to_ascii_lowercase().as_str() {
+                "yxyx" => BoxFormat::Yxyx,
+                "xyxy" => BoxFormat::Xyxy,
+                "cxcywh" => BoxFormat::Cxcywh,
+                other => {
+                    return Err(PostprocessingError::InvalidParameter(
+                        format!("Unknown box_format: {}", other)
+                    ));
+                }
+            };
+        }
 
-    /// YOLOv8 object detection postprocessor
-    pub fn yolov8_coco() -> UniversalPostprocessor {
-        let config = ObjectDetectionConfig {
-            confidence_threshold: 0.5,
-            nms_threshold: 0.4,
-            max_detections: 100,
-            class_labels: coco_labels(),
-            detection_format: "yolov8".to_string(),
-            num_classes: 80,
-        };
+        if let Some(normalized) = parameters.get("normalized_coordinates").and_then(|v| v.as_bool()) {
+            config.normalized_coordinates = normalized;
+        }
 
-        UniversalPostprocessor {
-            processor_type: PostprocessorType::ObjectDetection(config),
-            config: PostprocessingConfig {
-                output_tensors: vec![OutputTensorSpec {
-                    name: "output0".to_string(),
-                    expected_shape: vec![1, 84, 8400],
-                    semantic_meaning: "detections".to_string(),
-                }],
-                parameters: HashMap::new(),
-            },
+        if let Some(offset) = parameters.get("label_offset").and_then(|v| v.as_i64()) {
+            config.label_offset = offset;
         }
-    }
 
-    /// ImageNet classification postprocessor
-    pub fn imagenet_classification() -> UniversalPostprocessor {
-        let config = ClassificationConfig {
-            confidence_threshold: 0.1,
-            top_k: 5,
-            class_labels: Vec::new(), // Would load ImageNet labels in practice
-            apply_softmax: true,
-        };
+        if let Some(background) = parameters.get("background_class").and_then(|v| v.as_u64()) {
+            config.background_class = Some(background as usize);
+        }
 
-        UniversalPostprocessor {
-            processor_type: PostprocessorType::Classification(config),
-            config: PostprocessingConfig {
-                output_tensors: vec![OutputTensorSpec {
-                    name: "output".to_string(),
-                    expected_shape: vec![1, 1000],
-                    semantic_meaning: "classification".to_string(),
-                }],
-                parameters: HashMap::new(),
-            },
+        if let Some(activation) = parameters.get("score_activation").and_then(|v| v.as_str()) {
+            config.score_activation = parse_score_activation(activation, "score_activation")?;
         }
+
+        let scale_factors = match parameters.get("scale_factors") {
+            Some(value) => {
+                let factors = parse_f32_array(value, "scale_factors")?;
+                <[f32; 4]>::try_from(factors.as_slice()).map_err(|_| {
+                    PostprocessingError::InvalidParameter("scale_factors must have 4 elements".to_string())
+                })?
+            },
+            None => [10.0, 10.0, 5.0, 5.0],
+        };
+
+        let anchors = if let Some(value) = parameters.get("anchors") {
+            let rows = value.as_array().ok_or_else(|| {
+                PostprocessingError::InvalidParameter("anchors must be an array".to_string())
+            })?;
+            let mut anchors = Vec::with_capacity(rows.len());
+            for row in rows {
+                let values = parse_f32_array(row, "anchors")?;
+                anchors.push(<[f32; 4]>::try_from(values.as_slice()).map_err(|_| {
+                    PostprocessingError::InvalidParameter(
+                        "each anchor must be [center_y, center_x, height, width]".to_string()
+                    )
+                })?);
+            }
+            Some(anchors)
+        } else if let Some(generator) = parameters.get("anchor_generator") {
+            Some(generate_ssd_anchors(generator)?)
+        } else {
+            None
+        };
+
+        config.anchors = anchors.map(|anchors| AnchorConfig { anchors, scale_factors });
+
+        Ok(config)
     }
+}
 
-    /// COCO dataset class labels
-    fn coco_labels() -> Vec<String> {
-        vec![
-            "person", "bicycle", "car", "motorcycle", "airplane", "bus", "train", "truck", "boat",
-            "traffic light", "fire hydrant", "stop sign", "parking meter", "bench", "bird", "cat",
+/// Parse an activation name ("none", "sigmoid", "softmax")
+fn parse_score_activation(value: &str, name: &str) -> Result<ScoreActivation, PostprocessingError> {
+    match value.to_ascii_lowercase().as_str() {
+        "none" => Ok(ScoreActivation::None),
+        "sigmoid" => Ok(ScoreActivation::Sigmoid),
+        "softmax" => Ok(ScoreActivation::Softmax),
+        other => Err(PostprocessingError::InvalidParameter(
+            format!("Unknown {}: {}", name, other)
+        )),
+    }
+}
+
+/// Parse a JSON array of numbers
+fn parse_f32_array(value: &serde_json::Value, name: &str) -> Result<Vec<f32>, PostprocessingError> {
+    value.as_array()
+        .ok_or_else(|| PostprocessingError::InvalidParameter(format!("{} must be an array", name)))?
+        .iter()
+        .map(|v| v.as_f64().map(|f| f as f32).ok_or_else(|| {
+            PostprocessingError::InvalidParameter(format!("{} must contain only numbers", name))
+        }))
+        .collect()
+}
+
+/// Generate SSD anchors following the TF Object Detection API `ssd_anchor_generator`
+///
+/// Expects `feature_map_sizes` ([[h, w], ...]), `min_scale`, `max_scale` and `aspect_ratios`.
+/// Anchors are emitted per layer, row-major over the grid, with aspect ratios innermost.
+fn generate_ssd_anchors(generator: &serde_json::Value) -> Result<Vec<[f32; 4]>, PostprocessingError> {
+    let feature_map_sizes: Vec<(usize, usize)> = generator.get("feature_map_sizes")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| PostprocessingError::MissingParameter("anchor_generator.feature_map_sizes".to_string()))?
+        .iter()
+        .map(|size| {
+            let dims = parse_f32_array(size, "feature_map_sizes")?;
+            match dims.as_slice() {
+                [h, w] if *h >= 1.0 && *w >= 1.0 => Ok((*h as usize, *w as usize)),
+                _ => Err(PostprocessingError::InvalidParameter(
+                    "feature_map_sizes entries must be [height, width]".to_string()
+                )),
+            }
+        })
+        .collect::<Result<_, _>>()?;
+
+    let min_scale = generator.get("min_scale").and_then(|v| v.as_f64()).unwrap_or(0.2) as f32;
+    let max_scale = generator.get("max_scale").and_then(|v| v.as_f64()).unwrap_or(0.95) as f32;
+    let aspect_ratios = match generator.get("aspect_ratios") {
+        Some(value) => parse_f32_array(value, "aspect_ratios")?,
+        None => vec![1.0, 2.0, 0.5, 3.0, 1.0 / 3.0],
+    };
+
+    let num_layers = feature_map_sizes.len();
+    if num_layers == 0 {
+        return Err(PostprocessingError::InvalidParameter(
+            "anchor_generator.feature_map_sizes cannot be empty".to_string()
+        ));
+    }
+
+    // One scale per layer plus a final 1.0 used for the interpolated anchor
+    let mut scales: Vec<f32> = (0..num_layers)
+        .map(|i| {
+            if num_layers == 1 {
+                min_scale
+            } else {
+                min_scale + (max_scale - min_scale) * i as f32 / (num_layers - 1) as f32
+            }
+        })
+        .collect();
+    scales.push(1.0);
+
+    let mut anchors = Vec::new();
+    for (layer, &(grid_h, grid_w)) in feature_map_sizes.iter().enumerate() {
+        let scale = scales[layer];
+        let mut shapes: Vec<(f32, f32)> = aspect_ratios.iter()
+            .map(|&ratio| (scale / ratio.sqrt(), scale * ratio.sqrt()))
+            .collect();
+        let interpolated = (scale * scales[layer + 1]).sqrt();
+        shapes.push((interpolated, interpolated));
+
+        for y in 0..grid_h {
+            for x in 0..grid_w {
+                let center_y = (y as f32 + 0.5) / grid_h as f32;
+                let center_x = (x as f32 + 0.5) / grid_w as f32;
+                for &(height, width) in &shapes {
+                    anchors.push([center_y, center_x, height, width]);
+                }
+            }
+        }
+    }
+
+    Ok(anchors)
+}
+
+impl SsdTensorRole {
+    /// Resolve a tensor role from its configured semantic, falling back to its name
+    pub fn resolve(semantic: &str, name: &str) -> Option<Self> {
+        Self::from_label(semantic).or_else(|| Self::from_label(name))
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        let label = label.to_ascii_lowercase();
+
+        // TF exports also carry anchor indices, multiclass scores and raw
+        // (pre-NMS) tensors that must not shadow the final outputs
+        if label.contains("multiclass") || label.contains("raw") || label.contains("anchor") {
+            return None;
+        }
+
+        if label.contains("num") {
+            Some(Self::NumDetections)
+        } else if label.contains("box") {
+            Some(Self::Boxes)
+        } else if label.contains("score") {
+            Some(Self::Scores)
+        } else if label.contains("class") || label.contains("label") {
+            Some(Self::Classes)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> SsdOutputs<'a> {
+    /// Assign a flattened tensor to a role; the first tensor for a role wins
+    pub fn insert(&mut self, role: SsdTensorRole, data: &'a [f32]) {
+        let slot = match role {
+            SsdTensorRole::Boxes => &mut self.boxes,
+            SsdTensorRole::Scores => &mut self.scores,
+            SsdTensorRole::Classes => &mut self.classes,
+            SsdTensorRole::NumDetections => &mut self.num_detections,
+        };
+        if slot.is_none() {
+            *slot = Some(data);
+        }
+    }
+}
+
+impl SsdDecoder {
+    /// Create a decoder from its configuration
+    pub fn new(config: SsdConfig) -> Self {
+        Self { config }
+    }
+
+    /// Decode SSD outputs into boxes in model input pixel space
+    ///
+    /// `input_size` is the model input (width, height), used to scale normalized
+    /// coordinates. NMS is left to the caller.
+    pub fn decode(
+        &self,
+        outputs: &SsdOutputs<'_>,
+        input_size: (f32, f32),
+        confidence_threshold: f32,
+        class_labels: &[String],
+    ) -> Result<Vec<DetectionBox>, PostprocessingError> {
+        let boxes = outputs.boxes
+            .ok_or_else(|| PostprocessingError::MissingParameter("SSD boxes tensor".to_string()))?;
+        let scores = outputs.scores
+            .ok_or_else(|| PostprocessingError::MissingParameter("SSD scores tensor".to_string()))?;
+
+        if boxes.len() % 4 != 0 {
+            return Err(PostprocessingError::InvalidTensorShape {
+                expected: vec![boxes.len() / 4, 4],
+                actual: vec![boxes.len()],
+            });
+        }
+        let total_boxes = boxes.len() / 4;
+        if total_boxes == 0 {
+            return Ok(Vec::new());
+        }
+
+        // Per-detection scores (post-processed export) or per-class scores (raw head)
+        let num_score_columns = if scores.len() == total_boxes {
+            1
+        } else if scores.len() % total_boxes == 0 {
+            scores.len() / total_boxes
+        } else {
+            return Err(PostprocessingError::InvalidTensorShape {
+                expected: vec![total_boxes],
+                actual: vec![scores.len()],
+            });
+        };
+
+        if let Some(anchor_config) = &self.config.anchors {
+            if anchor_config.anchors.len() != total_boxes {
+                return Err(PostprocessingError::ConfigError(format!(
+                    "{} anchors configured but model produced {} boxes",
+                    anchor_config.anchors.len(),
+                    total_boxes,
+                )));
+            }
+        }
+
+        let valid_boxes = outputs.num_detections
+            .and_then(|n| n.first())
+            .map(|&n| (n.max(0.0) as usize).min(total_boxes))
+            .unwrap_or(total_boxes);
+
+        // Anchor-decoded boxes are always normalized since anchors are
+        let normalized = self.config.normalized_coordinates || self.config.anchors.is_some();
+        let (scale_x, scale_y) = if normalized { input_size } else { (1.0, 1.0) };
+
+        let mut detections = Vec::new();
+        let mut row_scores = vec![0.0f32; num_score_columns];
+
+        for index in 0..valid_boxes {
+            let (raw_class, confidence) = if num_score_columns == 1 {
+                let class = outputs.classes
+                    .and_then(|classes| classes.get(index))
+                    .map(|&c| c.round() as i64)
+                    .unwrap_or(0);
+                (class, self.activate_single(scores[index]))
+            } else {
+                row_scores.copy_from_slice(&scores[index * num_score_columns..(index + 1) * num_score_columns]);
+                self.activate_row(&mut row_scores);
+                match self.best_class(&row_scores) {
+                    Some((column, score)) => (column as i64, score),
+                    None => continue,
+                }
+            };
+
+            if confidence < confidence_threshold {
+                continue;
+            }
+
+            let class_id = raw_class - self.config.label_offset;
+            if class_id < 0 {
+                continue;
+            }
+            let class_id = class_id as usize;
+
+            let raw_box = [
+                boxes[index * 4],
+                boxes[index * 4 + 1],
+                boxes[index * 4 + 2],
+                boxes[index * 4 + 3],
+            ];
+            let (x1, y1, x2, y2) = match &self.config.anchors {
+                Some(anchor_config) => self.decode_with_anchor(raw_box, anchor_config, index),
+                None => self.to_corners(raw_box),
+            };
+
+            let class_name = class_labels.get(class_id)
+                .cloned()
+                .unwrap_or_else(|| format!("class_{}", class_id));
+
+            detections.push(DetectionBox {
+                x1: x1 * scale_x,
+                y1: y1 * scale_y,
+                x2: x2 * scale_x,
+                y2: y2 * scale_y,
+                confidence,
+                class_id,
+                class_name,
+                keypoints: Vec::new(),
+            });
+        }
+
+        Ok(detections)
+    }
+
+    /// Convert a box in the configured layout to (x1, y1, x2, y2)
+    fn to_corners(&self, raw: [f32; 4]) -> (f32, f32, f32, f32) {
+        match self.config.box_format {
+            BoxFormat::Yxyx => (raw[1], raw[0], raw[3], raw[2]),
+            BoxFormat::Xyxy => (raw[0], raw[1], raw[2], raw[3]),
+            BoxFormat::Cxcywh => (
+                raw[0] - raw[2] / 2.0,
+                raw[1] - raw[3] / 2.0,
+                raw[0] + raw[2] / 2.0,
+                raw[1] + raw[3] / 2.0,
+            ),
+        }
+    }
+
+    /// Decode a box regression against its anchor (TF `FasterRcnnBoxCoder`)
+    ///
+    /// Regressions are read as [ty, tx, th, tw] for `Yxyx` and [tx, ty, tw, th] otherwise.
+    fn decode_with_anchor(
+        &self,
+        raw: [f32; 4],
+        anchor_config: &AnchorConfig,
+        index: usize,
+    ) -> (f32, f32, f32, f32) {
+        let [anchor_cy, anchor_cx, anchor_h, anchor_w] = anchor_config.anchors[index];
+        let [scale_y, scale_x, scale_h, scale_w] = anchor_config.scale_factors;
+
+        let (ty, tx, th, tw) = match self.config.box_format {
+            BoxFormat::Yxyx => (raw[0], raw[1], raw[2], raw[3]),
+            BoxFormat::Xyxy | BoxFormat::Cxcywh => (raw[1], raw[0], raw[3], raw[2]),
+        };
+
+        let center_y = ty / scale_y * anchor_h + anchor_cy;
+        let center_x = tx / scale_x * anchor_w + anchor_cx;
+        let height = (th / scale_h).exp() * anchor_h;
+        let width = (tw / scale_w).exp() * anchor_w;
+
+        (
+            center_x - width / 2.0,
+            center_y - height / 2.0,
+            center_x + width / 2.0,
+            center_y + height / 2.0,
+        )
+    }
+
+    fn activate_single(&self, score: f32) -> f32 {
+        match self.config.score_activation {
+            ScoreActivation::Sigmoid => sigmoid(score),
+            ScoreActivation::None | ScoreActivation::Softmax => score,
+        }
+    }
+
+    fn activate_row(&self, row: &mut [f32]) {
+        match self.config.score_activation {
+            ScoreActivation::None => {},
+            ScoreActivation::Sigmoid => row.iter_mut().for_each(|v| *v = sigmoid(*v)),
+            ScoreActivation::Softmax => {
+                let max_val = row.iter().fold(f32::NEG_INFINITY, |acc, &x| acc.max(x));
+                let mut sum = 0.0;
+                for value in row.iter_mut() {
+                    *value = (*value - max_val).exp();
+                    sum += *value;
+                }
+                if sum > 0.0 {
+                    row.iter_mut().for_each(|v| *v /= sum);
+                }
+            },
+        }
+    }
+
+    /// Best non-background column of a score row
+    fn best_class(&self, row: &[f32]) -> Option<(usize, f32)> {
+        row.iter()
+            .enumerate()
+            .filter(|(column, _)| Some(*column) != self.config.background_class)
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(column, &score)| (column, score))
+    }
+}
+
+impl SegmentationConfig {
+    /// Parse segmentation options from postprocessing parameters
+    ///
+    /// Recognized keys: `apply_argmax`, `mask_threshold`, `background_class`,
+    /// `min_area_ratio`, `activation` ("none", "sigmoid", "softmax"), `mask_encoding`
+    /// (any of "rle", "polygon") and `polygon_epsilon`.
+    pub fn from_parameters(
+        class_labels: Vec<String>,
+        parameters: &HashMap<String, serde_json::Value>,
+    ) -> Result<Self, PostprocessingError> {
+        let mut config = Self {
+            num_classes: class_labels.len().max(1),
+            apply_argmax: parameters.get("apply_argmax").and_then(|v| v.as_bool()).unwrap_or(true),
+            class_labels,
+            mask_threshold: 0.5,
+            background_class: None,
+            min_area_ratio: 0.0,
+            activation: ScoreActivation::Softmax,
+            encoding: MaskEncodingOptions::default(),
+        };
+
+        if let Some(threshold) = parameters.get("mask_threshold").and_then(|v| v.as_f64()) {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(PostprocessingError::InvalidParameter(
+                    format!("mask_threshold must be in [0, 1], got {}", threshold)
+                ));
+            }
+            config.mask_threshold = threshold as f32;
+        }
+
+        if let Some(background) = parameters.get("background_class").and_then(|v| v.as_u64()) {
+            config.background_class = Some(background as usize);
+        }
+
+        if let Some(ratio) = parameters.get("min_area_ratio").and_then(|v| v.as_f64()) {
+            config.min_area_ratio = ratio as f32;
+        }
+
+        if let Some(activation) = parameters.get("activation").and_then(|v| v.as_str()) {
+            config.activation = parse_score_activation(activation, "activation")?;
+        }
+
+        if let Some(encodings) = parameters.get("mask_encoding") {
+            let names: Vec<&str> = match encodings {
+                serde_json::Value::String(name) => vec![name.as_str()],
+                serde_json::Value::Array(names) => names.iter().filter_map(|v| v.as_str()).collect(),
+                _ => {
+                    return Err(PostprocessingError::InvalidParameter(
+                        "mask_encoding must be a string or a list of strings".to_string()
+                    ));
+                }
+            };
+
+            config.encoding.rle = false;
+            config.encoding.polygons = false;
+            for name in names {
+                match name.to_ascii_lowercase().as_str() {
+                    "rle" => config.encoding.rle = true,
+                    "polygon" | "polygons" => config.encoding.polygons = true,
+                    other => {
+                        return Err(PostprocessingError::InvalidParameter(
+                            format!("Unknown mask_encoding: {}", other)
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(epsilon) = parameters.get("polygon_epsilon").and_then(|v| v.as_f64()) {
+            config.encoding.polygon_epsilon = epsilon as f32;
+        }
+
+        Ok(config)
+    }
+}
+
+/// COCO skeleton used by YOLOv8-pose and most person keypoint models
+pub const COCO_KEYPOINT_NAMES: [&str; 17] = [
+    "nose", "left_eye", "right_eye", "left_ear", "right_ear",
+    "left_shoulder", "right_shoulder", "left_elbow", "right_elbow",
+    "left_wrist", "right_wrist", "left_hip", "right_hip",
+    "left_knee", "right_knee", "left_ankle", "right_ankle",
+];
+
+impl Default for KeypointConfig {
+    fn default() -> Self {
+        Self {
+            names: COCO_KEYPOINT_NAMES.iter().map(|name| name.to_string()).collect(),
+            dims: 3,
+            min_score: 0.0,
+        }
+    }
+}
+
+impl ClassificationConfig {
+    /// Parse classification options from postprocessing parameters
+    ///
+    /// Recognized keys: `multi_label`, `activation` ("none", "sigmoid",
+    /// "softmax"; defaults to sigmoid for multi-label models and to
+    /// `single_label_activation` otherwise) and an `open_set` section with
+    /// `score` ("max_logit" or "energy"), `threshold`, `temperature` and `label`.
+    /// Per-label cuts are the calibration's `class_thresholds`.
+    pub fn from_parameters(
+        class_labels: Vec<String>,
+        confidence_threshold: f32,
+        top_k: usize,
+        single_label_activation: ScoreActivation,
+        parameters: &HashMap<String, serde_json::Value>,
+    ) -> Result<Self, PostprocessingError> {
+        let multi_label = parameters.get("multi_label").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let activation = match parameters.get("activation").and_then(|v| v.as_str()) {
+            Some(activation) => parse_score_activation(activation, "activation")?,
+            None if multi_label => ScoreActivation::Sigmoid,
+            None => single_label_activation,
+        };
+        if multi_label && activation == ScoreActivation::Softmax {
+            return Err(PostprocessingError::InvalidParameter(
+                "Multi-label models score classes independently; use sigmoid or none activation".to_string()
+            ));
+        }
+
+        let open_set = parameters.get("open_set")
+            .filter(|v| !v.is_null())
+            .map(OpenSetConfig::from_parameters)
+            .transpose()?;
+
+        Ok(Self {
+            confidence_threshold,
+            top_k,
+            class_labels,
+            activation,
+            multi_label,
+            open_set,
+        })
+    }
+
+    /// Decode one image's logits into classes, most confident first
+    ///
+    /// Single-label models report the top-k classes above `confidence_threshold`;
+    /// multi-label models report every class above it, capped at top-k. With
+    /// open-set rejection, an input scoring below the open-set threshold is
+    /// reported as a single unknown class whose confidence is one minus the
+    /// best class score.
+    pub fn decode(&self, logits: &[f32]) -> Vec<ClassScore> {
+        let scores = match self.activation {
+            ScoreActivation::None => logits.to_vec(),
+            ScoreActivation::Sigmoid => logits.iter().map(|&x| sigmoid(x)).collect(),
+            ScoreActivation::Softmax => softmax(logits),
+        };
+
+        let open_set_score = self.open_set.as_ref().map(|open_set| open_set.score(logits));
+        if let (Some(open_set), Some(score)) = (&self.open_set, open_set_score) {
+            if score < open_set.threshold {
+                let best = scores.iter().fold(0.0f32, |best, &score| best.max(score));
+                return vec![ClassScore {
+                    class_id: None,
+                    class_name: open_set.label.clone(),
+                    confidence: (1.0 - best).clamp(0.0, 1.0),
+                    open_set_score,
+                }];
+            }
+        }
+
+        let mut ranked: Vec<(usize, f32)> = scores.into_iter().enumerate().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranked.into_iter()
+            .map(|(class_id, confidence)| (class_id, self.class_name(class_id), confidence))
+            .filter(|(_, _, confidence)| *confidence >= self.confidence_threshold)
+            .take(self.top_k)
+            .map(|(class_id, class_name, confidence)| ClassScore {
+                class_id: Some(class_id),
+                class_name,
+                confidence,
+                open_set_score,
+            })
+            .collect()
+    }
+
+    fn class_name(&self, class_id: usize) -> String {
+        self.class_labels.get(class_id)
+            .cloned()
+            .unwrap_or_else(|| format!("class_{}", class_id))
+    }
+}
+
+impl OpenSetConfig {
+    fn from_parameters(value: &serde_json::Value) -> Result<Self, PostprocessingError> {
+        let score = match value.get("score").and_then(|v| v.as_str()).unwrap_or("energy") {
+            "max_logit" => OpenSetScore::MaxLogit,
+            "energy" => OpenSetScore::Energy,
+            other => {
+                return Err(PostprocessingError::InvalidParameter(format!("Unknown open_set score: {}", other)));
+            }
+        };
+        let threshold = value.get("threshold").and_then(|v| v.as_f64())
+            .ok_or_else(|| PostprocessingError::MissingParameter("open_set.threshold".to_string()))? as f32;
+        let temperature = value.get("temperature").and_then(|v| v.as_f64()).unwrap_or(1.0) as f32;
+        if temperature.is_nan() || temperature <= 0.0 {
+            return Err(PostprocessingError::InvalidParameter(
+                format!("open_set.temperature must be positive, got {}", temperature)
+            ));
+        }
+        let label = value.get("label").and_then(|v| v.as_str()).unwrap_or("unknown").to_string();
+        Ok(Self { score, threshold, temperature, label })
+    }
+
+    /// Familiarity of an input; higher means more like the training classes
+    pub fn score(&self, logits: &[f32]) -> f32 {
+        let max_logit = logits.iter().fold(f32::NEG_INFINITY, |acc, &x| acc.max(x));
+        match self.score {
+            OpenSetScore::MaxLogit => max_logit,
+            OpenSetScore::Energy => {
+                // logsumexp shifted by the max logit so large logits stay finite
+                let t = self.temperature;
+                let sum: f32 = logits.iter().map(|&x| ((x - max_logit) / t).exp()).sum();
+                max_logit + t * sum.ln()
+            }
+        }
+    }
+}
+
+impl KeypointConfig {
+    /// Parse keypoint options from postprocessing parameters
+    ///
+    /// Recognized keys: `keypoint_names` (defaults to the 17 COCO points),
+    /// `num_keypoints` (names beyond the list become `keypoint_<i>`),
+    /// `keypoint_dims` (2 or 3) and `min_keypoint_score`.
+    pub fn from_parameters(parameters: &HashMap<String, serde_json::Value>) -> Result<Self, PostprocessingError> {
+        let mut config = Self::default();
+
+        if let Some(names) = parameters.get("keypoint_names").and_then(|v| v.as_array()) {
+            config.names = names.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect();
+        }
+
+        if let Some(count) = parameters.get("num_keypoints").and_then(|v| v.as_u64()) {
+            let count = count as usize;
+            config.names.truncate(count);
+            config.names.extend((config.names.len()..count).map(|i| format!("keypoint_{}", i)));
+        }
+
+        if config.names.is_empty() {
+            return Err(PostprocessingError::InvalidParameter("Pose models need at least one keypoint".to_string()));
+        }
+
+        if let Some(dims) = parameters.get("keypoint_dims").and_then(|v| v.as_u64()) {
+            if !(2..=3).contains(&dims) {
+                return Err(PostprocessingError::InvalidParameter(
+                    format!("keypoint_dims must be 2 or 3, got {}", dims)
+                ));
+            }
+            config.dims = dims as usize;
+        }
+
+        if let Some(score) = parameters.get("min_keypoint_score").and_then(|v| v.as_f64()) {
+            config.min_score = score as f32;
+        }
+
+        Ok(config)
+    }
+
+    /// Number of output values holding the keypoints of one detection
+    pub fn values_per_detection(&self) -> usize {
+        self.names.len() * self.dims
+    }
+
+    /// Decode one detection's keypoints
+    ///
+    /// `value(i)` returns the i-th keypoint value of the detection, laid out as
+    /// `x, y[, visibility]` per keypoint. Coordinates stay in model input pixels.
+    pub fn decode(&self, value: impl Fn(usize) -> f32) -> Vec<Keypoint> {
+        self.names.iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let base = index * self.dims;
+                Keypoint {
+                    name: name.clone(),
+                    x: value(base),
+                    y: value(base + 1),
+                    score: if self.dims == 3 { value(base + 2) } else { 1.0 },
+                }
+            })
+            .filter(|keypoint| keypoint.score >= self.min_score)
+            .collect()
+    }
+}
+
+/// Region of a single class decoded from a semantic segmentation output
+#[derive(Debug, Clone)]
+pub struct SegmentedRegion {
+    pub class_id: usize,
+    pub class_name: String,
+    /// Mean per-pixel confidence over the region
+    pub confidence: f32,
+    /// Bounding box [x1, y1, x2, y2] in model input pixels
+    pub bbox: [f32; 4],
+    pub mask: MaskEncoding,
+}
+
+/// Mask decoder shared by the YAML-driven postprocessor and the ONNX backend
+#[derive(Debug, Clone)]
+pub struct SegmentationDecoder {
+    config: SegmentationConfig,
+}
+
+impl SegmentationDecoder {
+    pub fn new(config: SegmentationConfig) -> Self {
+        Self { config }
+    }
+
+    /// Decode a semantic segmentation output into one region per class present
+    ///
+    /// `output` is a row-major `[channels, height, width]` tensor. Multi-channel outputs
+    /// are reduced with a per-pixel argmax; single channel outputs are either a
+    /// foreground probability map or, with `apply_argmax: false`, a class id map.
+    pub fn decode_semantic(
+        &self,
+        output: &[f32],
+        shape: (usize, usize, usize),
+        input_size: (f32, f32),
+    ) -> Result<Vec<SegmentedRegion>, PostprocessingError> {
+        let (channels, height, width) = shape;
+        let plane = height * width;
+        if channels == 0 || plane == 0 || output.len() < channels * plane {
+            return Err(PostprocessingError::InvalidTensorShape {
+                expected: vec![channels, height, width],
+                actual: vec![output.len()],
+            });
+        }
+
+        // Per-pixel class id (None for unlabeled pixels) and confidence
+        let mut classes: Vec<Option<usize>> = vec![None; plane];
+        let mut scores = vec![0.0f32; plane];
+
+        if channels == 1 && !self.config.apply_argmax {
+            for (pixel, &value) in output[..plane].iter().enumerate() {
+                if value >= 0.0 {
+                    classes[pixel] = Some(value.round() as usize);
+                    scores[pixel] = 1.0;
+                }
+            }
+        } else if channels == 1 {
+            for (pixel, &value) in output[..plane].iter().enumerate() {
+                let probability = match self.config.activation {
+                    ScoreActivation::None => value,
+                    ScoreActivation::Sigmoid | ScoreActivation::Softmax => sigmoid(value),
+                };
+                if probability >= self.config.mask_threshold {
+                    classes[pixel] = Some(0);
+                    scores[pixel] = probability;
+                }
+            }
+        } else {
+            for pixel in 0..plane {
+                let mut best = (0usize, f32::NEG_INFINITY);
+                for channel in 0..channels {
+                    let value = output[channel * plane + pixel];
+                    if value > best.1 {
+                        best = (channel, value);
+                    }
+                }
+
+                scores[pixel] = match self.config.activation {
+                    ScoreActivation::None => best.1,
+                    ScoreActivation::Sigmoid => sigmoid(best.1),
+                    ScoreActivation::Softmax => {
+                        let sum: f32 = (0..channels)
+                            .map(|channel| (output[channel * plane + pixel] - best.1).exp())
+                            .sum();
+                        1.0 / sum
+                    },
+                };
+                classes[pixel] = Some(best.0);
+            }
+        }
+
+        let scale = (input_size.0 / width as f32, input_size.1 / height as f32);
+        let num_classes = classes.iter().flatten().max().map(|&c| c + 1).unwrap_or(0);
+        let mut regions = Vec::new();
+
+        for class_id in 0..num_classes {
+            if Some(class_id) == self.config.background_class {
+                continue;
+            }
+
+            let mask: Vec<bool> = classes.iter().map(|&c| c == Some(class_id)).collect();
+            let mut count = 0usize;
+            let mut score_sum = 0.0f32;
+            let (mut min_x, mut min_y, mut max_x, mut max_y) = (width, height, 0usize, 0usize);
+            for (pixel, _) in mask.iter().enumerate().filter(|(_, &set)| set) {
+                let (x, y) = (pixel % width, pixel / width);
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+                count += 1;
+                score_sum += scores[pixel];
+            }
+
+            if count == 0 || (count as f32 / plane as f32) < self.config.min_area_ratio {
+                continue;
+            }
+
+            regions.push(SegmentedRegion {
+                class_id,
+                class_name: self.config.class_labels.get(class_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("class_{}", class_id)),
+                confidence: score_sum / count as f32,
+                bbox: [
+                    min_x as f32 * scale.0,
+                    min_y as f32 * scale.1,
+                    (max_x + 1) as f32 * scale.0,
+                    (max_y + 1) as f32 * scale.1,
+                ],
+                mask: encode_mask(&mask, width, height, scale, &self.config.encoding),
+            });
+        }
+
+        Ok(regions)
+    }
+
+    /// Decode the mask of one YOLOv8-seg style detection from prototype masks
+    ///
+    /// `protos` is a row-major `[num_masks, height, width]` tensor and `bbox` is
+    /// [x1, y1, x2, y2] in model input pixels. The mask is cropped to the box and
+    /// encoded on the prototype grid; polygons are scaled to model input pixels.
+    /// Returns `None` when the coefficients do not match or no pixel passes the threshold.
+    pub fn decode_instance_mask(
+        &self,
+        coefficients: &[f32],
+        protos: &[f32],
+        proto_shape: (usize, usize, usize),
+        bbox: [f32; 4],
+        input_size: (f32, f32),
+    ) -> Option<MaskEncoding> {
+        let (num_masks, height, width) = proto_shape;
+        let plane = height * width;
+        if coefficients.len() != num_masks || protos.len() < num_masks * plane || plane == 0 {
+            return None;
+        }
+
+        let to_grid = (width as f32 / input_size.0, height as f32 / input_size.1);
+        let x1 = ((bbox[0] * to_grid.0).floor().max(0.0) as usize).min(width);
+        let y1 = ((bbox[1] * to_grid.1).floor().max(0.0) as usize).min(height);
+        let x2 = ((bbox[2] * to_grid.0).ceil().max(0.0) as usize).min(width);
+        let y2 = ((bbox[3] * to_grid.1).ceil().max(0.0) as usize).min(height);
+
+        let mut mask = vec![false; plane];
+        let mut any = false;
+        for y in y1..y2 {
+            for x in x1..x2 {
+                let pixel = y * width + x;
+                let logit: f32 = coefficients.iter()
+                    .enumerate()
+                    .map(|(k, &c)| c * protos[k * plane + pixel])
+                    .sum();
+                if sigmoid(logit) >= self.config.mask_threshold {
+                    mask[pixel] = true;
+                    any = true;
+                }
+            }
+        }
+
+        if !any {
+            return None;
+        }
+
+        let scale = (1.0 / to_grid.0, 1.0 / to_grid.1);
+        Some(encode_mask(&mask, width, height, scale, &self.config.encoding))
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn softmax(logits: &[f32]) -> Vec<f32> {
+    let max_val = logits.iter().fold(f32::NEG_INFINITY, |acc, &x| acc.max(x));
+    let exp_logits: Vec<f32> = logits.iter().map(|&x| (x - max_val).exp()).collect();
+    let sum_exp: f32 = exp_logits.iter().sum();
+    exp_logits.into_iter().map(|x| x / sum_exp).collect()
+}
+
+/// Create preset postprocessors for common model types
+pub mod presets {
+    use super::*;
+
+    /// YOLOv8 object detection postprocessor
+    pub fn yolov8_coco() -> UniversalPostprocessor {
+        let config = ObjectDetectionConfig {
+            confidence_threshold: 0.5,
+            suppression: SuppressionConfig::default().with_limits(0.4, Some(100)),
+            class_labels: coco_labels(),
+            detection_format: "yolov8".to_string(),
+            num_classes: 80,
+            ssd: None,
+            instance_masks: None,
+        };
+
+        UniversalPostprocessor {
+            processor_type: PostprocessorType::ObjectDetection(config),
+            config: PostprocessingConfig {
+                output_tensors: vec![OutputTensorSpec {
+                    name: "output0".to_string(),
+                    expected_shape: vec![1, 84, 8400],
+                    semantic_meaning: "detections".to_string(),
+                }],
+                parameters: HashMap::new(),
+            },
+            calibration: ScoreCalibration::default(),
+        }
+    }
+
+    /// ImageNet classification postprocessor
+    pub fn imagenet_classification() -> UniversalPostprocessor {
+        let config = ClassificationConfig {
+            confidence_threshold: 0.1,
+            top_k: 5,
+            class_labels: Vec::new(), // Would load ImageNet labels in practice
+            activation: ScoreActivation::Softmax,
+            multi_label: false,
+            open_set: None,
+        };
+
+        UniversalPostprocessor {
+            processor_type: PostprocessorType::Classification(config),
+            config: PostprocessingConfig {
+                output_tensors: vec![OutputTensorSpec {
+                    name: "output".to_string(),
+                    expected_shape: vec![1, 1000],
+                    semantic_meaning: "classification".to_string(),
+                }],
+                parameters: HashMap::new(),
+            },
+            calibration: ScoreCalibration::default(),
+        }
+    }
+
+    /// COCO dataset class labels
+    fn coco_labels() -> Vec<String> {
+        vec![
+            "person", "bicycle", "car", "motorcycle", "airplane", "bus", "train", "truck", "boat",
+            "traffic light", "fire hydrant", "stop sign", "parking meter", "bench", "bird", "cat",
             "dog", "horse", "sheep", "cow", "elephant", "bear", "zebra", "giraffe", "backpack",
             "umbrella", "handbag", "tie", "suitcase", "frisbee", "skis", "snowboard", "sports ball",
             "kite", "baseball bat", "baseball glove", "skateboard", "surfboard", "tennis racket",
@@ -676,6 +2041,7 @@ pub mod presets {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::calibration::Calibration;
     use ndarray::{Array3, Array4};
 
     #[test]
@@ -710,6 +2076,46 @@ mod tests {
         assert!(predictions[0].bbox.is_some());
     }
 
+    #[test]
+    fn test_confidence_threshold_applies_to_calibrated_scores() {
+        let output_config = OutputConfiguration {
+            tensors: vec![],
+            postprocess_type: "yolov8".to_string(),
+            confidence_threshold: Some(0.5),
+            nms_threshold: Some(0.5),
+            max_detections: Some(10),
+            class_labels: Some(vec!["person".to_string(), "forklift".to_string()]),
+            suppression: None,
+            calibration: Some(Calibration::Platt { a: 1.0, b: 1.0 }),
+            class_thresholds: Some([("forklift".to_string(), 0.7)].into()),
+        };
+        let postprocessor = UniversalPostprocessor::from_yaml_config(&output_config, None).unwrap();
+
+        // Raw 0.4 calibrates to 0.64: below the threshold raw, above it calibrated;
+        // the forklift's 0.64 still misses its class threshold
+        let mut output = Array3::<f32>::zeros((1, 3, 6));
+        for (row, class, confidence) in [(0, 0, 0.4), (1, 1, 0.4), (2, 0, 0.2)] {
+            output[[0, row, 0]] = 100.0 + 200.0 * row as f32;
+            output[[0, row, 1]] = 100.0;
+            output[[0, row, 2]] = 50.0;
+            output[[0, row, 3]] = 50.0;
+            output[[0, row, 4 + class]] = confidence;
+        }
+        let preprocessing_info = PreprocessedImage {
+            tensor: Array4::zeros((1, 3, 640, 640)),
+            original_size: (640, 640),
+            tensor_shape: vec![1, 3, 640, 640],
+            scale_factors: (1.0, 1.0),
+            padding: (0, 0, 0, 0),
+        };
+
+        let predictions = postprocessor.process(&[output], &preprocessing_info).unwrap();
+
+        assert_eq!(predictions.len(), 1);
+        assert_eq!(predictions[0].class, "person");
+        approx::assert_abs_diff_eq!(predictions[0].confidence, 0.6439, epsilon = 1e-3);
+    }
+
     #[test]
     fn test_classification_postprocessing() {
         let postprocessor = presets::imagenet_classification();
@@ -734,4 +2140,289 @@ mod tests {
         // The highest confidence prediction should be first
         assert!(predictions[0].confidence > predictions.get(1).map_or(0.0, |p| p.confidence));
     }
+
+    #[test]
+    fn test_multi_label_classification_uses_class_thresholds() {
+        let output_config = OutputConfiguration {
+            tensors: vec![],
+            postprocess_type: "classification".to_string(),
+            confidence_threshold: Some(0.5),
+            nms_threshold: None,
+            max_detections: Some(5),
+            class_labels: Some(vec!["person".to_string(), "helmet".to_string(), "vest".to_string()]),
+            suppression: None,
+            calibration: None,
+            class_thresholds: Some([("vest".to_string(), 0.9)].into()),
+        };
+        let postprocessing_config = PostprocessingConfiguration {
+            postprocess_type: "classification".to_string(),
+            parameters: Some(HashMap::from([("multi_label".to_string(), serde_yaml::Value::from(true))])),
+            output_format: "classification".to_string(),
+        };
+        let postprocessor = UniversalPostprocessor::from_yaml_config(&output_config, Some(&postprocessing_config)).unwrap();
+        let PostprocessorType::Classification(config) = &postprocessor.processor_type else {
+            panic!("expected a classification postprocessor");
+        };
+        assert_eq!(config.activation, ScoreActivation::Sigmoid);
+
+        // sigmoid: person 0.95, helmet 0.88, vest 0.82 (below its own 0.9)
+        let mut output = Array3::<f32>::zeros((1, 3, 1));
+        for (class_id, logit) in [3.0, 2.0, 1.5].into_iter().enumerate() {
+            output[[0, class_id, 0]] = logit;
+        }
+        let preprocessing_info = PreprocessedImage {
+            tensor: Array4::zeros((1, 3, 224, 224)),
+            original_size: (224, 224),
+            tensor_shape: vec![1, 3, 224, 224],
+            scale_factors: (1.0, 1.0),
+            padding: (0, 0, 0, 0),
+        };
+        let predictions = postprocessor.process(&[output], &preprocessing_info).unwrap();
+        let classes: Vec<&str> = predictions.iter().map(|p| p.class.as_str()).collect();
+        assert_eq!(classes, vec!["person", "helmet"]);
+
+        // Multi-label models score classes independently
+        let softmax = HashMap::from([
+            ("multi_label".to_string(), serde_json::json!(true)),
+            ("activation".to_string(), serde_json::json!("softmax")),
+        ]);
+        let labels = vec!["person".to_string()];
+        assert!(ClassificationConfig::from_parameters(labels, 0.5, 5, ScoreActivation::Softmax, &softmax).is_err());
+    }
+
+    #[test]
+    fn test_open_set_rejection_reports_unknown() {
+        let labels = vec!["cat".to_string(), "dog".to_string()];
+        let parameters = HashMap::from([
+            ("open_set".to_string(), serde_json::json!({"score": "energy", "threshold": 2.0})),
+        ]);
+        let config = ClassificationConfig::from_parameters(labels, 0.1, 5, ScoreActivation::Softmax, &parameters).unwrap();
+
+        let known = config.decode(&[6.0, 1.0]);
+        assert_eq!(known[0].class_id, Some(0));
+        assert!(known[0].open_set_score.unwrap() > 6.0);
+
+        // Flat, low logits look like neither class
+        let unknown = config.decode(&[0.2, 0.1]);
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].class_name, "unknown");
+        assert_eq!(unknown[0].class_id, None);
+        assert!((unknown[0].confidence - (1.0 - softmax(&[0.2, 0.1])[0])).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ssd_postprocessing_from_yaml() {
+        use crate::model_config::OutputTensorSpec as YamlTensorSpec;
+
+        let tensor = |name: &str, semantic: &str| YamlTensorSpec {
+            name: name.to_string(),
+            shape: vec![],
+            dtype: "float32".to_string(),
+            semantic: semantic.to_string(),
+        };
+        let output_config = OutputConfiguration {
+            tensors: vec![
+                tensor("detection_boxes", "boxes"),
+                tensor("detection_classes", "classes"),
+                tensor("detection_scores", "scores"),
+                tensor("num_detections", "num_detections"),
+            ],
+            postprocess_type: "ssd".to_string(),
+            confidence_threshold: Some(0.5),
+            nms_threshold: Some(0.5),
+            max_detections: Some(10),
+            class_labels: Some(vec!["person".to_string(), "forklift".to_string()]),
+            suppression: None,
+            calibration: None,
+            class_thresholds: None,
+        };
+        let mut parameters = HashMap::new();
+        parameters.insert("label_offset".to_string(), serde_yaml::Value::from(1));
+        let postprocessing_config = PostprocessingConfiguration {
+            postprocess_type: "ssd".to_string(),
+            parameters: Some(parameters),
+            output_format: "detection".to_string(),
+        };
+        let postprocessor = UniversalPostprocessor::from_yaml_config(&output_config, Some(&postprocessing_config)).unwrap();
+
+        // TF exports: normalized [ymin, xmin, ymax, xmax], 1-based classes
+        let mut boxes = Array3::<f32>::zeros((1, 3, 4));
+        boxes[[0, 0, 0]] = 0.25;
+        boxes[[0, 0, 1]] = 0.5;
+        boxes[[0, 0, 2]] = 0.75;
+        boxes[[0, 0, 3]] = 1.0;
+        let mut classes = Array3::<f32>::zeros((1, 3, 1));
+        classes[[0, 0, 0]] = 2.0;
+        classes[[0, 1, 0]] = 1.0;
+        let mut scores = Array3::<f32>::zeros((1, 3, 1));
+        scores[[0, 0, 0]] = 0.9;
+        scores[[0, 1, 0]] = 0.95; // beyond num_detections
+        let num_detections = Array3::<f32>::from_elem((1, 1, 1), 1.0);
+
+        let preprocessing_info = PreprocessedImage {
+            tensor: Array4::zeros((1, 3, 300, 300)),
+            original_size: (600, 600),
+            tensor_shape: vec![1, 3, 300, 300],
+            scale_factors: (0.5, 0.5),
+            padding: (0, 0, 0, 0),
+        };
+
+        let outputs = vec![boxes, classes, scores, num_detections];
+        let predictions = postprocessor.process(&outputs, &preprocessing_info).unwrap();
+
+        assert_eq!(predictions.len(), 1);
+        assert_eq!(predictions[0].class, "forklift");
+        let bbox = predictions[0].bbox.unwrap();
+        approx::assert_abs_diff_eq!(bbox[0], 300.0, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(bbox[1], 150.0, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(bbox[2], 600.0, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(bbox[3], 450.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_pose_keypoints_follow_nms_and_letterbox() {
+        let output_config = OutputConfiguration {
+            tensors: vec![],
+            postprocess_type: "yolov8-pose".to_string(),
+            confidence_threshold: Some(0.5),
+            nms_threshold: Some(0.5),
+            max_detections: Some(10),
+            class_labels: None,
+            suppression: None,
+            calibration: None,
+            class_thresholds: None,
+        };
+        let postprocessor = UniversalPostprocessor::from_yaml_config(&output_config, None).unwrap();
+
+        // Two overlapping person boxes with 17 (x, y, visibility) keypoints each
+        let mut output = Array3::<f32>::zeros((1, 2, 4 + 1 + 17 * 3));
+        for (row, confidence) in [(0, 0.9), (1, 0.6)] {
+            output[[0, row, 0]] = 320.0;
+            output[[0, row, 1]] = 320.0;
+            output[[0, row, 2]] = 100.0;
+            output[[0, row, 3]] = 200.0;
+            output[[0, row, 4]] = confidence;
+            for k in 0..17 {
+                output[[0, row, 5 + k * 3]] = 300.0 + k as f32;
+                output[[0, row, 5 + k * 3 + 1]] = 280.0;
+                output[[0, row, 5 + k * 3 + 2]] = 0.8;
+            }
+        }
+
+        let preprocessing_info = PreprocessedImage {
+            tensor: Array4::zeros((1, 3, 640, 640)),
+            original_size: (1280, 960),
+            tensor_shape: vec![1, 3, 640, 640],
+            scale_factors: (0.5, 0.5),
+            padding: (0, 80, 0, 80),
+        };
+
+        let predictions = postprocessor.process(&[output], &preprocessing_info).unwrap();
+        assert_eq!(predictions.len(), 1, "NMS keeps one of the overlapping people");
+        assert_eq!(predictions[0].class, "person");
+
+        let keypoints = predictions[0].keypoints.as_ref().unwrap();
+        assert_eq!(keypoints.len(), 17);
+        assert_eq!(keypoints[0].name, "nose");
+        assert_eq!(keypoints[16].name, "right_ankle");
+        approx::assert_abs_diff_eq!(keypoints[5].x, 610.0, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(keypoints[5].y, 400.0, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(keypoints[5].score, 0.8, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_ssd_anchor_decoding() {
+        let config = SsdConfig {
+            background_class: Some(0),
+            score_activation: ScoreActivation::Sigmoid,
+            anchors: Some(AnchorConfig {
+                anchors: vec![[0.5, 0.5, 0.2, 0.2], [0.1, 0.1, 0.1, 0.1]],
+                scale_factors: [10.0, 10.0, 5.0, 5.0],
+            }),
+            ..Default::default()
+        };
+        let decoder = SsdDecoder::new(config);
+
+        // Zero regression reproduces the anchor; background column is ignored
+        let boxes = [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let scores = [10.0, 3.0, -2.0, 10.0, -5.0, -5.0];
+        let outputs = SsdOutputs {
+            boxes: Some(&boxes),
+            scores: Some(&scores),
+            ..Default::default()
+        };
+
+        let labels = vec!["background".to_string(), "leak".to_string(), "spill".to_string()];
+        let detections = decoder.decode(&outputs, (100.0, 100.0), 0.5, &labels).unwrap();
+
+        assert_eq!(detections.len(), 1);
+        assert_eq!(detections[0].class_name, "leak");
+        approx::assert_abs_diff_eq!(detections[0].x1, 40.0, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(detections[0].y2, 60.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_ssd_anchor_generator_count() {
+        let mut parameters = HashMap::new();
+        parameters.insert("anchor_generator".to_string(), serde_json::json!({
+            "feature_map_sizes": [[2, 2], [1, 1]],
+            "aspect_ratios": [1.0, 2.0, 0.5],
+        }));
+
+        let config = SsdConfig::from_parameters(&parameters).unwrap();
+        let anchors = config.anchors.unwrap().anchors;
+
+        // (4 + 1) cells x (3 aspect ratios + 1 interpolated scale)
+        assert_eq!(anchors.len(), 20);
+    }
+
+    #[test]
+    fn test_semantic_segmentation_regions() {
+        let labels = vec!["floor".to_string(), "spill".to_string()];
+        let mut parameters = HashMap::new();
+        parameters.insert("background_class".to_string(), serde_json::json!(0));
+        let config = SegmentationConfig::from_parameters(labels, &parameters).unwrap();
+
+        // 2 classes on a 4x4 grid, spill in the 2x2 bottom-right corner
+        let mut logits = vec![0.0f32; 2 * 16];
+        for y in 0..4 {
+            for x in 0..4 {
+                let spill = x >= 2 && y >= 2;
+                logits[y * 4 + x] = if spill { 0.0 } else { 4.0 };
+                logits[16 + y * 4 + x] = if spill { 4.0 } else { 0.0 };
+            }
+        }
+
+        let regions = SegmentationDecoder::new(config)
+            .decode_semantic(&logits, (2, 4, 4), (8.0, 8.0))
+            .unwrap();
+
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].class_name, "spill");
+        assert_eq!(regions[0].bbox, [4.0, 4.0, 8.0, 8.0]);
+        assert!(regions[0].confidence > 0.9);
+        approx::assert_abs_diff_eq!(regions[0].mask.area_ratio, 0.25, epsilon = 1e-6);
+        assert_eq!(regions[0].mask.rle, Some(vec![10, 2, 2, 2]));
+    }
+
+    #[test]
+    fn test_instance_mask_cropped_to_box() {
+        let config = SegmentationConfig::from_parameters(vec!["pallet".to_string()], &HashMap::new()).unwrap();
+        let decoder = SegmentationDecoder::new(config);
+
+        // Single prototype that is positive everywhere on an 8x8 grid for a 16x16 input
+        let protos = vec![1.0f32; 64];
+        let mask = decoder
+            .decode_instance_mask(&[5.0], &protos, (1, 8, 8), [4.0, 4.0, 12.0, 8.0], (16.0, 16.0))
+            .unwrap();
+
+        assert_eq!(mask.size, [8, 8]);
+        approx::assert_abs_diff_eq!(mask.area_ratio, 8.0 / 64.0, epsilon = 1e-6);
+        let polygons = mask.polygons.unwrap();
+        assert_eq!(polygons.len(), 1);
+        assert!(polygons[0].chunks(2).all(|p| p[0] >= 4.0 && p[0] <= 12.0 && p[1] >= 4.0 && p[1] <= 8.0));
+
+        // Coefficients that push every pixel below the threshold yield no mask
+        assert!(decoder.decode_instance_mask(&[-5.0], &protos, (1, 8, 8), [4.0, 4.0, 12.0, 8.0], (16.0, 16.0)).is_none());
+    }
 }
@@ -7,9 +7,11 @@
 
 use image::{DynamicImage, ImageError};
 use ndarray::Array4;
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::image_input::{ImageInputConfig, ImageInputError, ImageInputPipeline, ResampleFilter};
+pub use crate::image_input::{InputFormat, NormalizationConfig, ResizeStrategy};
+
 /// Errors that can occur during preprocessing
 #[derive(Error, Debug)]
 pub enum PreprocessingError {
@@ -21,6 +23,8 @@ pub enum PreprocessingError {
     UnsupportedFormat(String),
     #[error("Dimension mismatch: expected {expected:?}, got {actual:?}")]
     DimensionMismatch { expected: Vec<i64>, actual: Vec<usize> },
+    #[error("Image input error: {0}")]
+    ImageInput(#[from] ImageInputError),
 }
 
 /// Universal image preprocessor that handles different model requirements
@@ -36,39 +40,8 @@ pub struct UniversalImagePreprocessor {
     format: InputFormat,
     /// Additional preprocessing steps
     steps: Vec<PreprocessingStep>,
-}
-
-/// Resize strategies for handling different aspect ratios
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum ResizeStrategy {
-    /// Letterbox resize - maintains aspect ratio, adds padding
-    Letterbox { fill_color: [u8; 3] },
-    /// Center crop - crops from center to target aspect ratio
-    CenterCrop,
-    /// Stretch - distorts image to exact target size
-    Stretch,
-    /// Pad to square and then resize
-    PadSquare { fill_color: [u8; 3] },
-}
-
-/// Normalization configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NormalizationConfig {
-    /// Mean values per channel
-    pub mean: Vec<f32>,
-    /// Standard deviation values per channel
-    pub std: Vec<f32>,
-    /// Scale to [0,1] before applying mean/std
-    pub scale_to_unit: bool,
-}
-
-/// Input tensor format
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum InputFormat {
-    /// Batch, Channel, Height, Width (PyTorch style)
-    NCHW,
-    /// Batch, Height, Width, Channel (TensorFlow style)
-    NHWC,
+    /// Fused resize, normalization and layout, used when there are no additional steps
+    pipeline: ImageInputPipeline,
 }
 
 /// Individual preprocessing step
@@ -89,16 +62,6 @@ pub struct PreprocessedImage {
     pub padding: (u32, u32, u32, u32),
 }
 
-impl Default for NormalizationConfig {
-    fn default() -> Self {
-        Self {
-            mean: vec![0.0, 0.0, 0.0],
-            std: vec![1.0, 1.0, 1.0],
-            scale_to_unit: true,
-        }
-    }
-}
-
 impl UniversalImagePreprocessor {
     /// Create a new preprocessor with configuration
     pub fn new(
@@ -107,12 +70,21 @@ impl UniversalImagePreprocessor {
         normalization: NormalizationConfig,
         format: InputFormat,
     ) -> Self {
+        let pipeline = ImageInputPipeline::new(ImageInputConfig {
+            size: target_size,
+            channels: 3,
+            resize: resize_strategy.clone(),
+            normalization: normalization.clone(),
+            format: format.clone(),
+            filter: ResampleFilter::Lanczos3,
+        });
         Self {
             target_size,
             resize_strategy,
             normalization,
             format,
             steps: Vec::new(),
+            pipeline,
         }
     }
 
@@ -126,7 +98,7 @@ impl UniversalImagePreprocessor {
             let (h, w) = match input_config.format.as_str() {
                 "NCHW" => {
                     if input_config.shape.len() == 4 {
-                        (input_config.shape[2] as u32, input_config.shape[3] as u32)
+                        Self::static_size(input_config.shape[2], input_config.shape[3], preprocessing_config)?
                     } else {
                         return Err(PreprocessingError::InvalidConfig(
                             "NCHW format requires 4D shape".to_string()
@@ -135,7 +107,7 @@ impl UniversalImagePreprocessor {
                 },
                 "NHWC" => {
                     if input_config.shape.len() == 4 {
-                        (input_config.shape[1] as u32, input_config.shape[2] as u32)
+                        Self::static_size(input_config.shape[1], input_config.shape[2], preprocessing_config)?
                     } else {
                         return Err(PreprocessingError::InvalidConfig(
                             "NHWC format requires 4D shape".to_string()
@@ -146,7 +118,7 @@ impl UniversalImagePreprocessor {
                     format!("Unsupported input format: {}", input_config.format)
                 )),
             };
-            (h, w)
+            (w, h)
         } else {
             return Err(PreprocessingError::InvalidConfig(
                 "Input shape must have at least 3 dimensions".to_string()
@@ -190,20 +162,48 @@ impl UniversalImagePreprocessor {
             )),
         };
 
-        let mut preprocessor = Self::new(target_size, resize_strategy, normalization, format);
+        let steps = preprocessing_config.and_then(|p| p.steps.clone()).unwrap_or_default();
+        Ok(Self::new(target_size, resize_strategy, normalization, format).with_steps(steps))
+    }
 
-        // Add additional steps if specified
-        if let Some(preproc) = preprocessing_config {
-            if let Some(steps) = &preproc.steps {
-                preprocessor.steps = steps.clone();
-            }
-        }
+    /// The preprocessor with additional steps run on the resized image
+    pub fn with_steps(mut self, steps: Vec<PreprocessingStep>) -> Self {
+        self.steps = steps;
+        self
+    }
 
-        Ok(preprocessor)
+    /// Input (height, width), taking dynamic (non-positive) dimensions from the
+    /// configured `target_size`
+    fn static_size(
+        height: i64,
+        width: i64,
+        preprocessing_config: Option<&crate::model_config::PreprocessingConfiguration>,
+    ) -> Result<(u32, u32), PreprocessingError> {
+        let target_size = preprocessing_config.and_then(|p| p.target_size);
+        let resolve = |dim: i64, index: usize| -> Result<u32, PreprocessingError> {
+            if dim > 0 {
+                return Ok(dim as u32);
+            }
+            target_size
+                .map(|size| size[index])
+                .filter(|&size| size > 0)
+                .map(|size| size as u32)
+                .ok_or_else(|| PreprocessingError::InvalidConfig(format!(
+                    "Input dimension {} is dynamic; set preprocessing.target_size", dim
+                )))
+        };
+        Ok((resolve(height, 0)?, resolve(width, 1)?))
     }
 
     /// Process an image according to the configuration
+    ///
+    /// Without additional steps the image goes through the fused pipeline in a
+    /// single pass; steps work on resized images, so they take the staged path.
     pub fn process(&self, image: DynamicImage) -> Result<PreprocessedImage, PreprocessingError> {
+        if self.steps.is_empty() {
+            return self.process_fused(&image);
+        }
+
         let original_size = (image.width(), image.height());
 
         // Step 1: Apply resize strategy
@@ -227,6 +227,32 @@ impl UniversalImagePreprocessor {
         })
     }
 
+    fn process_fused(&self, image: &DynamicImage) -> Result<PreprocessedImage, PreprocessingError> {
+        self.check_normalization()?;
+        let shape = self.pipeline.shape();
+        let mut data = vec![0.0f32; self.pipeline.tensor_len()];
+        let geometry = self.pipeline.fill(image, &mut data)?;
+        let tensor = Array4::from_shape_vec(shape, data)
+            .map_err(|e| PreprocessingError::InvalidConfig(format!("Tensor shape {:?}: {}", shape, e)))?;
+
+        Ok(PreprocessedImage {
+            tensor,
+            original_size: geometry.original_size,
+            tensor_shape: vec![1, 3, self.target_size.1 as usize, self.target_size.0 as usize],
+            scale_factors: geometry.scale_factors,
+            padding: geometry.padding,
+        })
+    }
+
+    fn check_normalization(&self) -> Result<(), PreprocessingError> {
+        if self.normalization.mean.len() != 3 || self.normalization.std.len() != 3 {
+            return Err(PreprocessingError::InvalidConfig(
+                "Normalization arrays must have 3 elements for 3 channels".to_string()
+            ));
+        }
+        Ok(())
+    }
+
     /// Apply the configured resize strategy
     #[allow(clippy::type_complexity)]
     fn apply_resize_strategy(
@@ -593,6 +619,47 @@ mod tests {
         DynamicImage::ImageRgb8(img_buffer)
     }
 
+    #[test]
+    fn test_fused_path_matches_staged_path() {
+        // A contrast factor of 1 changes nothing but forces the staged path
+        let identity = PreprocessingStep {
+            step_type: "contrast_enhancement".to_string(),
+            parameters: [("factor".to_string(), serde_yaml::Value::from(1.0))].into(),
+        };
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(200, 120, |x, y| {
+            Rgb([(x % 251) as u8, (y * 2 % 241) as u8, ((x * 3 + y * 7) % 256) as u8])
+        }));
+        // (strategy, target size, max and mean absolute difference); the staged
+        // path resizes the padded square, blending the fill into the image border
+        // that the fused path resizes on its own
+        let cases = [
+            (ResizeStrategy::Letterbox { fill_color: [114, 114, 114] }, (64, 48), 0.005, 0.005),
+            (ResizeStrategy::CenterCrop, (64, 48), 0.005, 0.005),
+            (ResizeStrategy::Stretch, (64, 48), 0.005, 0.005),
+            (ResizeStrategy::PadSquare { fill_color: [114, 114, 114] }, (64, 64), 0.25, 0.01),
+        ];
+
+        for (resize, size, max_tolerance, mean_tolerance) in cases {
+            for format in [InputFormat::NCHW, InputFormat::NHWC] {
+                let fused = UniversalImagePreprocessor::new(size, resize.clone(), NormalizationConfig::default(), format.clone());
+                let staged = fused.clone().with_steps(vec![identity.clone()]);
+
+                let expected = staged.process(image.clone()).unwrap();
+                let actual = fused.process(image.clone()).unwrap();
+
+                let case = format!("{:?} {:?}", resize, format);
+                assert_eq!(actual.tensor.shape(), expected.tensor.shape(), "{}", case);
+                assert_eq!(actual.padding, expected.padding, "{}", case);
+                assert_eq!(actual.scale_factors, expected.scale_factors, "{}", case);
+                let diffs: Vec<f32> = actual.tensor.iter().zip(expected.tensor.iter()).map(|(a, b)| (a - b).abs()).collect();
+                let max_diff = diffs.iter().fold(0.0f32, |max, &d| max.max(d));
+                let mean_diff = diffs.iter().sum::<f32>() / diffs.len() as f32;
+                assert!(max_diff < max_tolerance, "{}: tensors differ by up to {}", case, max_diff);
+                assert!(mean_diff < mean_tolerance, "{}: tensors differ by {} on average", case, mean_diff);
+            }
+        }
+    }
+
     #[test]
     fn test_letterbox_resize() {
         let preprocessor = presets::yolov8();
@@ -628,4 +695,32 @@ mod tests {
         let sample_value = result.tensor[[0, 0, 0, 0]];
         assert!(sample_value < 10.0 && sample_value > -10.0); // Should be in normalized range
     }
+
+    #[test]
+    fn test_dynamic_input_dims_use_target_size() {
+        let input = crate::model_config::InputConfiguration {
+            shape: vec![-1, 3, -1, -1],
+            dtype: "float32".to_string(),
+            format: "NCHW".to_string(),
+            value_range: vec![0.0, 1.0],
+            color_space: Some("RGB".to_string()),
+            input_type: "image".to_string(),
+            tensors: Vec::new(),
+        };
+        let mut preprocessing = crate::model_config::PreprocessingConfiguration {
+            resize_strategy: "letterbox".to_string(),
+            target_size: Some([300, 320]),
+            normalization: None,
+            steps: None,
+            audio: None,
+            tiling: None,
+        };
+
+        let preprocessor = UniversalImagePreprocessor::from_yaml_config(&input, Some(&preprocessing)).unwrap();
+        let result = preprocessor.process(create_test_image(800, 600)).unwrap();
+        assert_eq!(result.tensor.shape(), &[1, 3, 300, 320]);
+
+        preprocessing.target_size = None;
+        assert!(UniversalImagePreprocessor::from_yaml_config(&input, Some(&preprocessing)).is_err());
+    }
 }
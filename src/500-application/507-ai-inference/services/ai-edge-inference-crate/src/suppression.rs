@@ -0,0 +1,399 @@
+//! Duplicate suppression for detection models
+//!
+//! Shared by the YAML-driven postprocessor and the backend output decoders so
+//! both return the same boxes for the same model output. Tiled inference
+//! merges duplicates across tiles with it too. The strategy comes
+//! from the `output.suppression` section of a model YAML; the IoU threshold and
+//! the overall cap stay in `output.nms_threshold` and `output.max_detections`.
+//!
+//! ```yaml
+//! output:
+//!   nms_threshold: 0.5
+//!   max_detections: 300
+//!   suppression:
+//!     method: soft_nms_gaussian   # nms | diou_nms | soft_nms_linear | soft_nms_gaussian | wbf
+//!     overlap: iou                # iou | ios
+//!     class_agnostic: false
+//!     sigma: 0.5
+//!     max_per_class: 50
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const DEFAULT_IOU_THRESHOLD: f32 = 0.4;
+
+/// Errors in a suppression configuration
+#[derive(Error, Debug)]
+pub enum SuppressionError {
+    #[error("IoU threshold must be in (0, 1], got {0}")]
+    InvalidThreshold(f32),
+    #[error("Soft-NMS sigma must be positive, got {0}")]
+    InvalidSigma(f32),
+    #[error("Soft-NMS min_score must be in [0, 1), got {0}")]
+    InvalidMinScore(f32),
+    #[error("Detection caps must be positive")]
+    InvalidCap,
+    #[error("Invalid suppression config: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// How overlapping detections are reduced to one per object
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuppressionMethod {
+    /// Greedy NMS: drop boxes overlapping a more confident box
+    #[default]
+    Nms,
+    /// Greedy NMS on IoU minus the normalized distance between box centers, so
+    /// adjacent objects with overlapping boxes both survive
+    DiouNms,
+    /// Soft-NMS: scale overlapping confidences by `1 - IoU` instead of dropping them
+    SoftNmsLinear,
+    /// Soft-NMS: scale overlapping confidences by `exp(-IoU² / sigma)`
+    SoftNmsGaussian,
+    /// Weighted box fusion: average each overlapping group into one box,
+    /// weighted by confidence
+    Wbf,
+}
+
+/// Overlap measure used to decide whether two boxes are duplicates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapMetric {
+    /// Intersection over union
+    #[default]
+    Iou,
+    /// Intersection over the smaller box, which also matches a partial box
+    /// with the whole object, such as an object cut off at a tile border
+    Ios,
+}
+
+/// Suppression options from the `output.suppression` section of a model YAML
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SuppressionConfig {
+    pub method: SuppressionMethod,
+    /// Overlap measure for NMS, Soft-NMS and WBF; DIoU-NMS always uses DIoU
+    pub overlap: OverlapMetric,
+    /// Suppress overlapping boxes of different classes too
+    pub class_agnostic: bool,
+    /// Gaussian Soft-NMS decay width
+    pub sigma: f32,
+    /// Soft-NMS drops boxes whose decayed confidence falls below this
+    pub min_score: f32,
+    /// Boxes kept per class
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_per_class: Option<usize>,
+    /// Overlap at which boxes are duplicates, from `output.nms_threshold`
+    #[serde(skip)]
+    pub iou_threshold: f32,
+    /// Boxes kept overall, from `output.max_detections`
+    #[serde(skip)]
+    pub max_detections: Option<usize>,
+}
+
+impl Default for SuppressionConfig {
+    fn default() -> Self {
+        Self {
+            method: SuppressionMethod::Nms,
+            overlap: OverlapMetric::Iou,
+            class_agnostic: false,
+            sigma: 0.5,
+            min_score: 0.001,
+            max_per_class: None,
+            iou_threshold: DEFAULT_IOU_THRESHOLD,
+            max_detections: None,
+        }
+    }
+}
+
+/// Candidate box in any pixel space, `[x1, y1, x2, y2]`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoredBox {
+    pub bbox: [f32; 4],
+    pub confidence: f32,
+    pub class_id: usize,
+}
+
+/// Box kept by suppression
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeptBox {
+    /// Index of the candidate it came from; for WBF, the group's most confident member
+    pub index: usize,
+    /// Confidence after Soft-NMS decay
+    pub confidence: f32,
+    /// Fused box for WBF, otherwise the candidate's box
+    pub bbox: [f32; 4],
+    /// Candidates the box stands for, itself included: the duplicates NMS
+    /// dropped in its favour or the boxes WBF fused into it
+    pub members: usize,
+}
+
+impl SuppressionConfig {
+    /// Read the suppression settings of a model's postprocessing JSON
+    ///
+    /// Takes the method from the `suppression` entry, the threshold from
+    /// `nms_threshold` and the overall cap from `max_detections`, falling back
+    /// to `default_iou_threshold` when no threshold is configured.
+    pub fn from_postprocessing(
+        postprocessing: Option<&serde_json::Value>,
+        default_iou_threshold: f32,
+    ) -> Result<Self, SuppressionError> {
+        let mut config: Self = match postprocessing.and_then(|post| post.get("suppression")).filter(|v| !v.is_null()) {
+            Some(suppression) => serde_json::from_value(suppression.clone())?,
+            None => Self::default(),
+        };
+        config.iou_threshold = postprocessing
+            .and_then(|post| post.get("nms_threshold"))
+            .and_then(|v| v.as_f64())
+            .map_or(default_iou_threshold, |t| t as f32);
+        config.max_detections = postprocessing
+            .and_then(|post| post.get("max_detections"))
+            .and_then(|v| v.as_u64())
+            .map(|max| max as usize);
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// The configuration with its threshold and overall cap filled in
+    pub fn with_limits(mut self, iou_threshold: f32, max_detections: Option<usize>) -> Self {
+        self.iou_threshold = iou_threshold;
+        self.max_detections = max_detections;
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), SuppressionError> {
+        if !(self.iou_threshold > 0.0 && self.iou_threshold <= 1.0) {
+            return Err(SuppressionError::InvalidThreshold(self.iou_threshold));
+        }
+        if self.sigma <= 0.0 || !self.sigma.is_finite() {
+            return Err(SuppressionError::InvalidSigma(self.sigma));
+        }
+        if !(0.0..1.0).contains(&self.min_score) {
+            return Err(SuppressionError::InvalidMinScore(self.min_score));
+        }
+        if self.max_per_class == Some(0) || self.max_detections == Some(0) {
+            return Err(SuppressionError::InvalidCap);
+        }
+        Ok(())
+    }
+
+    /// Boxes that survive suppression and the caps, most confident first
+    pub fn suppress(&self, boxes: &[ScoredBox]) -> Vec<KeptBox> {
+        let mut kept = match self.method {
+            SuppressionMethod::Nms => self.greedy(boxes, |a, b| self.overlap(a, b)),
+            SuppressionMethod::DiouNms => self.greedy(boxes, diou),
+            SuppressionMethod::SoftNmsLinear | SuppressionMethod::SoftNmsGaussian => self.soft_nms(boxes),
+            SuppressionMethod::Wbf => self.weighted_fusion(boxes),
+        };
+        kept.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+        let mut per_class: HashMap<usize, usize> = HashMap::new();
+        kept.into_iter()
+            .filter(|kept| {
+                let count = per_class.entry(boxes[kept.index].class_id).or_default();
+                *count += 1;
+                self.max_per_class.is_none_or(|max| *count <= max)
+            })
+            .take(self.max_detections.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Whether two candidates compete for the same object
+    fn competes(&self, a: &ScoredBox, b: &ScoredBox) -> bool {
+        self.class_agnostic || a.class_id == b.class_id
+    }
+
+    fn overlap(&self, a: &[f32; 4], b: &[f32; 4]) -> f32 {
+        match self.overlap {
+            OverlapMetric::Iou => iou(a, b),
+            OverlapMetric::Ios => ios(a, b),
+        }
+    }
+
+    fn greedy(&self, boxes: &[ScoredBox], overlap: impl Fn(&[f32; 4], &[f32; 4]) -> f32) -> Vec<KeptBox> {
+        let mut kept: Vec<KeptBox> = Vec::new();
+        for index in by_confidence(boxes) {
+            let candidate = &boxes[index];
+            let duplicate_of = kept.iter_mut().find(|kept| {
+                self.competes(&boxes[kept.index], candidate) && overlap(&kept.bbox, &candidate.bbox) > self.iou_threshold
+            });
+            match duplicate_of {
+                Some(kept) => kept.members += 1,
+                None => kept.push(KeptBox { index, confidence: candidate.confidence, bbox: candidate.bbox, members: 1 }),
+            }
+        }
+        kept
+    }
+
+    fn soft_nms(&self, boxes: &[ScoredBox]) -> Vec<KeptBox> {
+        let mut scores: Vec<f32> = boxes.iter().map(|b| b.confidence).collect();
+        let mut remaining: Vec<usize> = (0..boxes.len()).filter(|&i| scores[i] >= self.min_score).collect();
+        let mut kept = Vec::new();
+
+        while let Some(position) = (0..remaining.len()).max_by(|&a, &b| scores[remaining[a]].total_cmp(&scores[remaining[b]])) {
+            let best = remaining.swap_remove(position);
+            kept.push(KeptBox { index: best, confidence: scores[best], bbox: boxes[best].bbox, members: 1 });
+
+            for &other in &remaining {
+                if self.competes(&boxes[best], &boxes[other]) {
+                    scores[other] *= self.decay(self.overlap(&boxes[best].bbox, &boxes[other].bbox));
+                }
+            }
+            remaining.retain(|&i| scores[i] >= self.min_score);
+        }
+        kept
+    }
+
+    fn decay(&self, overlap: f32) -> f32 {
+        match self.method {
+            SuppressionMethod::SoftNmsGaussian => (-overlap * overlap / self.sigma).exp(),
+            _ if overlap > self.iou_threshold => 1.0 - overlap,
+            _ => 1.0,
+        }
+    }
+
+    /// Groups keep the confidence of their most confident member, like tile merging
+    fn weighted_fusion(&self, boxes: &[ScoredBox]) -> Vec<KeptBox> {
+        // Fused box, running confidence-weighted coordinate sums and total weight
+        let mut groups: Vec<(KeptBox, [f32; 4], f32)> = Vec::new();
+        for index in by_confidence(boxes) {
+            let candidate = &boxes[index];
+            let group = groups.iter_mut().find(|(fused, _, _)| {
+                self.competes(&boxes[fused.index], candidate) && self.overlap(&fused.bbox, &candidate.bbox) > self.iou_threshold
+            });
+            match group {
+                Some((fused, sums, weight)) => {
+                    fused.members += 1;
+                    *weight += candidate.confidence;
+                    for ((sum, value), coordinate) in sums.iter_mut().zip(fused.bbox.iter_mut()).zip(candidate.bbox) {
+                        *sum += coordinate * candidate.confidence;
+                        if *weight > 0.0 {
+                            *value = *sum / *weight;
+                        }
+                    }
+                }
+                None => {
+                    let sums = candidate.bbox.map(|coordinate| coordinate * candidate.confidence);
+                    let fused = KeptBox { index, confidence: candidate.confidence, bbox: candidate.bbox, members: 1 };
+                    groups.push((fused, sums, candidate.confidence));
+                }
+            }
+        }
+        groups.into_iter().map(|(fused, _, _)| fused).collect()
+    }
+}
+
+/// Candidate indices, most confident first
+fn by_confidence(boxes: &[ScoredBox]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by(|&a, &b| boxes[b].confidence.total_cmp(&boxes[a].confidence));
+    order
+}
+
+fn area(bbox: &[f32; 4]) -> f32 {
+    (bbox[2] - bbox[0]).max(0.0) * (bbox[3] - bbox[1]).max(0.0)
+}
+
+fn intersection(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let width = (a[2].min(b[2]) - a[0].max(b[0])).max(0.0);
+    let height = (a[3].min(b[3]) - a[1].max(b[1])).max(0.0);
+    width * height
+}
+
+/// Intersection over union
+pub fn iou(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let intersection = intersection(a, b);
+    let union = area(a) + area(b) - intersection;
+    if union <= 0.0 { 0.0 } else { intersection / union }
+}
+
+/// Intersection over the area of the smaller box
+pub fn ios(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let smaller = area(a).min(area(b));
+    if smaller <= 0.0 { 0.0 } else { intersection(a, b) / smaller }
+}
+
+/// IoU minus the squared center distance over the squared diagonal of the enclosing box
+fn diou(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let center_distance = ((a[0] + a[2]) - (b[0] + b[2])).powi(2) / 4.0 + ((a[1] + a[3]) - (b[1] + b[3])).powi(2) / 4.0;
+    let diagonal = (a[2].max(b[2]) - a[0].min(b[0])).powi(2) + (a[3].max(b[3]) - a[1].min(b[1])).powi(2);
+    if diagonal <= 0.0 {
+        return iou(a, b);
+    }
+    iou(a, b) - center_distance / diagonal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored(bbox: [f32; 4], confidence: f32, class_id: usize) -> ScoredBox {
+        ScoredBox { bbox, confidence, class_id }
+    }
+
+    fn config(method: SuppressionMethod) -> SuppressionConfig {
+        SuppressionConfig { method, ..SuppressionConfig::default() }.with_limits(0.5, None)
+    }
+
+    #[test]
+    fn test_methods_treat_overlapping_boxes_differently() {
+        let boxes = [
+            scored([0.0, 0.0, 10.0, 10.0], 0.9, 0),
+            scored([1.0, 0.0, 11.0, 10.0], 0.8, 0),
+            scored([1.0, 0.0, 11.0, 10.0], 0.7, 1),
+            scored([50.0, 50.0, 60.0, 60.0], 0.6, 0),
+        ];
+
+        let nms = config(SuppressionMethod::Nms).suppress(&boxes);
+        assert_eq!(nms.iter().map(|k| k.index).collect::<Vec<_>>(), vec![0, 2, 3]);
+
+        let agnostic = SuppressionConfig { class_agnostic: true, ..config(SuppressionMethod::Nms) }.suppress(&boxes);
+        assert_eq!(agnostic.iter().map(|k| k.index).collect::<Vec<_>>(), vec![0, 3]);
+
+        // The overlapping box survives with a decayed confidence
+        let soft = config(SuppressionMethod::SoftNmsGaussian).suppress(&boxes);
+        assert_eq!(soft.len(), 4);
+        let decayed = soft.iter().find(|k| k.index == 1).unwrap();
+        assert!(decayed.confidence < 0.8 * 0.5 && decayed.confidence > 0.0);
+
+        let linear = config(SuppressionMethod::SoftNmsLinear).suppress(&boxes);
+        let decayed = linear.iter().find(|k| k.index == 1).unwrap();
+        assert!((decayed.confidence - 0.8 * (1.0 - iou(&boxes[0].bbox, &boxes[1].bbox))).abs() < 1e-6);
+
+        let fused = config(SuppressionMethod::Wbf).suppress(&boxes);
+        assert_eq!(fused.len(), 3);
+        assert_eq!(fused[0].confidence, 0.9);
+        assert!((fused[0].bbox[0] - 0.8 / 1.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_diou_and_caps_keep_crowded_neighbours() {
+        // Two pallets side by side whose boxes overlap at IoU 0.5
+        let boxes = [
+            scored([0.0, 0.0, 30.0, 40.0], 0.9, 0),
+            scored([10.0, 0.0, 40.0, 40.0], 0.85, 0),
+            scored([100.0, 0.0, 130.0, 40.0], 0.8, 0),
+            scored([200.0, 0.0, 230.0, 40.0], 0.7, 1),
+        ];
+        let threshold = SuppressionConfig::default().with_limits(0.48, None);
+        assert_eq!(SuppressionConfig { method: SuppressionMethod::Nms, ..threshold.clone() }.suppress(&boxes).len(), 3);
+        assert_eq!(SuppressionConfig { method: SuppressionMethod::DiouNms, ..threshold.clone() }.suppress(&boxes).len(), 4);
+
+        let capped = SuppressionConfig { method: SuppressionMethod::DiouNms, max_per_class: Some(2), ..threshold.clone() };
+        let kept: Vec<usize> = capped.suppress(&boxes).iter().map(|k| k.index).collect();
+        assert_eq!(kept, vec![0, 1, 3]);
+        let capped = capped.with_limits(0.48, Some(1));
+        assert_eq!(capped.suppress(&boxes).len(), 1);
+
+        let post = serde_json::json!({ "nms_threshold": 0.6, "max_detections": 20, "suppression": { "method": "wbf", "max_per_class": 5 } });
+        let parsed = SuppressionConfig::from_postprocessing(Some(&post), 0.4).unwrap();
+        assert_eq!((parsed.method, parsed.iou_threshold, parsed.max_detections, parsed.max_per_class),
+            (SuppressionMethod::Wbf, 0.6, Some(20), Some(5)));
+        let invalid = serde_json::json!({ "suppression": { "method": "soft_nms_gaussian", "sigma": 0.0 } });
+        assert!(SuppressionConfig::from_postprocessing(Some(&invalid), 0.4).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Named tensors for models without built-in pre- or postprocessing
+//!
+//! Requests with `input_type: "tensor"` hand the model its inputs directly,
+//! for embedding, regression or other bespoke models. Values travel either as
+//! JSON arrays (nested arrays are flattened row-major) or as base64-encoded
+//! little-endian binary:
+//!
+//! ```json
+//! {
+//!   "tensors": [
+//!     { "name": "input_ids", "dtype": "i64", "shape": [1, 4], "data": [[101, 2023, 2003, 102]] },
+//!     { "name": "features", "dtype": "f32", "shape": [1, 3], "data_base64": "AACAPwAAAEAAAEBA" }
+//!   ],
+//!   "raw_output": true
+//! }
+//! ```
+//!
+//! With `raw_output`, or for models whose `postprocess_type` is `raw`, the
+//! result carries the model's output tensors unprocessed in the same format.
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Errors in tensor data
+#[derive(Error, Debug)]
+pub enum TensorError {
+    #[error("Tensor '{name}' has {actual} values but shape {shape:?} needs {expected}")]
+    ShapeMismatch { name: String, shape: Vec<usize>, expected: usize, actual: usize },
+    #[error("Tensor '{name}': {message}")]
+    InvalidData { name: String, message: String },
+    #[error("Tensor input has no tensors")]
+    Empty,
+    #[error("Invalid tensor input: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Element type of a tensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TensorDType {
+    #[serde(alias = "float32", alias = "float")]
+    F32,
+    #[serde(alias = "float64", alias = "double")]
+    F64,
+    #[serde(alias = "int32")]
+    I32,
+    #[serde(alias = "int64")]
+    I64,
+    #[serde(alias = "uint8")]
+    U8,
+}
+
+impl TensorDType {
+    /// Bytes per element
+    pub fn size(self) -> usize {
+        match self {
+            TensorDType::F32 | TensorDType::I32 => 4,
+            TensorDType::F64 | TensorDType::I64 => 8,
+            TensorDType::U8 => 1,
+        }
+    }
+}
+
+/// Flat, row-major tensor values
+#[derive(Debug, Clone, PartialEq)]
+pub enum TensorData {
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+    I32(Vec<i32>),
+    I64(Vec<i64>),
+    U8(Vec<u8>),
+}
+
+impl TensorData {
+    pub fn dtype(&self) -> TensorDType {
+        match self {
+            TensorData::F32(_) => TensorDType::F32,
+            TensorData::F64(_) => TensorDType::F64,
+            TensorData::I32(_) => TensorDType::I32,
+            TensorData::I64(_) => TensorDType::I64,
+            TensorData::U8(_) => TensorDType::U8,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            TensorData::F32(values) => values.len(),
+            TensorData::F64(values) => values.len(),
+            TensorData::I32(values) => values.len(),
+            TensorData::I64(values) => values.len(),
+            TensorData::U8(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Values converted to `f32`, as the built-in postprocessors expect
+    pub fn to_f32(&self) -> Vec<f32> {
+        match self {
+            TensorData::F32(values) => values.clone(),
+            TensorData::F64(values) => values.iter().map(|&v| v as f32).collect(),
+            TensorData::I32(values) => values.iter().map(|&v| v as f32).collect(),
+            TensorData::I64(values) => values.iter().map(|&v| v as f32).collect(),
+            TensorData::U8(values) => values.iter().map(|&v| v as f32).collect(),
+        }
+    }
+
+    /// Little-endian bytes of the values
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        match self {
+            TensorData::F32(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            TensorData::F64(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            TensorData::I32(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            TensorData::I64(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            TensorData::U8(values) => values.clone(),
+        }
+    }
+
+    /// Read little-endian values; fails when the length is not a whole number of elements
+    pub fn from_le_bytes(dtype: TensorDType, bytes: &[u8]) -> Result<Self, String> {
+        if !bytes.len().is_multiple_of(dtype.size()) {
+            return Err(format!("{} bytes is not a whole number of {:?} values", bytes.len(), dtype));
+        }
+        let chunks = bytes.chunks_exact(dtype.size());
+        Ok(match dtype {
+            TensorDType::F32 => TensorData::F32(chunks.map(|c| f32::from_le_bytes(c.try_into().unwrap_or_default())).collect()),
+            TensorDType::F64 => TensorData::F64(chunks.map(|c| f64::from_le_bytes(c.try_into().unwrap_or_default())).collect()),
+            TensorDType::I32 => TensorData::I32(chunks.map(|c| i32::from_le_bytes(c.try_into().unwrap_or_default())).collect()),
+            TensorDType::I64 => TensorData::I64(chunks.map(|c| i64::from_le_bytes(c.try_into().unwrap_or_default())).collect()),
+            TensorDType::U8 => TensorData::U8(bytes.to_vec()),
+        })
+    }
+
+    /// Read a JSON array of numbers, flattening nested arrays row-major
+    fn from_json(dtype: TensorDType, value: &serde_json::Value) -> Result<Self, String> {
+        let mut numbers = Vec::new();
+        flatten_json(value, &mut numbers)?;
+
+        let integer = |number: &serde_json::Number| {
+            number.as_i64().ok_or_else(|| format!("{} is not an integer", number))
+        };
+        let ranged = |number: &serde_json::Number, min: i64, max: i64| {
+            integer(number).and_then(|v| {
+                if (min..=max).contains(&v) { Ok(v) } else { Err(format!("{} is out of range for {:?}", v, dtype)) }
+            })
+        };
+        let float = |number: &serde_json::Number| number.as_f64().ok_or_else(|| format!("{} is not a number", number));
+
+        Ok(match dtype {
+            TensorDType::F32 => TensorData::F32(numbers.iter().map(|n| float(n).map(|v| v as f32)).collect::<Result<_, _>>()?),
+            TensorDType::F64 => TensorData::F64(numbers.iter().copied().map(float).collect::<Result<_, _>>()?),
+            TensorDType::I32 => TensorData::I32(numbers.iter()
+                .map(|n| ranged(n, i32::MIN as i64, i32::MAX as i64).map(|v| v as i32))
+                .collect::<Result<_, _>>()?),
+            TensorDType::I64 => TensorData::I64(numbers.iter().copied().map(integer).collect::<Result<_, _>>()?),
+            TensorDType::U8 => TensorData::U8(numbers.iter()
+                .map(|n| ranged(n, 0, u8::MAX as i64).map(|v| v as u8))
+                .collect::<Result<_, _>>()?),
+        })
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            TensorData::F32(values) => serde_json::json!(values),
+            TensorData::F64(values) => serde_json::json!(values),
+            TensorData::I32(values) => serde_json::json!(values),
+            TensorData::I64(values) => serde_json::json!(values),
+            TensorData::U8(values) => serde_json::json!(values),
+        }
+    }
+}
+
+fn flatten_json<'a>(value: &'a serde_json::Value, numbers: &mut Vec<&'a serde_json::Number>) -> Result<(), String> {
+    match value {
+        serde_json::Value::Array(items) => items.iter().try_for_each(|item| flatten_json(item, numbers)),
+        serde_json::Value::Number(number) => {
+            numbers.push(number);
+            Ok(())
+        }
+        other => Err(format!("expected numbers, found {}", other)),
+    }
+}
+
+/// Named tensor with its shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TensorSpec", into = "TensorSpec")]
+pub struct Tensor {
+    /// Model input or output name; an empty input name means the model's first input
+    pub name: String,
+    pub shape: Vec<usize>,
+    pub data: TensorData,
+}
+
+impl Tensor {
+    /// Create a tensor, checking that the values fill the shape
+    pub fn new(name: impl Into<String>, shape: Vec<usize>, data: TensorData) -> Result<Self, TensorError> {
+        let name = name.into();
+        let expected: usize = shape.iter().product();
+        if expected != data.len() {
+            return Err(TensorError::ShapeMismatch { name, shape, expected, actual: data.len() });
+        }
+        Ok(Self { name, shape, data })
+    }
+
+    pub fn dtype(&self) -> TensorDType {
+        self.data.dtype()
+    }
+}
+
+/// JSON form of a tensor
+#[derive(Serialize, Deserialize)]
+struct TensorSpec {
+    #[serde(default)]
+    name: String,
+    dtype: TensorDType,
+    shape: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<serde_json::Value>,
+    /// Base64 of the little-endian values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data_base64: Option<String>,
+}
+
+impl TryFrom<TensorSpec> for Tensor {
+    type Error = TensorError;
+
+    fn try_from(spec: TensorSpec) -> Result<Self, Self::Error> {
+        let invalid = |message: String| TensorError::InvalidData { name: spec.name.clone(), message };
+        let data = match (&spec.data, &spec.data_base64) {
+            (Some(values), None) => TensorData::from_json(spec.dtype, values).map_err(invalid)?,
+            (None, Some(encoded)) => {
+                let bytes = general_purpose::STANDARD.decode(encoded)
+                    .map_err(|e| invalid(format!("invalid base64: {}", e)))?;
+                TensorData::from_le_bytes(spec.dtype, &bytes).map_err(invalid)?
+            }
+            _ => return Err(invalid("needs exactly one of 'data' or 'data_base64'".to_string())),
+        };
+        Tensor::new(spec.name, spec.shape, data)
+    }
+}
+
+impl From<Tensor> for TensorSpec {
+    fn from(tensor: Tensor) -> Self {
+        Self {
+            dtype: tensor.dtype(),
+            data: Some(tensor.data.to_json()),
+            name: tensor.name,
+            shape: tensor.shape,
+            data_base64: None,
+        }
+    }
+}
+
+/// `input_data` of a request with `input_type: "tensor"`
+#[derive(Debug, Clone, Deserialize)]
+pub struct TensorRequest {
+    pub tensors: Vec<Tensor>,
+    /// Return the output tensors instead of postprocessed predictions
+    #[serde(default)]
+    pub raw_output: bool,
+}
+
+impl TensorRequest {
+    pub fn parse(input_data: &str) -> Result<Self, TensorError> {
+        let request: Self = serde_json::from_str(input_data)?;
+        if request.tensors.is_empty() {
+            return Err(TensorError::Empty);
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_and_binary_tensors() {
+        let request = TensorRequest::parse(r#"{
+            "tensors": [
+                { "name": "input_ids", "dtype": "int64", "shape": [1, 4], "data": [[101, 2023, 2003, 102]] },
+                { "name": "features", "dtype": "f32", "shape": [1, 3], "data_base64": "AACAPwAAAEAAAEBA" }
+            ],
+            "raw_output": true
+        }"#).unwrap();
+
+        assert!(request.raw_output);
+        assert_eq!(request.tensors[0].data, TensorData::I64(vec![101, 2023, 2003, 102]));
+        assert_eq!(request.tensors[1].data, TensorData::F32(vec![1.0, 2.0, 3.0]));
+        assert_eq!(request.tensors[1].data.to_le_bytes().len(), 12);
+
+        let round_trip: Tensor = serde_json::from_value(serde_json::to_value(&request.tensors[0]).unwrap()).unwrap();
+        assert_eq!(round_trip, request.tensors[0]);
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_tensors() {
+        let invalid = [
+            r#"{ "tensors": [] }"#,
+            r#"{ "tensors": [{ "dtype": "f32", "shape": [2, 2], "data": [1, 2, 3] }] }"#,
+            r#"{ "tensors": [{ "dtype": "u8", "shape": [1], "data": [256] }] }"#,
+            r#"{ "tensors": [{ "dtype": "i32", "shape": [1], "data": [1.5] }] }"#,
+            r#"{ "tensors": [{ "dtype": "f32", "shape": [1], "data_base64": "AAA=" }] }"#,
+            r#"{ "tensors": [{ "dtype": "f32", "shape": [1] }] }"#,
+        ];
+        for input in invalid {
+            assert!(TensorRequest::parse(input).is_err(), "accepted {}", input);
+        }
+
+        let scalar = Tensor::new("scale", Vec::new(), TensorData::F64(vec![0.5])).unwrap();
+        assert_eq!(scalar.data.to_f32(), vec![0.5]);
+    }
+}
@@ -0,0 +1,246 @@
+//! Sliced (tiled) inference for high-resolution imagery
+//!
+//! Letterboxing a 4K frame down to a 640×640 model input shrinks small objects
+//! below what the detector can see. With tiling configured, the engine cuts the
+//! image into overlapping model-sized tiles, runs every tile through the
+//! backend, moves the boxes back into image pixels and merges the duplicates
+//! found where tiles overlap. An optional full-frame pass keeps large objects
+//! that no single tile contains.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::pipeline::CropRegion;
+use crate::suppression::{OverlapMetric, ScoredBox, SuppressionConfig, SuppressionMethod};
+use crate::types::Prediction;
+
+/// Errors in a tiling configuration
+#[derive(Error, Debug)]
+pub enum TilingError {
+    #[error("Tile size must be positive, got {0:?}")]
+    InvalidTileSize([u32; 2]),
+    #[error("Tile overlap must be in [0, 1), got {0}")]
+    InvalidOverlap(f32),
+    #[error("Merge threshold must be in (0, 1], got {0}")]
+    InvalidThreshold(f32),
+    #[error("Invalid tiling config: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// How duplicate detections from overlapping tiles are merged
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Keep the most confident box of each overlapping group
+    #[default]
+    Nms,
+    /// Average each overlapping group into one box, weighted by confidence
+    Wbf,
+}
+
+/// Tiling options from the `preprocessing.tiling` section of a model YAML
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TilingConfig {
+    /// Tile `[width, height]` in image pixels, usually the model input size
+    pub tile_size: [u32; 2],
+    /// Fraction of a tile shared with its neighbour on each axis
+    pub overlap: f32,
+    pub merge: MergeStrategy,
+    /// Overlap measure; IoS also matches an object cut off at a tile border
+    /// with the whole object from the neighbouring tile
+    pub match_metric: OverlapMetric,
+    /// Boxes of the same class overlapping more than this are merged
+    pub match_threshold: f32,
+    /// Also run the whole, downscaled image
+    pub full_frame: bool,
+    /// Tiles sent to the backend per batch
+    pub batch_size: usize,
+}
+
+impl Default for TilingConfig {
+    fn default() -> Self {
+        Self {
+            tile_size: [640, 640],
+            overlap: 0.2,
+            merge: MergeStrategy::Nms,
+            match_metric: OverlapMetric::Ios,
+            match_threshold: 0.5,
+            full_frame: false,
+            batch_size: 8,
+        }
+    }
+}
+
+impl TilingConfig {
+    /// Read the `tiling` entry of a model's preprocessing JSON, if present
+    pub fn from_preprocessing(preprocessing: Option<&serde_json::Value>) -> Result<Option<Self>, TilingError> {
+        let Some(tiling) = preprocessing.and_then(|pre| pre.get("tiling")).filter(|v| !v.is_null()) else {
+            return Ok(None);
+        };
+        let config: Self = serde_json::from_value(tiling.clone())?;
+        config.validate()?;
+        Ok(Some(config))
+    }
+
+    pub fn validate(&self) -> Result<(), TilingError> {
+        if self.tile_size.contains(&0) {
+            return Err(TilingError::InvalidTileSize(self.tile_size));
+        }
+        if !(0.0..1.0).contains(&self.overlap) {
+            return Err(TilingError::InvalidOverlap(self.overlap));
+        }
+        if !(self.match_threshold > 0.0 && self.match_threshold <= 1.0) {
+            return Err(TilingError::InvalidThreshold(self.match_threshold));
+        }
+        Ok(())
+    }
+
+    /// Suppression settings that merge duplicate detections across tiles
+    pub fn suppression(&self) -> SuppressionConfig {
+        let method = match self.merge {
+            MergeStrategy::Nms => SuppressionMethod::Nms,
+            MergeStrategy::Wbf => SuppressionMethod::Wbf,
+        };
+        SuppressionConfig { method, overlap: self.match_metric, ..SuppressionConfig::default() }
+            .with_limits(self.match_threshold, None)
+    }
+
+    /// Whether an image is larger than one tile and so worth slicing
+    pub fn applies_to(&self, image_size: (u32, u32)) -> bool {
+        image_size.0 > self.tile_size[0] || image_size.1 > self.tile_size[1]
+    }
+
+    /// Tiles covering an image, row by row
+    ///
+    /// Tiles step by `tile * (1 - overlap)` and the last tile on each axis is
+    /// aligned with the image edge, so no tile extends past the image. An axis
+    /// shorter than the tile gets a single tile of the image's length.
+    pub fn tile_regions(&self, image_size: (u32, u32)) -> Vec<CropRegion> {
+        let (xs, width) = axis_tiles(image_size.0, self.tile_size[0], self.overlap);
+        let (ys, height) = axis_tiles(image_size.1, self.tile_size[1], self.overlap);
+        ys.iter()
+            .flat_map(|&y| xs.iter().map(move |&x| (x, y, width, height)))
+            .collect()
+    }
+}
+
+/// Tile start offsets and tile length along one image axis
+fn axis_tiles(length: u32, tile: u32, overlap: f32) -> (Vec<u32>, u32) {
+    if length <= tile {
+        return (vec![0], length);
+    }
+    let step = ((tile as f32 * (1.0 - overlap)).round() as u32).max(1);
+    let mut starts: Vec<u32> = (0..)
+        .map(|i| i * step)
+        .take_while(|start| start + tile < length)
+        .collect();
+    starts.push(length - tile);
+    (starts, tile)
+}
+
+/// Merge the detections of all tiles into one set
+///
+/// Only boxes of the same class are merged, using the shared duplicate
+/// suppression. Predictions without a box are kept as they are. The result
+/// is sorted by confidence.
+pub fn merge_predictions(predictions: Vec<Prediction>, config: &TilingConfig) -> Vec<Prediction> {
+    let (boxed, unboxed): (Vec<Prediction>, Vec<Prediction>) =
+        predictions.into_iter().partition(|prediction| prediction.bbox.is_some());
+
+    let mut class_ids: HashMap<&str, usize> = HashMap::new();
+    let candidates: Vec<ScoredBox> = boxed.iter()
+        .map(|prediction| {
+            let next_id = class_ids.len();
+            ScoredBox {
+                bbox: prediction.bbox.unwrap_or_default(),
+                confidence: prediction.confidence,
+                class_id: *class_ids.entry(prediction.class.as_str()).or_insert(next_id),
+            }
+        })
+        .collect();
+    let kept = config.suppression().suppress(&candidates);
+
+    let mut boxed: Vec<Option<Prediction>> = boxed.into_iter().map(Some).collect();
+    let mut merged: Vec<Prediction> = kept.into_iter()
+        .filter_map(|kept| {
+            let mut best = boxed[kept.index].take()?;
+            best.bbox = Some(kept.bbox);
+            if kept.members > 1 {
+                best.metadata.insert("merged_detections".to_string(), serde_json::json!(kept.members));
+            }
+            Some(best)
+        })
+        .collect();
+    merged.extend(unboxed);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prediction(class: &str, confidence: f32, bbox: [f32; 4]) -> Prediction {
+        Prediction {
+            class: class.to_string(),
+            confidence,
+            bbox: Some(bbox),
+            metadata: HashMap::new(),
+            severity: None,
+            mask: None,
+            keypoints: None,
+        }
+    }
+
+    #[test]
+    fn test_tiles_cover_image_and_stay_inside() {
+        let config = TilingConfig { tile_size: [640, 640], overlap: 0.25, ..TilingConfig::default() };
+
+        // 480-pixel steps along x, with the last tile aligned to the right edge
+        let tiles = config.tile_regions((1920, 1080));
+        let xs: Vec<u32> = tiles.iter().filter(|t| t.1 == 0).map(|t| t.0).collect();
+        let ys: Vec<u32> = tiles.iter().filter(|t| t.0 == 0).map(|t| t.1).collect();
+        assert_eq!(xs, vec![0, 480, 960, 1280]);
+        assert_eq!(ys, vec![0, 440]);
+        assert!(tiles.iter().all(|&(x, y, w, h)| x + w <= 1920 && y + h <= 1080 && (w, h) == (640, 640)));
+
+        assert!(!config.applies_to((640, 480)));
+        assert_eq!(config.tile_regions((640, 480)), vec![(0, 0, 640, 480)]);
+
+        let invalid = serde_json::json!({ "tiling": { "overlap": 1.0 } });
+        assert!(TilingConfig::from_preprocessing(Some(&invalid)).is_err());
+        let parsed = serde_json::json!({ "tiling": { "tile_size": [512, 512], "merge": "wbf" } });
+        let parsed = TilingConfig::from_preprocessing(Some(&parsed)).unwrap().unwrap();
+        assert_eq!(parsed.merge, MergeStrategy::Wbf);
+        assert_eq!(parsed.overlap, 0.2);
+    }
+
+    #[test]
+    fn test_merge_joins_tile_duplicates_per_class() {
+        let detections = vec![
+            // Whole defect in one tile and its cut-off half in the neighbour
+            prediction("solder_bridge", 0.9, [100.0, 100.0, 140.0, 120.0]),
+            prediction("solder_bridge", 0.6, [120.0, 100.0, 140.0, 120.0]),
+            // Same place, other class
+            prediction("missing_part", 0.7, [100.0, 100.0, 140.0, 120.0]),
+            prediction("solder_bridge", 0.8, [500.0, 500.0, 520.0, 520.0]),
+        ];
+
+        let nms = merge_predictions(detections.clone(), &TilingConfig::default());
+        assert_eq!(nms.len(), 3);
+        assert_eq!(nms[0].bbox, Some([100.0, 100.0, 140.0, 120.0]));
+        assert_eq!(nms[0].metadata["merged_detections"], 2);
+
+        // The same pair has an IoU of 0.5, below this threshold
+        let iou = TilingConfig { match_metric: OverlapMetric::Iou, match_threshold: 0.6, ..TilingConfig::default() };
+        assert_eq!(merge_predictions(detections.clone(), &iou).len(), 4);
+
+        let wbf = TilingConfig { merge: MergeStrategy::Wbf, ..TilingConfig::default() };
+        let fused = merge_predictions(detections, &wbf);
+        assert_eq!(fused.len(), 3);
+        assert_eq!(fused[0].confidence, 0.9);
+        assert!((fused[0].bbox.unwrap()[0] - 108.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,419 @@
+//! Sensor time-series windowing and output interpretation
+//!
+//! Sensor readings arrive as a flat series of frame-major samples
+//! (`[t0c0, t0c1, .., t1c0, ..]`). Models consume fixed-length windows of that
+//! series, so this module slices it into normalized windows in the tensor layout
+//! the model expects and turns anomaly scores, reconstructions and forecasts back
+//! into per-window results.
+
+use thiserror::Error;
+
+/// Errors that can occur while windowing sensor data or reading model outputs
+#[derive(Error, Debug)]
+pub enum TimeSeriesError {
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Output size mismatch: expected {expected} values, got {actual}")]
+    OutputMismatch { expected: usize, actual: usize },
+}
+
+/// How to fill windows that extend past the end of the series
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowPadding {
+    /// Drop incomplete trailing windows
+    None,
+    /// Fill with zeros in normalized space (the channel mean)
+    Zero,
+    /// Repeat the last sample
+    Edge,
+}
+
+/// Tensor layout of one window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowLayout {
+    /// `[1, window, channels]` (recurrent and transformer models)
+    Nwc,
+    /// `[1, channels, window]` (1D convolutional models)
+    Ncw,
+    /// `[1, window * channels]`, frame-major (dense models)
+    Flat,
+}
+
+/// Windowing configuration parsed from `ModelConfig.preprocessing`
+#[derive(Debug, Clone)]
+pub struct SensorWindowConfig {
+    /// Samples per window; `None` uses the whole series as one window
+    pub window_length: Option<usize>,
+    /// Samples between window starts; defaults to the window length
+    pub stride: Option<usize>,
+    /// Interleaved channels per sample
+    pub channels: usize,
+    pub padding: WindowPadding,
+    pub layout: WindowLayout,
+    /// Per-channel mean and standard deviation
+    pub normalization: Option<(Vec<f32>, Vec<f32>)>,
+}
+
+impl Default for SensorWindowConfig {
+    fn default() -> Self {
+        Self {
+            window_length: None,
+            stride: None,
+            channels: 1,
+            padding: WindowPadding::Zero,
+            layout: WindowLayout::Nwc,
+            normalization: None,
+        }
+    }
+}
+
+impl SensorWindowConfig {
+    /// Parse from preprocessing JSON:
+    /// `{window: {length, stride, padding}, channels, layout, normalization: {mean, std}}`
+    pub fn from_preprocessing(preprocessing: &serde_json::Value) -> Result<Self, TimeSeriesError> {
+        let mut config = Self::default();
+        let window = preprocessing.get("window");
+        let usize_field = |value: Option<&serde_json::Value>, key: &str| -> Result<Option<usize>, TimeSeriesError> {
+            match value.and_then(|v| v.get(key)) {
+                None | Some(serde_json::Value::Null) => Ok(None),
+                Some(v) => v.as_u64()
+                    .filter(|&n| n > 0)
+                    .map(|n| Some(n as usize))
+                    .ok_or_else(|| TimeSeriesError::InvalidConfig(format!("{} must be a positive integer", key))),
+            }
+        };
+
+        config.window_length = usize_field(window, "length")?;
+        config.stride = usize_field(window, "stride")?;
+        if let Some(channels) = usize_field(Some(preprocessing), "channels")? {
+            config.channels = channels;
+        }
+
+        if let Some(padding) = window.and_then(|w| w.get("padding")).and_then(|v| v.as_str()) {
+            config.padding = match padding {
+                "none" | "drop" => WindowPadding::None,
+                "zero" | "zeros" => WindowPadding::Zero,
+                "edge" | "repeat" => WindowPadding::Edge,
+                other => return Err(TimeSeriesError::InvalidConfig(format!("Unknown window padding '{}'", other))),
+            };
+        }
+
+        if let Some(layout) = preprocessing.get("layout").and_then(|v| v.as_str()) {
+            config.layout = match layout.to_lowercase().as_str() {
+                "nwc" | "ntc" => WindowLayout::Nwc,
+                "ncw" | "nct" => WindowLayout::Ncw,
+                "flat" | "nw" => WindowLayout::Flat,
+                other => return Err(TimeSeriesError::InvalidConfig(format!("Unknown window layout '{}'", other))),
+            };
+        }
+
+        if let Some(normalization) = preprocessing.get("normalization") {
+            let values = |key: &str| -> Option<Vec<f32>> {
+                normalization.get(key)?.as_array()?.iter().map(|v| v.as_f64().map(|f| f as f32)).collect()
+            };
+            if let (Some(mean), Some(std)) = (values("mean"), values("std")) {
+                let broadcastable = |v: &[f32]| v.len() == 1 || v.len() == config.channels;
+                if !broadcastable(&mean) || !broadcastable(&std) {
+                    return Err(TimeSeriesError::InvalidConfig(format!(
+                        "normalization needs 1 or {} mean/std values", config.channels,
+                    )));
+                }
+                if std.contains(&0.0) {
+                    return Err(TimeSeriesError::InvalidConfig("normalization std must be non-zero".to_string()));
+                }
+                config.normalization = Some((mean, std));
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Tensor shape of one window
+    pub fn window_shape(&self, window_length: usize) -> Vec<i64> {
+        let (w, c) = (window_length as i64, self.channels as i64);
+        match self.layout {
+            WindowLayout::Nwc => vec![1, w, c],
+            WindowLayout::Ncw => vec![1, c, w],
+            WindowLayout::Flat => vec![1, w * c],
+        }
+    }
+
+    fn mean_std(&self, channel: usize) -> (f32, f32) {
+        match &self.normalization {
+            Some((mean, std)) => (mean[channel.min(mean.len() - 1)], std[channel.min(std.len() - 1)]),
+            None => (0.0, 1.0),
+        }
+    }
+
+    /// Normalize a raw value for `channel`
+    pub fn normalize(&self, value: f32, channel: usize) -> f32 {
+        let (mean, std) = self.mean_std(channel);
+        (value - mean) / std
+    }
+
+    /// Map a normalized value for `channel` back to sensor units
+    pub fn denormalize(&self, value: f32, channel: usize) -> f32 {
+        let (mean, std) = self.mean_std(channel);
+        value * std + mean
+    }
+
+    /// Read `[step][channel]` from window-shaped data in this layout
+    fn value_at(&self, data: &[f32], steps: usize, step: usize, channel: usize) -> f32 {
+        match self.layout {
+            WindowLayout::Nwc | WindowLayout::Flat => data[step * self.channels + channel],
+            WindowLayout::Ncw => data[channel * steps + step],
+        }
+    }
+}
+
+/// One model-ready window of a sensor series
+#[derive(Debug, Clone)]
+pub struct SensorWindow {
+    pub index: usize,
+    /// First sample of the window in the series
+    pub start: usize,
+    /// Number of real (unpadded) samples
+    pub valid_samples: usize,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub shape: Vec<i64>,
+    /// Normalized values in the configured layout
+    pub data: Vec<f32>,
+}
+
+/// Slice a frame-major series into normalized windows
+///
+/// Timestamps are per sample; when they do not match the sample count the
+/// sample indices are used instead.
+pub fn build_windows(
+    values: &[f32],
+    timestamps: &[i64],
+    config: &SensorWindowConfig,
+) -> Result<Vec<SensorWindow>, TimeSeriesError> {
+    let channels = config.channels;
+    if values.is_empty() {
+        return Err(TimeSeriesError::InvalidInput("Sensor series is empty".to_string()));
+    }
+    if !values.len().is_multiple_of(channels) {
+        return Err(TimeSeriesError::InvalidInput(format!(
+            "{} values cannot be split into {} channels", values.len(), channels,
+        )));
+    }
+
+    let samples = values.len() / channels;
+    let window_length = config.window_length.unwrap_or(samples);
+    let stride = config.stride.unwrap_or(window_length);
+    let timestamp = |sample: usize| {
+        if timestamps.len() == samples { timestamps[sample] } else { sample as i64 }
+    };
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while start < samples {
+        let valid_samples = window_length.min(samples - start);
+        if valid_samples < window_length && config.padding == WindowPadding::None {
+            break;
+        }
+
+        let mut frames = vec![0.0f32; window_length * channels];
+        for step in 0..window_length {
+            let sample = match config.padding {
+                _ if step < valid_samples => Some(start + step),
+                WindowPadding::Edge => Some(start + valid_samples - 1),
+                _ => None,
+            };
+            if let Some(sample) = sample {
+                for channel in 0..channels {
+                    frames[step * channels + channel] = config.normalize(values[sample * channels + channel], channel);
+                }
+            }
+        }
+
+        let data = match config.layout {
+            WindowLayout::Nwc | WindowLayout::Flat => frames,
+            WindowLayout::Ncw => (0..channels)
+                .flat_map(|channel| (0..window_length).map(move |step| (step, channel)))
+                .map(|(step, channel)| frames[step * channels + channel])
+                .collect(),
+        };
+
+        windows.push(SensorWindow {
+            index: windows.len(),
+            start,
+            valid_samples,
+            start_timestamp: timestamp(start),
+            end_timestamp: timestamp(start + valid_samples - 1),
+            shape: config.window_shape(window_length),
+            data,
+        });
+
+        if start + window_length >= samples {
+            break;
+        }
+        start += stride;
+    }
+
+    if windows.is_empty() {
+        return Err(TimeSeriesError::InvalidInput(format!(
+            "Series of {} samples is shorter than the {}-sample window and padding is disabled",
+            samples, window_length,
+        )));
+    }
+
+    Ok(windows)
+}
+
+/// How to interpret the output of a sensor model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeSeriesOutputKind {
+    /// One anomaly score (or `[normal, anomaly]` probabilities) per window
+    AnomalyScore,
+    /// Autoencoder reconstruction of the input window
+    Reconstruction,
+    /// Future samples in the input layout, in normalized units
+    Forecast,
+}
+
+impl TimeSeriesOutputKind {
+    pub fn from_postprocess_type(postprocess_type: &str) -> Option<Self> {
+        match postprocess_type {
+            "anomaly" | "anomaly_score" | "anomaly_detection" => Some(Self::AnomalyScore),
+            "reconstruction" | "autoencoder" | "reconstruction_error" => Some(Self::Reconstruction),
+            "forecast" | "forecasting" => Some(Self::Forecast),
+            _ => None,
+        }
+    }
+}
+
+/// Anomaly score from a window output: the value itself for a single output,
+/// otherwise the last entry (the anomaly probability of a two-class head)
+pub fn anomaly_score(output: &[f32]) -> Option<f32> {
+    output.last().copied()
+}
+
+/// Mean squared reconstruction error over the real samples of a window
+///
+/// Returns the overall error and the error of each channel, in normalized units.
+pub fn reconstruction_error(
+    window: &SensorWindow,
+    reconstruction: &[f32],
+    config: &SensorWindowConfig,
+) -> Result<(f32, Vec<f32>), TimeSeriesError> {
+    if reconstruction.len() != window.data.len() {
+        return Err(TimeSeriesError::OutputMismatch { expected: window.data.len(), actual: reconstruction.len() });
+    }
+
+    let steps = window.data.len() / config.channels;
+    let mut per_channel = vec![0.0f32; config.channels];
+    for (channel, error) in per_channel.iter_mut().enumerate() {
+        for step in 0..window.valid_samples {
+            let diff = config.value_at(&window.data, steps, step, channel)
+                - config.value_at(reconstruction, steps, step, channel);
+            *error += diff * diff;
+        }
+        *error /= window.valid_samples.max(1) as f32;
+    }
+
+    let overall = per_channel.iter().sum::<f32>() / config.channels as f32;
+    Ok((overall, per_channel))
+}
+
+/// Forecast in sensor units as `[step][channel]`
+pub fn decode_forecast(output: &[f32], config: &SensorWindowConfig) -> Result<Vec<Vec<f32>>, TimeSeriesError> {
+    if output.is_empty() || !output.len().is_multiple_of(config.channels) {
+        return Err(TimeSeriesError::InvalidInput(format!(
+            "Forecast of {} values cannot be split into {} channels", output.len(), config.channels,
+        )));
+    }
+
+    let horizon = output.len() / config.channels;
+    Ok((0..horizon)
+        .map(|step| {
+            (0..config.channels)
+                .map(|channel| config.denormalize(config.value_at(output, horizon, step, channel), channel))
+                .collect()
+        })
+        .collect())
+}
+
+/// Timestamps for `horizon` steps after `last`, spaced like the input series
+pub fn forecast_timestamps(timestamps: &[i64], last: i64, horizon: usize) -> Vec<i64> {
+    let step = match (timestamps.first(), timestamps.last()) {
+        (Some(&first), Some(&end)) if timestamps.len() > 1 && end > first => {
+            (end - first) / (timestamps.len() as i64 - 1)
+        }
+        _ => 1,
+    };
+    (1..=horizon as i64).map(|k| last + k * step.max(1)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(json: serde_json::Value) -> SensorWindowConfig {
+        SensorWindowConfig::from_preprocessing(&json).unwrap()
+    }
+
+    #[test]
+    fn test_windows_with_stride_and_edge_padding() {
+        let config = config(serde_json::json!({
+            "window": {"length": 4, "stride": 3, "padding": "edge"},
+        }));
+        let values: Vec<f32> = (0..8).map(|v| v as f32).collect();
+        let timestamps: Vec<i64> = (0..8).map(|t| 1000 + t * 10).collect();
+
+        let windows = build_windows(&values, &timestamps, &config).unwrap();
+
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[1].data, vec![3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(windows[2].data, vec![6.0, 7.0, 7.0, 7.0]);
+        assert_eq!(windows[2].valid_samples, 2);
+        assert_eq!((windows[2].start_timestamp, windows[2].end_timestamp), (1060, 1070));
+        assert_eq!(windows[0].shape, vec![1, 4, 1]);
+    }
+
+    #[test]
+    fn test_channel_first_layout_and_normalization() {
+        let config = config(serde_json::json!({
+            "window": {"length": 2, "padding": "none"},
+            "channels": 2,
+            "layout": "ncw",
+            "normalization": {"mean": [1.0, 10.0], "std": [1.0, 5.0]},
+        }));
+        // Three samples of (a, b); the incomplete trailing window is dropped
+        let values = [1.0, 10.0, 2.0, 20.0, 3.0, 30.0];
+
+        let windows = build_windows(&values, &[], &config).unwrap();
+
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].shape, vec![1, 2, 2]);
+        assert_eq!(windows[0].data, vec![0.0, 1.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_reconstruction_error_ignores_padding() {
+        let config = config(serde_json::json!({"window": {"length": 4}}));
+        let windows = build_windows(&[1.0, 2.0, 3.0], &[], &config).unwrap();
+
+        let (error, per_channel) = reconstruction_error(&windows[0], &[1.0, 2.0, 1.0, 9.0], &config).unwrap();
+
+        approx::assert_abs_diff_eq!(error, 4.0 / 3.0, epsilon = 1e-6);
+        assert_eq!(per_channel.len(), 1);
+    }
+
+    #[test]
+    fn test_forecast_denormalized() {
+        let config = config(serde_json::json!({
+            "channels": 2,
+            "normalization": {"mean": [0.0, 100.0], "std": [1.0, 10.0]},
+        }));
+
+        let forecast = decode_forecast(&[0.5, 1.0, 1.5, -1.0], &config).unwrap();
+
+        assert_eq!(forecast, vec![vec![0.5, 110.0], vec![1.5, 90.0]]);
+        assert_eq!(forecast_timestamps(&[0, 10, 20], 20, 2), vec![30, 40]);
+    }
+}
@@ -0,0 +1,440 @@
+//! Multi-object tracking across frames
+//!
+//! Detections from consecutive frames of the same stream (usually a
+//! `camera_id`) are associated ByteTrack-style: high-confidence detections are
+//! matched to existing tracks first, then the leftover tracks get a second
+//! chance against low-confidence detections, which keeps IDs alive through
+//! brief occlusions. Tracks move with a constant-velocity prediction between
+//! frames. Matching is greedy on IoU.
+//!
+//! A track is reported once it has been matched `min_hits` times and ends after
+//! `max_age` consecutive frames without a match. Tracked predictions carry
+//! `metadata.track_id` and `metadata.track` (velocity, first/last seen), and the
+//! result carries a `metadata.tracking` summary with the tracks that started
+//! and ended on this frame.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::suppression::iou;
+use crate::types::{InferenceRequest, Prediction};
+use crate::InferenceResult;
+
+/// Request metadata keys that identify a stream, in order of preference
+const STREAM_KEYS: [&str; 2] = ["camera_id", "stream_id"];
+
+/// Tracker settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackingConfig {
+    /// Track detections of requests that carry a `camera_id` or `stream_id`
+    pub enabled: bool,
+    /// Minimum IoU between a predicted track box and a detection to match them
+    pub iou_threshold: f32,
+    /// Detections at or above this confidence can match tracks and start new ones
+    pub high_confidence: f32,
+    /// Detections between this and `high_confidence` only extend existing tracks
+    pub low_confidence: f32,
+    /// Matches needed before a track is reported
+    pub min_hits: u32,
+    /// Consecutive unmatched frames after which a track ends
+    pub max_age: u32,
+    /// Weight of the newest displacement in the smoothed velocity (0-1)
+    pub velocity_smoothing: f32,
+    /// Only match detections of the same class as the track
+    pub class_aware: bool,
+    /// Drop a stream's state when it has not been updated for this long
+    pub stream_timeout_sec: u64,
+}
+
+impl Default for TrackingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            iou_threshold: 0.3,
+            high_confidence: 0.5,
+            low_confidence: 0.1,
+            min_hits: 3,
+            max_age: 30,
+            velocity_smoothing: 0.5,
+            class_aware: true,
+            stream_timeout_sec: 300,
+        }
+    }
+}
+
+/// One object followed across frames
+#[derive(Debug, Clone)]
+struct Track {
+    id: u64,
+    class: String,
+    /// Last matched box `[x1, y1, x2, y2]`
+    bbox: [f32; 4],
+    /// Box centre velocity in pixels per second
+    velocity: [f32; 2],
+    /// Timestamps in milliseconds since the Unix epoch
+    first_seen: i64,
+    last_seen: i64,
+    hits: u32,
+    missed: u32,
+    confirmed: bool,
+}
+
+impl Track {
+    /// Box moved along its velocity to `timestamp`
+    fn predicted_bbox(&self, timestamp: i64) -> [f32; 4] {
+        let dt = (timestamp - self.last_seen).max(0) as f32 / 1000.0;
+        let (dx, dy) = (self.velocity[0] * dt, self.velocity[1] * dt);
+        [self.bbox[0] + dx, self.bbox[1] + dy, self.bbox[2] + dx, self.bbox[3] + dy]
+    }
+
+    fn update(&mut self, bbox: [f32; 4], timestamp: i64, config: &TrackingConfig) {
+        let dt = (timestamp - self.last_seen) as f32 / 1000.0;
+        if dt > 0.0 {
+            let (old, new) = (center(&self.bbox), center(&bbox));
+            let observed = [(new[0] - old[0]) / dt, (new[1] - old[1]) / dt];
+            let alpha = if self.hits == 1 { 1.0 } else { config.velocity_smoothing.clamp(0.0, 1.0) };
+            for (velocity, observed) in self.velocity.iter_mut().zip(observed) {
+                *velocity = alpha * observed + (1.0 - alpha) * *velocity;
+            }
+            self.last_seen = timestamp;
+        }
+        self.bbox = bbox;
+        self.hits += 1;
+        self.missed = 0;
+        self.confirmed |= self.hits >= config.min_hits;
+    }
+
+    fn summary(&self) -> serde_json::Value {
+        json!({
+            "track_id": self.id,
+            "class": self.class,
+            "first_seen": self.first_seen,
+            "last_seen": self.last_seen,
+            "duration_ms": self.last_seen - self.first_seen,
+        })
+    }
+}
+
+/// Track state of a single stream
+#[derive(Debug)]
+pub struct StreamTracker {
+    tracks: Vec<Track>,
+    next_id: u64,
+    last_update: Instant,
+}
+
+impl Default for StreamTracker {
+    fn default() -> Self {
+        Self { tracks: Vec::new(), next_id: 1, last_update: Instant::now() }
+    }
+}
+
+impl StreamTracker {
+    /// Associate one frame of detections with the stream's tracks
+    ///
+    /// Predictions without a bounding box are left untouched. Returns the
+    /// `metadata.tracking` summary for the frame.
+    pub fn update(&mut self, predictions: &mut [Prediction], timestamp: i64, config: &TrackingConfig) -> serde_json::Value {
+        self.last_update = Instant::now();
+
+        let (mut high, mut low) = (Vec::new(), Vec::new());
+        for (index, prediction) in predictions.iter().enumerate() {
+            if prediction.bbox.is_none() {
+                continue;
+            }
+            if prediction.confidence >= config.high_confidence {
+                high.push(index);
+            } else if prediction.confidence >= config.low_confidence {
+                low.push(index);
+            }
+        }
+
+        let predicted: Vec<[f32; 4]> = self.tracks.iter().map(|track| track.predicted_bbox(timestamp)).collect();
+        let mut open_tracks: Vec<usize> = (0..self.tracks.len()).collect();
+        let mut matches = self.associate(predictions, &predicted, &mut open_tracks, &mut high, config);
+        matches.extend(self.associate(predictions, &predicted, &mut open_tracks, &mut low, config));
+
+        let mut started = Vec::new();
+        let mut assigned = Vec::with_capacity(matches.len());
+        for (track_index, detection) in matches {
+            let track = &mut self.tracks[track_index];
+            let was_confirmed = track.confirmed;
+            track.update(predictions[detection].bbox.unwrap_or_default(), timestamp, config);
+            if track.confirmed && !was_confirmed {
+                started.push(track.id);
+            }
+            assigned.push((track.id, detection));
+        }
+
+        for &track_index in &open_tracks {
+            self.tracks[track_index].missed += 1;
+        }
+        let mut ended = Vec::new();
+        self.tracks.retain(|track| {
+            let alive = track.missed <= config.max_age;
+            if !alive && track.confirmed {
+                ended.push(track.summary());
+            }
+            alive
+        });
+
+        // Unmatched high-confidence detections start new tracks
+        for detection in high {
+            let prediction = &predictions[detection];
+            let track = Track {
+                id: self.next_id,
+                class: prediction.class.clone(),
+                bbox: prediction.bbox.unwrap_or_default(),
+                velocity: [0.0, 0.0],
+                first_seen: timestamp,
+                last_seen: timestamp,
+                hits: 1,
+                missed: 0,
+                confirmed: config.min_hits <= 1,
+            };
+            self.next_id += 1;
+            if track.confirmed {
+                started.push(track.id);
+            }
+            assigned.push((track.id, detection));
+            self.tracks.push(track);
+        }
+
+        let mut tracked = 0;
+        for (track_id, detection) in assigned {
+            let Some(track) = self.tracks.iter().find(|track| track.id == track_id && track.confirmed) else {
+                continue;
+            };
+            let prediction = &mut predictions[detection];
+            prediction.metadata.insert("track_id".to_string(), json!(track.id));
+            prediction.metadata.insert("track".to_string(), json!({
+                "velocity": track.velocity,
+                "first_seen": track.first_seen,
+                "last_seen": track.last_seen,
+                "age_ms": track.last_seen - track.first_seen,
+                "hits": track.hits,
+            }));
+            tracked += 1;
+        }
+
+        json!({
+            "active_tracks": self.tracks.iter().filter(|track| track.confirmed).count(),
+            "tracked_predictions": tracked,
+            "started": started,
+            "ended": ended,
+            "timestamp": timestamp,
+        })
+    }
+
+    /// Greedily pair open tracks with `detections` by descending IoU
+    ///
+    /// Matched tracks and detections are removed from the open lists.
+    fn associate(
+        &self,
+        predictions: &[Prediction],
+        predicted: &[[f32; 4]],
+        open_tracks: &mut Vec<usize>,
+        detections: &mut Vec<usize>,
+        config: &TrackingConfig,
+    ) -> Vec<(usize, usize)> {
+        let mut candidates = Vec::new();
+        for &track_index in open_tracks.iter() {
+            for &detection in detections.iter() {
+                let prediction = &predictions[detection];
+                if config.class_aware && prediction.class != self.tracks[track_index].class {
+                    continue;
+                }
+                let overlap = iou(&predicted[track_index], &prediction.bbox.unwrap_or_default());
+                if overlap >= config.iou_threshold && overlap > 0.0 {
+                    candidates.push((overlap, track_index, detection));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut matches = Vec::new();
+        for (_, track_index, detection) in candidates {
+            if open_tracks.contains(&track_index) && detections.contains(&detection) {
+                open_tracks.retain(|&t| t != track_index);
+                detections.retain(|&d| d != detection);
+                matches.push((track_index, detection));
+            }
+        }
+        matches
+    }
+}
+
+/// Per-stream trackers shared by all requests of an engine
+#[derive(Debug)]
+pub struct TrackerRegistry {
+    config: TrackingConfig,
+    streams: Mutex<HashMap<String, StreamTracker>>,
+}
+
+impl TrackerRegistry {
+    /// Registry for the given settings, `None` when tracking is disabled
+    pub fn from_config(config: &TrackingConfig) -> Option<Self> {
+        config.enabled.then(|| Self { config: config.clone(), streams: Mutex::new(HashMap::new()) })
+    }
+
+    /// Track the predictions of a result when its request names a stream
+    ///
+    /// Streams are keyed by stream id and model, so two models on the same
+    /// camera keep separate track IDs. The frame time is the request's
+    /// `timestamp` metadata (seconds or milliseconds), falling back to now.
+    pub fn track(&self, request: &InferenceRequest, result: &mut InferenceResult) {
+        let Some(stream_id) = stream_id(request) else {
+            return;
+        };
+        let timestamp = frame_timestamp(request);
+
+        let key = format!("{}/{}", stream_id, result.model_name);
+        let summary = {
+            let mut streams = self.streams.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let timeout = Duration::from_secs(self.config.stream_timeout_sec);
+            streams.retain(|_, stream| stream.last_update.elapsed() < timeout);
+            streams.entry(key).or_default().update(&mut result.predictions, timestamp, &self.config)
+        };
+
+        if let Some(metadata) = result.metadata.as_object_mut() {
+            let mut summary = summary;
+            summary["stream_id"] = json!(stream_id);
+            metadata.insert("tracking".to_string(), summary);
+        }
+    }
+
+    /// Number of streams with live tracker state
+    pub fn active_streams(&self) -> usize {
+        self.streams.lock().map(|streams| streams.len()).unwrap_or_default()
+    }
+
+    /// Forget the tracks of every model on a stream
+    pub fn reset_stream(&self, stream_id: &str) {
+        let prefix = format!("{}/", stream_id);
+        if let Ok(mut streams) = self.streams.lock() {
+            streams.retain(|key, _| !key.starts_with(&prefix));
+        }
+    }
+}
+
+/// Camera or stream a request belongs to
+pub(crate) fn stream_id(request: &InferenceRequest) -> Option<&str> {
+    STREAM_KEYS.iter().find_map(|key| request.metadata.get(*key).and_then(|value| value.as_str()))
+}
+
+/// Frame time of a request in milliseconds, from its `timestamp` metadata or now
+pub(crate) fn frame_timestamp(request: &InferenceRequest) -> i64 {
+    request.metadata.get("timestamp")
+        .and_then(|value| value.as_i64())
+        .map(normalize_timestamp)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis())
+}
+
+/// Treat small epoch values as seconds and convert them to milliseconds
+fn normalize_timestamp(timestamp: i64) -> i64 {
+    if timestamp.abs() < 100_000_000_000 {
+        timestamp * 1000
+    } else {
+        timestamp
+    }
+}
+
+fn center(bbox: &[f32; 4]) -> [f32; 2] {
+    [(bbox[0] + bbox[2]) / 2.0, (bbox[1] + bbox[3]) / 2.0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detection(class: &str, confidence: f32, bbox: [f32; 4]) -> Prediction {
+        Prediction {
+            class: class.to_string(),
+            confidence,
+            bbox: Some(bbox),
+            metadata: HashMap::new(),
+            severity: None,
+            mask: None,
+            keypoints: None,
+        }
+    }
+
+    fn config() -> TrackingConfig {
+        TrackingConfig { enabled: true, min_hits: 2, max_age: 2, ..Default::default() }
+    }
+
+    fn track_id(prediction: &Prediction) -> Option<u64> {
+        prediction.metadata.get("track_id").and_then(|id| id.as_u64())
+    }
+
+    #[test]
+    fn test_ids_persist_and_velocity_is_estimated() {
+        let config = config();
+        let mut tracker = StreamTracker::default();
+
+        let mut frame = vec![detection("person", 0.9, [0.0, 0.0, 20.0, 40.0]), detection("forklift", 0.8, [100.0, 100.0, 180.0, 160.0])];
+        let summary = tracker.update(&mut frame, 1_000, &config);
+        assert!(frame.iter().all(|p| track_id(p).is_none()), "tracks are tentative until min_hits");
+        assert_eq!(summary["active_tracks"], 0);
+
+        let mut frame = vec![detection("forklift", 0.8, [100.0, 100.0, 180.0, 160.0]), detection("person", 0.9, [5.0, 0.0, 25.0, 40.0])];
+        let summary = tracker.update(&mut frame, 1_500, &config);
+        let (forklift, person) = (track_id(&frame[0]).unwrap(), track_id(&frame[1]).unwrap());
+        assert_ne!(forklift, person);
+        assert_eq!(summary["started"].as_array().unwrap().len(), 2);
+        assert_eq!(frame[1].metadata["track"]["velocity"], json!([10.0, 0.0]));
+        assert_eq!(frame[1].metadata["track"]["first_seen"], 1_000);
+
+        // The person keeps moving right; its predicted box still overlaps
+        let mut frame = vec![detection("person", 0.9, [10.0, 0.0, 30.0, 40.0])];
+        tracker.update(&mut frame, 2_000, &config);
+        assert_eq!(track_id(&frame[0]), Some(person));
+        assert_eq!(frame[0].metadata["track"]["last_seen"], 2_000);
+    }
+
+    #[test]
+    fn test_low_confidence_detections_only_extend_tracks() {
+        let config = config();
+        let mut tracker = StreamTracker::default();
+        for timestamp in [0, 100] {
+            let mut frame = vec![detection("person", 0.9, [0.0, 0.0, 20.0, 40.0])];
+            tracker.update(&mut frame, timestamp, &config);
+        }
+
+        // Partially occluded: low confidence keeps the existing track alive
+        let mut frame = vec![detection("person", 0.2, [1.0, 0.0, 21.0, 40.0]), detection("person", 0.2, [200.0, 0.0, 220.0, 40.0])];
+        tracker.update(&mut frame, 200, &config);
+        assert_eq!(track_id(&frame[0]), Some(1));
+        assert_eq!(track_id(&frame[1]), None);
+        assert_eq!(tracker.tracks.len(), 1, "low-confidence detections never start tracks");
+    }
+
+    #[test]
+    fn test_tracks_end_after_max_age() {
+        let config = config();
+        let mut tracker = StreamTracker::default();
+        for timestamp in [0, 100] {
+            let mut frame = vec![detection("person", 0.9, [0.0, 0.0, 20.0, 40.0])];
+            tracker.update(&mut frame, timestamp, &config);
+        }
+
+        let mut summary = json!({});
+        for timestamp in [200, 300, 400] {
+            summary = tracker.update(&mut [], timestamp, &config);
+        }
+        let ended = summary["ended"].as_array().unwrap();
+        assert_eq!(ended.len(), 1);
+        assert_eq!(ended[0]["track_id"], 1);
+        assert_eq!(ended[0]["duration_ms"], 100);
+
+        // A reappearing object gets a fresh id
+        let mut frame = vec![detection("person", 0.9, [0.0, 0.0, 20.0, 40.0])];
+        tracker.update(&mut frame, 500, &config);
+        assert_eq!(tracker.tracks[0].id, 2);
+    }
+}
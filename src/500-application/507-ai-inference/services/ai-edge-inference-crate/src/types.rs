@@ -48,6 +48,9 @@ pub struct InferenceResult {
     pub inference_time_ms: f64,
     /// Additional metadata
     pub metadata: serde_json::Value,
+    /// Unprocessed output tensors, in raw output mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tensors: Option<Vec<crate::tensors::Tensor>>,
 }
 
 /// Main inference result structure sent to MQTT topics (backward compatibility)
@@ -92,9 +95,42 @@ pub struct Prediction {
     /// Bounding box coordinates [x, y, width, height] for vision models
     pub bbox: Option<[f32; 4]>,
     /// Additional metadata specific to prediction type
+    #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
     /// Severity level for operational prioritization
     pub severity: Option<String>,
+    /// Pixel mask for segmentation models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<MaskEncoding>,
+    /// Named skeleton points for pose models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keypoints: Option<Vec<Keypoint>>,
+}
+
+/// Named skeleton point of a pose estimate
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Keypoint {
+    /// Skeleton point name (e.g. "left_shoulder")
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    /// Visibility score [0.0, 1.0]
+    pub score: f32,
+}
+
+/// Compact encoding of a binary segmentation mask
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MaskEncoding {
+    /// Mask grid size [height, width]
+    pub size: [u32; 2],
+    /// Row-major run-length counts, alternating unset/set and starting with an unset run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rle: Option<Vec<u32>>,
+    /// Outer contours of each region as flat [x0, y0, x1, y1, ...] in model input pixels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polygons: Option<Vec<Vec<f32>>>,
+    /// Fraction of the mask grid that is set
+    pub area_ratio: f32,
 }
 
 /// Additional contextual information for industrial operations
@@ -182,6 +218,7 @@ impl Default for InferenceResult {
             confidence: 0.0,
             inference_time_ms: 0.0,
             metadata: serde_json::json!({}),
+            tensors: None,
         }
     }
 }
@@ -219,6 +256,8 @@ impl Default for Prediction {
             bbox: None,
             metadata: HashMap::new(),
             severity: None,
+            mask: None,
+            keypoints: None,
         }
     }
 }
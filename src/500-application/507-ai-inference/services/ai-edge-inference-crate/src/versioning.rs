@@ -0,0 +1,498 @@
+//! Side-by-side model versions with canary and shadow traffic
+//!
+//! Each version is loaded into the backend under its own key (`name@version`),
+//! so a new version can be loaded and warmed up while the current one keeps
+//! serving. Requests for the logical model name go to the stable version, or to
+//! a canary version for a weighted share of traffic. A shadow version receives a
+//! copy of the same input; its result is compared against the served one and is
+//! never returned to the caller.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::InferenceResult;
+
+/// Errors raised by version management operations
+#[derive(Error, Debug)]
+pub enum VersionError {
+    #[error("Model '{0}' has no registered versions")]
+    UnknownModel(String),
+    #[error("Model '{model}' has no version '{version}'")]
+    UnknownVersion { model: String, version: String },
+    #[error("Version '{version}' of model '{model}' is {role} and cannot be removed")]
+    VersionInUse { model: String, version: String, role: &'static str },
+    #[error("Canary weight must be between 0.0 and 1.0, got {0}")]
+    InvalidWeight(f32),
+    #[error("Model '{0}' has no previous version to roll back to")]
+    NoPreviousVersion(String),
+}
+
+/// Shadow runs of one model that may be in flight at once; further requests
+/// skip the shadow
+pub const MAX_SHADOW_RUNS: usize = 4;
+
+/// Backend key under which a model version is loaded
+pub fn version_key(model_name: &str, version: &str) -> String {
+    format!("{}@{}", model_name, version)
+}
+
+/// Weighted share of traffic sent to a candidate version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanarySplit {
+    pub version: String,
+    /// Fraction of requests in [0, 1] served by the canary
+    pub weight: f32,
+}
+
+/// Serving statistics for one version
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VersionStats {
+    pub requests: u64,
+    pub failures: u64,
+    pub average_latency_ms: f64,
+}
+
+/// Paired comparison between the served version and the shadow version
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShadowComparison {
+    pub served_version: String,
+    pub shadow_version: String,
+    pub comparisons: u64,
+    pub agreements: u64,
+    pub shadow_failures: u64,
+    /// Requests that skipped the shadow because too many shadow runs were in
+    /// flight or the shadow version was not loaded
+    pub skipped: u64,
+    /// Mean of shadow latency minus served latency over paired runs
+    pub mean_latency_delta_ms: f64,
+}
+
+impl ShadowComparison {
+    pub fn agreement_rate(&self) -> f64 {
+        if self.comparisons == 0 {
+            return 0.0;
+        }
+        self.agreements as f64 / self.comparisons as f64
+    }
+}
+
+/// Routing state and statistics for one logical model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionStatus {
+    pub model_name: String,
+    pub stable: String,
+    pub previous: Option<String>,
+    pub versions: Vec<String>,
+    pub canary: Option<CanarySplit>,
+    pub shadow: Option<String>,
+    pub stats: HashMap<String, VersionStats>,
+    pub shadow_comparisons: Vec<ShadowComparison>,
+}
+
+/// Version chosen for a request
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionTarget {
+    pub version: String,
+    pub backend_key: String,
+}
+
+/// Where a request for a logical model name is sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionRoute {
+    pub model_name: String,
+    pub served: VersionTarget,
+    pub shadow: Option<VersionTarget>,
+}
+
+#[derive(Debug)]
+struct ModelVersionState {
+    stable: String,
+    previous: Option<String>,
+    versions: BTreeSet<String>,
+    canary: Option<CanarySplit>,
+    shadow: Option<String>,
+    stats: HashMap<String, VersionStats>,
+    /// Keyed by (served version, shadow version)
+    comparisons: HashMap<(String, String), ShadowComparison>,
+    /// Bounds the shadow runs in flight
+    shadow_slots: Arc<Semaphore>,
+}
+
+impl ModelVersionState {
+    fn require(&self, model_name: &str, version: &str) -> Result<(), VersionError> {
+        if self.versions.contains(version) {
+            Ok(())
+        } else {
+            Err(VersionError::UnknownVersion { model: model_name.to_string(), version: version.to_string() })
+        }
+    }
+
+    fn target(&self, model_name: &str, version: &str) -> VersionTarget {
+        VersionTarget { version: version.to_string(), backend_key: version_key(model_name, version) }
+    }
+
+    fn comparison(&mut self, served: &VersionTarget, shadow: &VersionTarget) -> &mut ShadowComparison {
+        self.comparisons
+            .entry((served.version.clone(), shadow.version.clone()))
+            .or_insert_with(|| ShadowComparison {
+                served_version: served.version.clone(),
+                shadow_version: shadow.version.clone(),
+                ..Default::default()
+            })
+    }
+}
+
+/// Routes logical model names to loaded versions
+#[derive(Debug, Default)]
+pub struct ModelVersionRouter {
+    models: RwLock<HashMap<String, ModelVersionState>>,
+}
+
+impl ModelVersionRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, ModelVersionState>> {
+        self.models.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, ModelVersionState>> {
+        self.models.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Record a loaded version; the first version of a model becomes stable
+    pub fn register(&self, model_name: &str, version: &str) {
+        let mut models = self.write();
+        let state = models.entry(model_name.to_string()).or_insert_with(|| ModelVersionState {
+            stable: version.to_string(),
+            previous: None,
+            versions: BTreeSet::new(),
+            canary: None,
+            shadow: None,
+            stats: HashMap::new(),
+            comparisons: HashMap::new(),
+            shadow_slots: Arc::new(Semaphore::new(MAX_SHADOW_RUNS)),
+        });
+        state.versions.insert(version.to_string());
+    }
+
+    pub fn has_model(&self, model_name: &str) -> bool {
+        self.read().contains_key(model_name)
+    }
+
+    /// Pick the version serving `request_id`
+    ///
+    /// The canary split hashes the request id, so a retried request keeps the
+    /// version it was first routed to.
+    pub fn route(&self, model_name: &str, request_id: &str) -> Option<VersionRoute> {
+        let models = self.read();
+        let state = models.get(model_name)?;
+
+        let served = match &state.canary {
+            Some(canary) if traffic_fraction(request_id) < canary.weight as f64 => &canary.version,
+            _ => &state.stable,
+        };
+        let shadow = state.shadow.as_ref()
+            .filter(|shadow| *shadow != served)
+            .map(|shadow| state.target(model_name, shadow));
+
+        Some(VersionRoute {
+            model_name: model_name.to_string(),
+            served: state.target(model_name, served),
+            shadow,
+        })
+    }
+
+    /// Make `version` the stable version, keeping the old one for rollback
+    pub fn promote(&self, model_name: &str, version: &str) -> Result<(), VersionError> {
+        let mut models = self.write();
+        let state = models.get_mut(model_name).ok_or_else(|| VersionError::UnknownModel(model_name.to_string()))?;
+        state.require(model_name, version)?;
+
+        if state.stable != version {
+            state.previous = Some(std::mem::replace(&mut state.stable, version.to_string()));
+        }
+        if state.canary.as_ref().is_some_and(|canary| canary.version == version) {
+            state.canary = None;
+        }
+        if state.shadow.as_deref() == Some(version) {
+            state.shadow = None;
+        }
+        Ok(())
+    }
+
+    /// Swap back to the previous stable version and stop any canary
+    pub fn rollback(&self, model_name: &str) -> Result<String, VersionError> {
+        let mut models = self.write();
+        let state = models.get_mut(model_name).ok_or_else(|| VersionError::UnknownModel(model_name.to_string()))?;
+        let previous = state.previous.take().ok_or_else(|| VersionError::NoPreviousVersion(model_name.to_string()))?;
+
+        state.previous = Some(std::mem::replace(&mut state.stable, previous.clone()));
+        state.canary = None;
+        Ok(previous)
+    }
+
+    /// Set or clear the canary split
+    pub fn set_canary(&self, model_name: &str, canary: Option<CanarySplit>) -> Result<(), VersionError> {
+        let mut models = self.write();
+        let state = models.get_mut(model_name).ok_or_else(|| VersionError::UnknownModel(model_name.to_string()))?;
+        if let Some(canary) = &canary {
+            if !(0.0..=1.0).contains(&canary.weight) {
+                return Err(VersionError::InvalidWeight(canary.weight));
+            }
+            state.require(model_name, &canary.version)?;
+        }
+        state.canary = canary;
+        Ok(())
+    }
+
+    /// Set or clear the shadow version
+    pub fn set_shadow(&self, model_name: &str, shadow: Option<String>) -> Result<(), VersionError> {
+        let mut models = self.write();
+        let state = models.get_mut(model_name).ok_or_else(|| VersionError::UnknownModel(model_name.to_string()))?;
+        if let Some(shadow) = &shadow {
+            state.require(model_name, shadow)?;
+        }
+        state.shadow = shadow;
+        Ok(())
+    }
+
+    /// Forget a version that no longer receives traffic
+    pub fn remove(&self, model_name: &str, version: &str) -> Result<(), VersionError> {
+        let mut models = self.write();
+        let state = models.get_mut(model_name).ok_or_else(|| VersionError::UnknownModel(model_name.to_string()))?;
+        state.require(model_name, version)?;
+
+        let role = if state.stable == version {
+            Some("stable")
+        } else if state.canary.as_ref().is_some_and(|canary| canary.version == version) {
+            Some("the canary")
+        } else if state.shadow.as_deref() == Some(version) {
+            Some("the shadow")
+        } else {
+            None
+        };
+        if let Some(role) = role {
+            return Err(VersionError::VersionInUse { model: model_name.to_string(), version: version.to_string(), role });
+        }
+
+        state.versions.remove(version);
+        state.stats.remove(version);
+        if state.previous.as_deref() == Some(version) {
+            state.previous = None;
+        }
+        Ok(())
+    }
+
+    /// Record one served request for a version
+    pub fn record(&self, model_name: &str, version: &str, latency_ms: f64, successful: bool) {
+        let mut models = self.write();
+        let Some(state) = models.get_mut(model_name) else {
+            return;
+        };
+
+        let stats = state.stats.entry(version.to_string()).or_default();
+        stats.requests += 1;
+        if !successful {
+            stats.failures += 1;
+        }
+        stats.average_latency_ms += (latency_ms - stats.average_latency_ms) / stats.requests as f64;
+    }
+
+    /// Reserve a slot for the shadow run of `route`
+    ///
+    /// Returns `None`, counting a skip, while the model already has
+    /// [`MAX_SHADOW_RUNS`] shadow runs in flight.
+    pub fn shadow_permit(&self, route: &VersionRoute) -> Option<OwnedSemaphorePermit> {
+        let slots = Arc::clone(&self.read().get(&route.model_name)?.shadow_slots);
+        let permit = slots.try_acquire_owned().ok();
+        if permit.is_none() {
+            self.record_shadow_skip(route);
+        }
+        permit
+    }
+
+    /// Count a request whose shadow run was skipped
+    pub fn record_shadow_skip(&self, route: &VersionRoute) {
+        let Some(shadow_target) = &route.shadow else {
+            return;
+        };
+        if let Some(state) = self.write().get_mut(&route.model_name) {
+            state.comparison(&route.served, shadow_target).skipped += 1;
+        }
+    }
+
+    /// Record a shadow run against the result that was served
+    ///
+    /// Pairs where the served version failed are not compared.
+    pub fn record_shadow(
+        &self,
+        route: &VersionRoute,
+        served: Option<(&InferenceResult, f64)>,
+        shadow: Option<(&InferenceResult, f64)>,
+    ) {
+        let (Some(shadow_target), Some((served_result, served_latency_ms))) = (&route.shadow, served) else {
+            return;
+        };
+
+        let mut models = self.write();
+        let Some(state) = models.get_mut(&route.model_name) else {
+            return;
+        };
+
+        let comparison = state.comparison(&route.served, shadow_target);
+
+        match shadow {
+            Some((shadow_result, shadow_latency_ms)) => {
+                comparison.comparisons += 1;
+                if predictions_agree(served_result, shadow_result) {
+                    comparison.agreements += 1;
+                }
+                let delta = shadow_latency_ms - served_latency_ms;
+                comparison.mean_latency_delta_ms += (delta - comparison.mean_latency_delta_ms) / comparison.comparisons as f64;
+            }
+            None => comparison.shadow_failures += 1,
+        }
+    }
+
+    /// Routing state and statistics for a model
+    pub fn status(&self, model_name: &str) -> Option<VersionStatus> {
+        let models = self.read();
+        let state = models.get(model_name)?;
+
+        Some(VersionStatus {
+            model_name: model_name.to_string(),
+            stable: state.stable.clone(),
+            previous: state.previous.clone(),
+            versions: state.versions.iter().cloned().collect(),
+            canary: state.canary.clone(),
+            shadow: state.shadow.clone(),
+            stats: state.stats.clone(),
+            shadow_comparisons: state.comparisons.values().cloned().collect(),
+        })
+    }
+}
+
+/// Whether two results found the same set of classes
+pub fn predictions_agree(a: &InferenceResult, b: &InferenceResult) -> bool {
+    let classes = |result: &InferenceResult| {
+        result.predictions.iter().map(|p| p.class.clone()).collect::<BTreeSet<_>>()
+    };
+    classes(a) == classes(b)
+}
+
+/// Stable position of a request in [0, 1) for traffic splitting (FNV-1a)
+fn traffic_fraction(request_id: &str) -> f64 {
+    let hash = request_id.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Prediction;
+
+    fn result(classes: &[&str]) -> InferenceResult {
+        InferenceResult {
+            model_name: "detector".to_string(),
+            model_type: "onnx".to_string(),
+            predictions: classes.iter().map(|class| Prediction {
+                class: class.to_string(),
+                confidence: 0.9,
+                bbox: None,
+                metadata: HashMap::new(),
+                severity: None,
+                mask: None,
+                keypoints: None,
+            }).collect(),
+            confidence: 0.9,
+            inference_time_ms: 1.0,
+            metadata: serde_json::json!({}),
+            tensors: None,
+        }
+    }
+
+    fn router() -> ModelVersionRouter {
+        let router = ModelVersionRouter::new();
+        router.register("detector", "1.0");
+        router.register("detector", "2.0");
+        router
+    }
+
+    #[test]
+    fn test_canary_weight_splits_traffic() {
+        let router = router();
+        router.set_canary("detector", Some(CanarySplit { version: "2.0".to_string(), weight: 0.2 })).unwrap();
+
+        let canary_requests = (0..5000)
+            .filter(|i| router.route("detector", &format!("req-{}", i)).unwrap().served.version == "2.0")
+            .count();
+        assert!((800..1200).contains(&canary_requests), "canary served {}", canary_requests);
+
+        // Routing is sticky per request id
+        assert_eq!(router.route("detector", "req-7"), router.route("detector", "req-7"));
+        assert!(matches!(
+            router.set_canary("detector", Some(CanarySplit { version: "2.0".to_string(), weight: 1.5 })),
+            Err(VersionError::InvalidWeight(_))
+        ));
+    }
+
+    #[test]
+    fn test_promote_and_rollback() {
+        let router = router();
+        assert_eq!(router.route("detector", "a").unwrap().served.backend_key, "detector@1.0");
+
+        router.promote("detector", "2.0").unwrap();
+        assert_eq!(router.route("detector", "a").unwrap().served.version, "2.0");
+        assert!(matches!(router.remove("detector", "2.0"), Err(VersionError::VersionInUse { .. })));
+
+        assert_eq!(router.rollback("detector").unwrap(), "1.0");
+        let status = router.status("detector").unwrap();
+        assert_eq!(status.stable, "1.0");
+        assert_eq!(status.previous.as_deref(), Some("2.0"));
+
+        router.remove("detector", "2.0").unwrap();
+        assert!(matches!(router.rollback("detector"), Err(VersionError::NoPreviousVersion(_))));
+    }
+
+    #[test]
+    fn test_shadow_comparison() {
+        let router = router();
+        router.set_shadow("detector", Some("2.0".to_string())).unwrap();
+        let route = router.route("detector", "a").unwrap();
+        assert_eq!(route.shadow.as_ref().unwrap().backend_key, "detector@2.0");
+
+        let served = result(&["person", "helmet"]);
+        router.record_shadow(&route, Some((&served, 10.0)), Some((&result(&["helmet", "person"]), 14.0)));
+        router.record_shadow(&route, Some((&served, 10.0)), Some((&result(&["person"]), 12.0)));
+        router.record_shadow(&route, Some((&served, 10.0)), None);
+
+        let comparison = &router.status("detector").unwrap().shadow_comparisons[0];
+        assert_eq!(comparison.comparisons, 2);
+        assert_eq!(comparison.agreements, 1);
+        assert_eq!(comparison.shadow_failures, 1);
+        assert!((comparison.agreement_rate() - 0.5).abs() < 1e-9);
+        assert!((comparison.mean_latency_delta_ms - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shadow_runs_are_bounded() {
+        let router = router();
+        router.set_shadow("detector", Some("2.0".to_string())).unwrap();
+        let route = router.route("detector", "a").unwrap();
+
+        let permits: Vec<_> = (0..MAX_SHADOW_RUNS).map(|_| router.shadow_permit(&route).unwrap()).collect();
+        assert!(router.shadow_permit(&route).is_none());
+        assert_eq!(router.status("detector").unwrap().shadow_comparisons[0].skipped, 1);
+
+        drop(permits);
+        assert!(router.shadow_permit(&route).is_some());
+    }
+}
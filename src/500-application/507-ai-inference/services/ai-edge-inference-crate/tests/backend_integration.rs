@@ -77,4 +77,15 @@ fn test_error_constructors() {
     assert!(postprocessing_error.to_string().contains("Test postprocessing error"));
     
     println!("✅ Error constructor functions work correctly");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_timeout_error_is_recoverable() {
+    use ai_edge_inference_crate::error::InferenceError;
+
+    let timeout_error = InferenceError::timeout(250);
+
+    assert!(timeout_error.is_recoverable());
+    assert_eq!(timeout_error.category(), "timeout");
+    assert_eq!(timeout_error.to_string(), "Operation timed out after 250ms");
+}
@@ -132,7 +132,7 @@ async fn test_specific_backend_types() {
         println!("✅ Correctly handled unavailable ONNX Runtime request: {}", error);
     }
 
-    #[cfg(not(feature = "candle"))]
+    #[cfg(not(any(feature = "candle", feature = "pure-rust")))]
     {
         println!("🧪 Testing unavailable Candle backend request...");
         let config = BackendConfig {
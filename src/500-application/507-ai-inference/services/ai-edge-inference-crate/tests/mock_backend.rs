@@ -0,0 +1,117 @@
+#![cfg(feature = "mock")]
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+use ai_edge_inference_crate::{InferenceConfig, InferenceEngine, InferenceRequest, MockBackend, MockFixture, ModelConfig};
+use base64::{engine::general_purpose, Engine as _};
+
+/// Shared by every test, since the mock backend registration is process-wide
+const FIXTURE: &str = r#"
+models:
+  ppe_detector:
+    sequence:
+      - predictions: [{ class: no_hardhat, confidence: 0.91, bbox: [10, 8, 30, 40] }]
+        latency_ms: 5
+      - error: simulated GPU reset
+      - timeout: true
+  vest_detector@v1:
+    sequence:
+      - predictions: [{ class: vest, confidence: 0.88, bbox: [4, 4, 20, 30] }]
+  vest_detector@v2:
+    sequence:
+      - predictions: [{ class: vest, confidence: 0.9, bbox: [4, 4, 20, 30] }]
+        latency_ms: 300
+"#;
+
+fn model_config(model_path: &str) -> ModelConfig {
+    ModelConfig {
+        model_path: model_path.to_string(),
+        model_type: "object_detection".to_string(),
+        confidence_threshold: Some(0.5),
+        preprocessing: None,
+        postprocessing: None,
+        integrity: None,
+        memory_mb: None,
+    }
+}
+
+fn image_request(request_id: &str, metadata: HashMap<String, serde_json::Value>) -> InferenceRequest {
+    let mut png = Vec::new();
+    image::DynamicImage::new_rgb8(64, 48)
+        .write_to(&mut Cursor::new(&mut png), image::ImageOutputFormat::Png)
+        .unwrap();
+    InferenceRequest {
+        request_id: request_id.to_string(),
+        model_name: Some("ppe_detector".to_string()),
+        input_data: general_purpose::STANDARD.encode(png),
+        input_type: "image".to_string(),
+        metadata,
+    }
+}
+
+#[tokio::test]
+async fn test_engine_serves_scripted_responses_without_models() {
+    let fixture: MockFixture = serde_yaml::from_str(FIXTURE).unwrap();
+    MockBackend::register(fixture).unwrap();
+
+    let mut engine = InferenceEngine::new(InferenceConfig::default()).await.unwrap();
+    engine.load_model("ppe_detector", &model_config("models/ppe_detector.onnx")).await.unwrap();
+
+    let result = engine.infer(image_request("req-1", HashMap::new())).await.unwrap();
+    assert_eq!(result.predictions.len(), 1);
+    assert_eq!(result.predictions[0].class, "no_hardhat");
+    assert_eq!(result.predictions[0].bbox, Some([10.0, 8.0, 30.0, 40.0]));
+
+    let failed = engine.infer(image_request("req-2", HashMap::new())).await.unwrap_err();
+    assert!(failed.to_string().contains("simulated GPU reset"));
+
+    let deadline = HashMap::from([("timeout_ms".to_string(), serde_json::json!(50))]);
+    assert!(engine.infer(image_request("req-3", deadline)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_shadow_version_does_not_delay_served_response() {
+    let fixture: MockFixture = serde_yaml::from_str(FIXTURE).unwrap();
+    MockBackend::register(fixture).unwrap();
+
+    let mut engine = InferenceEngine::new(InferenceConfig::default()).await.unwrap();
+    engine.load_model_version("vest_detector", "v1", &model_config("models/vest_v1.onnx")).await.unwrap();
+    engine.load_model_version("vest_detector", "v2", &model_config("models/vest_v2.onnx")).await.unwrap();
+    engine.set_model_traffic("vest_detector", None, Some("v2".to_string())).unwrap();
+
+    let mut request = image_request("req-shadow", HashMap::new());
+    request.model_name = Some("vest_detector".to_string());
+    let started = Instant::now();
+    let result = engine.infer(request).await.unwrap();
+    assert!(started.elapsed() < Duration::from_millis(300), "served response waited for the shadow");
+    assert_eq!(result.metadata["model_version"], "v1");
+
+    // The shadow finishes in the background and is compared with the served result
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let status = engine.model_version_status("vest_detector").unwrap();
+    assert_eq!(status.stats["v2"].requests, 1);
+    assert_eq!(status.shadow_comparisons.len(), 1);
+    assert_eq!(status.shadow_comparisons[0].comparisons, 1);
+}
+
+#[tokio::test]
+async fn test_declared_model_memory_counts_against_the_budget() {
+    let fixture: MockFixture = serde_yaml::from_str(FIXTURE).unwrap();
+    MockBackend::register(fixture).unwrap();
+
+    let mut config = InferenceConfig::default();
+    config.models.cache.memory_budget_mb = 500;
+    let mut engine = InferenceEngine::new(config).await.unwrap();
+    for model_name in ["ppe_detector", "vest_detector"] {
+        let model_config = ModelConfig { memory_mb: Some(300.0), ..model_config("models/model.onnx") };
+        engine.load_model(model_name, &model_config).await.unwrap();
+    }
+
+    // The mock backend reports no memory of its own; the declared 300 MB each
+    // push the first model out
+    let metrics = engine.get_metrics().await.model_cache;
+    assert_eq!(metrics.evictions, 1);
+    assert_eq!(metrics.estimated_resident_memory_mb, 300.0);
+}
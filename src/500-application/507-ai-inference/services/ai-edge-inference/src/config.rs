@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context};
 use ai_edge_inference_crate::{
     InferenceConfig as CrateInferenceConfig,
-    ModelsConfig, HardwareConfig, PerformanceConfig,
+    ModelsConfig, HardwareConfig, PerformanceConfig, IntegrityConfig, TrackingConfig, AnalyticsConfig, ModelCacheConfig,
     MonitoringConfig as CrateMonitoringConfig, SiteContext
 };
 
@@ -48,12 +48,20 @@ pub struct InferenceConfig {
     pub num_threads: Option<usize>,
     pub enable_parallel_processing: bool,
     pub batch_size: usize,
+    pub max_batch_wait_ms: u64,
     pub inference_timeout_ms: u64,
     pub enable_model_caching: bool,
     pub max_concurrent_inferences: usize,
     pub message_queue_capacity: usize,
     pub rate_limit_per_second: f64,
     pub is_drop_on_backpressure: bool,
+    pub model_integrity: IntegrityConfig,
+    /// Integrity flags set to something other than `true` or `false`
+    #[serde(skip)]
+    pub invalid_integrity_flags: Vec<String>,
+    pub model_cache: ModelCacheConfig,
+    pub tracking: TrackingConfig,
+    pub analytics: AnalyticsConfig,
 }
 
 /// Default model configuration
@@ -118,6 +126,14 @@ impl ComponentConfig {
             anyhow::bail!("Batch size must be greater than 0");
         }
 
+        if let Some(flag) = self.inference.invalid_integrity_flags.first() {
+            anyhow::bail!("{} must be 'true' or 'false'", flag);
+        }
+
+        if self.inference.model_integrity.require_signature && self.inference.model_integrity.trusted_keys.is_empty() {
+            anyhow::bail!("REQUIRE_MODEL_SIGNATURE is set but MODEL_TRUSTED_KEYS is empty");
+        }
+
         // Validate site configuration
         if self.site.site_id.is_empty() {
             anyhow::bail!("Site ID cannot be empty");
@@ -137,11 +153,14 @@ impl ComponentConfig {
                 model_configs: HashMap::new(),
                 global_confidence_threshold: self.inference.global_confidence_threshold,
                 max_predictions_per_model: self.inference.max_predictions_per_model,
+                integrity: self.inference.model_integrity.clone(),
+                cache: self.inference.model_cache.clone(),
             },
             performance: PerformanceConfig {
                 num_threads: self.inference.num_threads,
                 enable_parallel_processing: self.inference.enable_parallel_processing,
                 batch_size: self.inference.batch_size,
+                max_batch_wait_ms: self.inference.max_batch_wait_ms,
                 inference_timeout_ms: self.inference.inference_timeout_ms,
                 enable_model_caching: self.inference.enable_model_caching,
                 memory_pool_size_mb: self.inference.gpu_memory_limit_mb,
@@ -169,6 +188,8 @@ impl ComponentConfig {
                 environmental_data: self.site.environmental_data.clone(),
                 equipment_mapping: self.site.equipment_mapping.clone(),
             },
+            tracking: self.inference.tracking.clone(),
+            analytics: self.inference.analytics.clone(),
         }
     }
 
@@ -208,6 +229,7 @@ impl MqttConfig {
 
 impl InferenceConfig {
     fn from_env() -> Self {
+        let mut invalid_integrity_flags = Vec::new();
         Self {
             models_directory: PathBuf::from(get_env_or_default("MODELS_DIRECTORY", "/models")),
             default_models: parse_default_models(&get_env_or_default("DEFAULT_MODELS", "")),
@@ -220,12 +242,38 @@ impl InferenceConfig {
             num_threads: get_env_or_default("NUM_THREADS", "").parse().ok(),
             enable_parallel_processing: get_env_or_default("ENABLE_PARALLEL_PROCESSING", "true").parse().unwrap_or(true),
             batch_size: get_env_or_default("BATCH_SIZE", "1").parse().unwrap_or(1),
+            max_batch_wait_ms: get_env_or_default("MAX_BATCH_WAIT_MS", "5").parse().unwrap_or(5),
             inference_timeout_ms: get_env_or_default("INFERENCE_TIMEOUT_MS", "5000").parse().unwrap_or(5000),
             enable_model_caching: get_env_or_default("ENABLE_MODEL_CACHING", "true").parse().unwrap_or(true),
             max_concurrent_inferences: get_env_or_default("MAX_CONCURRENT_INFERENCES", "2").parse().unwrap_or(2),
             message_queue_capacity: get_env_or_default("MESSAGE_QUEUE_CAPACITY", "16").parse().unwrap_or(16),
             rate_limit_per_second: get_env_or_default("RATE_LIMIT_PER_SECOND", "5.0").parse().unwrap_or(5.0),
             is_drop_on_backpressure: get_env_or_default("DROP_ON_BACKPRESSURE", "true").parse().unwrap_or(true),
+            model_integrity: IntegrityConfig {
+                trusted_keys: parse_trusted_keys(&get_env_or_default("MODEL_TRUSTED_KEYS", "{}")),
+                require_digest: parse_integrity_flag("REQUIRE_MODEL_DIGEST", &mut invalid_integrity_flags),
+                require_signature: parse_integrity_flag("REQUIRE_MODEL_SIGNATURE", &mut invalid_integrity_flags),
+            },
+            invalid_integrity_flags,
+            model_cache: ModelCacheConfig {
+                memory_budget_mb: get_env_or_default("MODEL_MEMORY_BUDGET_MB", "0").parse().unwrap_or(0),
+                lazy_loading: get_env_or_default("LAZY_MODEL_LOADING", "false").parse().unwrap_or(false),
+                pinned: get_env_or_default("PINNED_MODELS", "")
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            },
+            tracking: TrackingConfig {
+                enabled: get_env_or_default("ENABLE_TRACKING", "false").parse().unwrap_or(false),
+                iou_threshold: get_env_or_default("TRACKING_IOU_THRESHOLD", "0.3").parse().unwrap_or(0.3),
+                high_confidence: get_env_or_default("TRACKING_HIGH_CONFIDENCE", "0.5").parse().unwrap_or(0.5),
+                low_confidence: get_env_or_default("TRACKING_LOW_CONFIDENCE", "0.1").parse().unwrap_or(0.1),
+                min_hits: get_env_or_default("TRACKING_MIN_HITS", "3").parse().unwrap_or(3),
+                max_age: get_env_or_default("TRACKING_MAX_AGE", "30").parse().unwrap_or(30),
+                ..TrackingConfig::default()
+            },
+            analytics: load_analytics_config(&get_env_or_default("ANALYTICS_CONFIG_PATH", "")),
         }
     }
 }
@@ -341,6 +389,36 @@ fn parse_equipment_mapping(mapping_str: &str) -> HashMap<String, String> {
     })
 }
 
+/// Parse trusted model signing keys (JSON object of key id to base64 public key)
+fn parse_trusted_keys(keys_str: &str) -> HashMap<String, String> {
+    serde_json::from_str(keys_str).unwrap_or_else(|e| {
+        tracing::warn!("Failed to parse MODEL_TRUSTED_KEYS, no model signing keys are trusted: {}", e);
+        HashMap::new()
+    })
+}
+
+/// Parse an integrity flag, recording values that are not a bool
+///
+/// Unlike the tuning knobs, a mistyped integrity flag must not silently turn
+/// verification off, so `validate` rejects the recorded names.
+fn parse_integrity_flag(key: &str, invalid: &mut Vec<String>) -> bool {
+    get_env_or_default(key, "false").parse().unwrap_or_else(|_| {
+        invalid.push(key.to_string());
+        true
+    })
+}
+
+/// Load per-camera zone rules; an unset path disables analytics
+fn load_analytics_config(path: &str) -> AnalyticsConfig {
+    if path.is_empty() {
+        return AnalyticsConfig::default();
+    }
+    AnalyticsConfig::from_file(path).unwrap_or_else(|e| {
+        tracing::warn!("Failed to load analytics rules from {}, zone analytics disabled: {}", path, e);
+        AnalyticsConfig::default()
+    })
+}
+
 #[allow(dead_code)]
 fn parse_shape(shape_str: &str) -> Result<Vec<i64>> {
     shape_str
@@ -7,7 +7,7 @@ use anyhow::Result;
 
 // Import the AI inference crate
 use ai_edge_inference_crate::{
-    InferenceEngine
+    InferenceEngine, InferenceError
 };
 
 mod config;
@@ -46,6 +46,13 @@ async fn main() -> Result<()> {
     }
     info!("Configuration validated");
 
+    #[cfg(feature = "mock")]
+    if let Ok(fixture_path) = std::env::var("MOCK_BACKEND_FIXTURE") {
+        let fixture = ai_edge_inference_crate::MockFixture::from_file(&fixture_path)?;
+        ai_edge_inference_crate::MockBackend::register(fixture)?;
+        warn!("Serving scripted predictions from mock fixture {}", fixture_path);
+    }
+
     // Initialize AI inference engine using the crate library
     let inference_config = config.create_inference_config();
     let mut inference_engine = InferenceEngine::new(inference_config).await?;
@@ -67,6 +74,7 @@ async fn main() -> Result<()> {
     if let Ok(config_path) = std::env::var("MODEL_CONFIG_PATH") {
         match inference_engine.load_model_from_yaml(&config_path).await {
             Ok(model_name) => info!("Loaded model '{}' from YAML config", model_name),
+            Err(e @ InferenceError::Integrity { .. }) => error!("Rejected model from YAML config: {}", e),
             Err(e) => warn!("Failed to load model from YAML config: {}", e),
         }
     }
@@ -14,7 +14,9 @@ use tracing::{error, info, debug, warn, instrument};
 use serde::{Deserialize, Serialize};
 use base64::Engine;
 use crate::config::MqttConfig;
-use ai_edge_inference_crate::{InferenceEngine, InferenceInput, InferenceResult, InferenceRequest, ImageMetadata, SensorMetadata};
+use ai_edge_inference_crate::{
+    analytics, AnalyticsEvent, CanarySplit, InferenceEngine, InferenceInput, InferenceResult, InferenceRequest, ImageMetadata
+};
 use anyhow::Result;
 
 
@@ -76,6 +78,28 @@ pub enum IncomingMessage {
         #[allow(dead_code)]
         metadata: serde_json::Value,
     },
+    #[serde(rename = "audio_clip")]
+    AudioClip {
+        microphone_id: String,
+        timestamp: i64,
+        audio_data: String, // Base64 encoded WAV or raw PCM
+        format: Option<String>,
+        sample_rate: Option<u32>,
+        channels: Option<u16>,
+        device_name: String,
+        #[allow(dead_code)]
+        metadata: serde_json::Value,
+    },
+    #[serde(rename = "tensor_input")]
+    TensorInput {
+        source_id: String,
+        timestamp: i64,
+        model_name: Option<String>,
+        tensors: serde_json::Value, // Named tensors, see ai_edge_inference_crate::tensors
+        #[serde(default)]
+        raw_output: bool,
+        device_name: String,
+    },
     #[serde(rename = "alert_trigger")]
     AlertTrigger {
         trigger_id: String,
@@ -103,6 +127,12 @@ pub enum ModelCommandType {
     Reload,
     SetConfidence,
     GetStatus,
+    /// Make `parameters.version` the stable version
+    Promote,
+    /// Return to the previous stable version
+    Rollback,
+    /// Set `parameters.canary` ({version, weight}) and `parameters.shadow`
+    SetTraffic,
 }
 
 /// Output message for inference results
@@ -115,6 +145,17 @@ pub struct InferenceResultMessage {
     pub enrichment: EnrichmentData,
 }
 
+/// Output message for a zone, tripwire or separation event
+#[derive(Debug, Serialize)]
+pub struct AnalyticsEventMessage {
+    pub message_type: String,
+    pub timestamp: i64,
+    pub source_device: String,
+    pub model_name: String,
+    pub event: AnalyticsEvent,
+    pub enrichment: EnrichmentData,
+}
+
 /// Additional enrichment data for downstream processing
 #[derive(Debug, Serialize)]
 pub struct EnrichmentData {
@@ -341,61 +382,6 @@ impl MqttPublisher {
         Ok(())
     }
 
-    /// Handle sensor inference using the crate library
-    #[expect(dead_code)]
-    async fn handle_sensor_inference(
-        &self,
-        sensor_id: String,
-        sensor_type: String,
-        values: Vec<f32>,
-        timestamps: Vec<i64>,
-        unit: String,
-        device_name: String
-    ) -> Result<(), Box<dyn Error>> {
-        let metadata = SensorMetadata {
-            sensor_type,
-            sampling_rate: 1.0, // Default sampling rate
-            units: unit,
-        };
-
-        // Clone values for serialization since we use them in the request
-        let values_for_serialization = values.clone();
-
-        let _inference_input = InferenceInput::TimeSeries {
-            values,
-            timestamps,
-            metadata,
-        };
-
-        // Create inference request
-        let mut request_metadata = HashMap::new();
-        request_metadata.insert("sensor_id".to_string(), serde_json::Value::String(sensor_id.clone()));
-        request_metadata.insert("device_name".to_string(), serde_json::Value::String(device_name.clone()));
-
-        let request = InferenceRequest {
-            request_id: uuid::Uuid::new_v4().to_string(),
-            model_name: None, // Use default model
-            input_data: serde_json::to_string(&values_for_serialization)?, // Serialize sensor values
-            input_type: "time_series".to_string(),
-            metadata: request_metadata,
-        };
-
-        // Run inference using the crate
-        let result = self.inference_engine.infer(request).await?;
-
-        // Use topic router to determine output topic
-        if let Some(topic_router) = &self.topic_router {
-            let output_topic = topic_router.route_result(&result);
-            self.publish_inference_result(result, &output_topic).await?;
-        } else {
-            // Fallback to simple topic construction
-            let output_topic = format!("{}ai/results/{}", self.config.topic_prefix, sensor_id);
-            self.publish_inference_result(result, &output_topic).await?;
-        }
-
-        Ok(())
-    }
-
     /// Handle alert triggers
     #[expect(dead_code)]
     async fn handle_alert_trigger(
@@ -496,46 +482,7 @@ impl MqttPublisher {
 
     /// Create enrichment data for results
     async fn create_enrichment_data(&self, result: &InferenceResult) -> EnrichmentData {
-        // Determine alert level based on confidence and predictions
-        let alert_level = if result.confidence >= 0.9 {
-            AlertLevel::Critical
-        } else if result.confidence >= 0.7 {
-            AlertLevel::High
-        } else if result.confidence >= 0.5 {
-            AlertLevel::Medium
-        } else {
-            AlertLevel::Low
-        };
-
-        // Generate recommended actions based on alert level
-        let recommended_actions = match alert_level {
-            AlertLevel::Critical => vec![
-                "Immediate manual inspection required".to_string(),
-                "Alert operations team".to_string(),
-                "Consider shutting down affected equipment".to_string(),
-            ],
-            AlertLevel::High => vec![
-                "Schedule inspection within 1 hour".to_string(),
-                "Notify maintenance team".to_string(),
-            ],
-            AlertLevel::Medium => vec![
-                "Schedule inspection within 4 hours".to_string(),
-                "Log for trending analysis".to_string(),
-            ],
-            AlertLevel::Low => vec![
-                "Continue monitoring".to_string(),
-                "Log for trending analysis".to_string(),
-            ],
-        };
-
-        EnrichmentData {
-            site: std::env::var("SITE").unwrap_or_else(|_| "unknown_site".to_string()),
-            facility: std::env::var("FACILITY").unwrap_or_else(|_| "unknown_facility".to_string()),
-            region: std::env::var("REGION").unwrap_or_else(|_| "unknown_region".to_string()),
-            business_unit: std::env::var("BUSINESS_UNIT").unwrap_or_else(|_| "unknown_bu".to_string()),
-            alert_level,
-            recommended_actions,
-        }
+        enrichment_data(result_alert_level(result))
     }
 
     /// Publish message with retry logic
@@ -879,6 +826,19 @@ impl MqttProcessingContext {
                     IncomingMessage::SensorData { sensor_id, sensor_type, values, timestamps, unit, device_name, .. } => {
                         self.handle_sensor_inference(sensor_id, sensor_type, values, timestamps, unit, device_name).await?;
                     }
+                    IncomingMessage::AudioClip { microphone_id, timestamp, audio_data, format, sample_rate, channels, device_name, .. } => {
+                        let audio = serde_json::json!({
+                            "data": audio_data,
+                            "format": format.unwrap_or_else(|| "wav".to_string()),
+                            "sample_rate": sample_rate,
+                            "channels": channels,
+                        });
+                        self.handle_audio_inference(microphone_id, timestamp, audio, device_name).await?;
+                    }
+                    IncomingMessage::TensorInput { source_id, timestamp, model_name, tensors, raw_output, device_name } => {
+                        let input = serde_json::json!({ "tensors": tensors, "raw_output": raw_output });
+                        self.handle_tensor_inference(source_id, timestamp, model_name, input, device_name).await?;
+                    }
                     IncomingMessage::AlertTrigger { trigger_id, camera_id, sensor_id, timestamp, priority, .. } => {
                         self.handle_alert_trigger(trigger_id, camera_id, sensor_id, timestamp, priority).await?;
                     }
@@ -979,6 +939,9 @@ impl MqttProcessingContext {
                 info!("Inference result: model={}, confidence={:.2}, predictions={}",
                       result.model_name, result.confidence, result.predictions.len());
 
+                // Zone and tripwire events go out as separate messages
+                self.publish_analytics_events(&result, &camera_id).await;
+
                 // Publish the result back to MQTT
                 match self.publish_inference_result(result, &camera_id).await {
                     Ok(_) => {
@@ -1004,19 +967,200 @@ impl MqttProcessingContext {
         Ok(())
     }
 
-    /// Placeholder implementations for other message types
-    async fn handle_sensor_inference(&self, _sensor_id: String, _sensor_type: String, _values: Vec<f32>, _timestamps: Vec<i64>, _unit: String, _device_name: String) -> anyhow::Result<()> {
-        info!("Sensor inference not yet implemented");
+    /// Handle audio clip inference; the engine decodes and segments the clip
+    async fn handle_audio_inference(
+        &self,
+        microphone_id: String,
+        timestamp: i64,
+        audio: serde_json::Value,
+        device_name: String,
+    ) -> anyhow::Result<()> {
+        info!("Processing audio inference for microphone: {} from device: {}", microphone_id, device_name);
+
+        let request = InferenceRequest {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            input_data: audio.to_string(),
+            input_type: "audio".to_string(),
+            model_name: None, // Use default model
+            metadata: {
+                let mut map = std::collections::HashMap::new();
+                map.insert("microphone_id".to_string(), serde_json::Value::String(microphone_id.clone()));
+                map.insert("device_name".to_string(), serde_json::Value::String(device_name.clone()));
+                map.insert("timestamp".to_string(), serde_json::Value::Number(serde_json::Number::from(timestamp)));
+                map
+            },
+        };
+
+        match self.inference_engine.infer(request).await {
+            Ok(result) => {
+                info!("Audio inference completed for microphone: {} ({} segment predictions)",
+                      microphone_id, result.predictions.len());
+
+                match self.publish_inference_result(result, &microphone_id).await {
+                    Ok(_) => {
+                        let mut stats = self.stats.write().await;
+                        stats.successful_publishes += 1;
+                        stats.last_publish_time = Some(chrono::Utc::now());
+                    }
+                    Err(e) => {
+                        error!("Failed to publish audio inference result for microphone {}: {}", microphone_id, e);
+                        let mut stats = self.stats.write().await;
+                        stats.failed_publishes += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Audio inference failed for microphone {}: {}", microphone_id, e);
+                let mut stats = self.stats.write().await;
+                stats.failed_publishes += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle raw tensor inference for models without built-in postprocessing
+    async fn handle_tensor_inference(
+        &self,
+        source_id: String,
+        timestamp: i64,
+        model_name: Option<String>,
+        input: serde_json::Value,
+        device_name: String,
+    ) -> anyhow::Result<()> {
+        info!("Processing tensor inference for source: {} from device: {}", source_id, device_name);
+
+        let request = InferenceRequest {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            input_data: input.to_string(),
+            input_type: "tensor".to_string(),
+            model_name,
+            metadata: {
+                let mut map = std::collections::HashMap::new();
+                map.insert("source_id".to_string(), serde_json::Value::String(source_id.clone()));
+                map.insert("device_name".to_string(), serde_json::Value::String(device_name.clone()));
+                map.insert("timestamp".to_string(), serde_json::Value::Number(serde_json::Number::from(timestamp)));
+                map
+            },
+        };
+
+        match self.inference_engine.infer(request).await {
+            Ok(result) => {
+                info!("Tensor inference completed for source: {} ({} output tensors)",
+                      source_id, result.tensors.as_ref().map_or(0, Vec::len));
+
+                match self.publish_inference_result(result, &source_id).await {
+                    Ok(_) => {
+                        let mut stats = self.stats.write().await;
+                        stats.successful_publishes += 1;
+                        stats.last_publish_time = Some(chrono::Utc::now());
+                    }
+                    Err(e) => {
+                        error!("Failed to publish tensor inference result for source {}: {}", source_id, e);
+                        let mut stats = self.stats.write().await;
+                        stats.failed_publishes += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Tensor inference failed for source {}: {}", source_id, e);
+                let mut stats = self.stats.write().await;
+                stats.failed_publishes += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle sensor readings; the engine windows them for time-series models
+    async fn handle_sensor_inference(
+        &self,
+        sensor_id: String,
+        sensor_type: String,
+        values: Vec<f32>,
+        timestamps: Vec<i64>,
+        unit: String,
+        device_name: String,
+    ) -> anyhow::Result<()> {
+        info!("Processing sensor inference for sensor: {} from device: {}", sensor_id, device_name);
+
+        // The engine parses sensor requests from this JSON shape into InferenceInput::TimeSeries
+        let sensor_data = serde_json::json!({
+            "values": values,
+            "timestamps": timestamps,
+            "sensor_type": sensor_type,
+            "sampling_rate": 1.0, // Default sampling rate
+            "units": unit,
+        });
+
+        let request = InferenceRequest {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            input_data: sensor_data.to_string(),
+            input_type: "time_series".to_string(),
+            model_name: None, // Use default model
+            metadata: {
+                let mut map = std::collections::HashMap::new();
+                map.insert("sensor_id".to_string(), serde_json::Value::String(sensor_id.clone()));
+                map.insert("device_name".to_string(), serde_json::Value::String(device_name.clone()));
+                map
+            },
+        };
+
+        match self.inference_engine.infer(request).await {
+            Ok(result) => {
+                info!("Sensor inference completed for sensor: {} ({} predictions)",
+                      sensor_id, result.predictions.len());
+
+                match self.publish_inference_result(result, &sensor_id).await {
+                    Ok(_) => {
+                        let mut stats = self.stats.write().await;
+                        stats.successful_publishes += 1;
+                        stats.last_publish_time = Some(chrono::Utc::now());
+                    }
+                    Err(e) => {
+                        error!("Failed to publish sensor inference result for sensor {}: {}", sensor_id, e);
+                        let mut stats = self.stats.write().await;
+                        stats.failed_publishes += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Sensor inference failed for sensor {}: {}", sensor_id, e);
+                let mut stats = self.stats.write().await;
+                stats.failed_publishes += 1;
+            }
+        }
+
         Ok(())
     }
 
+    /// Placeholder implementations for other message types
     async fn handle_alert_trigger(&self, _trigger_id: String, _camera_id: Option<String>, _sensor_id: Option<String>, _timestamp: i64, _priority: String) -> anyhow::Result<()> {
         info!("Alert trigger handling not yet implemented");
         Ok(())
     }
 
-    async fn handle_model_command(&self, _command: ModelCommandType, _model_name: String, _parameters: serde_json::Value) -> anyhow::Result<()> {
-        info!("Model command handling not yet implemented");
+    async fn handle_model_command(&self, command: ModelCommandType, model_name: String, parameters: serde_json::Value) -> anyhow::Result<()> {
+        match command {
+            ModelCommandType::Promote => {
+                let version = parameters["version"].as_str()
+                    .ok_or_else(|| anyhow::anyhow!("promote command needs a 'version' parameter"))?;
+                self.inference_engine.promote_model_version(&model_name, version)?;
+            }
+            ModelCommandType::Rollback => {
+                let version = self.inference_engine.rollback_model_version(&model_name)?;
+                info!("Model '{}' rolled back to version '{}'", model_name, version);
+            }
+            ModelCommandType::SetTraffic => {
+                let canary = serde_json::from_value::<Option<CanarySplit>>(parameters["canary"].clone())?;
+                let shadow = parameters["shadow"].as_str().map(str::to_string);
+                self.inference_engine.set_model_traffic(&model_name, canary, shadow)?;
+                info!("Updated traffic split for model '{}'", model_name);
+            }
+            _ => {
+                info!("Model command {:?} not yet implemented", command);
+            }
+        }
         Ok(())
     }
 
@@ -1048,11 +1192,47 @@ impl MqttProcessingContext {
         info!("Publishing inference result to topic: {} (payload size: {} bytes)", output_topic, payload.len());
         debug!("Inference result payload: {}", payload);
 
-        let output_topic_name = TopicName::new(&output_topic)?;
+        self.publish_payload(&output_topic, payload).await
+    }
+
+    /// Publish each analytics event of a result as its own message
+    async fn publish_analytics_events(&self, result: &InferenceResult, camera_id: &str) {
+        for event in analytics::result_events(result) {
+            let output_topic = match &self.topic_router {
+                Some(topic_router) => topic_router.route_event(&event),
+                None => format!("{}ai/events/{}", self.config.topic_prefix, camera_id),
+            };
+            let message = AnalyticsEventMessage {
+                message_type: "analytics_event".to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+                source_device: camera_id.to_string(),
+                model_name: result.model_name.clone(),
+                enrichment: enrichment_data(event_alert_level(&event)),
+                event,
+            };
+
+            let published = match serde_json::to_string(&message) {
+                Ok(payload) => self.publish_payload(&output_topic, payload).await,
+                Err(e) => Err(e.into()),
+            };
+            let mut stats = self.stats.write().await;
+            match published {
+                Ok(()) => stats.successful_publishes += 1,
+                Err(e) => {
+                    error!("Failed to publish analytics event for camera {}: {}", camera_id, e);
+                    stats.failed_publishes += 1;
+                }
+            }
+        }
+    }
+
+    /// Publish a payload with QoS 1 and a bounded wait
+    async fn publish_payload(&self, output_topic: &str, payload: String) -> anyhow::Result<()> {
+        let output_topic_name = TopicName::new(output_topic)?;
         match timeout(Duration::from_secs(10),
                      self.client.publish_qos1(output_topic_name, false, payload, PublishProperties::default())).await {
             Ok(Ok(_)) => {
-                info!("Successfully published inference result to topic: {}", output_topic);
+                info!("Successfully published to topic: {}", output_topic);
                 Ok(())
             }
             Ok(Err(e)) => {
@@ -1068,46 +1248,75 @@ impl MqttProcessingContext {
 
     /// Create enrichment data for results (same as in MqttPublisher)
     async fn create_enrichment_data(&self, result: &InferenceResult) -> EnrichmentData {
-        // Determine alert level based on confidence and predictions
-        let alert_level = if result.confidence >= 0.9 {
-            AlertLevel::Critical
-        } else if result.confidence >= 0.7 {
-            AlertLevel::High
-        } else if result.confidence >= 0.5 {
-            AlertLevel::Medium
-        } else {
-            AlertLevel::Low
-        };
+        enrichment_data(result_alert_level(result))
+    }
+}
 
-        // Generate recommended actions based on alert level
-        let recommended_actions = match alert_level {
-            AlertLevel::Critical => vec![
-                "Immediate manual inspection required".to_string(),
-                "Alert operations team".to_string(),
-                "Consider shutting down affected equipment".to_string(),
-            ],
-            AlertLevel::High => vec![
-                "Schedule inspection within 1 hour".to_string(),
-                "Notify maintenance team".to_string(),
-            ],
-            AlertLevel::Medium => vec![
-                "Schedule inspection within 4 hours".to_string(),
-                "Log for trending analysis".to_string(),
-            ],
-            AlertLevel::Low => vec![
-                "Continue monitoring".to_string(),
-                "Log for trending analysis".to_string(),
-            ],
-        };
+/// Alert level from result confidence, raised by any analytics events on the result
+///
+/// Models with an `output.calibration` report calibrated confidences, so the
+/// same level means the same likelihood of a correct prediction across models.
+fn result_alert_level(result: &InferenceResult) -> AlertLevel {
+    let events = analytics::result_events(result);
+    if events.iter().any(|event| event.event_type.is_safety_critical()) {
+        return AlertLevel::Critical;
+    }
 
-        EnrichmentData {
-            site: std::env::var("SITE").unwrap_or_else(|_| "unknown_site".to_string()),
-            facility: std::env::var("FACILITY").unwrap_or_else(|_| "unknown_facility".to_string()),
-            region: std::env::var("REGION").unwrap_or_else(|_| "unknown_region".to_string()),
-            business_unit: std::env::var("BUSINESS_UNIT").unwrap_or_else(|_| "unknown_bu".to_string()),
-            alert_level,
-            recommended_actions,
-        }
+    let alert_level = if result.confidence >= 0.9 {
+        AlertLevel::Critical
+    } else if result.confidence >= 0.7 {
+        AlertLevel::High
+    } else if result.confidence >= 0.5 {
+        AlertLevel::Medium
+    } else {
+        AlertLevel::Low
+    };
+
+    match alert_level {
+        AlertLevel::Medium | AlertLevel::Low if !events.is_empty() => AlertLevel::High,
+        alert_level => alert_level,
+    }
+}
+
+/// Alert level of a single analytics event
+fn event_alert_level(event: &AnalyticsEvent) -> AlertLevel {
+    if event.event_type.is_safety_critical() {
+        AlertLevel::Critical
+    } else {
+        AlertLevel::High
+    }
+}
+
+/// Site context and recommended actions for an alert level
+fn enrichment_data(alert_level: AlertLevel) -> EnrichmentData {
+    // Generate recommended actions based on alert level
+    let recommended_actions = match alert_level {
+        AlertLevel::Critical => vec![
+            "Immediate manual inspection required".to_string(),
+            "Alert operations team".to_string(),
+            "Consider shutting down affected equipment".to_string(),
+        ],
+        AlertLevel::High => vec![
+            "Schedule inspection within 1 hour".to_string(),
+            "Notify maintenance team".to_string(),
+        ],
+        AlertLevel::Medium => vec![
+            "Schedule inspection within 4 hours".to_string(),
+            "Log for trending analysis".to_string(),
+        ],
+        AlertLevel::Low => vec![
+            "Continue monitoring".to_string(),
+            "Log for trending analysis".to_string(),
+        ],
+    };
+
+    EnrichmentData {
+        site: std::env::var("SITE").unwrap_or_else(|_| "unknown_site".to_string()),
+        facility: std::env::var("FACILITY").unwrap_or_else(|_| "unknown_facility".to_string()),
+        region: std::env::var("REGION").unwrap_or_else(|_| "unknown_region".to_string()),
+        business_unit: std::env::var("BUSINESS_UNIT").unwrap_or_else(|_| "unknown_bu".to_string()),
+        alert_level,
+        recommended_actions,
     }
 }
 
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use ai_edge_inference_crate::InferenceResult;
+use ai_edge_inference_crate::{analytics, AnalyticsEvent, InferenceResult};
 use tracing::debug;
 
 /// Topic router for intelligent MQTT topic selection based on inference results
@@ -60,6 +60,15 @@ impl TopicRouter {
         topic
     }
 
+    /// Generate topic for a zone, tripwire or separation event
+    pub fn route_event(&self, event: &AnalyticsEvent) -> String {
+        format!("{}/events/{}/{}",
+            self.topic_prefix.trim_end_matches('/'),
+            event.camera_id,
+            event.event_type.as_str()
+        )
+    }
+
     /// Generate topic for status/health messages
     #[allow(dead_code)]
     pub fn route_status(&self, component: &str, status_type: &str) -> String {
@@ -95,7 +104,18 @@ impl TopicRouter {
     }
 
     /// Determine priority level based on inference results
+    ///
+    /// Safety-critical analytics events (restricted area, separation) raise the
+    /// priority to critical, and any other event to at least high.
     fn determine_priority(&self, result: &InferenceResult) -> String {
+        let events = analytics::result_events(result);
+        if events.iter().any(|event| event.event_type.is_safety_critical()) {
+            return "critical".to_string();
+        }
+        if !events.is_empty() {
+            return "high".to_string();
+        }
+
         let mut max_confidence = result.confidence;
         let prediction_count = result.predictions.len();
 
@@ -167,10 +187,13 @@ mod tests {
                 bbox: Some([0.1, 0.1, 0.3, 0.4]),
                 metadata: HashMap::new(),
                 severity: None,
+                mask: None,
+                keypoints: None,
             }],
             confidence: 0.95,
             inference_time_ms: 45.0,
             metadata: serde_json::json!({}),
+            tensors: None,
         }
     }
 
@@ -201,6 +224,32 @@ mod tests {
         assert_eq!(topic, "edge-ai/safety/industrial-safety-vision/alerts/high");
     }
 
+    #[test]
+    fn test_analytics_events_raise_priority() {
+        let router = TopicRouter::new("edge-ai".to_string());
+        let mut result = create_test_result();
+        result.predictions[0].confidence = 0.4;
+        result.confidence = 0.4;
+        assert!(router.route_result(&result).ends_with("/low"));
+
+        result.metadata = serde_json::json!({
+            "analytics": {
+                "events": [{
+                    "event_type": "separation_violation",
+                    "rule": "forklift-pedestrian",
+                    "camera_id": "dock-cam-01",
+                    "timestamp": 1718000000000i64,
+                    "detections": [],
+                    "details": {}
+                }]
+            }
+        });
+        assert!(router.route_result(&result).ends_with("/critical"));
+
+        let event = analytics::result_events(&result).remove(0);
+        assert_eq!(router.route_event(&event), "edge-ai/events/dock-cam-01/separation_violation");
+    }
+
     #[test]
     fn test_status_routing() {
         let router = TopicRouter::new("edge-ai/test".to_string());